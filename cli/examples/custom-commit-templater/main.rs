@@ -29,6 +29,7 @@ use jj_lib::revset::FunctionCallNode;
 use jj_lib::revset::LoweringContext;
 use jj_lib::revset::PartialSymbolResolver;
 use jj_lib::revset::RevsetDiagnostics;
+use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterExtension;
 use jj_lib::revset::RevsetFilterPredicate;
@@ -173,8 +174,8 @@ impl CommitTemplateLanguageExtension for HexCounter {
 struct EvenDigitsFilter;
 
 impl RevsetFilterExtension for EvenDigitsFilter {
-    fn matches_commit(&self, commit: &Commit) -> bool {
-        num_digits_in_id(commit.id()) % 2 == 0
+    fn matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError> {
+        Ok(num_digits_in_id(commit.id()) % 2 == 0)
     }
 }
 
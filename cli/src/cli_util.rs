@@ -21,6 +21,7 @@ use std::env;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::Debug;
+use std::fs;
 use std::io;
 use std::io::Write as _;
 use std::mem;
@@ -62,7 +63,14 @@ use jj_lib::config::ConfigMigrationRule;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
+use jj_lib::config::TREE_CONFIG_PATH;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::copies::record_detected_copies;
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::default_index::RevsetCache;
+use jj_lib::default_index::RevsetCacheError;
+use jj_lib::file_util::IoResultExt as _;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
@@ -86,6 +94,7 @@ use jj_lib::operation::Operation;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RefNameBuf;
 use jj_lib::ref_name::RemoteName;
+use jj_lib::ref_name::RemoteNameBuf;
 use jj_lib::ref_name::RemoteRefSymbol;
 use jj_lib::ref_name::WorkspaceName;
 use jj_lib::ref_name::WorkspaceNameBuf;
@@ -103,7 +112,9 @@ use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::repo_path::UiPathParseError;
 use jj_lib::revset;
+use jj_lib::revset::DefaultPushRemoteFn;
 use jj_lib::revset::ResolvedRevsetExpression;
+use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
 use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::revset::RevsetExpression;
@@ -162,8 +173,10 @@ use crate::complete;
 use crate::config::ConfigArgKind;
 use crate::config::ConfigEnv;
 use crate::config::RawConfig;
+use crate::config::TREE_CONFIG_CHECKED_FILE;
 use crate::config::config_from_environment;
 use crate::config::parse_config_args;
+use crate::config::read_tree_config;
 use crate::description_util::TextEditor;
 use crate::diff_util;
 use crate::diff_util::DiffFormat;
@@ -369,6 +382,7 @@ impl CommandHelper {
         let repo_path = workspace_root.join(".jj").join("repo");
         config_env.reset_repo_path(&repo_path);
         config_env.reload_repo_config(ui, &mut raw_config)?;
+        config_env.reload_tree_config(ui, &mut raw_config)?;
         config_env.reset_workspace_path(workspace_root);
         config_env.reload_workspace_config(ui, &mut raw_config)?;
         let mut config = config_env.resolve_config(&raw_config)?;
@@ -464,6 +478,7 @@ impl CommandHelper {
                 self.recover_stale_working_copy(ui)?
             }
         };
+        workspace_command.warn_if_tree_config_changed(ui)?;
 
         Ok((workspace_command, stats))
     }
@@ -803,12 +818,14 @@ pub struct WorkspaceCommandEnvironment {
     revset_aliases_map: RevsetAliasesMap,
     template_aliases_map: TemplateAliasesMap,
     default_ignored_remote: Option<&'static RemoteName>,
+    default_push_remote: Box<DefaultPushRemoteFn<'static>>,
     revsets_use_glob_by_default: bool,
     path_converter: RepoPathUiConverter,
     workspace_name: WorkspaceNameBuf,
     immutable_heads_expression: Arc<UserRevsetExpression>,
     short_prefixes_expression: Option<Arc<UserRevsetExpression>>,
     conflict_marker_style: ConflictMarkerStyle,
+    revset_cache: Option<RevsetCache>,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -818,22 +835,46 @@ impl WorkspaceCommandEnvironment {
         let revset_aliases_map = revset_util::load_revset_aliases(ui, settings.config())?;
         let template_aliases_map = load_template_aliases(ui, settings.config())?;
         let default_ignored_remote = default_ignored_remote_name(workspace.repo_loader().store());
+        let default_push_remote: Box<DefaultPushRemoteFn> = {
+            // Looked up on demand as it may read the Git configuration.
+            let settings = settings.clone();
+            let store = workspace.repo_loader().store().clone();
+            let remote: OnceCell<Option<RemoteNameBuf>> = OnceCell::new();
+            Box::new(move || {
+                if let Some(remote) = remote.get() {
+                    return Ok(remote.clone());
+                }
+                let name = default_push_remote_name(&settings, &store).map_err(|err| err.error)?;
+                Ok(remote.get_or_init(|| name).clone())
+            })
+        };
         let path_converter = RepoPathUiConverter::Fs {
             cwd: command.cwd().to_owned(),
             base: workspace.workspace_root().to_owned(),
         };
+        let revset_cache = if settings.get_bool("index.revset-cache")? {
+            workspace
+                .repo_loader()
+                .index_store()
+                .downcast_ref::<DefaultIndexStore>()
+                .map(DefaultIndexStore::revset_cache)
+        } else {
+            None
+        };
         let mut env = Self {
             command: command.clone(),
             settings: settings.clone(),
             revset_aliases_map,
             template_aliases_map,
             default_ignored_remote,
+            default_push_remote,
             revsets_use_glob_by_default: settings.get("ui.revsets-use-glob-by-default")?,
             path_converter,
             workspace_name: workspace.workspace_name().to_owned(),
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
             conflict_marker_style: settings.get("ui.conflict-marker-style")?,
+            revset_cache,
         };
         env.reload_revset_expressions(ui)?;
         Ok(env)
@@ -865,6 +906,7 @@ impl WorkspaceCommandEnvironment {
             user_email: self.settings.user_email(),
             date_pattern_context: now.into(),
             default_ignored_remote: self.default_ignored_remote,
+            default_push_remote: Some(&*self.default_push_remote),
             use_glob_by_default: self.revsets_use_glob_by_default,
             extensions: self.command.revset_extensions(),
             workspace: Some(workspace_context),
@@ -943,14 +985,168 @@ impl WorkspaceCommandEnvironment {
         }
     }
 
+    /// Returns the persistent revset cache and the index of the `repo` if the
+    /// cache is enabled.
+    ///
+    /// Sets are cached per operation, so the cache can only be used for
+    /// readonly repos, not for repos being mutated by a transaction.
+    fn revset_cache_for<'a>(
+        &'a self,
+        repo: &'a ReadonlyRepo,
+    ) -> Option<(&'a RevsetCache, &'a DefaultReadonlyIndex)> {
+        let cache = self.revset_cache.as_ref()?;
+        let index = repo.readonly_index().downcast_ref()?;
+        Some((cache, index))
+    }
+
+    /// Builds key of the persistent revset cache for the `revset_str` of the
+    /// given `kind`.
+    ///
+    /// The key includes the configuration the revset is resolved with, such as
+    /// the aliases and the user email.
+    fn revset_cache_key(&self, kind: &str, revset_str: &str) -> String {
+        let aliases_name = ConfigNamePathBuf::from_iter(["revset-aliases"]);
+        let aliases = self
+            .settings
+            .config()
+            .layers()
+            .iter()
+            .filter_map(|layer| layer.look_up_table(&aliases_name).ok().flatten())
+            .flat_map(|table| table.iter())
+            .map(|(decl, item)| format!("{decl}={item}"))
+            .join("\n");
+        let context = self.revset_parse_context();
+        // If the remote can't be looked up, the revset using it can't be
+        // parsed either.
+        let default_push_remote = (self.default_push_remote)().ok().flatten();
+        [
+            kind,
+            revset_str,
+            self.workspace_name.as_str(),
+            context.user_email,
+            &context.use_glob_by_default.to_string(),
+            context
+                .default_ignored_remote
+                .map_or("", |name| name.as_str()),
+            default_push_remote
+                .as_ref()
+                .map_or("", |name| name.as_str()),
+            &aliases,
+        ]
+        .join("\0")
+    }
+
+    /// Evaluates the immutable set by using the persistent revset cache.
+    ///
+    /// Returns `None` if the cache is disabled, or if the set can't be cached.
+    fn evaluate_cached_immutable(
+        &self,
+        repo: &ReadonlyRepo,
+    ) -> Result<Option<Box<dyn Revset>>, CommandError> {
+        let Some((cache, index)) = self.revset_cache_for(repo) else {
+            return Ok(None);
+        };
+        if self.immutable_heads_expression.has_volatile_filter() {
+            return Ok(None);
+        }
+        let (_, _, immutable_heads_str) = self
+            .revset_aliases_map
+            .get_function(revset_util::USER_IMMUTABLE_HEADS, 0)
+            .unwrap();
+        let key = self.revset_cache_key("immutable_heads", immutable_heads_str);
+        if let Some(revset) = cache.get(index, &key, repo.op_id()) {
+            return Ok(Some(revset));
+        }
+        // See find_immutable_commit() for why a new IdPrefixContext is used.
+        let id_prefix_context = IdPrefixContext::new(self.command.revset_extensions().clone());
+        let heads: Vec<_> = RevsetExpressionEvaluator::new(
+            repo,
+            self.command.revset_extensions().clone(),
+            &id_prefix_context,
+            self.immutable_heads_expression
+                .union(&RevsetExpression::root()),
+        )
+        .resolve()
+        .map_err(|e| config_error_with_message("Invalid `revset-aliases.immutable_heads()`", e))?
+        .evaluate(repo)?
+        .iter()
+        .try_collect()?;
+        match cache.ancestors(index, &key, repo.op_id(), &heads) {
+            Ok(revset) => Ok(Some(revset)),
+            // This may be called while rendering templates, where warnings
+            // can't be printed. The set is evaluated without the cache.
+            Err(err @ RevsetCacheError::Write(_)) => {
+                tracing::warn!(?err, "failed to update revset cache");
+                Ok(None)
+            }
+            Err(err) => Err(internal_error_with_message(
+                "Failed to update revset cache",
+                err,
+            )),
+        }
+    }
+
+    /// Evaluates the `expression` parsed from `revset_str` against the `repo`
+    /// by using the persistent revset cache.
+    ///
+    /// Expressions with filters whose results may change at the same
+    /// operation, such as date and extension filters, aren't cached.
+    fn evaluate_cached_revset<'a>(
+        &self,
+        ui: &Ui,
+        repo: &ReadonlyRepo,
+        revset_str: &str,
+        expression: &RevsetExpressionEvaluator<'a>,
+    ) -> Result<Box<dyn Revset + 'a>, CommandError> {
+        let Some((cache, index)) = self.revset_cache_for(repo) else {
+            return Ok(expression.evaluate()?);
+        };
+        if expression.expression().has_volatile_filter() {
+            return Ok(expression.evaluate()?);
+        }
+        let key = self.revset_cache_key("expression", revset_str);
+        if let Some(revset) = cache.get(index, &key, repo.op_id()) {
+            return Ok(revset);
+        }
+        let commit_ids: Vec<_> = expression.evaluate()?.iter().try_collect()?;
+        match cache.insert(index, &key, repo.op_id(), &commit_ids) {
+            Ok(revset) => Ok(revset),
+            Err(err @ RevsetCacheError::Write(_)) => {
+                writeln!(ui.warning_default(), "Failed to update revset cache")?;
+                print_error_sources(ui, Some(&err))?;
+                Ok(expression.evaluate()?)
+            }
+            Err(err) => Err(internal_error_with_message(
+                "Failed to update revset cache",
+                err,
+            )),
+        }
+    }
+
     /// Returns first immutable commit.
+    ///
+    /// If `readonly_repo` is specified, `repo` must be that readonly repo, and
+    /// the persistent revset cache can be used.
     fn find_immutable_commit(
         &self,
         repo: &dyn Repo,
+        readonly_repo: Option<&ReadonlyRepo>,
         to_rewrite_expr: &Arc<ResolvedRevsetExpression>,
     ) -> Result<Option<CommitId>, CommandError> {
         let immutable_expression = if self.command.global_args().ignore_immutable {
             UserRevsetExpression::root()
+        } else if let Some(readonly_repo) = readonly_repo
+            && let Some(immutable_revset) = self.evaluate_cached_immutable(readonly_repo)?
+        {
+            let is_immutable = immutable_revset.containing_fn();
+            let to_rewrite_revset = to_rewrite_expr.clone().evaluate(repo)?;
+            for commit_id in to_rewrite_revset.iter() {
+                let commit_id = commit_id?;
+                if is_immutable(&commit_id)? {
+                    return Ok(Some(commit_id));
+                }
+            }
+            return Ok(None);
         } else {
             self.immutable_expression()
         };
@@ -1021,6 +1217,25 @@ impl WorkspaceCommandEnvironment {
         )
     }
 
+    /// Creates commit template language environment for the readonly `repo`,
+    /// which can use the persistent revset cache.
+    fn readonly_commit_template_language<'a>(
+        &'a self,
+        repo: &'a ReadonlyRepo,
+        id_prefix_context: &'a IdPrefixContext,
+    ) -> CommitTemplateLanguage<'a> {
+        let mut language = self.commit_template_language(repo, id_prefix_context);
+        if self.revset_cache_for(repo).is_some() {
+            language.set_immutable_revset_fn(Box::new(move || {
+                match self.evaluate_cached_immutable(repo) {
+                    Ok(revset) => Ok(revset.map(|revset| revset as Box<dyn Revset>)),
+                    Err(err) => Err(err.error.into()),
+                }
+            }));
+        }
+        language
+    }
+
     pub fn operation_template_extensions(&self) -> &[Arc<dyn OperationTemplateLanguageExtension>] {
         &self.command.data.operation_template_extensions
     }
@@ -1106,6 +1321,49 @@ impl WorkspaceCommandHelper {
         self.workspace.settings()
     }
 
+    /// Warns if the tree config at `trunk()` differs from the trusted copy
+    /// applied to the settings.
+    ///
+    /// The check is done only when the file at `trunk()` changed. The last
+    /// checked file ID is recorded next to the trusted copy.
+    fn warn_if_tree_config_changed(&self, ui: &Ui) -> Result<(), CommandError> {
+        let Some(path) = self
+            .settings()
+            .config()
+            .layers_for(ConfigSource::Tree)
+            .iter()
+            .find_map(|layer| layer.path.as_ref())
+        else {
+            return Ok(());
+        };
+        let trunk_arg = RevisionArg::from("trunk()".to_owned());
+        let trunk = self.resolve_single_rev(ui, &trunk_arg)?;
+        // The contents are only compared if the file changed at trunk() since
+        // the last check.
+        let config_path = RepoPath::from_internal_string(TREE_CONFIG_PATH).unwrap();
+        let file_id = match trunk.tree().path_value(config_path)?.as_resolved() {
+            Some(Some(TreeValue::File { id, .. })) => id.hex(),
+            _ => String::new(),
+        };
+        let checked_path = path.with_file_name(TREE_CONFIG_CHECKED_FILE);
+        if fs::read_to_string(&checked_path).is_ok_and(|checked| checked == file_id) {
+            return Ok(());
+        }
+        let trusted = fs::read(path).context(path)?;
+        if read_tree_config(&trunk)?.as_ref() != Some(&trusted) {
+            writeln!(
+                ui.warning_default(),
+                "The tree config `{TREE_CONFIG_PATH}` at trunk() differs from the trusted copy"
+            )?;
+            writeln!(
+                ui.hint_default(),
+                "Run `jj config trust` to review and trust the changes."
+            )?;
+        }
+        fs::write(&checked_path, file_id).context(&checked_path)?;
+        Ok(())
+    }
+
     pub fn check_working_copy_writable(&self) -> Result<(), CommandError> {
         if self.may_update_working_copy {
             Ok(())
@@ -1155,21 +1413,8 @@ impl WorkspaceCommandHelper {
         // Reload at current head to avoid creating divergent operations if another
         // process committed an operation while we were waiting for the lock.
         if self.working_copy_shared_with_git {
-            let repo = self.repo().clone();
-            let op_heads_store = repo.loader().op_heads_store();
-            let op_heads = op_heads_store
-                .get_op_heads()
-                .block_on()
+            self.reload_repo_at_head(ui)
                 .map_err(snapshot_command_error)?;
-            if std::slice::from_ref(repo.op_id()) != op_heads {
-                let op = self
-                    .env
-                    .command
-                    .resolve_operation(ui, repo.loader())
-                    .map_err(snapshot_command_error)?;
-                let current_repo = repo.loader().load_at(&op).map_err(snapshot_command_error)?;
-                self.user_repo = ReadonlyUserRepo::new(current_repo);
-            }
         }
 
         #[cfg(feature = "git")]
@@ -1192,6 +1437,23 @@ impl WorkspaceCommandHelper {
         Ok(stats)
     }
 
+    /// Reloads the repo at the head operation if the operation heads have
+    /// changed since the repo was loaded. Returns true if reloaded.
+    ///
+    /// The caller should ensure that the command was loaded at the head
+    /// operation.
+    pub fn reload_repo_at_head(&mut self, ui: &Ui) -> Result<bool, CommandError> {
+        let repo = self.repo().clone();
+        let op_heads = repo.loader().op_heads_store().get_op_heads().block_on()?;
+        if std::slice::from_ref(repo.op_id()) == op_heads {
+            return Ok(false);
+        }
+        let op = self.env.command.resolve_operation(ui, repo.loader())?;
+        let current_repo = repo.loader().load_at(&op)?;
+        self.user_repo = ReadonlyUserRepo::new(current_repo);
+        Ok(true)
+    }
+
     /// Snapshot the working copy if allowed, and import Git refs if the working
     /// copy is collocated with Git.
     #[instrument(skip_all)]
@@ -1780,10 +2042,25 @@ to the current parents may contain changes from multiple commits.
         self.parse_template(ui, &language, template_text)
     }
 
+    /// Evaluates the `expression` parsed from `revset_str` by using the
+    /// persistent revset cache if enabled.
+    ///
+    /// The evaluated set is cached per operation, so the `expression` shouldn't
+    /// depend on external state such as the current time.
+    pub fn evaluate_cached_revset<'a>(
+        &'a self,
+        ui: &Ui,
+        revset_str: &str,
+        expression: &RevsetExpressionEvaluator<'a>,
+    ) -> Result<Box<dyn Revset + 'a>, CommandError> {
+        self.env
+            .evaluate_cached_revset(ui, self.repo(), revset_str, expression)
+    }
+
     /// Creates commit template language environment for this workspace.
     pub fn commit_template_language(&self) -> CommitTemplateLanguage<'_> {
         self.env
-            .commit_template_language(self.repo().as_ref(), self.id_prefix_context())
+            .readonly_commit_template_language(self.repo(), self.id_prefix_context())
     }
 
     /// Creates operation template language environment for this workspace.
@@ -1850,7 +2127,10 @@ to the current parents may contain changes from multiple commits.
         to_rewrite_expr: &Arc<ResolvedRevsetExpression>,
     ) -> Result<(), CommandError> {
         let repo = self.repo().as_ref();
-        let Some(commit_id) = self.env.find_immutable_commit(repo, to_rewrite_expr)? else {
+        let Some(commit_id) = self
+            .env
+            .find_immutable_commit(repo, Some(repo), to_rewrite_expr)?
+        else {
             return Ok(());
         };
         let error = if &commit_id == repo.store().root_commit_id() {
@@ -1907,6 +2187,10 @@ to the current parents may contain changes from multiple commits.
         let options = self
             .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
             .map_err(snapshot_command_error)?;
+        let record_copies = self
+            .settings()
+            .get_bool("snapshot.record-copies")
+            .map_err(snapshot_command_error)?;
 
         // Compare working-copy tree and operation with repo's, and reload as needed.
         let mut locked_ws = self
@@ -1934,6 +2218,18 @@ to the current parents may contain changes from multiple commits.
                 .map_err(snapshot_command_error)?
         };
         if new_tree.tree_ids_and_labels() != wc_commit.tree().tree_ids_and_labels() {
+            let snapshot_tree = new_tree.clone();
+            let new_tree = if record_copies {
+                let repo = &self.user_repo.repo;
+                let parent_tree = wc_commit
+                    .parent_tree(repo.as_ref())
+                    .map_err(snapshot_command_error)?;
+                record_detected_copies(&parent_tree, &new_tree, repo.store().copy_detection())
+                    .block_on()
+                    .map_err(snapshot_command_error)?
+            } else {
+                new_tree
+            };
             let mut tx =
                 start_repo_transaction(&self.user_repo.repo, self.env.command.string_args());
             tx.set_is_snapshot(true);
@@ -1943,6 +2239,14 @@ to the current parents may contain changes from multiple commits.
                 .set_tree(new_tree)
                 .write()
                 .map_err(snapshot_command_error)?;
+            // Recording copies may have changed the tree ID.
+            if commit.tree().tree_ids() != snapshot_tree.tree_ids() {
+                locked_ws
+                    .locked_wc()
+                    .reset(&commit)
+                    .block_on()
+                    .map_err(snapshot_command_error)?;
+            }
             mut_repo
                 .set_wc_commit(workspace_name, commit.id().clone())
                 .map_err(snapshot_command_error)?;
@@ -2058,7 +2362,7 @@ to the current parents may contain changes from multiple commits.
             // the unresolvable trunk() issue gets addressed differently, it
             // should be okay to propagate the error.
             let wc_expr = RevsetExpression::commit(wc_commit_id.clone());
-            let is_immutable = match self.env.find_immutable_commit(tx.repo(), &wc_expr) {
+            let is_immutable = match self.env.find_immutable_commit(tx.repo(), None, &wc_expr) {
                 Ok(commit_id) => commit_id.is_some(),
                 Err(CommandError { error, .. }) => {
                     writeln!(
@@ -2507,7 +2811,25 @@ impl WorkspaceCommandTransaction<'_> {
         self.helper.env.parse_template(ui, &language, template_text)
     }
 
+    /// Attaches the given revset `expression` to be evaluated with the current
+    /// transaction state.
+    pub fn attach_revset_evaluator(
+        &self,
+        expression: Arc<UserRevsetExpression>,
+    ) -> RevsetExpressionEvaluator<'_> {
+        let id_prefix_context = self
+            .id_prefix_context
+            .get_or_init(|| self.helper.env.new_id_prefix_context());
+        RevsetExpressionEvaluator::new(
+            self.tx.repo(),
+            self.helper.env.command.revset_extensions().clone(),
+            id_prefix_context,
+            expression,
+        )
+    }
+
     pub fn finish(self, ui: &Ui, description: impl Into<String>) -> Result<(), CommandError> {
+        self.print_merge_driver_failures(ui)?;
         if !self.tx.repo().has_changes() {
             writeln!(ui.status(), "Nothing changed.")?;
             return Ok(());
@@ -2519,6 +2841,27 @@ impl WorkspaceCommandTransaction<'_> {
             .finish_transaction(ui, self.tx, description, &git_import_export_lock)
     }
 
+    /// Warns about files which were left conflicted because the merge driver
+    /// failed while rewriting commits.
+    fn print_merge_driver_failures(&self, ui: &Ui) -> io::Result<()> {
+        let failures = self
+            .tx
+            .repo()
+            .store()
+            .merge_options()
+            .drivers
+            .take_failures();
+        for failure in failures {
+            writeln!(
+                ui.warning_default(),
+                "Failed to merge {} by using the merge driver. The file was left conflicted.",
+                self.helper.format_file_path(&failure.path)
+            )?;
+            print_error_sources(ui, Some(&failure.error))?;
+        }
+        Ok(())
+    }
+
     /// Returns the wrapped [`Transaction`] for circumstances where
     /// finer-grained control is needed. The caller becomes responsible for
     /// finishing the `Transaction`, including rebasing descendants and updating
@@ -3002,6 +3345,33 @@ pub fn default_ignored_remote_name(store: &Store) -> Option<&'static RemoteName>
     None
 }
 
+/// Returns the remote that `jj git push` pushes to by default.
+///
+/// This is the `git.push` setting if configured, or the only existing remote.
+/// Otherwise, the remote named "origin" is used.
+#[cfg_attr(not(feature = "git"), expect(unused_variables))]
+pub fn default_push_remote_name(
+    settings: &UserSettings,
+    store: &Store,
+) -> Result<Option<RemoteNameBuf>, CommandError> {
+    #[cfg(feature = "git")]
+    {
+        use jj_lib::git;
+        if git::get_git_backend(store).is_ok() {
+            if let Some(remote) = settings.get_string("git.push").optional()? {
+                return Ok(Some(remote.into()));
+            }
+            let mut names = git::get_all_remote_names(store)?;
+            let remote = match names.len() {
+                1 => names.pop().unwrap(),
+                _ => RemoteName::new("origin").to_owned(),
+            };
+            return Ok(Some(remote));
+        }
+    }
+    Ok(None)
+}
+
 /// Whether or not the `bookmark` has any tracked remotes (i.e. is a tracking
 /// local bookmark.)
 pub fn has_tracked_remote_bookmarks(repo: &dyn Repo, bookmark: &RefName) -> bool {
@@ -4066,6 +4436,7 @@ impl<'a> CliRunner<'a> {
         if let Ok(loader) = &maybe_cwd_workspace_loader {
             config_env.reset_repo_path(loader.repo_path());
             config_env.reload_repo_config(ui, &mut raw_config)?;
+            config_env.reload_tree_config(ui, &mut raw_config)?;
             config_env.reset_workspace_path(loader.workspace_root());
             config_env.reload_workspace_config(ui, &mut raw_config)?;
         }
@@ -4112,6 +4483,7 @@ impl<'a> CliRunner<'a> {
                 .map_err(|err| map_workspace_load_error(err, Some(path)))?;
             config_env.reset_repo_path(loader.repo_path());
             config_env.reload_repo_config(ui, &mut raw_config)?;
+            config_env.reload_tree_config(ui, &mut raw_config)?;
             config_env.reset_workspace_path(loader.workspace_root());
             config_env.reload_workspace_config(ui, &mut raw_config)?;
             Ok(loader)
@@ -4130,6 +4502,7 @@ impl<'a> CliRunner<'a> {
                 ConfigSource::Default => "default-provided",
                 ConfigSource::EnvBase | ConfigSource::EnvOverrides => "environment-provided",
                 ConfigSource::User => "user-level",
+                ConfigSource::Tree => "tree-level",
                 ConfigSource::Repo => "repo-level",
                 ConfigSource::Workspace => "workspace-level",
                 ConfigSource::CommandArg => "CLI-provided",
@@ -4145,6 +4518,8 @@ impl<'a> CliRunner<'a> {
         }
 
         let settings = UserSettings::from_config(config)?;
+        let mut revset_extensions = self.revset_extensions;
+        revset_util::load_revset_filters(&settings, &mut revset_extensions)?;
         let command_helper_data = CommandHelperData {
             app: self.app,
             cwd,
@@ -4155,7 +4530,7 @@ impl<'a> CliRunner<'a> {
             config_migrations: self.config_migrations,
             raw_config,
             settings,
-            revset_extensions: self.revset_extensions.into(),
+            revset_extensions: revset_extensions.into(),
             commit_template_extensions: self.commit_template_extensions,
             operation_template_extensions: self.operation_template_extensions,
             maybe_workspace_loader,
@@ -29,6 +29,7 @@ use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
 use jj_lib::config::ConfigMigrateError;
 use jj_lib::dsl_util::Diagnostics;
+use jj_lib::evolution::FindLatestVersionsError;
 use jj_lib::evolution::WalkPredecessorsError;
 use jj_lib::fileset::FilePatternParseError;
 use jj_lib::fileset::FilesetParseError;
@@ -53,6 +54,7 @@ use jj_lib::revset::RevsetParseError;
 use jj_lib::revset::RevsetParseErrorKind;
 use jj_lib::revset::RevsetResolutionError;
 use jj_lib::secure_config::SecureConfigError;
+use jj_lib::signing::SignError;
 use jj_lib::str_util::StringPatternParseError;
 use jj_lib::trailer::TrailerParseError;
 use jj_lib::transaction::TransactionCommitError;
@@ -425,7 +427,27 @@ impl From<ResetError> for CommandError {
 
 impl From<TransactionCommitError> for CommandError {
     fn from(err: TransactionCommitError) -> Self {
-        internal_error(err)
+        match err {
+            TransactionCommitError::Sign(err @ SignError::NoBackend) => {
+                user_error_with_message("Failed to sign operation", err)
+                    .hinted(SIGNING_BACKEND_HINT)
+            }
+            TransactionCommitError::Sign(err) => {
+                user_error_with_message("Failed to sign operation", err)
+            }
+            err => internal_error(err),
+        }
+    }
+}
+
+impl From<FindLatestVersionsError> for CommandError {
+    fn from(err: FindLatestVersionsError) -> Self {
+        match err {
+            FindLatestVersionsError::Backend(err) => err.into(),
+            FindLatestVersionsError::Index(err) => err.into(),
+            FindLatestVersionsError::OpStore(err) => err.into(),
+            FindLatestVersionsError::RevsetEvaluation(err) => err.into(),
+        }
     }
 }
 
@@ -736,6 +758,8 @@ impl From<AbsorbError> for CommandError {
         match err {
             AbsorbError::Backend(err) => err.into(),
             AbsorbError::RevsetEvaluation(err) => err.into(),
+            AbsorbError::Index(err) => err.into(),
+            AbsorbError::UnmappedHunks { .. } => internal_error(err),
         }
     }
 }
@@ -768,6 +792,18 @@ impl From<SecureConfigError> for CommandError {
     }
 }
 
+impl From<SignError> for CommandError {
+    fn from(err: SignError) -> Self {
+        match err {
+            SignError::InvalidSignatureFormat => {
+                user_error_with_message("Failed to verify signature", err)
+            }
+            SignError::NoBackend => user_error(err).hinted(SIGNING_BACKEND_HINT),
+            SignError::Backend(_) => internal_error_with_message("Failed to verify signature", err),
+        }
+    }
+}
+
 fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
     let source = err.source()?;
     if let Some(source) = source.downcast_ref() {
@@ -797,6 +833,9 @@ fn find_source_parse_error_hint(err: &dyn error::Error) -> Option<String> {
     }
 }
 
+const SIGNING_BACKEND_HINT: &str = "For configuring a signing backend, see \
+                                    https://docs.jj-vcs.dev/latest/config/#commit-signing";
+
 const REVSET_SYMBOL_HINT: &str = "See https://docs.jj-vcs.dev/latest/revsets/ or use `jj help -k \
                                   revsets` for how to quote symbols.";
 
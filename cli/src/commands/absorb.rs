@@ -12,21 +12,42 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io;
+use std::io::Write as _;
+use std::iter;
+use std::ops::Range;
+
+use bstr::ByteSlice as _;
+use clap::ValueEnum;
+use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use indoc::formatdoc;
+use itertools::Itertools as _;
+use jj_lib::absorb::AbsorbHeuristics;
+use jj_lib::absorb::AbsorbHunkReason;
+use jj_lib::absorb::AbsorbPlan;
 use jj_lib::absorb::AbsorbSource;
 use jj_lib::absorb::absorb_hunks;
-use jj_lib::absorb::split_hunks_to_trees;
+use jj_lib::absorb::plan_absorb;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::diff_presentation::DiffAlgorithms;
 use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merge::Diff;
+use jj_lib::repo::Repo as _;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::DiffSelector;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::cli_util::print_updated_commits;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::diff_util::DiffFormat;
+use crate::formatter::Formatter;
 use crate::ui::Ui;
 
 /// Move changes from a revision into the stack of mutable revisions
@@ -39,6 +60,15 @@ use crate::ui::Ui;
 /// The source revision will be abandoned if all changes are absorbed into the
 /// destination revisions, and if the source revision has no description.
 ///
+/// Hunks which can't be attributed unambiguously are reported with the reason.
+/// Use `--heuristic` to absorb them by less strict rules.
+///
+/// With `--interactive`, the diff editor is opened for each revision which
+/// modified the lines near the changes. The right side initially shows the
+/// changes that can be absorbed into the revision, and you can choose which of
+/// them to absorb. Changes not chosen are offered to the next revision, or left
+/// in the source revision.
+///
 /// The modification made by `jj absorb` can be reviewed by `jj op show -p`.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct AbsorbArgs {
@@ -60,12 +90,37 @@ pub(crate) struct AbsorbArgs {
     #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
     into: Vec<RevisionArg>,
 
+    /// Interactively choose which changes to absorb into each revision
+    #[arg(long, short)]
+    interactive: bool,
+
+    /// Specify diff editor to be used (implies --interactive)
+    #[arg(long, value_name = "NAME")]
+    #[arg(add = ArgValueCandidates::new(complete::diff_editors))]
+    tool: Option<String>,
+
+    /// Heuristics to choose destinations of hunks which can't be attributed
+    /// by line annotation
+    #[arg(long, value_name = "HEURISTIC", value_enum)]
+    heuristic: Vec<AbsorbHeuristic>,
+
     /// Move only changes to these paths (instead of all paths)
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     #[arg(add = ArgValueCompleter::new(complete::modified_from_files))]
     paths: Vec<String>,
 }
 
+/// Heuristic for the `jj absorb --heuristic` option.
+#[derive(Copy, Clone, PartialEq, Debug, ValueEnum)]
+enum AbsorbHeuristic {
+    /// Absorb lines inserted between lines from different revisions into the
+    /// revision which last modified the surrounding lines
+    Context,
+    /// Absorb hunks into the only destination revision which modified the
+    /// file, unless they modify lines from other revisions
+    File,
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_absorb(
     ui: &mut Ui,
@@ -82,9 +137,25 @@ pub(crate) fn cmd_absorb(
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
     let matcher = fileset_expression.to_matcher();
 
+    let diff_selector =
+        workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
+
     let repo = workspace_command.repo().as_ref();
     let source = AbsorbSource::from_commit(repo, source_commit.clone())?;
-    let selected_trees = split_hunks_to_trees(repo, &source, &destinations, &matcher).block_on()?;
+    let diff_algorithms = DiffAlgorithms::from_settings(workspace_command.settings())?;
+    let heuristics = AbsorbHeuristics {
+        context: args.heuristic.contains(&AbsorbHeuristic::Context),
+        file: args.heuristic.contains(&AbsorbHeuristic::File),
+    };
+    let mut plan = plan_absorb(
+        repo,
+        &source,
+        &destinations,
+        &matcher,
+        &diff_algorithms,
+        &heuristics,
+    )
+    .block_on()?;
 
     print_unmatched_explicit_paths(
         ui,
@@ -94,11 +165,26 @@ pub(crate) fn cmd_absorb(
     )?;
 
     let path_converter = workspace_command.path_converter();
-    for (path, reason) in selected_trees.skipped_paths {
-        let ui_path = path_converter.format_file_path(&path);
+    for (path, reason) in &plan.skipped_paths {
+        let ui_path = path_converter.format_file_path(path);
         writeln!(ui.warning_default(), "Skipping {ui_path}: {reason}")?;
     }
 
+    if diff_selector.is_interactive() {
+        review_hunks(
+            &workspace_command,
+            &diff_selector,
+            &source,
+            &source_commit,
+            &mut plan,
+        )?;
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        print_unabsorbed_hunks(formatter.as_mut(), &workspace_command, &plan)?;
+    }
+    let repo = workspace_command.repo().as_ref();
+    let selected_trees = plan.to_selected_trees(repo, &source).block_on()?;
+
     workspace_command.check_rewritable(selected_trees.target_commits.keys())?;
 
     let mut tx = workspace_command.start_transaction();
@@ -150,3 +236,164 @@ pub(crate) fn cmd_absorb(
     }
     Ok(())
 }
+
+/// Lets the user select hunks to absorb into each candidate revision by using
+/// the diff editor. Hunks which aren't selected are offered to the next
+/// candidate revision.
+fn review_hunks(
+    workspace_command: &WorkspaceCommandHelper,
+    diff_selector: &DiffSelector,
+    source: &AbsorbSource,
+    source_commit: &Commit,
+    plan: &mut AbsorbPlan,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo().as_ref();
+    let store = repo.store();
+    let path_converter = workspace_command.path_converter();
+    let parent_tree = source_commit.parent_tree(repo)?;
+    let tree_labels = Diff::new(
+        source_commit.parents_conflict_label()?,
+        source_commit.conflict_label(),
+    );
+    // Proposed destinations come first in the candidates of each hunk.
+    let candidate_ids = plan
+        .files
+        .iter()
+        .flat_map(|file| &file.hunks)
+        .flat_map(|hunk| &hunk.candidates)
+        .unique()
+        .cloned()
+        .collect_vec();
+    let mut selected: Vec<Vec<Option<CommitId>>> = plan
+        .files
+        .iter()
+        .map(|file| vec![None; file.hunks.len()])
+        .collect();
+    for candidate_id in &candidate_ids {
+        let mut offered_plan = plan.clone();
+        let mut offered_lines = Vec::new();
+        for (file, selected) in iter::zip(&mut offered_plan.files, &selected) {
+            let ui_path = path_converter.format_file_path(&file.path);
+            for (hunk, destination) in iter::zip(&mut file.hunks, selected) {
+                let offered = destination.is_none() && hunk.candidates.contains(candidate_id);
+                if offered {
+                    let (line, _) = line_range(&file.right_text, &hunk.right_range);
+                    let proposed = hunk.destination.as_ref() == Some(candidate_id);
+                    let note = if proposed { " (proposed)" } else { "" };
+                    offered_lines.push(format!("  {ui_path}:{line}{note}"));
+                }
+                hunk.destination = offered.then(|| candidate_id.clone());
+            }
+        }
+        if offered_lines.is_empty() {
+            continue;
+        }
+        let mut offered_trees = offered_plan.to_selected_trees(repo, source).block_on()?;
+        let offered_tree = offered_trees
+            .target_commits
+            .remove(candidate_id)
+            .unwrap()
+            .write_tree()?;
+        let candidate = store.get_commit(candidate_id)?;
+        let format_instructions = || {
+            formatdoc! {"
+                You are absorbing changes from: {source}
+                into commit: {destination}
+
+                The left side of the diff shows the contents of the parent commit. The
+                right side initially shows the changes that can be absorbed into the
+                destination commit:
+                {offered_lines}
+
+                Adjust the right side until the diff shows the changes you want to absorb
+                into the destination. Changes that aren't absorbed will be offered to the
+                next commit that modified the nearby lines, or left in the source commit.
+                Changes are absorbed by whole hunks; partially edited hunks are skipped.
+                ",
+                source = workspace_command.format_commit_summary(source_commit),
+                destination = workspace_command.format_commit_summary(&candidate),
+                offered_lines = offered_lines.join("\n"),
+            }
+        };
+        let selected_tree = diff_selector.select(
+            Diff::new(&parent_tree, &offered_tree),
+            tree_labels.clone(),
+            &EverythingMatcher,
+            format_instructions,
+        )?;
+        for ((file, offered_file), selected) in
+            iter::zip(iter::zip(&plan.files, &offered_plan.files), &mut selected)
+        {
+            if offered_file
+                .hunks
+                .iter()
+                .all(|hunk| hunk.destination.is_none())
+            {
+                continue;
+            }
+            for index in file.find_hunks_in_tree(store, &selected_tree).block_on()? {
+                if offered_file.hunks[index].destination.is_some() {
+                    selected[index] = Some(candidate_id.clone());
+                }
+            }
+        }
+    }
+    for (file, selected) in iter::zip(&mut plan.files, selected) {
+        // Hunks without candidates can only be left in the source revision.
+        for (hunk, destination) in iter::zip(&mut file.hunks, selected) {
+            if hunk.candidates.is_empty() {
+                continue;
+            }
+            if destination.is_none() {
+                hunk.reason = AbsorbHunkReason::Excluded;
+            }
+            hunk.destination = destination;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the first line number and the number of lines of the byte `range`.
+/// The line number of an empty range is the number of the preceding line as in
+/// unified diff.
+fn line_range(text: &[u8], range: &Range<usize>) -> (usize, usize) {
+    let start = text[..range.start].lines_with_terminator().count();
+    let len = text[range.clone()].lines_with_terminator().count();
+    let first = if len == 0 { start } else { start + 1 };
+    (first, len)
+}
+
+/// Prints hunks which will be left in the source revision, and the reasons.
+fn print_unabsorbed_hunks(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    plan: &AbsorbPlan,
+) -> io::Result<()> {
+    let path_converter = workspace_command.path_converter();
+    let mut has_header = false;
+    for file in &plan.files {
+        let ui_path = path_converter.format_file_path(&file.path);
+        for hunk in file.hunks.iter().filter(|hunk| hunk.destination.is_none()) {
+            let message = match hunk.reason {
+                AbsorbHunkReason::Annotation
+                | AbsorbHunkReason::Context
+                | AbsorbHunkReason::File
+                | AbsorbHunkReason::Excluded => continue,
+                AbsorbHunkReason::MultipleCommits => "modified lines came from multiple revisions",
+                AbsorbHunkReason::AmbiguousInsertion => {
+                    "inserted between lines from different revisions"
+                }
+                AbsorbHunkReason::NotInDestinations => {
+                    "modified lines weren't introduced by the destination revisions"
+                }
+            };
+            if !has_header {
+                writeln!(formatter, "Hunks left in the source revision:")?;
+                has_header = true;
+            }
+            let (line, _) = line_range(&file.right_text, &hunk.right_range);
+            writeln!(formatter, "  {ui_path}:{line}: {message}")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use super::session::BisectSession;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+/// Show the revisions marked in the current bisection
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectLogArgs {}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect_log(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectLogArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let Some(session) = BisectSession::load(&workspace_command)? else {
+        return Err(user_error("No bisection in progress"));
+    };
+    let store = workspace_command.repo().store();
+    let commit_template = workspace_command.commit_summary_template();
+    let mut formatter = ui.stdout_formatter();
+    for (commit_id, mark) in session.marks()? {
+        let commit = store.get_commit(&commit_id)?;
+        write!(formatter, "{}: ", mark.as_str())?;
+        commit_template.format(&commit, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    if let Some(commit_id) = session.current_commit_id()? {
+        let commit = store.get_commit(&commit_id)?;
+        write!(formatter, "Now evaluating: ")?;
+        commit_template.format(&commit, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
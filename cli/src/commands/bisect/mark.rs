@@ -0,0 +1,106 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use tracing::instrument;
+
+use super::session::BisectSession;
+use super::session::Mark;
+use super::session::advance_bisection;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Mark a revision as good and move on to the next one
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectGoodArgs {
+    #[command(flatten)]
+    target: MarkTargetArgs,
+}
+
+/// Mark a revision as bad and move on to the next one
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectBadArgs {
+    #[command(flatten)]
+    target: MarkTargetArgs,
+}
+
+/// Skip a revision that cannot be evaluated and move on to the next one
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectSkipArgs {
+    #[command(flatten)]
+    target: MarkTargetArgs,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+struct MarkTargetArgs {
+    /// The revision to mark
+    ///
+    /// Defaults to the revision currently being evaluated.
+    #[arg(long, short, value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: Option<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect_good(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectGoodArgs,
+) -> Result<(), CommandError> {
+    mark_revision(ui, command, &args.target, Mark::Good)
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect_bad(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectBadArgs,
+) -> Result<(), CommandError> {
+    mark_revision(ui, command, &args.target, Mark::Bad)
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect_skip(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectSkipArgs,
+) -> Result<(), CommandError> {
+    mark_revision(ui, command, &args.target, Mark::Skip)
+}
+
+fn mark_revision(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    target: &MarkTargetArgs,
+    mark: Mark,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut session = BisectSession::load_current(&workspace_command)?;
+    let commit_id = match &target.revision {
+        Some(revision) => workspace_command
+            .resolve_single_rev(ui, revision)?
+            .id()
+            .clone(),
+        None => session.current_commit_id()?.ok_or_else(|| {
+            user_error("No revision is being evaluated")
+                .hinted("Use `--revision` to mark a revision explicitly.")
+        })?,
+    };
+    session.record(workspace_command.repo().as_ref(), &commit_id, mark)?;
+    advance_bisection(ui, &mut workspace_command, &mut session)
+}
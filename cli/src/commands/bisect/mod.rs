@@ -12,16 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod log;
+mod mark;
+mod reset;
 mod run;
+mod session;
+mod start;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Find a bad revision by bisection.
+///
+/// Use `jj bisect run` to evaluate revisions by running a command, or
+/// `jj bisect start` to evaluate them by hand.
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum BisectCommand {
+    Bad(mark::BisectBadArgs),
+    Good(mark::BisectGoodArgs),
+    Log(log::BisectLogArgs),
+    Reset(reset::BisectResetArgs),
     Run(run::BisectRunArgs),
+    Skip(mark::BisectSkipArgs),
+    Start(start::BisectStartArgs),
 }
 
 pub fn cmd_bisect(
@@ -30,6 +44,12 @@ pub fn cmd_bisect(
     subcommand: &BisectCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        BisectCommand::Bad(args) => mark::cmd_bisect_bad(ui, command, args),
+        BisectCommand::Good(args) => mark::cmd_bisect_good(ui, command, args),
+        BisectCommand::Log(args) => log::cmd_bisect_log(ui, command, args),
+        BisectCommand::Reset(args) => reset::cmd_bisect_reset(ui, command, args),
         BisectCommand::Run(args) => run::cmd_bisect_run(ui, command, args),
+        BisectCommand::Skip(args) => mark::cmd_bisect_skip(ui, command, args),
+        BisectCommand::Start(args) => start::cmd_bisect_start(ui, command, args),
     }
 }
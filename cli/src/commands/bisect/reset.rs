@@ -0,0 +1,53 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use super::session::BisectSession;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// End the current bisection
+///
+/// Returns to the working-copy commit from before the bisection was started.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectResetArgs {}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectResetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let session = BisectSession::load_current(&workspace_command)?;
+    let original_commit_id = session.original_commit_id()?;
+    if workspace_command.get_wc_commit_id() != Some(&original_commit_id) {
+        let commit = workspace_command
+            .repo()
+            .store()
+            .get_commit(&original_commit_id)?;
+        let mut tx = workspace_command.start_transaction();
+        tx.edit(&commit)?;
+        tx.finish(
+            ui,
+            format!("Reset bisection to revision {}", commit.id().hex()),
+        )?;
+    }
+    BisectSession::remove(&workspace_command)?;
+    Ok(())
+}
@@ -0,0 +1,299 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State of a manual bisection, persisted in the repo directory between
+//! commands.
+
+use std::io;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectionResult;
+use jj_lib::bisect::Bisector;
+use jj_lib::bisect::Evaluation;
+use jj_lib::bisect::NextStep;
+use jj_lib::file_util::persist_temp_file;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo;
+use jj_lib::revset::RevsetExpression;
+use tempfile::NamedTempFile;
+
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::short_commit_hash;
+use crate::command_error::CommandError;
+use crate::command_error::internal_error_with_message;
+use crate::command_error::user_error;
+use crate::ui::Ui;
+
+const STATE_FILE_NAME: &str = "bisect_state.json";
+
+/// Outcome recorded for a revision by `jj bisect good/bad/skip`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Mark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl Mark {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Skip => "skipped",
+        }
+    }
+
+    fn to_evaluation(self) -> Evaluation {
+        match self {
+            Self::Good => Evaluation::Good,
+            Self::Bad => Evaluation::Bad,
+            Self::Skip => Evaluation::Skip,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub(crate) struct MarkEntry {
+    pub commit_id: String,
+    pub mark: Mark,
+}
+
+/// Bisection in progress. Commit IDs are stored in hex.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub(crate) struct BisectSession {
+    /// Workspace the bisection was started in.
+    pub workspace: String,
+    /// Working-copy commit to return to when the bisection is reset.
+    pub original_commit_id: String,
+    /// Roots of the bisected range.
+    pub range_roots: Vec<String>,
+    /// Heads of the bisected range. The range consists of the revisions
+    /// between the roots and the heads.
+    pub range_heads: Vec<String>,
+    /// Revision currently checked out for evaluation.
+    pub current_commit_id: Option<String>,
+    /// Marks in the order they were recorded.
+    pub marks: Vec<MarkEntry>,
+}
+
+impl BisectSession {
+    fn path(workspace_command: &WorkspaceCommandHelper) -> PathBuf {
+        workspace_command.repo_path().join(STATE_FILE_NAME)
+    }
+
+    /// Loads the bisection in progress, if any.
+    pub fn load(workspace_command: &WorkspaceCommandHelper) -> Result<Option<Self>, CommandError> {
+        let data = match std::fs::read(Self::path(workspace_command)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(internal_error_with_message(
+                    "Failed to read bisection state",
+                    err,
+                ));
+            }
+        };
+        let session = serde_json::from_slice(&data)
+            .map_err(|err| internal_error_with_message("Failed to parse bisection state", err))?;
+        Ok(Some(session))
+    }
+
+    /// Loads the bisection in progress, which must have been started in the
+    /// current workspace.
+    pub fn load_current(workspace_command: &WorkspaceCommandHelper) -> Result<Self, CommandError> {
+        let Some(session) = Self::load(workspace_command)? else {
+            return Err(user_error("No bisection in progress")
+                .hinted("Use `jj bisect start` to start one."));
+        };
+        if session.workspace != workspace_command.workspace_name().as_str() {
+            return Err(user_error(format!(
+                "The bisection was started in workspace {}",
+                session.workspace
+            )));
+        }
+        Ok(session)
+    }
+
+    pub fn save(&self, workspace_command: &WorkspaceCommandHelper) -> Result<(), CommandError> {
+        let data = serde_json::to_vec_pretty(self).unwrap();
+        // Write to a temporary file first so the state isn't lost if the
+        // process is interrupted.
+        let write = || {
+            let mut temp_file = NamedTempFile::new_in(workspace_command.repo_path())?;
+            temp_file.write_all(&data)?;
+            persist_temp_file(temp_file, Self::path(workspace_command))
+        };
+        write()
+            .map(|_| ())
+            .map_err(|err| internal_error_with_message("Failed to write bisection state", err))
+    }
+
+    pub fn remove(workspace_command: &WorkspaceCommandHelper) -> Result<(), CommandError> {
+        std::fs::remove_file(Self::path(workspace_command))
+            .map_err(|err| internal_error_with_message("Failed to remove bisection state", err))
+    }
+
+    pub fn original_commit_id(&self) -> Result<CommitId, CommandError> {
+        parse_commit_id(&self.original_commit_id)
+    }
+
+    pub fn current_commit_id(&self) -> Result<Option<CommitId>, CommandError> {
+        self.current_commit_id
+            .as_deref()
+            .map(parse_commit_id)
+            .transpose()
+    }
+
+    pub fn marks(&self) -> Result<Vec<(CommitId, Mark)>, CommandError> {
+        self.marks
+            .iter()
+            .map(|entry| Ok((parse_commit_id(&entry.commit_id)?, entry.mark)))
+            .collect()
+    }
+
+    /// Creates a bisector with the recorded marks applied.
+    pub fn bisector<'repo>(&self, repo: &'repo dyn Repo) -> Result<Bisector<'repo>, CommandError> {
+        let parse_ids = |ids: &[String]| -> Result<Vec<_>, CommandError> {
+            ids.iter().map(|hex| parse_commit_id(hex)).try_collect()
+        };
+        let roots = RevsetExpression::commits(parse_ids(&self.range_roots)?);
+        let heads = RevsetExpression::commits(parse_ids(&self.range_heads)?);
+        let mut bisector = Bisector::new(repo, roots.dag_range_to(&heads))?;
+        for (commit_id, mark) in self.marks()? {
+            if find_mark(&bisector, &commit_id) != Some(mark) {
+                bisector.mark(commit_id, mark.to_evaluation());
+            }
+        }
+        Ok(bisector)
+    }
+
+    /// Records `mark` for the given revision. Fails if the revision was marked
+    /// differently before.
+    pub fn record(
+        &mut self,
+        repo: &dyn Repo,
+        commit_id: &CommitId,
+        mark: Mark,
+    ) -> Result<(), CommandError> {
+        let bisector = self.bisector(repo)?;
+        match find_mark(&bisector, commit_id) {
+            Some(old_mark) if old_mark != mark => Err(user_error(format!(
+                "Revision {} is already marked as {}",
+                short_commit_hash(commit_id),
+                old_mark.as_str()
+            ))),
+            _ => {
+                self.marks.push(MarkEntry {
+                    commit_id: commit_id.hex(),
+                    mark,
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+fn parse_commit_id(hex: &str) -> Result<CommitId, CommandError> {
+    CommitId::try_from_hex(hex).ok_or_else(|| {
+        internal_error_with_message(
+            "Failed to parse bisection state",
+            format!("Invalid commit ID: {hex}"),
+        )
+    })
+}
+
+fn find_mark(bisector: &Bisector<'_>, commit_id: &CommitId) -> Option<Mark> {
+    if bisector.good_commits().contains(commit_id) {
+        Some(Mark::Good)
+    } else if bisector.bad_commits().contains(commit_id) {
+        Some(Mark::Bad)
+    } else if bisector.skipped_commits().contains(commit_id) {
+        Some(Mark::Skip)
+    } else {
+        None
+    }
+}
+
+/// Checks out the next revision to evaluate, or reports the first bad revision
+/// if the bisection is complete. The session is saved either way.
+pub(crate) fn advance_bisection(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    session: &mut BisectSession,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo().clone();
+    let mut bisector = session.bisector(repo.as_ref())?;
+    match bisector.next_step()? {
+        NextStep::Evaluate(commit) => {
+            session.current_commit_id = Some(commit.id().hex());
+            session.save(workspace_command)?;
+            {
+                let mut formatter = ui.stdout_formatter();
+                let commit_template = workspace_command.commit_summary_template();
+                write!(formatter, "Now evaluating: ")?;
+                commit_template.format(&commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            }
+            let mut tx = workspace_command.start_transaction();
+            tx.check_out(&commit)?;
+            tx.finish(
+                ui,
+                format!("Updated to revision {} for bisection", commit.id().hex()),
+            )?;
+        }
+        NextStep::Done(result) => {
+            session.current_commit_id = None;
+            session.save(workspace_command)?;
+            write_bisection_result(ui, workspace_command, &result)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_bisection_result(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    result: &BisectionResult,
+) -> io::Result<()> {
+    let mut formatter = ui.stdout_formatter();
+    let commit_template = workspace_command.commit_summary_template();
+    match result {
+        BisectionResult::Found(commits) => {
+            if let [commit] = commits.as_slice() {
+                write!(formatter, "The first bad revision is: ")?;
+                commit_template.format(commit, formatter.as_mut())?;
+                writeln!(formatter)?;
+            } else {
+                writeln!(formatter, "The first bad revisions are:")?;
+                for commit in commits {
+                    commit_template.format(commit, formatter.as_mut())?;
+                    writeln!(formatter)?;
+                }
+            }
+        }
+        BisectionResult::Indeterminate | BisectionResult::Abort => {
+            writeln!(formatter, "Could not find the first bad revision.")?;
+        }
+    }
+    drop(formatter);
+    writeln!(
+        ui.hint_default(),
+        "Use `jj bisect reset` to return to the original working-copy commit."
+    )?;
+    Ok(())
+}
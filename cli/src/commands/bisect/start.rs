@@ -0,0 +1,98 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId as _;
+use tracing::instrument;
+
+use super::session::BisectSession;
+use super::session::advance_bisection;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Start a manual bisection
+///
+/// Checks out the first revision to evaluate. After testing it, mark it with
+/// `jj bisect good`, `jj bisect bad`, or `jj bisect skip` to move on to the
+/// next revision. The state of the bisection is kept in the repo until
+/// `jj bisect reset` is run.
+///
+/// It is assumed that if a given revision is bad, then all its descendants
+/// in the input range are also bad.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectStartArgs {
+    /// Range of revisions to bisect
+    ///
+    /// This is typically a range like `v1.0..main`. The heads of the range are
+    /// assumed to be bad. Ancestors of the range that are not also in the range
+    /// are assumed to be good.
+    ///
+    /// The range must include all revisions between its roots and heads.
+    #[arg(long, short, value_name = "REVSETS", required = true)]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    range: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect_start(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectStartArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if BisectSession::load(&workspace_command)?.is_some() {
+        return Err(user_error("A bisection is already in progress")
+            .hinted("Use `jj bisect reset` to end it first."));
+    }
+    let original_commit_id = workspace_command
+        .get_wc_commit_id()
+        .ok_or_else(|| user_error("This command requires a working copy"))?
+        .hex();
+    // The range is stored by its roots and heads because the revset may
+    // resolve differently once revisions are checked out.
+    let range = workspace_command
+        .parse_union_revsets(ui, &args.range)?
+        .expression()
+        .clone();
+    let evaluate_to_hex = |expression| -> Result<Vec<_>, CommandError> {
+        let ids = workspace_command
+            .attach_revset_evaluator(expression)
+            .evaluate_to_commit_ids()?
+            .map_ok(|id| id.hex())
+            .try_collect()?;
+        Ok(ids)
+    };
+    let range_roots = evaluate_to_hex(range.roots())?;
+    let range_heads = evaluate_to_hex(range.heads())?;
+    let enclosed_count = evaluate_to_hex(range.roots().dag_range_to(&range.heads()))?.len();
+    if evaluate_to_hex(range)?.len() != enclosed_count {
+        return Err(user_error(
+            "The range to bisect must include all revisions between its roots and heads",
+        ));
+    }
+    let mut session = BisectSession {
+        workspace: workspace_command.workspace_name().as_str().to_owned(),
+        original_commit_id,
+        range_roots,
+        range_heads,
+        current_commit_id: None,
+        marks: vec![],
+    };
+    advance_bisection(ui, &mut workspace_command, &mut session)
+}
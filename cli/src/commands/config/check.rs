@@ -0,0 +1,112 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+
+use jj_lib::config::ConfigLayer;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::config::ConfigSchemaProblem;
+use crate::config::check_config_schema;
+use crate::config::is_schema_checked_source;
+use crate::ui::Ui;
+
+/// Check config files for unknown keys and values of invalid types
+///
+/// Checks the user, repo, and workspace config files, as well as the
+/// `--config` and `--config-file` arguments, against the config schema. Each
+/// problem is reported with the location of the offending item. The built-in
+/// settings and the settings derived from environment variables aren't
+/// checked.
+///
+/// Unknown keys at the top level are allowed since other tools may store their
+/// settings there.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ConfigCheckArgs {}
+
+#[instrument(skip_all)]
+pub fn cmd_config_check(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &ConfigCheckArgs,
+) -> Result<(), CommandError> {
+    let mut problem_count = 0;
+    {
+        let mut formatter = ui.stdout_formatter();
+        for layer in command.raw_config().as_ref().layers() {
+            if !is_schema_checked_source(layer.source) {
+                continue;
+            }
+            for (location, problem) in check_layer(layer) {
+                writeln!(formatter, "{location}: {problem}")?;
+                if let Some(hint) = problem.hint() {
+                    writeln!(formatter, "  {hint}")?;
+                }
+                problem_count += 1;
+            }
+        }
+    }
+    match problem_count {
+        0 => {
+            writeln!(ui.status(), "No problems found in config")?;
+            Ok(())
+        }
+        1 => Err(user_error("Found 1 problem in config")),
+        n => Err(user_error(format!("Found {n} problems in config"))),
+    }
+}
+
+/// Checks the `layer` against the config schema, and returns the problems
+/// along with their locations.
+fn check_layer(layer: &ConfigLayer) -> Vec<(String, ConfigSchemaProblem)> {
+    let Some(path) = &layer.path else {
+        return check_config_schema(layer.data.as_table())
+            .into_iter()
+            .map(|problem| (format!("{} config", layer.source), problem))
+            .collect();
+    };
+    // The loaded layer doesn't know the source locations, so parse the file
+    // again.
+    let document = fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml_edit::Document::parse(text).ok());
+    let Some(document) = document else {
+        return check_config_schema(layer.data.as_table())
+            .into_iter()
+            .map(|problem| (path.display().to_string(), problem))
+            .collect();
+    };
+    // Items that weren't loaded, such as the items the tree config isn't
+    // allowed to set, aren't checked.
+    check_config_schema(document.as_table())
+        .into_iter()
+        .filter(|problem| matches!(layer.look_up_item(&problem.name), Ok(Some(_))))
+        .map(|problem| {
+            let location = match &problem.span {
+                Some(span) => {
+                    let before = &document.raw()[..span.start];
+                    let line = before.matches('\n').count() + 1;
+                    let column = before.rsplit('\n').next().unwrap().chars().count() + 1;
+                    format!("{}:{line}:{column}", path.display())
+                }
+                None => path.display().to_string(),
+            };
+            (location, problem)
+        })
+        .collect()
+}
@@ -0,0 +1,129 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::config::TREE_CONFIG_PATH;
+use jj_lib::file_util::IoResultExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::complete;
+use crate::config::TREE_CONFIG_ALLOWED_NAMES;
+use crate::config::TREE_CONFIG_CHECKED_FILE;
+use crate::config::read_tree_config;
+use crate::config::retain_tree_config_items;
+use crate::ui::Ui;
+
+/// Review and trust the config file committed to the repository
+///
+/// Prints the `.jj/config.toml` file of the given revision and asks whether to
+/// trust it. A copy of the trusted file is stored next to the repo config
+/// file, and is loaded after the user config and before the repo config. The
+/// config file of the repository is never loaded without being trusted: if
+/// the file at `trunk()` changes, jj warns about it once per operation and
+/// keeps using the trusted copy until the new version is trusted with this
+/// command.
+///
+/// Since the file is written by other people, only the following settings are
+/// applied: `revset-aliases` (including `immutable_heads()`),
+/// `template-aliases`, `fix.tools`, `merge-tools`, and `ui.merge-editor`.
+/// Other settings are ignored.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ConfigTrustArgs {
+    /// The revision to read the config file from
+    #[arg(long, short, default_value = "trunk()", value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: RevisionArg,
+
+    /// Stop applying the trusted config file
+    #[arg(long, conflicts_with = "revision")]
+    revoke: bool,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_config_trust(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigTrustArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let Some(trusted_path) = command.config_env().tree_config_path(ui)? else {
+        return Err(user_error("No repo config path found"));
+    };
+
+    if args.revoke {
+        fs::remove_file(trusted_path.with_file_name(TREE_CONFIG_CHECKED_FILE)).ok();
+        match fs::remove_file(&trusted_path) {
+            Ok(()) => writeln!(ui.status(), "The tree config is no longer trusted")?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                writeln!(ui.status(), "No tree config is trusted")?;
+            }
+            Err(err) => return Err(err.into()),
+        }
+        return Ok(());
+    }
+
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let Some(content) = read_tree_config(&commit)? else {
+        return Err(user_error(format!(
+            "No `{TREE_CONFIG_PATH}` file in revision {}",
+            args.revision
+        )));
+    };
+    if fs::read(&trusted_path).ok().as_ref() == Some(&content) {
+        writeln!(ui.status(), "The tree config is already trusted")?;
+        return Ok(());
+    }
+    let text = std::str::from_utf8(&content).map_err(|err| {
+        user_error_with_message(format!("`{TREE_CONFIG_PATH}` is not valid UTF-8"), err)
+    })?;
+    let mut layer = ConfigLayer::parse(ConfigSource::Tree, text)?;
+    let ignored_names = retain_tree_config_items(&mut layer);
+
+    ui.stdout().write_all(&content)?;
+    for name in &ignored_names {
+        writeln!(
+            ui.warning_default(),
+            "`{name}` is ignored because it can't be set by the tree config"
+        )?;
+    }
+    if !ignored_names.is_empty() {
+        let allowed_names = TREE_CONFIG_ALLOWED_NAMES
+            .iter()
+            .map(|names| format!("`{}`", names.join(".")));
+        writeln!(
+            ui.hint_default(),
+            "The tree config can only set {}.",
+            itertools::join(allowed_names, ", ")
+        )?;
+    }
+    if !ui.prompt_yes_no("Trust this config file?", Some(false))? {
+        return Err(user_error("Config file not trusted"));
+    }
+    fs::write(&trusted_path, &content).context(&trusted_path)?;
+    // Compare the new trusted copy with trunk() on the next command.
+    fs::remove_file(trusted_path.with_file_name(TREE_CONFIG_CHECKED_FILE)).ok();
+    writeln!(ui.status(), "The tree config is now trusted")?;
+    Ok(())
+}
@@ -12,15 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::annotate::FileAnnotator;
 use jj_lib::annotate::LineOrigin;
+use jj_lib::diff_presentation::DiffAlgorithms;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use tracing::instrument;
 
+use super::parse_line_range;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
@@ -34,6 +38,14 @@ use crate::ui::Ui;
 ///
 /// Annotates a revision line by line. Each line includes the source change that
 /// introduced the associated line. A path to the desired file must be provided.
+///
+/// Revisions matching the `annotate.ignore-revs` setting are looked through, so
+/// the lines they changed are attributed to the previous revisions that changed
+/// the most similar lines.
+///
+/// Lines of files that were renamed or copied are followed to the source file.
+/// Set `copies.detect` to also detect renames and copies the backend doesn't
+/// report.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileAnnotateArgs {
     /// the file to annotate
@@ -46,6 +58,23 @@ pub(crate) struct FileAnnotateArgs {
     #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
     revision: Option<RevisionArg>,
 
+    /// Only annotate lines in the given range
+    ///
+    /// Line numbers start at 1, and the range includes both ends. History is
+    /// only searched until all lines in the range are attributed.
+    #[arg(long, value_name = "START:END", value_parser = parse_line_range)]
+    lines: Option<Range<usize>>,
+
+    /// Revisions to look through
+    ///
+    /// Lines changed by these revisions are attributed to the revisions that
+    /// last changed the corresponding lines in their parents.
+    ///
+    /// If not specified, this defaults to the `annotate.ignore-revs` setting.
+    #[arg(long, value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    ignore_revs: Option<RevisionArg>,
+
     /// Render each line using the given template
     ///
     /// All 0-argument methods of the [`AnnotationLine` type] are available as
@@ -97,15 +126,40 @@ pub(crate) fn cmd_file_annotate(
     let language = workspace_command.commit_template_language();
     let template = workspace_command.parse_template(ui, &language, &template_text)?;
 
+    let ignore_revs = match &args.ignore_revs {
+        Some(revset) => revset.clone(),
+        None => RevisionArg::from(
+            workspace_command
+                .settings()
+                .get_string("annotate.ignore-revs")?,
+        ),
+    };
+    let ignored_expression = workspace_command
+        .parse_revset(ui, &ignore_revs)?
+        .resolve()?;
+
     // TODO: Should we add an option to limit the domain to e.g. recent commits?
-    // Note that this is probably different from "--skip REVS", which won't
-    // exclude the revisions, but will ignore diffs in those revisions as if
-    // ancestor revisions had new content.
+    let diff_algorithms = DiffAlgorithms::from_settings(workspace_command.settings())?;
     let mut annotator = FileAnnotator::from_commit(&starting_commit, &file_path)?;
+    let num_lines = annotator.to_annotation().line_origins().count();
+    let line_range = match &args.lines {
+        Some(range) if range.start >= num_lines => {
+            return Err(user_error(format!(
+                "Line {} is out of range: {ui_path} has {num_lines} lines",
+                range.start + 1
+            )));
+        }
+        Some(range) => range.start..range.end.min(num_lines),
+        None => 0..num_lines,
+    };
+    annotator.set_diff_algorithm(diff_algorithms.for_path(&file_path));
+    annotator.set_line_range(line_range.clone());
+    annotator.set_ignored_revisions(ignored_expression);
+    annotator.set_follow_copies(true);
     annotator.compute(repo.as_ref(), &RevsetExpression::all())?;
     let annotation = annotator.to_annotation();
 
-    render_file_annotation(repo.as_ref(), ui, &template, &annotation)?;
+    render_file_annotation(repo.as_ref(), ui, &template, &annotation, line_range)?;
     Ok(())
 }
 
@@ -114,6 +168,7 @@ fn render_file_annotation(
     ui: &mut Ui,
     template_render: &TemplateRenderer<AnnotationLine>,
     annotation: &FileAnnotation,
+    line_range: Range<usize>,
 ) -> Result<(), CommandError> {
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
@@ -126,7 +181,12 @@ fn render_file_annotation(
         commit_id: repo.store().root_commit_id().clone(),
         line_number: 0,
     };
-    for (line_number, (line_origin, content)) in annotation.line_origins().enumerate() {
+    let line_origins = annotation
+        .line_origins()
+        .enumerate()
+        .skip(line_range.start)
+        .take(line_range.len());
+    for (line_number, (line_origin, content)) in line_origins {
         let line_origin = line_origin.unwrap_or(&default_line_origin);
         let commit = repo.store().get_commit(&line_origin.commit_id)?;
         let first_line_in_hunk = last_id != Some(&line_origin.commit_id);
@@ -0,0 +1,121 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::backend::TreeValue;
+use jj_lib::copies::record_copy;
+use jj_lib::object_id::ObjectId as _;
+use pollster::FutureExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Copy a file, recording the copy
+///
+/// The copy is recorded in the revision, so the history of the source file is
+/// followed by `jj diff`, `jj file annotate` and `jj log --follow`, without
+/// relying on content similarity.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileCpArgs {
+    /// The revision to update
+    #[arg(long, short, default_value = "@", value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
+    revision: RevisionArg,
+
+    /// The file to copy
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    #[arg(add = ArgValueCompleter::new(complete::all_revision_files))]
+    source: String,
+
+    /// The path to copy the file to
+    ///
+    /// If this is a directory, the file is copied into it.
+    #[arg(value_hint = clap::ValueHint::AnyPath)]
+    destination: String,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_cp(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileCpArgs,
+) -> Result<(), CommandError> {
+    copy_file(
+        ui,
+        command,
+        &args.revision,
+        &args.source,
+        &args.destination,
+        false,
+    )
+}
+
+/// Copies or moves the `source` file to `destination` in the revision, and
+/// records the copy in it.
+pub(super) fn copy_file(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    revision: &RevisionArg,
+    source: &str,
+    destination: &str,
+    rename: bool,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, revision)?;
+    workspace_command.check_rewritable([commit.id()])?;
+    let tree = commit.tree();
+    let source = workspace_command.parse_file_path(source)?;
+    let mut target = workspace_command.parse_file_path(destination)?;
+    let source_value = tree.path_value(&source)?;
+    let ui_source = workspace_command.format_file_path(&source);
+    if source_value.is_absent() {
+        return Err(user_error(format!("No such path: {ui_source}")));
+    }
+    if source_value.to_file_merge().is_none() {
+        return Err(user_error(format!(
+            "Path exists but is not a regular file: {ui_source}"
+        )));
+    }
+    let target_value = tree.path_value(&target)?;
+    if matches!(target_value.as_resolved(), Some(Some(TreeValue::Tree(_)))) {
+        let (_, file_name) = source.split().expect("file path shouldn't be root");
+        target = target.join(file_name);
+    }
+    let ui_target = workspace_command.format_file_path(&target);
+    if !tree.path_value(&target)?.is_absent() {
+        return Err(user_error(format!(
+            "Destination already exists: {ui_target}"
+        )));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let new_tree = record_copy(&tree, &source, &target, rename).block_on()?;
+    tx.repo_mut()
+        .rewrite_commit(&commit)
+        .set_tree(new_tree)
+        .write()?;
+    tx.finish(
+        ui,
+        format!(
+            "{} {ui_source} to {ui_target} in commit {}",
+            if rename { "move" } else { "copy" },
+            commit.id().hex(),
+        ),
+    )
+}
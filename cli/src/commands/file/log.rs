@@ -0,0 +1,203 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::ops::Range;
+
+use bstr::BStr;
+use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
+use jj_lib::annotate::FileAnnotator;
+use jj_lib::annotate::trace_line_range;
+use jj_lib::diff_presentation::DiffAlgorithms;
+use jj_lib::merge::Diff;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::revset::RevsetExpression;
+use regex::bytes::Regex;
+use tracing::instrument;
+
+use super::parse_line_range;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::diff_util::UnifiedDiffOptions;
+use crate::diff_util::show_git_diff_line_range;
+use crate::ui::Ui;
+
+/// Show the history of a range of lines in a file
+///
+/// Follows the lines back through history, and shows each revision that
+/// changed them along with the diff of the lines. Lines moved within the file
+/// and file renames detected by the backend are followed.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileLogArgs {
+    /// the file to follow
+    #[arg(value_hint = clap::ValueHint::AnyPath)]
+    #[arg(add = ArgValueCompleter::new(complete::all_revision_files))]
+    path: String,
+
+    /// an optional revision to start at
+    #[arg(long, short, value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: Option<RevisionArg>,
+
+    /// Lines to follow
+    ///
+    /// `START:END` selects lines by number. Line numbers start at 1, and the
+    /// range includes both ends.
+    ///
+    /// `:REGEX` selects the block starting at the first line matching the
+    /// regular expression, such as a function definition. The block extends
+    /// over the following lines that are blank or indented more deeply, up to
+    /// a closing bracket at the same indentation.
+    #[arg(long, short = 'L', value_name = "RANGE", value_parser = parse_line_range_arg)]
+    lines: LineRangeArg,
+}
+
+#[derive(Clone, Debug)]
+enum LineRangeArg {
+    Lines(Range<usize>),
+    Block(Regex),
+}
+
+fn parse_line_range_arg(s: &str) -> Result<LineRangeArg, String> {
+    if let Some(pattern) = s.strip_prefix(':') {
+        let regex = Regex::new(pattern).map_err(|err| err.to_string())?;
+        Ok(LineRangeArg::Block(regex))
+    } else {
+        parse_line_range(s).map(LineRangeArg::Lines)
+    }
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_log(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileLogArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let starting_commit = workspace_command
+        .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let file_path = workspace_command.parse_file_path(&args.path)?;
+    let file_value = starting_commit.tree().path_value(&file_path)?;
+    let ui_path = workspace_command.format_file_path(&file_path);
+
+    if file_value.is_absent() {
+        return Err(user_error(format!("No such path: {ui_path}")));
+    }
+    if file_value.to_file_merge().is_none() {
+        return Err(user_error(format!(
+            "Path exists but is not a regular file: {ui_path}"
+        )));
+    }
+
+    let annotation = FileAnnotator::from_commit(&starting_commit, &file_path)?.to_annotation();
+    let lines = annotation
+        .text()
+        .split_inclusive(|b| *b == b'\n')
+        .collect_vec();
+    let line_range = match &args.lines {
+        LineRangeArg::Lines(range) if range.start >= lines.len() => {
+            return Err(user_error(format!(
+                "Line {} is out of range: {ui_path} has {} lines",
+                range.start + 1,
+                lines.len()
+            )));
+        }
+        LineRangeArg::Lines(range) => range.start..range.end.min(lines.len()),
+        LineRangeArg::Block(regex) => find_block(&lines, regex).ok_or_else(|| {
+            user_error(format!(
+                "No line in {ui_path} matches the regular expression {:?}",
+                regex.as_str()
+            ))
+        })?,
+    };
+
+    let settings = workspace_command.settings();
+    let diff_algorithms = DiffAlgorithms::from_settings(settings)?;
+    let changes = trace_line_range(
+        repo.as_ref(),
+        &starting_commit,
+        &file_path,
+        line_range,
+        diff_algorithms.for_path(&file_path),
+        &RevsetExpression::all(),
+    )?;
+
+    let options = UnifiedDiffOptions::from_settings(settings)?;
+    let commit_template = workspace_command.commit_summary_template();
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for change in &changes {
+        let commit = repo.store().get_commit(&change.current.commit_id)?;
+        commit_template.format(&commit, formatter.as_mut())?;
+        writeln!(formatter)?;
+        let current = &change.current;
+        let (parent_path, parent_range, parent_text): (Option<&RepoPath>, _, &BStr) =
+            match &change.parent {
+                Some(parent) => (
+                    Some(parent.path.as_ref()),
+                    parent.range.clone(),
+                    parent.text.as_ref(),
+                ),
+                None => (None, 0..0, BStr::new("")),
+            };
+        show_git_diff_line_range(
+            formatter.as_mut(),
+            Diff::new(parent_path, Some(current.path.as_ref())),
+            Diff::new(parent_range, current.range.clone()),
+            Diff::new(parent_text, current.text.as_ref()),
+            &options,
+        )?;
+    }
+    Ok(())
+}
+
+/// Finds the block starting at the first line matching the `regex`.
+///
+/// The block extends over the following blank and more deeply indented lines,
+/// and a closing bracket line at the same indentation. Trailing blank lines are
+/// excluded.
+fn find_block(lines: &[&[u8]], regex: &Regex) -> Option<Range<usize>> {
+    let indent = |line: &[u8]| {
+        line.iter()
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .count()
+    };
+    let start = lines.iter().position(|line| regex.is_match(line))?;
+    let base_indent = indent(lines[start]);
+    let mut end = start + 1;
+    for line in &lines[start + 1..] {
+        let content = line.trim_ascii();
+        if content.is_empty() || indent(line) > base_indent {
+            end += 1;
+        } else if content.starts_with(b"}")
+            || content.starts_with(b")")
+            || content.starts_with(b"]")
+        {
+            end += 1;
+            break;
+        } else {
+            break;
+        }
+    }
+    while end > start + 1 && lines[end - 1].trim_ascii().is_empty() {
+        end -= 1;
+    }
+    Some(start..end)
+}
@@ -14,12 +14,17 @@
 
 mod annotate;
 mod chmod;
+mod cp;
 mod list;
+mod log;
+mod mv;
 mod search;
 mod show;
 mod track;
 mod untrack;
 
+use std::ops::Range;
+
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -29,7 +34,10 @@ use crate::ui::Ui;
 pub enum FileCommand {
     Annotate(annotate::FileAnnotateArgs),
     Chmod(chmod::FileChmodArgs),
+    Cp(cp::FileCpArgs),
     List(list::FileListArgs),
+    Log(log::FileLogArgs),
+    Mv(mv::FileMvArgs),
     Search(search::FileSearchArgs),
     Show(show::FileShowArgs),
     Track(track::FileTrackArgs),
@@ -44,10 +52,31 @@ pub fn cmd_file(
     match subcommand {
         FileCommand::Annotate(args) => annotate::cmd_file_annotate(ui, command, args),
         FileCommand::Chmod(args) => chmod::cmd_file_chmod(ui, command, args),
+        FileCommand::Cp(args) => cp::cmd_file_cp(ui, command, args),
         FileCommand::List(args) => list::cmd_file_list(ui, command, args),
+        FileCommand::Log(args) => log::cmd_file_log(ui, command, args),
+        FileCommand::Mv(args) => mv::cmd_file_mv(ui, command, args),
         FileCommand::Search(args) => search::cmd_file_search(ui, command, args),
         FileCommand::Show(args) => show::cmd_file_show(ui, command, args),
         FileCommand::Track(args) => track::cmd_file_track(ui, command, args),
         FileCommand::Untrack(args) => untrack::cmd_file_untrack(ui, command, args),
     }
 }
+
+/// Parses `START:END` into a 0-based line range.
+fn parse_line_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once(':')
+        .ok_or_else(|| "Expected a range in the form START:END".to_owned())?;
+    let parse_line_number = |s: &str| match s.parse::<usize>() {
+        Ok(0) => Err("Line numbers start at 1".to_owned()),
+        Ok(n) => Ok(n),
+        Err(err) => Err(format!("Invalid line number {s:?}: {err}")),
+    };
+    let start = parse_line_number(start)?;
+    let end = parse_line_number(end)?;
+    if end < start {
+        return Err(format!("The range {start}:{end} is empty"));
+    }
+    Ok(start - 1..end)
+}
@@ -0,0 +1,64 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use tracing::instrument;
+
+use super::cp::copy_file;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Move or rename a file, recording the rename
+///
+/// The rename is recorded in the revision, so the history of the file is
+/// followed by `jj diff`, `jj file annotate` and `jj log --follow`, and changes
+/// to the file on other branches are merged into the renamed file when they
+/// are rebased onto each other.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileMvArgs {
+    /// The revision to update
+    #[arg(long, short, default_value = "@", value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
+    revision: RevisionArg,
+
+    /// The file to move
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    #[arg(add = ArgValueCompleter::new(complete::all_revision_files))]
+    source: String,
+
+    /// The path to move the file to
+    ///
+    /// If this is a directory, the file is moved into it.
+    #[arg(value_hint = clap::ValueHint::AnyPath)]
+    destination: String,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_mv(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileMvArgs,
+) -> Result<(), CommandError> {
+    copy_file(
+        ui,
+        command,
+        &args.revision,
+        &args.source,
+        &args.destination,
+        true,
+    )
+}
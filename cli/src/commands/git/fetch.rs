@@ -21,6 +21,7 @@ use jj_lib::git;
 use jj_lib::git::FetchTagsOverride;
 use jj_lib::git::GitFetch;
 use jj_lib::git::GitFetchRefExpression;
+use jj_lib::git::GitImportStats;
 use jj_lib::git::GitSettings;
 use jj_lib::git::IgnoredRefspec;
 use jj_lib::git::IgnoredRefspecs;
@@ -30,6 +31,7 @@ use jj_lib::git::load_default_fetch_bookmarks;
 use jj_lib::ref_name::RefName;
 use jj_lib::ref_name::RemoteName;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
 use jj_lib::str_util::StringExpression;
 
 use crate::cli_util::CommandHelper;
@@ -43,6 +45,7 @@ use crate::git_util::GitSubprocessUi;
 use crate::git_util::load_git_import_options;
 use crate::git_util::print_git_import_stats;
 use crate::revset_util::parse_union_name_patterns;
+use crate::signing_util::SignaturePolicy;
 use crate::ui::Ui;
 
 /// Fetch from a Git remote
@@ -229,6 +232,15 @@ pub fn cmd_git_fetch(
     }
 
     let import_stats = git_fetch.import_refs()?;
+    // The fetched commits have only been imported to the transaction, but Git
+    // has already updated its refs. Restore them if the commits are rejected
+    // so that they won't be imported by the next command.
+    if tx.settings().get_bool("signing.enforce-on-fetch")?
+        && let Err(err) = check_fetched_signatures(ui, &tx, &import_stats)
+    {
+        git::revert_imported_git_refs(tx.repo().store(), &import_stats)?;
+        return Err(err);
+    }
     print_git_import_stats(ui, &tx, &import_stats)?;
 
     if let Some(bookmark_expr) = &common_bookmark_expr {
@@ -272,6 +284,56 @@ fn get_default_fetch_remotes(
     }
 }
 
+/// Fails if any of the fetched commits doesn't satisfy the `signing.require`
+/// policy, so that the fetched refs aren't imported.
+fn check_fetched_signatures(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    import_stats: &GitImportStats,
+) -> Result<(), CommandError> {
+    let policy = SignaturePolicy::from_settings(tx.settings())?;
+    let required = tx
+        .base_workspace_helper()
+        .parse_revset(ui, &policy.require)?
+        .expression()
+        .clone();
+    let new_heads = import_stats
+        .changed_remote_bookmarks
+        .iter()
+        .chain(&import_stats.changed_remote_tags)
+        .flat_map(|(_, (_, new_target))| new_target.added_ids())
+        .cloned()
+        .collect_vec();
+    let old_heads = tx.base_repo().view().heads().iter().cloned().collect_vec();
+    let fetched = RevsetExpression::commits(old_heads).range(&RevsetExpression::commits(new_heads));
+    let mut rejected = vec![];
+    for commit in tx
+        .attach_revset_evaluator(fetched.intersection(&required))
+        .evaluate_to_commits()?
+    {
+        let commit = commit?;
+        if let Some(problem) = policy.check(&commit)? {
+            rejected.push((commit, problem));
+        }
+    }
+    if rejected.is_empty() {
+        return Ok(());
+    }
+    let mut error = user_error(format!(
+        "Won't import {} fetched commits since they are not signed as required",
+        rejected.len()
+    ));
+    for (commit, problem) in &rejected {
+        error.add_formatted_hint_with(|formatter| {
+            write!(formatter, "Rejected commit ({problem}): ")?;
+            tx.write_commit_summary(formatter, commit)?;
+            Ok(())
+        });
+    }
+    error.add_hint(format!("Configured signing.require: '{}'", policy.require));
+    Err(error)
+}
+
 fn warn_if_branches_not_found(
     ui: &mut Ui,
     tx: &WorkspaceCommandTransaction,
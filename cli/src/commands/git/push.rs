@@ -44,7 +44,6 @@ use jj_lib::refs::BookmarkPushUpdate;
 use jj_lib::refs::LocalAndRemoteRef;
 use jj_lib::refs::classify_bookmark_push_action;
 use jj_lib::repo::Repo;
-use jj_lib::revset::RemoteRefSymbolExpression;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::signing::SignBehavior;
 use jj_lib::str_util::StringExpression;
@@ -54,6 +53,7 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::cli_util::default_push_remote_name;
 use crate::cli_util::has_tracked_remote_bookmarks;
 use crate::cli_util::short_commit_hash;
 use crate::command_error::CommandError;
@@ -61,13 +61,13 @@ use crate::command_error::cli_error;
 use crate::command_error::cli_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
-use crate::commands::git::get_single_remote;
 use crate::complete;
 use crate::formatter::Formatter;
 use crate::git_util::GitSubprocessUi;
 use crate::git_util::print_push_stats;
 use crate::revset_util::parse_bookmark_name;
 use crate::revset_util::parse_union_name_patterns;
+use crate::signing_util::SignaturePolicy;
 use crate::ui::Ui;
 
 /// Push to a Git remote
@@ -421,7 +421,7 @@ pub fn cmd_git_push(
     } else {
         None
     };
-    let commits_to_sign =
+    let (commits_to_sign, required_to_sign) =
         validate_commits_ready_to_push(ui, &bookmark_updates, remote, &tx, args, sign_behavior)?;
     if !args.dry_run
         && !commits_to_sign.is_empty()
@@ -429,8 +429,13 @@ pub fn cmd_git_push(
     {
         let num_updated_signatures = commits_to_sign.len();
         let num_rebased_descendants;
-        (num_rebased_descendants, bookmark_updates) =
-            sign_commits_before_push(&mut tx, commits_to_sign, sign_behavior, bookmark_updates)?;
+        (num_rebased_descendants, bookmark_updates) = sign_commits_before_push(
+            &mut tx,
+            commits_to_sign,
+            &required_to_sign,
+            sign_behavior,
+            bookmark_updates,
+        )?;
         if let Some(mut formatter) = ui.status_formatter() {
             writeln!(
                 formatter,
@@ -486,7 +491,8 @@ pub fn cmd_git_push(
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.).
 ///
-/// Returns the list of commits which need to be signed.
+/// Returns the list of commits which need to be signed, and the ids of those
+/// that have to satisfy the `signing.require` policy once signed.
 fn validate_commits_ready_to_push(
     ui: &Ui,
     bookmark_updates: &[(RefNameBuf, BookmarkPushUpdate)],
@@ -494,7 +500,7 @@ fn validate_commits_ready_to_push(
     tx: &WorkspaceCommandTransaction,
     args: &GitPushArgs,
     sign_behavior: Option<SignBehavior>,
-) -> Result<Vec<Commit>, CommandError> {
+) -> Result<(Vec<Commit>, HashSet<CommitId>), CommandError> {
     let workspace_helper = tx.base_workspace_helper();
     let repo = workspace_helper.repo();
 
@@ -523,8 +529,14 @@ fn validate_commits_ready_to_push(
         sign_settings.behavior = sign_behavior;
         sign_settings
     });
+    let signature_policy = SignaturePolicy::from_settings(settings)?;
+    let is_signature_required = workspace_helper
+        .parse_revset(ui, &signature_policy.require)?
+        .evaluate()?
+        .containing_fn();
 
     let mut commits_to_sign = vec![];
+    let mut required_to_sign = HashSet::new();
 
     for commit in workspace_helper
         .attach_revset_evaluator(commits_to_push)
@@ -549,6 +561,20 @@ fn validate_commits_ready_to_push(
         if !args.allow_private && is_private {
             reasons.push("it is private");
         }
+        let will_sign = sign_settings.as_ref().is_some_and(|sign_settings| {
+            !commit.is_signed() && sign_settings.should_sign(commit.store_commit())
+        });
+        // Commits signed on push are checked once they are signed.
+        let is_required = is_signature_required(commit.id())?;
+        let signature_problem = if !will_sign && is_required {
+            signature_policy.check(&commit)?
+        } else {
+            None
+        };
+        let signature_reason = signature_problem.as_ref().map(|problem| problem.reason());
+        if let Some(reason) = &signature_reason {
+            reasons.push(reason.as_str());
+        }
         if !reasons.is_empty() {
             let mut error = user_error(format!(
                 "Won't push commit {} since {}",
@@ -565,25 +591,36 @@ fn validate_commits_ready_to_push(
                     "Configured git.private-commits: '{private_revset_str}'",
                 ));
             }
+            if signature_problem.is_some() {
+                error.add_hint(format!(
+                    "Configured signing.require: '{}'",
+                    signature_policy.require
+                ));
+            }
             return Err(error);
         }
-        if let Some(sign_settings) = &sign_settings
-            && !commit.is_signed()
-            && sign_settings.should_sign(commit.store_commit())
-        {
+        if will_sign {
+            if is_required {
+                required_to_sign.insert(commit.id().clone());
+            }
             commits_to_sign.push(commit);
         }
     }
-    Ok(commits_to_sign)
+    Ok((commits_to_sign, required_to_sign))
 }
 
 /// Signs commits before pushing.
 ///
+/// Fails if any of the newly signed commits in `required_ids` doesn't satisfy
+/// the `signing.require` policy, e.g. because the user's own key isn't in
+/// `signing.allowed-keys`.
+///
 /// Returns the number of commits with rebased descendants and the updated list
 /// of bookmark names and corresponding [`BookmarkPushUpdate`]s.
 fn sign_commits_before_push(
     tx: &mut WorkspaceCommandTransaction,
     commits_to_sign: Vec<Commit>,
+    required_ids: &HashSet<CommitId>,
     sign_behavior: SignBehavior,
     bookmark_updates: Vec<(RefNameBuf, BookmarkPushUpdate)>,
 ) -> Result<(usize, Vec<(RefNameBuf, BookmarkPushUpdate)>), CommandError> {
@@ -609,6 +646,36 @@ fn sign_commits_before_push(
         },
     )?;
 
+    if !required_ids.is_empty() {
+        let signature_policy = SignaturePolicy::from_settings(tx.settings())?;
+        for old_commit in commits_to_sign
+            .iter()
+            .filter(|commit| required_ids.contains(commit.id()))
+        {
+            let new_commit_id = &old_to_new_commits_map[old_commit.id()];
+            let commit = tx.repo().store().get_commit(new_commit_id)?;
+            let Some(problem) = signature_policy.check(&commit)? else {
+                continue;
+            };
+            let mut error = user_error(format!(
+                "Won't push commit {} since {}",
+                short_commit_hash(old_commit.id()),
+                problem.reason()
+            ));
+            error.add_formatted_hint_with(|formatter| {
+                write!(formatter, "Rejected commit: ")?;
+                tx.base_workspace_helper()
+                    .write_commit_summary(formatter, old_commit)?;
+                Ok(())
+            });
+            error.add_hint(format!(
+                "Configured signing.require: '{}'",
+                signature_policy.require
+            ));
+            return Err(error);
+        }
+    }
+
     let bookmark_updates = bookmark_updates
         .into_iter()
         .map(|(bookmark_name, update)| {
@@ -698,21 +765,17 @@ fn get_default_push_remote(
     workspace_command: &WorkspaceCommandHelper,
 ) -> Result<RemoteNameBuf, CommandError> {
     let settings = workspace_command.settings();
-    if let Some(remote) = settings.get_string("git.push").optional()? {
-        Ok(remote.into())
-    } else if let Some(remote) = get_single_remote(workspace_command.repo().store())? {
-        // similar to get_default_fetch_remotes
-        if remote != DEFAULT_REMOTE {
-            writeln!(
-                ui.hint_default(),
-                "Pushing to the only existing remote: {remote}",
-                remote = remote.as_symbol()
-            )?;
-        }
-        Ok(remote)
-    } else {
-        Ok(DEFAULT_REMOTE.to_owned())
+    let remote = default_push_remote_name(settings, workspace_command.repo().store())?
+        .unwrap_or_else(|| DEFAULT_REMOTE.to_owned());
+    // similar to get_default_fetch_remotes
+    if remote != DEFAULT_REMOTE && settings.get_string("git.push").optional()?.is_none() {
+        writeln!(
+            ui.hint_default(),
+            "Pushing to the only existing remote: {remote}",
+            remote = remote.as_symbol()
+        )?;
     }
+    Ok(remote)
 }
 
 #[derive(Clone, Debug)]
@@ -958,17 +1021,10 @@ fn find_bookmarks_targeted_by_revisions<'a>(
 ) -> Result<Vec<(&'a RefName, LocalAndRemoteRef<'a>)>, CommandError> {
     let mut revision_commit_ids = HashSet::new();
     if use_default_revset {
-        // remote_bookmarks(remote=<remote>)..@
-        let workspace_name = workspace_command.workspace_name();
-        let expression = RevsetExpression::remote_bookmarks(
-            RemoteRefSymbolExpression {
-                name: StringExpression::all(),
-                remote: StringExpression::exact(remote),
-            },
-            None,
-        )
-        .range(&RevsetExpression::working_copy(workspace_name.to_owned()))
-        .intersection(&RevsetExpression::bookmarks(StringExpression::all()));
+        let expression = RevsetExpression::default_push_heads(
+            StringExpression::exact(remote),
+            workspace_command.workspace_name().to_owned(),
+        );
         let mut commit_ids = workspace_command
             .attach_revset_evaluator(expression)
             .evaluate_to_commit_ids()?
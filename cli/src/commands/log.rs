@@ -12,32 +12,57 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
 use std::cmp::min;
-
+use std::collections::HashSet;
+use std::io::IsTerminal as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use clap::ValueEnum;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use crossterm::cursor::MoveTo;
+use crossterm::execute;
+use crossterm::terminal::Clear;
+use crossterm::terminal::ClearType;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::copies::trace_renames;
+use jj_lib::fileset::FilesetExpression;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphEdgeType;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::graph::reverse_graph;
+use jj_lib::op_store::OperationId;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::revset::RevsetIteratorExt as _;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher as _;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::format_template;
 use crate::command_error::CommandError;
+use crate::command_error::internal_error;
+use crate::command_error::print_error_sources;
+use crate::command_error::user_error;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
 use crate::graphlog::GraphStyle;
 use crate::graphlog::get_graphlog;
@@ -81,6 +106,14 @@ pub(crate) struct LogArgs {
     #[arg(add = ArgValueCompleter::new(complete::log_files))]
     paths: Vec<String>,
 
+    /// Follow the history of a file across renames and copies
+    ///
+    /// Requires a single file path. The file is followed back from the
+    /// working-copy revision through the files it was renamed or copied from,
+    /// as reported by the backend or detected per the `copies.detect` setting.
+    #[arg(long, requires = "paths")]
+    follow: bool,
+
     /// Limit number of revisions to show
     ///
     /// Applied after revisions are filtered and reordered topologically, but
@@ -92,6 +125,30 @@ pub(crate) struct LogArgs {
     #[arg(long)]
     reversed: bool,
 
+    /// Sort revisions based on the given key (or multiple keys)
+    ///
+    /// Suffix the key with `-` to sort in descending order of the value (e.g.
+    /// `--sort committer-date-`). Note that when using multiple keys, the first
+    /// key is the most significant. Revisions with equal keys are kept in
+    /// topological order. `--limit` is applied after sorting.
+    #[arg(
+        long,
+        value_name = "SORT_KEY",
+        value_enum,
+        value_delimiter = ',',
+        requires = "no_graph"
+    )]
+    sort: Vec<LogSortKey>,
+
+    /// Emit branches containing the given revisions first in the graph
+    ///
+    /// If specified multiple times, branches of the earlier revsets are
+    /// emitted first. This defaults to the `revsets.log-graph-prioritize`
+    /// setting.
+    #[arg(long, value_name = "REVSETS", conflicts_with = "no_graph")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    prioritize: Vec<RevisionArg>,
+
     /// Don't show the graph, show a flat list of revisions
     #[arg(long, short = 'G')]
     no_graph: bool,
@@ -123,10 +180,78 @@ pub(crate) struct LogArgs {
     #[arg(long, conflicts_with_all = ["DiffFormatArgs", "no_graph", "patch", "reversed", "template"])]
     count: bool,
 
+    /// Keep running and redraw the log when the repository changes
+    ///
+    /// The repository is watched for new operations and working-copy changes.
+    /// If the file system can't be watched, it's checked periodically instead.
+    /// Revisions that weren't shown before the last change are highlighted with
+    /// the `changed` label. Press Ctrl-C to exit.
+    #[arg(long, conflicts_with = "count")]
+    watch: bool,
+
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
 
+/// Sort key for the `jj log --sort` option.
+#[derive(Copy, Clone, PartialEq, Debug, ValueEnum)]
+enum LogSortKey {
+    ChangeId,
+    #[value(name = "change-id-")]
+    ChangeIdDesc,
+    AuthorName,
+    #[value(name = "author-name-")]
+    AuthorNameDesc,
+    AuthorEmail,
+    #[value(name = "author-email-")]
+    AuthorEmailDesc,
+    AuthorDate,
+    #[value(name = "author-date-")]
+    AuthorDateDesc,
+    CommitterName,
+    #[value(name = "committer-name-")]
+    CommitterNameDesc,
+    CommitterEmail,
+    #[value(name = "committer-email-")]
+    CommitterEmailDesc,
+    CommitterDate,
+    #[value(name = "committer-date-")]
+    CommitterDateDesc,
+}
+
+impl LogSortKey {
+    fn compare(self, a: &Commit, b: &Commit) -> Ordering {
+        match self {
+            Self::ChangeId => a.change_id().cmp(b.change_id()),
+            Self::ChangeIdDesc => b.change_id().cmp(a.change_id()),
+            Self::AuthorName => a.author().name.cmp(&b.author().name),
+            Self::AuthorNameDesc => b.author().name.cmp(&a.author().name),
+            Self::AuthorEmail => a.author().email.cmp(&b.author().email),
+            Self::AuthorEmailDesc => b.author().email.cmp(&a.author().email),
+            Self::AuthorDate => a.author().timestamp.cmp(&b.author().timestamp),
+            Self::AuthorDateDesc => b.author().timestamp.cmp(&a.author().timestamp),
+            Self::CommitterName => a.committer().name.cmp(&b.committer().name),
+            Self::CommitterNameDesc => b.committer().name.cmp(&a.committer().name),
+            Self::CommitterEmail => a.committer().email.cmp(&b.committer().email),
+            Self::CommitterEmailDesc => b.committer().email.cmp(&a.committer().email),
+            Self::CommitterDate => a.committer().timestamp.cmp(&b.committer().timestamp),
+            Self::CommitterDateDesc => b.committer().timestamp.cmp(&a.committer().timestamp),
+        }
+    }
+}
+
+/// Sorts `commits` by multiple `sort_keys`. The first key is most significant.
+fn sort_commits(commits: &mut [Commit], sort_keys: &[LogSortKey]) {
+    // Stable sort preserves the topological order of equal commits.
+    commits.sort_by(|a, b| {
+        sort_keys
+            .iter()
+            .map(|key| key.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_log(
     ui: &mut Ui,
@@ -134,22 +259,301 @@ pub(crate) fn cmd_log(
     args: &LogArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
+    if args.watch {
+        watch_log(ui, command, workspace_command, args)
+    } else {
+        show_log(ui, &workspace_command, args, None)
+    }
+}
+
+/// How often `jj log --watch` checks for changes if the file system can't be
+/// watched.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long `jj log --watch` waits for more file system events before
+/// redrawing, so a burst of changes results in a single redraw.
+const WATCH_DEBOUNCE_DELAY: Duration = Duration::from_millis(100);
+
+/// Maximum delay between retries after `jj log --watch` failed to update the
+/// log. The delay is doubled on each consecutive failure.
+const WATCH_MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// State carried over between redraws of `jj log --watch`.
+#[derive(Debug, Default)]
+struct LogWatchState {
+    /// Operation heads seen by the previous check.
+    op_heads: Option<Vec<OperationId>>,
+    /// Operation shown by the previous redraw.
+    shown_op_id: Option<OperationId>,
+    /// Revisions shown by the previous redraw, or `None` at first.
+    shown_ids: Option<HashSet<CommitId>>,
+    /// Revisions being shown by the current redraw.
+    new_shown_ids: HashSet<CommitId>,
+    /// Rendered output of the current redraw.
+    output: Vec<u8>,
+}
+
+impl LogWatchState {
+    fn add_shown(&mut self, id: &CommitId) -> bool {
+        self.new_shown_ids.insert(id.clone());
+        self.shown_ids
+            .as_ref()
+            .is_some_and(|shown_ids| !shown_ids.contains(id))
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        self.shown_ids = Some(std::mem::take(&mut self.new_shown_ids));
+        std::mem::take(&mut self.output)
+    }
+}
+
+fn watch_log(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    mut workspace_command: WorkspaceCommandHelper,
+    args: &LogArgs,
+) -> Result<(), CommandError> {
+    if !command.is_at_head_operation() {
+        return Err(user_error("--watch cannot be used with --at-op"));
+    }
+    let watcher = match LogWatcher::new(&workspace_command) {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            writeln!(
+                ui.warning_default(),
+                "Failed to watch the repository for changes. Checking periodically instead."
+            )?;
+            print_error_sources(ui, Some(&err))?;
+            None
+        }
+    };
+    let mut state = LogWatchState::default();
+    let mut last_error = None;
+    let mut retry_delay = None;
+    let mut snapshot = true;
+    loop {
+        match update_watched_log(ui, &mut workspace_command, args, &mut state, snapshot) {
+            Ok(()) => {
+                last_error = None;
+                retry_delay = None;
+            }
+            // The repo may be temporarily unavailable while another process is
+            // writing to it. Retry later, but don't repeat the same warning.
+            Err(err) => {
+                let message = err.error.to_string();
+                if last_error.as_ref() != Some(&message) {
+                    writeln!(ui.warning_default(), "Failed to update the log")?;
+                    print_error_sources(ui, Some(&*err.error))?;
+                    last_error = Some(message);
+                }
+                let delay = retry_delay.map_or(WATCH_POLL_INTERVAL, |delay: Duration| {
+                    min(delay * 2, WATCH_MAX_RETRY_DELAY)
+                });
+                retry_delay = Some(delay);
+            }
+        }
+        snapshot = match (&watcher, retry_delay) {
+            (_, Some(delay)) => {
+                thread::sleep(delay);
+                true
+            }
+            (Some(watcher), None) => watcher.wait_for_changes()?,
+            (None, None) => {
+                thread::sleep(WATCH_POLL_INTERVAL);
+                true
+            }
+        };
+    }
+}
+
+/// Watches the operation heads and the working copy of `jj log --watch`.
+struct LogWatcher {
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<notify::Result<notify::Event>>,
+    op_heads_dir: PathBuf,
+    jj_dir: PathBuf,
+}
+
+impl LogWatcher {
+    fn new(workspace_command: &WorkspaceCommandHelper) -> notify::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            sender.send(event).ok();
+        })?;
+        let op_heads_dir = workspace_command.repo_path().join("op_heads");
+        let workspace_root = workspace_command.workspace_root();
+        watcher.watch(&op_heads_dir, RecursiveMode::Recursive)?;
+        watcher.watch(workspace_root, RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+            op_heads_dir,
+            jj_dir: workspace_root.join(".jj"),
+        })
+    }
+
+    /// Blocks until the operation heads or the working copy change. Returns
+    /// true if the working copy may have changed.
+    fn wait_for_changes(&self) -> Result<bool, CommandError> {
+        let disconnected = || internal_error("File system watcher stopped unexpectedly");
+        let mut snapshot = false;
+        let mut changed = false;
+        let mut timeout = None;
+        loop {
+            let event = match timeout {
+                None => self.receiver.recv().map_err(|_| disconnected())?,
+                Some(timeout) => match self.receiver.recv_timeout(timeout) {
+                    Ok(event) => event,
+                    Err(mpsc::RecvTimeoutError::Timeout) => return Ok(snapshot),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return Err(disconnected()),
+                },
+            };
+            match event {
+                Ok(event) => {
+                    for path in &event.paths {
+                        if path.starts_with(&self.op_heads_dir) {
+                            changed = true;
+                        } else if !path.starts_with(&self.jj_dir) {
+                            // Changes to the internal state, such as the ones
+                            // made by our snapshot, are ignored.
+                            changed = true;
+                            snapshot = true;
+                        }
+                    }
+                }
+                // Events may have been lost, so check everything.
+                Err(_) => {
+                    changed = true;
+                    snapshot = true;
+                }
+            }
+            if changed {
+                timeout = Some(WATCH_DEBOUNCE_DELAY);
+            }
+        }
+    }
+}
+
+/// Reloads the repo if the operation heads have changed, and redraws the log
+/// if the repo is now at a different operation.
+fn update_watched_log(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    args: &LogArgs,
+    state: &mut LogWatchState,
+    snapshot: bool,
+) -> Result<(), CommandError> {
+    if snapshot {
+        // The working-copy snapshot is committed on top of the latest
+        // operation, so reload the repo first.
+        workspace_command.reload_repo_at_head(ui)?;
+        workspace_command.maybe_snapshot(ui)?;
+    }
+    let op_heads = workspace_command
+        .repo()
+        .loader()
+        .op_heads_store()
+        .get_op_heads()
+        .block_on()?;
+    if state.op_heads.as_ref() != Some(&op_heads) {
+        workspace_command.reload_repo_at_head(ui)?;
+        state.op_heads = Some(op_heads);
+    }
+    let op_id = workspace_command.repo().op_id();
+    if state.shown_op_id.as_ref() == Some(op_id) {
+        return Ok(());
+    }
+    show_log(ui, workspace_command, args, Some(state))?;
+    state.shown_op_id = Some(op_id.clone());
+    let output = state.finish();
+    let mut stdout = ui.stdout();
+    if std::io::stdout().is_terminal() {
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+    }
+    stdout.write_all(&output)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Traces the renames of the single file in `fileset_expression` back from the
+/// working-copy revision.
+fn trace_followed_file(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    fileset_expression: &FilesetExpression,
+) -> Result<Vec<(RepoPathBuf, CommitId)>, CommandError> {
+    let path = match fileset_expression {
+        FilesetExpression::Pattern(pattern) => pattern.as_path(),
+        _ => None,
+    }
+    .ok_or_else(|| user_error("--follow requires a single file path"))?;
+    let commit = workspace_command.resolve_single_rev(ui, &RevisionArg::AT)?;
+    let value = commit.tree().path_value(path)?;
+    if value.is_absent() || value.to_file_merge().is_none() {
+        let ui_path = workspace_command.format_file_path(path);
+        return Err(user_error(format!(
+            "--follow requires a file, but {ui_path} is not a file in the working copy"
+        )));
+    }
+    Ok(trace_renames(
+        workspace_command.repo().as_ref(),
+        commit.id(),
+        path,
+    )?)
+}
+
+/// Renders the log. If `watch` state is given, the output is captured in it
+/// instead of being printed.
+fn show_log(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    args: &LogArgs,
+    mut watch: Option<&mut LogWatchState>,
+) -> Result<(), CommandError> {
     let settings = workspace_command.settings();
 
-    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let mut fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let renames = if args.follow {
+        let renames = trace_followed_file(ui, workspace_command, &fileset_expression)?;
+        fileset_expression = FilesetExpression::union_all(
+            renames
+                .iter()
+                .map(|(path, _)| FilesetExpression::file_path(path.clone()))
+                .collect(),
+        );
+        Some(renames)
+    } else {
+        None
+    };
     let mut explicit_paths = fileset_expression.explicit_paths().collect_vec();
+    let default_revset_string = if args.revisions.is_empty() && args.paths.is_empty() {
+        Some(settings.get_string("revsets.log")?)
+    } else {
+        None
+    };
     let revset_expression = {
         // only use default revset if neither revset nor path are specified
-        let mut expression = if args.revisions.is_empty() && args.paths.is_empty() {
-            let revset_string = settings.get_string("revsets.log")?;
-            workspace_command.parse_revset(ui, &RevisionArg::from(revset_string))?
+        let mut expression = if let Some(revset_string) = &default_revset_string {
+            workspace_command.parse_revset(ui, &RevisionArg::from(revset_string.clone()))?
         } else if !args.revisions.is_empty() {
             workspace_command.parse_union_revsets(ui, &args.revisions)?
         } else {
             // a path was specified so we use all() and add path filter later
             workspace_command.attach_revset_evaluator(RevsetExpression::all())
         };
-        if !args.paths.is_empty() {
+        if let Some(renames) = &renames {
+            let expressions = renames
+                .iter()
+                .map(|(path, commit_id)| {
+                    let predicate =
+                        RevsetFilterPredicate::File(FilesetExpression::file_path(path.clone()));
+                    RevsetExpression::commit(commit_id.clone())
+                        .ancestors()
+                        .filtered(predicate)
+                })
+                .collect_vec();
+            expression.intersect_with(&RevsetExpression::union_all(&expressions));
+        } else if !args.paths.is_empty() {
             // Beware that args.paths = ["root:."] is not identical to []. The
             // former will filter out empty commits.
             let predicate = RevsetFilterPredicate::File(fileset_expression.clone());
@@ -158,7 +562,15 @@ pub(crate) fn cmd_log(
         expression
     };
 
-    let revset = revset_expression.evaluate()?;
+    let revset = if let Some(revset_string) = &default_revset_string
+        && args.limit.is_none()
+    {
+        // The default revset tends to be evaluated repeatedly at the same
+        // operation.
+        workspace_command.evaluate_cached_revset(ui, revset_string, &revset_expression)?
+    } else {
+        revset_expression.evaluate()?
+    };
 
     if args.count {
         let (lower, upper) = revset.count_estimate()?;
@@ -178,8 +590,15 @@ pub(crate) fn cmd_log(
         return Ok(());
     }
 
-    let prio_revset = settings.get_string("revsets.log-graph-prioritize")?;
-    let prio_revset = workspace_command.parse_revset(ui, &RevisionArg::from(prio_revset))?;
+    let prio_revsets: Vec<_> = if args.prioritize.is_empty() {
+        let prio_revset = settings.get_string("revsets.log-graph-prioritize")?;
+        vec![workspace_command.parse_revset(ui, &RevisionArg::from(prio_revset))?]
+    } else {
+        args.prioritize
+            .iter()
+            .map(|revision| workspace_command.parse_revset(ui, revision))
+            .try_collect()?
+    };
 
     let repo = workspace_command.repo();
     let matcher = fileset_expression.to_matcher();
@@ -207,9 +626,14 @@ pub(crate) fn cmd_log(
             .labeled(["log", "commit", "node"]);
     }
 
+    let mut watch_output = vec![];
     {
-        ui.request_pager();
-        let mut formatter = ui.stdout_formatter();
+        let mut formatter = if watch.is_some() {
+            ui.new_formatter(&mut watch_output)
+        } else {
+            ui.request_pager();
+            ui.stdout_formatter()
+        };
         let formatter = formatter.as_mut();
 
         if !args.no_graph {
@@ -220,10 +644,12 @@ pub(crate) fn cmd_log(
 
                 let has_commit = revset.containing_fn();
 
-                for prio in prio_revset.evaluate_to_commit_ids()? {
-                    let prio = prio?;
-                    if has_commit(&prio)? {
-                        forward_iter.prioritize_branch(prio);
+                for prio_revset in &prio_revsets {
+                    for prio in prio_revset.evaluate_to_commit_ids()? {
+                        let prio = prio?;
+                        if has_commit(&prio)? {
+                            forward_iter.prioritize_branch(prio);
+                        }
                     }
                 }
 
@@ -270,10 +696,11 @@ pub(crate) fn cmd_log(
                 let mut buffer = vec![];
                 let key = (commit_id, false);
                 let commit = store.get_commit(&key.0)?;
+                let changed = watch.as_mut().is_some_and(|state| state.add_shown(&key.0));
                 let within_graph =
                     with_content_format.sub_width(graph.width(&key, &graphlog_edges));
                 within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                    template.format(&commit, formatter)
+                    format_commit(&template, &commit, formatter, changed)
                 })?;
                 if let Some(renderer) = &diff_renderer {
                     let mut formatter = ui.new_formatter(&mut buffer);
@@ -321,19 +748,35 @@ pub(crate) fn cmd_log(
                 }
             }
         } else {
-            let iter: Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>>> = {
-                let forward_iter = revset.iter().take(args.limit.unwrap_or(usize::MAX));
-                if args.reversed {
-                    let entries: Vec<_> = forward_iter.try_collect()?;
-                    Box::new(entries.into_iter().rev().map(Ok))
+            let iter: Box<dyn Iterator<Item = Result<Commit, RevsetEvaluationError>>> =
+                if !args.sort.is_empty() {
+                    let mut commits: Vec<_> = revset.iter().commits(store).try_collect()?;
+                    sort_commits(&mut commits, &args.sort);
+                    commits.truncate(args.limit.unwrap_or(usize::MAX));
+                    if args.reversed {
+                        commits.reverse();
+                    }
+                    Box::new(commits.into_iter().map(Ok))
                 } else {
-                    Box::new(forward_iter)
-                }
-            };
-            for commit_or_error in iter.commits(store) {
+                    let forward_iter = revset
+                        .iter()
+                        .take(args.limit.unwrap_or(usize::MAX))
+                        .commits(store);
+                    if args.reversed {
+                        let entries: Vec<_> = forward_iter.try_collect()?;
+                        Box::new(entries.into_iter().rev().map(Ok))
+                    } else {
+                        Box::new(forward_iter)
+                    }
+                };
+            for commit_or_error in iter {
                 let commit = commit_or_error?;
-                with_content_format
-                    .write(formatter, |formatter| template.format(&commit, formatter))?;
+                let changed = watch
+                    .as_mut()
+                    .is_some_and(|state| state.add_shown(commit.id()));
+                with_content_format.write(formatter, |formatter| {
+                    format_commit(&template, &commit, formatter, changed)
+                })?;
                 if let Some(renderer) = &diff_renderer {
                     let width = ui.term_width();
                     renderer
@@ -359,6 +802,11 @@ pub(crate) fn cmd_log(
         }
     }
 
+    if let Some(state) = watch {
+        state.output = watch_output;
+        return Ok(());
+    }
+
     // Check to see if the user might have specified a path when they intended
     // to specify a revset.
     if let ([], [only_path]) = (args.revisions.as_slice(), args.paths.as_slice()) {
@@ -385,3 +833,16 @@ pub(crate) fn cmd_log(
 
     Ok(())
 }
+
+fn format_commit(
+    template: &TemplateRenderer<Commit>,
+    commit: &Commit,
+    formatter: &mut dyn Formatter,
+    changed: bool,
+) -> std::io::Result<()> {
+    if changed {
+        template.format(commit, &mut *formatter.labeled("changed"))
+    } else {
+        template.format(commit, formatter)
+    }
+}
@@ -19,7 +19,7 @@ use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::repo::Repo as _;
-use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::rewrite::merge_commit_trees_with_drivers;
 use jj_lib::rewrite::rebase_commit;
 use pollster::FutureExt as _;
 use tracing::instrument;
@@ -132,7 +132,7 @@ pub(crate) struct NewArgs {
     /// Example: `jj new --insert-after A --insert-before D`:
     ///
     /// ```text
-    /// 
+    ///
     ///     D            D
     ///     |           / \
     ///     C          |   C
@@ -201,7 +201,7 @@ pub(crate) fn cmd_new(
     let parent_commit_ids_set: HashSet<CommitId> = parent_commit_ids.iter().cloned().collect();
 
     let mut tx = workspace_command.start_transaction();
-    let merged_tree = merge_commit_trees(tx.repo(), &parent_commits).block_on()?;
+    let merged_tree = merge_commit_trees_with_drivers(tx.repo(), &parent_commits).block_on()?;
     let mut commit_builder = tx
         .repo_mut()
         .new_commit(parent_commit_ids, merged_tree)
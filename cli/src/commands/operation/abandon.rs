@@ -18,7 +18,9 @@ use std::slice;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::backend::SigningFn;
 use jj_lib::op_walk;
+use jj_lib::signing::SignError;
 use pollster::FutureExt as _;
 
 use crate::cli_util::CommandHelper;
@@ -105,12 +107,23 @@ pub fn cmd_op_abandon(
         return Err(err);
     }
 
-    // Reparent descendants, count the number of abandoned operations.
-    let stats = op_walk::reparent_range(
+    // Reparent descendants, count the number of abandoned operations. Signed
+    // operations are signed again if operations are to be signed.
+    let sign_settings = command.settings().sign_settings();
+    let signer = repo_loader.store().signer();
+    let mut sign_fn = |data: &[u8]| signer.sign(data, sign_settings.key.as_deref());
+    if sign_settings.sign_operations && !signer.can_sign() {
+        return Err(SignError::NoBackend.into());
+    }
+    let sign = sign_settings
+        .sign_operations
+        .then_some(&mut sign_fn as &mut SigningFn);
+    let stats = op_walk::reparent_range_with_signer(
         op_store.as_ref(),
         &abandon_head_ops,
         &current_head_ops,
         &abandon_root_op,
+        sign,
     )?;
     assert_eq!(
         current_head_ops.len(),
@@ -128,6 +141,17 @@ pub fn cmd_op_abandon(
         stats.unreachable_count,
         stats.rewritten_count,
     )?;
+    if stats.unsigned_count > 0 {
+        writeln!(
+            ui.warning_default(),
+            "Dropped the signatures of {} reparented operations.",
+            stats.unsigned_count,
+        )?;
+        writeln!(
+            ui.hint_default(),
+            "Set `signing.sign-operations = true` to sign reparented operations again."
+        )?;
+    }
     for (old, new_id) in reparented_head_ops().filter(|&(old, new_id)| old.id() != new_id) {
         op_heads_store
             .update_op_heads(slice::from_ref(old.id()), new_id)
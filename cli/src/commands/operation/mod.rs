@@ -19,6 +19,7 @@ mod log;
 mod restore;
 pub mod revert;
 mod show;
+mod verify;
 
 use abandon::OperationAbandonArgs;
 use abandon::cmd_op_abandon;
@@ -35,6 +36,8 @@ use revert::OperationRevertArgs;
 use revert::cmd_op_revert;
 use show::OperationShowArgs;
 use show::cmd_op_show;
+use verify::OperationVerifyArgs;
+use verify::cmd_op_verify;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -56,6 +59,7 @@ pub enum OperationCommand {
     Restore(OperationRestoreArgs),
     Revert(OperationRevertArgs),
     Show(OperationShowArgs),
+    Verify(OperationVerifyArgs),
     // TODO: Delete in jj 0.39.0+
     #[command(hide = true)]
     Undo(OperationRevertArgs),
@@ -74,6 +78,7 @@ pub fn cmd_operation(
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
         OperationCommand::Revert(args) => cmd_op_revert(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
+        OperationCommand::Verify(args) => cmd_op_verify(ui, command, args),
         OperationCommand::Undo(args) => {
             let cmd = renamed_cmd("op undo", "op revert", cmd_op_revert);
             cmd(ui, command, args)
@@ -0,0 +1,100 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::op_walk;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::short_operation_hash;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::formatter::FormatterExt as _;
+use crate::signing_util::SignaturePolicy;
+use crate::ui::Ui;
+
+/// Verify the signatures of operations
+///
+/// Walks the operation log from the current operation (or the one given by
+/// `--at-operation`) and reports every operation that isn't signed, or whose
+/// signature isn't good or was made with a key that isn't listed in
+/// `signing.allowed-keys`. Operations are signed when
+/// `signing.sign-operations` is enabled. The root operation is never signed
+/// and isn't checked.
+///
+/// Fails if any of the checked operations is not signed as required.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationVerifyArgs {
+    /// Only verify operations that are descendants of this operation
+    ///
+    /// The operation itself and its ancestors are not checked. Use this to
+    /// skip the history recorded before signing was enabled.
+    #[arg(long, value_name = "OPERATION")]
+    #[arg(add = ArgValueCandidates::new(complete::operations))]
+    since: Option<String>,
+}
+
+pub fn cmd_op_verify(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationVerifyArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let policy = SignaturePolicy::from_settings(workspace_command.settings())?;
+    let repo_loader = workspace_command.workspace().repo_loader();
+    let signer = repo_loader.store().signer();
+    let root_op_id = repo_loader.op_store().root_operation_id();
+    let current_op = workspace_command.repo().operation();
+    let since_ops = match &args.since {
+        Some(op_str) => vec![workspace_command.resolve_single_op(op_str)?],
+        None => vec![],
+    };
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let mut num_checked = 0;
+    let mut num_rejected = 0;
+    for op in op_walk::walk_ancestors_range(slice::from_ref(current_op), &since_ops) {
+        let op = op?;
+        if op.id() == root_op_id {
+            continue;
+        }
+        num_checked += 1;
+        if let Some(problem) = policy.check_operation(&op, signer)? {
+            num_rejected += 1;
+            write!(formatter.labeled("warning"), "{problem}")?;
+            writeln!(
+                formatter,
+                ": {} {}",
+                short_operation_hash(op.id()),
+                op.metadata().description.lines().next().unwrap_or_default()
+            )?;
+        }
+    }
+    drop(formatter);
+
+    if num_rejected > 0 {
+        return Err(user_error(format!(
+            "{num_rejected} of {num_checked} operations are not signed as required"
+        )));
+    }
+    writeln!(
+        ui.status(),
+        "All {num_checked} operations are signed as required"
+    )?;
+    Ok(())
+}
@@ -20,10 +20,12 @@ use clap_complete::ArgValueCompleter;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::evolution::find_latest_versions;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::revset::RevsetExpression;
+use jj_lib::rewrite::ComputedMoveCommits;
 use jj_lib::rewrite::EmptyBehavior;
 use jj_lib::rewrite::MoveCommitsLocation;
 use jj_lib::rewrite::MoveCommitsStats;
@@ -37,6 +39,7 @@ use tracing::instrument;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::cli_util::compute_commit_location;
 use crate::cli_util::print_updated_commits;
 use crate::cli_util::short_commit_hash;
@@ -59,7 +62,7 @@ use crate::ui::Ui;
 ///
 /// If no option is specified, it defaults to `-b @`.
 ///
-/// There are three different ways of specifying where the revisions should be
+/// There are four different ways of specifying where the revisions should be
 /// rebased to:
 ///
 /// * `--onto/-o` to rebase the revisions onto the specified targets
@@ -68,6 +71,8 @@ use crate::ui::Ui;
 /// * `--insert-before/-B` to rebase the revisions onto the specified targets'
 ///   parents and to rebase the targets and their descendants onto the rebased
 ///   revisions
+/// * `--onto-latest` to rebase the revisions onto the newest versions of their
+///   current parents
 ///
 /// See the sections below for details about the different ways of specifying
 /// which revisions to rebase where.
@@ -262,6 +267,21 @@ use crate::ui::Ui;
 /// |/          |/
 /// J           J
 /// ```
+///
+/// ### Following rewritten parents
+///
+/// With `--onto-latest`, each root of the selected revisions is rebased onto the
+/// newest visible versions of its current parents. A parent is superseded by
+/// its successors in the evolution history, by newer commits with the same
+/// change ID (such as a rewritten version fetched from a remote), and by the
+/// new target of a remote bookmark that used to point to it. For example, if
+/// `main@origin` pointed to J and has moved to O after fetching, `jj rebase -s
+/// K --onto-latest` would rebase K and its descendants onto O.
+///
+/// Commits that become empty are abandoned, as with `--skip-emptied`, which
+/// drops the local copies of changes that were squashed into the new parents.
+/// With `--branch/-b`, the roots are the mutable ancestors of the specified
+/// revisions. `--onto-latest` cannot be combined with `--revisions/-r`.
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 #[command(group(ArgGroup::new("to_rebase").args(&["branch", "source", "revisions"])))]
@@ -363,6 +383,17 @@ pub struct RebaseDestinationArgs {
     )]
     #[arg(add = ArgValueCompleter::new(complete::revset_expression_mutable))]
     insert_before: Option<Vec<RevisionArg>>,
+
+    /// Rebase onto the newest versions of the current parents
+    ///
+    /// Parents are followed through rewrites recorded in the evolution
+    /// history, commits fetched with the same change ID, and remote bookmarks
+    /// that have moved away from them. Newly emptied commits are abandoned.
+    #[arg(
+        long,
+        conflicts_with_all = ["onto", "insert_after", "insert_before", "revisions"]
+    )]
+    onto_latest: bool,
 }
 
 #[instrument(skip_all)]
@@ -372,7 +403,7 @@ pub(crate) fn cmd_rebase(
     args: &RebaseArgs,
 ) -> Result<(), CommandError> {
     let rebase_options = RebaseOptions {
-        empty: match args.skip_emptied {
+        empty: match args.skip_emptied || args.destination.onto_latest {
             true => EmptyBehavior::AbandonNewlyEmpty,
             false => EmptyBehavior::Keep,
         },
@@ -382,6 +413,26 @@ pub(crate) fn cmd_rebase(
         simplify_ancestor_merge: args.simplify_parents,
     };
     let mut workspace_command = command.workspace_helper(ui)?;
+    if args.destination.onto_latest {
+        let root_commit_ids: Vec<_> = if !args.source.is_empty() {
+            let source_commit_ids =
+                Vec::from_iter(workspace_command.resolve_some_revsets(ui, &args.source)?);
+            RevsetExpression::commits(source_commit_ids)
+                .roots()
+                .evaluate(workspace_command.repo().as_ref())?
+                .iter()
+                .try_collect()?
+        } else {
+            find_mutable_branch_roots(ui, &workspace_command, &args.branch)?
+        };
+        return rebase_onto_latest(
+            ui,
+            workspace_command,
+            root_commit_ids,
+            &rebase_options,
+            args.keep_divergent,
+        );
+    }
     let loc = if !args.revisions.is_empty() {
         plan_rebase_revisions(ui, &workspace_command, &args.revisions, &args.destination)?
     } else if !args.source.is_empty() {
@@ -393,25 +444,158 @@ pub(crate) fn cmd_rebase(
     let mut tx = workspace_command.start_transaction();
     let mut computed_move = compute_move_commits(tx.repo(), &loc)?;
     if !args.keep_divergent {
-        let abandoned_divergent =
-            find_duplicate_divergent_commits(tx.repo(), &loc.new_parent_ids, &loc.target)?;
-        computed_move.record_to_abandon(abandoned_divergent.iter().map(Commit::id).cloned());
-        if !abandoned_divergent.is_empty()
-            && let Some(mut formatter) = ui.status_formatter()
+        abandon_duplicate_divergent_commits(ui, &tx, &loc, &mut computed_move)?;
+    }
+    let stats = computed_move.apply(tx.repo_mut(), &rebase_options)?;
+    print_move_commits_stats(ui, &stats)?;
+    tx.finish(ui, tx_description(&loc.target))?;
+
+    Ok(())
+}
+
+fn abandon_duplicate_divergent_commits(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    loc: &MoveCommitsLocation,
+    computed_move: &mut ComputedMoveCommits,
+) -> Result<(), CommandError> {
+    let abandoned_divergent =
+        find_duplicate_divergent_commits(tx.repo(), &loc.new_parent_ids, &loc.target)?;
+    record_abandoned_divergent_commits(ui, tx, &abandoned_divergent, computed_move)
+}
+
+fn record_abandoned_divergent_commits(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    abandoned_divergent: &[Commit],
+    computed_move: &mut ComputedMoveCommits,
+) -> Result<(), CommandError> {
+    computed_move.record_to_abandon(abandoned_divergent.iter().map(Commit::id).cloned());
+    if !abandoned_divergent.is_empty()
+        && let Some(mut formatter) = ui.status_formatter()
+    {
+        writeln!(
+            formatter,
+            "Abandoned {} divergent commits that were already present in the destination:",
+            abandoned_divergent.len(),
+        )?;
+        print_updated_commits(
+            formatter.as_mut(),
+            &tx.base_workspace_helper().commit_summary_template(),
+            abandoned_divergent,
+        )?;
+    }
+    Ok(())
+}
+
+fn find_mutable_branch_roots(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    branch: &[RevisionArg],
+) -> Result<Vec<CommitId>, CommandError> {
+    let branch_expression = if branch.is_empty() {
+        workspace_command
+            .parse_revset(ui, &RevisionArg::AT)?
+            .expression()
+            .clone()
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, branch)?
+            .expression()
+            .clone()
+    };
+    let roots_expression = branch_expression
+        .ancestors()
+        .minus(&workspace_command.env().immutable_expression())
+        .roots();
+    let root_commit_ids: Vec<_> = workspace_command
+        .attach_revset_evaluator(roots_expression)
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    Ok(root_commit_ids)
+}
+
+/// Rebases each of the roots onto the newest versions of its parents.
+fn rebase_onto_latest(
+    ui: &mut Ui,
+    mut workspace_command: WorkspaceCommandHelper,
+    root_commit_ids: Vec<CommitId>,
+    rebase_options: &RebaseOptions,
+    keep_divergent: bool,
+) -> Result<(), CommandError> {
+    workspace_command.check_rewritable(&root_commit_ids)?;
+    let repo = workspace_command.repo().clone();
+    let root_commits: Vec<_> = root_commit_ids
+        .iter()
+        .map(|id| repo.store().get_commit(id))
+        .try_collect()?;
+    let parent_ids = root_commits
+        .iter()
+        .flat_map(|commit| commit.parent_ids())
+        .unique()
+        .cloned()
+        .collect_vec();
+    let excluded = RevsetExpression::commits(root_commit_ids).descendants();
+    let latest_versions = find_latest_versions(&repo, &parent_ids, &excluded)?;
+
+    // Roots sharing the same new parents are moved together.
+    let mut moves: Vec<(Vec<CommitId>, Vec<CommitId>)> = vec![];
+    for commit in &root_commits {
+        let new_parent_ids = commit
+            .parent_ids()
+            .iter()
+            .map(|id| latest_versions.get(id).unwrap_or(id))
+            .unique()
+            .cloned()
+            .collect_vec();
+        if new_parent_ids == commit.parent_ids() {
+            continue;
+        }
+        if let Some((_, ids)) = moves
+            .iter_mut()
+            .find(|(parent_ids, _)| *parent_ids == new_parent_ids)
         {
-            writeln!(
-                formatter,
-                "Abandoned {} divergent commits that were already present in the destination:",
-                abandoned_divergent.len(),
-            )?;
-            print_updated_commits(
-                formatter.as_mut(),
-                &tx.base_workspace_helper().commit_summary_template(),
-                &abandoned_divergent,
-            )?;
+            ids.push(commit.id().clone());
+        } else {
+            moves.push((new_parent_ids, vec![commit.id().clone()]));
         }
     }
-    let stats = computed_move.apply(tx.repo_mut(), &rebase_options)?;
+    if moves.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let moved_root_ids = moves
+        .iter()
+        .flat_map(|(_, root_ids)| root_ids)
+        .cloned()
+        .collect_vec();
+    let loc = MoveCommitsLocation {
+        // The parents are set per root below.
+        new_parent_ids: vec![],
+        new_child_ids: vec![],
+        target: MoveCommitsTarget::Roots(moved_root_ids),
+    };
+    let mut computed_move = compute_move_commits(tx.repo(), &loc)?;
+    for (new_parent_ids, root_ids) in &moves {
+        for root_id in root_ids {
+            computed_move.set_root_parent_ids(root_id, new_parent_ids.clone());
+        }
+    }
+    if !keep_divergent {
+        let mut abandoned_divergent = vec![];
+        for (new_parent_ids, root_ids) in &moves {
+            let target = MoveCommitsTarget::Roots(root_ids.clone());
+            abandoned_divergent.extend(find_duplicate_divergent_commits(
+                tx.repo(),
+                new_parent_ids,
+                &target,
+            )?);
+        }
+        record_abandoned_divergent_commits(ui, &tx, &abandoned_divergent, &mut computed_move)?;
+    }
+    let stats = computed_move.apply(tx.repo_mut(), rebase_options)?;
     print_move_commits_stats(ui, &stats)?;
     tx.finish(ui, tx_description(&loc.target))?;
 
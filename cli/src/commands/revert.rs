@@ -157,7 +157,7 @@ pub(crate) fn cmd_revert(
         let old_parents: Vec<_> = commit_to_revert.parents().try_collect()?;
         let old_base_tree = commit_to_revert.parent_tree(tx.repo())?;
         let old_tree = commit_to_revert.tree();
-        let new_tree = MergedTree::merge(Merge::from_vec(vec![
+        let new_tree = MergedTree::merge_with_drivers(Merge::from_vec(vec![
             (
                 new_base_tree,
                 format!("{parent_labels} (revert destination)"),
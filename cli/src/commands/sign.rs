@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use clap_complete::ArgValueCompleter;
 use indexmap::IndexSet;
 use itertools::Itertools as _;
@@ -23,16 +25,22 @@ use jj_lib::signing::SignBehavior;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::print_updated_commits;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::complete;
+use crate::formatter::FormatterExt as _;
+use crate::signing_util::SignaturePolicy;
 use crate::ui::Ui;
 
 /// Cryptographically sign a revision
 ///
 /// This command requires configuring a [commit signing] backend.
 ///
+/// With `--verify`, revisions are not signed, but checked against the
+/// `signing.allowed-keys` setting instead.
+///
 /// [commit signing]:
 ///     https://docs.jj-vcs.dev/latest/config/#commit-signing
 #[derive(clap::Args, Clone, Debug)]
@@ -57,11 +65,23 @@ pub struct SignArgs {
     /// The key used for signing
     #[arg(long)]
     key: Option<String>,
+
+    /// List the revisions that don't have a good signature from an allowed key
+    ///
+    /// Revisions are checked against the `signing.allowed-keys` setting. The
+    /// command fails if any of them isn't signed as required.
+    #[arg(long, value_name = "REVSETS", conflicts_with_all = ["revisions", "key"])]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    verify: Vec<RevisionArg>,
 }
 
 pub fn cmd_sign(ui: &mut Ui, command: &CommandHelper, args: &SignArgs) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
 
+    if !args.verify.is_empty() {
+        return verify_signatures(ui, &workspace_command, &args.verify);
+    }
+
     if !workspace_command.repo().store().signer().can_sign() {
         return Err(user_error(
             "No signing backend configured").hinted(
@@ -154,3 +174,43 @@ pub fn cmd_sign(ui: &mut Ui, command: &CommandHelper, args: &SignArgs) -> Result
 
     Ok(())
 }
+
+fn verify_signatures(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    revisions: &[RevisionArg],
+) -> Result<(), CommandError> {
+    let policy = SignaturePolicy::from_settings(workspace_command.settings())?;
+    let commits = workspace_command
+        .parse_union_revsets(ui, revisions)?
+        .evaluate_to_commits()?;
+    let template = workspace_command.commit_summary_template();
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let mut num_checked = 0;
+    let mut num_rejected = 0;
+    for commit in commits {
+        let commit = commit?;
+        num_checked += 1;
+        if let Some(problem) = policy.check(&commit)? {
+            num_rejected += 1;
+            write!(formatter.labeled("warning"), "{problem}")?;
+            write!(formatter, ": ")?;
+            template.format(&commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+    }
+    drop(formatter);
+
+    if num_rejected > 0 {
+        return Err(user_error(format!(
+            "{num_rejected} of {num_checked} commits are not signed as required"
+        )));
+    }
+    writeln!(
+        ui.status(),
+        "All {num_checked} commits are signed as required"
+    )?;
+    Ok(())
+}
@@ -18,6 +18,7 @@ use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
 use jj_lib::merge::Merge;
@@ -44,6 +45,7 @@ use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
 use crate::complete;
+use crate::description_util::TextEditor;
 use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
@@ -208,14 +210,7 @@ impl SplitArgs {
         workspace_command: &WorkspaceCommandHelper,
     ) -> Result<ResolvedSplitArgs, CommandError> {
         let target_commit = workspace_command.resolve_single_rev(ui, &self.revision)?;
-        if target_commit.is_empty(workspace_command.repo().as_ref())? {
-            return Err(user_error(format!(
-                "Refusing to split empty commit {}.",
-                target_commit.id().hex()
-            ))
-            .hinted("Use `jj new` if you want to create another empty commit."));
-        }
-        workspace_command.check_rewritable([target_commit.id()])?;
+        check_split_target(workspace_command, &target_commit)?;
         let repo = workspace_command.repo();
         let fileset_expression = workspace_command.parse_file_patterns(ui, &self.paths)?;
         let matcher = fileset_expression.to_matcher();
@@ -258,11 +253,13 @@ impl SplitArgs {
             use_move_flags,
             new_parent_ids,
             new_child_ids,
+            message_paragraphs: self.message_paragraphs.clone(),
+            editor: self.editor,
         })
     }
 }
 
-struct ResolvedSplitArgs {
+pub(crate) struct ResolvedSplitArgs {
     target_commit: Commit,
     matcher: Box<dyn Matcher>,
     diff_selector: DiffSelector,
@@ -270,6 +267,58 @@ struct ResolvedSplitArgs {
     use_move_flags: bool,
     new_parent_ids: Vec<CommitId>,
     new_child_ids: Vec<CommitId>,
+    message_paragraphs: Vec<String>,
+    editor: bool,
+}
+
+impl ResolvedSplitArgs {
+    /// Arguments to split the `target_commit` interactively, as `jj split -r`
+    /// does without other options.
+    pub(crate) fn interactive(
+        ui: &Ui,
+        workspace_command: &WorkspaceCommandHelper,
+        target_commit: Commit,
+    ) -> Result<Self, CommandError> {
+        check_split_target(workspace_command, &target_commit)?;
+        Ok(Self {
+            target_commit,
+            matcher: Box::new(EverythingMatcher),
+            diff_selector: workspace_command.diff_selector(ui, None, true)?,
+            parallel: false,
+            use_move_flags: false,
+            new_parent_ids: vec![],
+            new_child_ids: vec![],
+            message_paragraphs: vec![],
+            editor: false,
+        })
+    }
+}
+
+/// Result of [`split_commit()`].
+pub(crate) struct SplitCommits {
+    /// The commit that was split, and the selected changes.
+    pub target: CommitWithSelection,
+    /// The commit containing the selected changes.
+    pub first_commit: Commit,
+    /// The commit containing the remaining changes.
+    pub second_commit: Commit,
+    /// Number of rebased descendant commits.
+    pub num_rebased: usize,
+}
+
+fn check_split_target(
+    workspace_command: &WorkspaceCommandHelper,
+    target_commit: &Commit,
+) -> Result<(), CommandError> {
+    if target_commit.is_empty(workspace_command.repo().as_ref())? {
+        return Err(user_error(format!(
+            "Refusing to split empty commit {}.",
+            target_commit.id().hex()
+        ))
+        .hinted("Use `jj new` if you want to create another empty commit."));
+    }
+    workspace_command.check_rewritable([target_commit.id()])?;
+    Ok(())
 }
 
 #[instrument(skip_all)]
@@ -279,6 +328,37 @@ pub(crate) fn cmd_split(
     args: &SplitArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    let args = args.resolve(ui, &workspace_command)?;
+    let text_editor = workspace_command.text_editor()?;
+    let mut tx = workspace_command.start_transaction();
+    let SplitCommits {
+        target,
+        first_commit,
+        second_commit,
+        num_rebased,
+    } = split_commit(ui, &mut tx, &text_editor, args)?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+        write!(formatter, "Selected changes : ")?;
+        tx.write_commit_summary(formatter.as_mut(), &first_commit)?;
+        write!(formatter, "\nRemaining changes: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &second_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(ui, format!("split commit {}", target.commit.id().hex()))?;
+    Ok(())
+}
+
+/// Splits the commit in the transaction as specified by the `args`, prompting
+/// the user for the changes and the descriptions.
+pub(crate) fn split_commit(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    text_editor: &TextEditor,
+    args: ResolvedSplitArgs,
+) -> Result<SplitCommits, CommandError> {
     let ResolvedSplitArgs {
         target_commit,
         matcher,
@@ -287,12 +367,12 @@ pub(crate) fn cmd_split(
         use_move_flags,
         new_parent_ids,
         new_child_ids,
-    } = args.resolve(ui, &workspace_command)?;
-    let text_editor = workspace_command.text_editor()?;
-    let mut tx = workspace_command.start_transaction();
+        message_paragraphs,
+        editor,
+    } = args;
 
     // Prompt the user to select the changes they want for the first commit.
-    let target = select_diff(ui, &tx, &target_commit, &matcher, &diff_selector)?;
+    let target = select_diff(ui, tx, &target_commit, &matcher, &diff_selector)?;
 
     // Create the first commit, which includes the changes selected by the user.
     let first_commit = {
@@ -304,23 +384,23 @@ pub(crate) fn cmd_split(
             // become divergent.
             commit_builder.generate_new_change_id();
         }
-        let description = if args.message_paragraphs.is_empty() {
+        let description = if message_paragraphs.is_empty() {
             commit_builder.description().to_owned()
         } else {
-            join_message_paragraphs(&args.message_paragraphs)
+            join_message_paragraphs(&message_paragraphs)
         };
-        let description = if !description.is_empty() || args.editor {
+        let description = if !description.is_empty() || editor {
             commit_builder.set_description(description);
-            add_trailers(ui, &tx, &commit_builder)?
+            add_trailers(ui, tx, &commit_builder)?
         } else {
             description
         };
-        let description = if args.editor || args.message_paragraphs.is_empty() {
+        let description = if editor || message_paragraphs.is_empty() {
             commit_builder.set_description(description);
             let temp_commit = commit_builder.write_hidden()?;
             let intro = "Enter a description for the selected changes.";
-            let template = description_template(ui, &tx, intro, &temp_commit)?;
-            edit_description(&text_editor, &template)?
+            let template = description_template(ui, tx, intro, &temp_commit)?;
+            edit_description(text_editor, &template)?
         } else {
             description
         };
@@ -359,7 +439,7 @@ pub(crate) fn cmd_split(
         };
         let mut commit_builder = tx.repo_mut().rewrite_commit(&target.commit).detach();
         commit_builder.set_parents(parents).set_tree(new_tree);
-        let mut show_editor = args.editor;
+        let mut show_editor = editor;
         if !use_move_flags {
             commit_builder.clear_rewrite_source();
             // Generate a new change id so that the commit being split doesn't
@@ -371,17 +451,17 @@ pub(crate) fn cmd_split(
             // second commit.
             "".to_string()
         } else {
-            show_editor = show_editor || args.message_paragraphs.is_empty();
+            show_editor = show_editor || message_paragraphs.is_empty();
             // Just keep the original message unchanged
             commit_builder.description().to_owned()
         };
         let description = if show_editor {
-            let new_description = add_trailers(ui, &tx, &commit_builder)?;
+            let new_description = add_trailers(ui, tx, &commit_builder)?;
             commit_builder.set_description(new_description);
             let temp_commit = commit_builder.write_hidden()?;
             let intro = "Enter a description for the remaining changes.";
-            let template = description_template(ui, &tx, intro, &temp_commit)?;
-            edit_description(&text_editor, &template)?
+            let template = description_template(ui, tx, intro, &temp_commit)?;
+            edit_description(text_editor, &template)?
         } else {
             description
         };
@@ -391,7 +471,7 @@ pub(crate) fn cmd_split(
 
     let (first_commit, second_commit, num_rebased) = if use_move_flags {
         move_first_commit(
-            &mut tx,
+            tx,
             &target,
             first_commit,
             second_commit,
@@ -399,20 +479,14 @@ pub(crate) fn cmd_split(
             new_child_ids,
         )?
     } else {
-        rewrite_descendants(&mut tx, &target, first_commit, second_commit, parallel)?
+        rewrite_descendants(tx, &target, first_commit, second_commit, parallel)?
     };
-    if let Some(mut formatter) = ui.status_formatter() {
-        if num_rebased > 0 {
-            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
-        }
-        write!(formatter, "Selected changes : ")?;
-        tx.write_commit_summary(formatter.as_mut(), &first_commit)?;
-        write!(formatter, "\nRemaining changes: ")?;
-        tx.write_commit_summary(formatter.as_mut(), &second_commit)?;
-        writeln!(formatter)?;
-    }
-    tx.finish(ui, format!("split commit {}", target.commit.id().hex()))?;
-    Ok(())
+    Ok(SplitCommits {
+        target,
+        first_commit,
+        second_commit,
+        num_rebased,
+    })
 }
 
 fn move_first_commit(
@@ -27,7 +27,7 @@ use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo as _;
 use jj_lib::rewrite;
 use jj_lib::rewrite::CommitWithSelection;
-use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::rewrite::merge_commit_trees_with_drivers;
 use pollster::FutureExt as _;
 use tracing::instrument;
 
@@ -266,7 +266,7 @@ pub(crate) fn cmd_squash(
                     .get_commit(commit_id)
             })
             .try_collect()?;
-        let merged_tree = merge_commit_trees(tx.repo(), &parent_commits).block_on()?;
+        let merged_tree = merge_commit_trees_with_drivers(tx.repo(), &parent_commits).block_on()?;
         let commit = tx
             .repo_mut()
             .new_commit(parent_ids.clone(), merged_tree)
@@ -0,0 +1,897 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io;
+use std::io::IsTerminal as _;
+use std::io::Write;
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use clap_complete::ArgValueCompleter;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use crossterm::execute;
+use crossterm::terminal::EnterAlternateScreen;
+use crossterm::terminal::enable_raw_mode;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::graph::GraphEdge;
+use jj_lib::graph::GraphEdgeType;
+use jj_lib::graph::TopoGroupedGraphIterator;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::Revset;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::UserRevsetExpression;
+use jj_lib::rewrite::CommitWithSelection;
+use jj_lib::rewrite::MoveCommitsLocation;
+use jj_lib::rewrite::MoveCommitsTarget;
+use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::move_commits;
+use jj_lib::rewrite::squash_commits;
+use jj_lib::store::Store;
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use tracing::instrument;
+
+use super::split::ResolvedSplitArgs;
+use super::split::split_commit;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::cli_util::short_change_hash;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::description_util::add_trailers;
+use crate::description_util::combine_messages_for_editing;
+use crate::description_util::description_template;
+use crate::description_util::edit_description;
+use crate::description_util::try_combine_messages;
+use crate::graphlog::GraphStyle;
+use crate::graphlog::get_graphlog;
+use crate::templater::TemplateRenderer;
+use crate::ui::Ui;
+
+/// Browse and rewrite the commit graph interactively
+///
+/// Opens a full-screen view of the graph shown by `jj log`. Move the cursor
+/// with the arrow keys (or `j` and `k`), and press one of the following keys
+/// to rewrite the revision under the cursor:
+///
+/// * `r`: Rebase the revision and its descendants. Move the cursor to the
+///   destination and press `Enter`.
+/// * `s`: Squash the revision into its parent.
+/// * `S`: Split the revision interactively, like `jj split` does.
+/// * `d`: Edit the description in the text editor.
+/// * `a`: Abandon the revision.
+/// * `e`: Edit the revision in the working copy.
+/// * `n`: Create a new revision on top of the revision.
+///
+/// The resulting graph is shown before the operation is recorded. Press
+/// `Enter` to apply the change, or `Esc` to discard it. Press `q` to quit.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct UiArgs {
+    /// Which revisions to show
+    ///
+    /// If not specified, this defaults to the `revsets.log` setting.
+    #[arg(long, short, value_name = "REVSETS")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revisions: Vec<RevisionArg>,
+
+    /// Process the given space-separated keys instead of reading from the
+    /// terminal, and print the last screen
+    #[arg(long, hide = true, value_name = "KEYS")]
+    debug_keys: Option<String>,
+}
+
+/// Number of lines of the screen printed by `--debug-keys`.
+const DEBUG_SCREEN_HEIGHT: u16 = 16;
+
+const NORMAL_HELP: &str =
+    "j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit";
+const REBASE_HELP: &str = "j/k:move Enter:select destination Esc:cancel";
+const PREVIEW_HELP: &str = "j/k:scroll Enter:apply Esc:discard";
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_ui(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UiArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let expression = if args.revisions.is_empty() {
+        let revset_string = workspace_command.settings().get_string("revsets.log")?;
+        workspace_command.parse_revset(ui, &RevisionArg::from(revset_string))?
+    } else {
+        workspace_command.parse_union_revsets(ui, &args.revisions)?
+    }
+    .expression()
+    .clone();
+    let templates = LogTemplates::new(ui, &workspace_command)?;
+
+    if let Some(keys) = &args.debug_keys {
+        let keys = parse_debug_keys(keys)?;
+        let width = u16::try_from(ui.term_width()).unwrap_or(u16::MAX);
+        let Ok(terminal) = Terminal::new(TestBackend::new(width, DEBUG_SCREEN_HEIGHT));
+        let mut screen = ScriptedScreen {
+            terminal,
+            keys: keys.into_iter(),
+        };
+        let mut status_output = vec![];
+        run_ui(
+            ui,
+            &mut workspace_command,
+            &expression,
+            &templates,
+            &mut screen,
+            &mut status_output,
+        )?;
+        let buffer = screen.terminal.backend().buffer();
+        let mut stdout = ui.stdout();
+        for y in 0..buffer.area.height {
+            let line: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            writeln!(stdout, "{}", line.trim_end())?;
+        }
+        ui.stderr().write_all(&status_output)?;
+        return Ok(());
+    }
+
+    if !io::stdout().is_terminal() || !io::stdin().is_terminal() {
+        return Err(user_error("`jj ui` must be run in a terminal"));
+    }
+    let mut screen = TerminalScreen {
+        terminal: ratatui::try_init()?,
+    };
+    let mut status_output = vec![];
+    let result = run_ui(
+        ui,
+        &mut workspace_command,
+        &expression,
+        &templates,
+        &mut screen,
+        &mut status_output,
+    );
+    ratatui::try_restore()?;
+    // The status output of the applied changes would be lost in the alternate
+    // screen, so it's printed once the interface is closed.
+    ui.stderr().write_all(&status_output)?;
+    result
+}
+
+/// Template texts used to render the graph.
+struct LogTemplates {
+    template: String,
+    node_template: String,
+    graph_style: GraphStyle,
+}
+
+impl LogTemplates {
+    fn new(ui: &Ui, workspace_command: &WorkspaceCommandHelper) -> Result<Self, CommandError> {
+        let settings = workspace_command.settings();
+        let templates = Self {
+            template: settings.get_string("templates.log")?,
+            node_template: settings.get_string("templates.log_node")?,
+            graph_style: GraphStyle::from_settings(settings)?,
+        };
+        // Report errors early. The templates will be parsed again for each
+        // repo state.
+        let language = workspace_command.commit_template_language();
+        workspace_command.parse_template::<Commit, _>(ui, &language, &templates.template)?;
+        workspace_command.parse_template::<Option<Commit>, _>(
+            ui,
+            &language,
+            &templates.node_template,
+        )?;
+        Ok(templates)
+    }
+}
+
+/// Terminal the interface is drawn on.
+trait Screen {
+    fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<()>;
+
+    /// Waits for the next key. Returns `None` if no more keys are available.
+    fn next_key(&mut self) -> io::Result<Option<KeyEvent>>;
+
+    /// Restores the terminal to run external programs such as editors.
+    fn suspend(&mut self) -> io::Result<()>;
+
+    fn resume(&mut self) -> io::Result<()>;
+}
+
+struct TerminalScreen {
+    terminal: DefaultTerminal,
+}
+
+impl Screen for TerminalScreen {
+    fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<()> {
+        self.terminal.draw(render)?;
+        Ok(())
+    }
+
+    fn next_key(&mut self) -> io::Result<Option<KeyEvent>> {
+        loop {
+            match crossterm::event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => return Ok(Some(key)),
+                // Redraw by sending no-op key.
+                Event::Resize(..) => return Ok(Some(KeyEvent::from(KeyCode::Null))),
+                _ => {}
+            }
+        }
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        ratatui::try_restore()
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        self.terminal.clear()
+    }
+}
+
+struct ScriptedScreen {
+    terminal: Terminal<TestBackend>,
+    keys: std::vec::IntoIter<KeyEvent>,
+}
+
+impl Screen for ScriptedScreen {
+    fn draw(&mut self, render: &mut dyn FnMut(&mut Frame)) -> io::Result<()> {
+        let Ok(_) = self.terminal.draw(render);
+        Ok(())
+    }
+
+    fn next_key(&mut self) -> io::Result<Option<KeyEvent>> {
+        Ok(self.keys.next())
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn parse_debug_keys(text: &str) -> Result<Vec<KeyEvent>, CommandError> {
+    text.split_whitespace()
+        .map(|name| {
+            let code = match name {
+                "Enter" => KeyCode::Enter,
+                "Esc" => KeyCode::Esc,
+                "Up" => KeyCode::Up,
+                "Down" => KeyCode::Down,
+                "PageUp" => KeyCode::PageUp,
+                "PageDown" => KeyCode::PageDown,
+                "Home" => KeyCode::Home,
+                "End" => KeyCode::End,
+                _ => match name.chars().collect_vec()[..] {
+                    [c] => KeyCode::Char(c),
+                    _ => return Err(user_error(format!("Invalid key: {name}"))),
+                },
+            };
+            Ok(KeyEvent::from(code))
+        })
+        .try_collect()
+}
+
+/// Commit graph rendered as lines of text.
+#[derive(Debug, Default)]
+struct GraphView {
+    lines: Vec<String>,
+    nodes: Vec<GraphNode>,
+    /// Index of the node each line belongs to.
+    line_nodes: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct GraphNode {
+    commit: Commit,
+    lines: Range<usize>,
+}
+
+impl GraphView {
+    fn node_index_by_change_id(&self, commit: &Commit) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.commit.change_id() == commit.change_id())
+    }
+}
+
+/// `Write` that can be inspected while being borrowed by the graph renderer.
+#[derive(Clone, Debug, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn render_graph(
+    store: &Arc<Store>,
+    revset: &dyn Revset,
+    template: &TemplateRenderer<Commit>,
+    node_template: &TemplateRenderer<Option<Commit>>,
+    graph_style: GraphStyle,
+) -> Result<GraphView, CommandError> {
+    let buffer = SharedBuffer::default();
+    let mut writer = buffer.clone();
+    let mut graph = get_graphlog(graph_style, &mut writer);
+    let mut nodes = vec![];
+    let mut line_nodes = vec![];
+    let mut num_bytes = 0;
+    for node in TopoGroupedGraphIterator::new(revset.iter_graph(), |id| id) {
+        let (commit_id, edges) = node?;
+        let mut graph_edges = edges
+            .iter()
+            .filter_map(|edge| match edge.edge_type {
+                GraphEdgeType::Missing => None,
+                GraphEdgeType::Direct => Some(GraphEdge::direct(edge.target.clone())),
+                GraphEdgeType::Indirect => Some(GraphEdge::indirect(edge.target.clone())),
+            })
+            .collect_vec();
+        if let Some(edge) = edges
+            .iter()
+            .find(|edge| edge.edge_type == GraphEdgeType::Missing)
+        {
+            graph_edges.push(GraphEdge::missing(edge.target.clone()));
+        }
+        let commit = store.get_commit(&commit_id)?;
+        let text = String::from_utf8_lossy(&template.format_plain_text(&commit)).into_owned();
+        let node_symbol =
+            String::from_utf8_lossy(&node_template.format_plain_text(&Some(commit.clone())))
+                .into_owned();
+        graph.add_node(&commit_id, &graph_edges, &node_symbol, &text)?;
+
+        let new_num_bytes = buffer.0.borrow().len();
+        let num_lines = buffer.0.borrow()[num_bytes..]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        num_bytes = new_num_bytes;
+        let start = line_nodes.len();
+        line_nodes.extend(itertools::repeat_n(nodes.len(), num_lines));
+        nodes.push(GraphNode {
+            commit,
+            lines: start..line_nodes.len(),
+        });
+    }
+    drop(graph);
+    let lines = String::from_utf8_lossy(&buffer.0.borrow())
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect();
+    Ok(GraphView {
+        lines,
+        nodes,
+        line_nodes,
+    })
+}
+
+fn render_base_graph(
+    workspace_command: &WorkspaceCommandHelper,
+    expression: &Arc<UserRevsetExpression>,
+    templates: &LogTemplates,
+) -> Result<GraphView, CommandError> {
+    let ui = Ui::null();
+    let revset = workspace_command
+        .attach_revset_evaluator(expression.clone())
+        .evaluate()?;
+    let language = workspace_command.commit_template_language();
+    let template = workspace_command.parse_template(&ui, &language, &templates.template)?;
+    let node_template =
+        workspace_command.parse_template(&ui, &language, &templates.node_template)?;
+    render_graph(
+        workspace_command.repo().store(),
+        revset.as_ref(),
+        &template,
+        &node_template,
+        templates.graph_style,
+    )
+}
+
+fn render_transaction_graph(
+    tx: &WorkspaceCommandTransaction,
+    expression: &Arc<UserRevsetExpression>,
+    templates: &LogTemplates,
+) -> Result<GraphView, CommandError> {
+    let ui = Ui::null();
+    let revset = tx.attach_revset_evaluator(expression.clone()).evaluate()?;
+    let helper = tx.base_workspace_helper();
+    let language = tx.commit_template_language();
+    let template = helper.parse_template(&ui, &language, &templates.template)?;
+    let node_template = helper.parse_template(&ui, &language, &templates.node_template)?;
+    render_graph(
+        tx.repo().store(),
+        revset.as_ref(),
+        &template,
+        &node_template,
+        templates.graph_style,
+    )
+}
+
+/// Draws the `graph` and the status lines. Adjusts `scroll` to make the
+/// `cursor` node visible.
+#[expect(clippy::too_many_arguments)]
+fn draw_graph(
+    frame: &mut Frame,
+    graph: &GraphView,
+    cursor: Option<usize>,
+    marked: Option<usize>,
+    changed: &HashSet<usize>,
+    scroll: &mut usize,
+    message: &str,
+    help: &str,
+) {
+    let [graph_area, message_area, help_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let height = usize::from(graph_area.height);
+    if let Some(cursor) = cursor {
+        let lines = &graph.nodes[cursor].lines;
+        if lines.end > *scroll + height {
+            *scroll = lines.end.saturating_sub(height);
+        }
+        if lines.start < *scroll {
+            *scroll = lines.start;
+        }
+    }
+    *scroll = (*scroll).min(graph.lines.len().saturating_sub(height));
+
+    let lines = graph
+        .lines
+        .iter()
+        .zip(&graph.line_nodes)
+        .skip(*scroll)
+        .take(height)
+        .map(|(text, &node)| {
+            let mut style = Style::new();
+            if changed.contains(&node) {
+                style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+            }
+            if marked == Some(node) {
+                style = style.fg(Color::Cyan).add_modifier(Modifier::BOLD);
+            }
+            if cursor == Some(node) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Line::styled(text.as_str(), style)
+        })
+        .collect_vec();
+    frame.render_widget(Paragraph::new(lines), graph_area);
+    frame.render_widget(Line::raw(message), message_area);
+    frame.render_widget(
+        Line::styled(help, Style::new().add_modifier(Modifier::DIM)),
+        help_area,
+    );
+}
+
+/// Rewrite of the graph to be previewed.
+#[derive(Clone, Debug)]
+enum Action {
+    Rebase { source: Commit, destination: Commit },
+    Squash(Commit),
+    Split(Commit),
+    Describe(Commit),
+    Abandon(Commit),
+    Edit(Commit),
+    New(Commit),
+}
+
+impl Action {
+    fn summary(&self) -> String {
+        let short = |commit: &Commit| short_change_hash(commit.change_id());
+        match self {
+            Self::Rebase {
+                source,
+                destination,
+            } => format!(
+                "rebase {} and descendants onto {}",
+                short(source),
+                short(destination)
+            ),
+            Self::Squash(commit) => format!("squash {} into its parent", short(commit)),
+            Self::Split(commit) => format!("split {}", short(commit)),
+            Self::Describe(commit) => format!("describe {}", short(commit)),
+            Self::Abandon(commit) => format!("abandon {}", short(commit)),
+            Self::Edit(commit) => format!("edit {}", short(commit)),
+            Self::New(commit) => format!("new revision on top of {}", short(commit)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Mode {
+    Normal,
+    /// Selecting the destination to rebase the marked revision onto.
+    Rebase {
+        source: Commit,
+    },
+}
+
+/// Runs the interface until the user quits. The status output of the applied
+/// changes is appended to `status_output`.
+fn run_ui(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    expression: &Arc<UserRevsetExpression>,
+    templates: &LogTemplates,
+    screen: &mut dyn Screen,
+    status_output: &mut Vec<u8>,
+) -> Result<(), CommandError> {
+    let mut graph = render_base_graph(workspace_command, expression, templates)?;
+    let mut cursor = workspace_command
+        .get_wc_commit_id()
+        .and_then(|id| graph.nodes.iter().position(|node| node.commit.id() == id))
+        .unwrap_or(0);
+    let mut scroll = 0;
+    let mut mode = Mode::Normal;
+    let mut message = String::new();
+    loop {
+        if graph.nodes.is_empty() {
+            return Err(user_error("No revisions to show"));
+        }
+        let (marked, help) = match &mode {
+            Mode::Normal => (None, NORMAL_HELP),
+            Mode::Rebase { source } => (graph.node_index_by_change_id(source), REBASE_HELP),
+        };
+        screen.draw(&mut |frame| {
+            draw_graph(
+                frame,
+                &graph,
+                Some(cursor),
+                marked,
+                &HashSet::new(),
+                &mut scroll,
+                &message,
+                help,
+            );
+        })?;
+        let Some(key) = screen.next_key()? else {
+            return Ok(());
+        };
+        let last = graph.nodes.len() - 1;
+        let current = graph.nodes[cursor].commit.clone();
+        let action = match (&mode, key.code) {
+            (_, KeyCode::Char('c')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(());
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) => {
+                cursor = (cursor + 1).min(last);
+                None
+            }
+            (_, KeyCode::Char('k') | KeyCode::Up) => {
+                cursor = cursor.saturating_sub(1);
+                None
+            }
+            (_, KeyCode::PageDown) => {
+                cursor = (cursor + 10).min(last);
+                None
+            }
+            (_, KeyCode::PageUp) => {
+                cursor = cursor.saturating_sub(10);
+                None
+            }
+            (_, KeyCode::Char('g') | KeyCode::Home) => {
+                cursor = 0;
+                None
+            }
+            (_, KeyCode::Char('G') | KeyCode::End) => {
+                cursor = last;
+                None
+            }
+            (Mode::Normal, KeyCode::Char('q') | KeyCode::Esc) => return Ok(()),
+            (Mode::Normal, KeyCode::Char('r')) => {
+                message = format!(
+                    "Select destination to rebase {} onto",
+                    short_change_hash(current.change_id())
+                );
+                mode = Mode::Rebase { source: current };
+                None
+            }
+            (Mode::Normal, KeyCode::Char('s')) => Some(Action::Squash(current)),
+            (Mode::Normal, KeyCode::Char('S')) => Some(Action::Split(current)),
+            (Mode::Normal, KeyCode::Char('d')) => Some(Action::Describe(current)),
+            (Mode::Normal, KeyCode::Char('a')) => Some(Action::Abandon(current)),
+            (Mode::Normal, KeyCode::Char('e')) => Some(Action::Edit(current)),
+            (Mode::Normal, KeyCode::Char('n')) => Some(Action::New(current)),
+            (Mode::Rebase { .. }, KeyCode::Esc) => {
+                mode = Mode::Normal;
+                message.clear();
+                None
+            }
+            (Mode::Rebase { source }, KeyCode::Enter) => Some(Action::Rebase {
+                source: source.clone(),
+                destination: current,
+            }),
+            _ => None,
+        };
+        let Some(action) = action else {
+            continue;
+        };
+
+        mode = Mode::Normal;
+        message = match preview_action(
+            ui,
+            workspace_command,
+            expression,
+            templates,
+            screen,
+            &graph,
+            &action,
+            status_output,
+        ) {
+            Ok(Some(message)) => message,
+            // No more keys to process
+            Ok(None) => return Ok(()),
+            Err(err) => format!("Error: {}", err.error),
+        };
+        // The repo may be updated by the action, or by another process.
+        let cursor_commit = graph.nodes[cursor].commit.clone();
+        let status_ui = ui.captured();
+        workspace_command.reload_repo_at_head(&status_ui)?;
+        status_output.extend(status_ui.take_captured_output());
+        graph = render_base_graph(workspace_command, expression, templates)?;
+        cursor = graph
+            .node_index_by_change_id(&cursor_commit)
+            .unwrap_or(cursor)
+            .min(graph.nodes.len().saturating_sub(1));
+    }
+}
+
+/// Applies the `action` in a transaction, and lets the user decide whether to
+/// commit the transaction by showing the resulting graph. Returns a status
+/// message, or `None` if the input ended. The status output of the commands is
+/// appended to `status_output`.
+#[expect(clippy::too_many_arguments)]
+fn preview_action(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    expression: &Arc<UserRevsetExpression>,
+    templates: &LogTemplates,
+    screen: &mut dyn Screen,
+    base_graph: &GraphView,
+    action: &Action,
+    status_output: &mut Vec<u8>,
+) -> Result<Option<String>, CommandError> {
+    let status_ui = ui.captured();
+    // Don't lose working-copy changes made while the interface is open.
+    workspace_command.maybe_snapshot(&status_ui)?;
+    status_output.extend(status_ui.take_captured_output());
+    let summary = action.summary();
+    let Some((tx, tx_description)) = apply_action(ui, workspace_command, screen, action)? else {
+        return Ok(Some(format!("Nothing changed: {summary}")));
+    };
+    let graph = render_transaction_graph(&tx, expression, templates)?;
+
+    let base_ids: HashSet<_> = base_graph
+        .nodes
+        .iter()
+        .map(|node| node.commit.id())
+        .collect();
+    let changed = graph
+        .nodes
+        .iter()
+        .positions(|node| !base_ids.contains(node.commit.id()))
+        .collect();
+    let message = format!("Preview: {summary}");
+    let mut scroll = 0;
+    loop {
+        screen.draw(&mut |frame| {
+            draw_graph(
+                frame,
+                &graph,
+                None,
+                None,
+                &changed,
+                &mut scroll,
+                &message,
+                PREVIEW_HELP,
+            );
+        })?;
+        let Some(key) = screen.next_key()? else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') => break,
+            KeyCode::Esc | KeyCode::Char('n' | 'q') => {
+                return Ok(Some(format!("Discarded: {summary}")));
+            }
+            KeyCode::Char('j') | KeyCode::Down => scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => scroll = scroll.saturating_sub(1),
+            _ => {}
+        }
+    }
+    tx.finish(&status_ui, tx_description)?;
+    let output = status_ui.take_captured_output();
+    // Show the first line of the output, such as the new working-copy commit,
+    // in the message line.
+    let message = match String::from_utf8_lossy(&output).lines().next() {
+        Some(line) => format!("Applied: {summary}. {line}"),
+        None => format!("Applied: {summary}"),
+    };
+    status_output.extend(output);
+    Ok(Some(message))
+}
+
+/// Rewrites commits in a new transaction. Returns the transaction and its
+/// description, or `None` if nothing changed.
+fn apply_action<'a>(
+    ui: &Ui,
+    workspace_command: &'a mut WorkspaceCommandHelper,
+    screen: &mut dyn Screen,
+    action: &Action,
+) -> Result<Option<(WorkspaceCommandTransaction<'a>, String)>, CommandError> {
+    match action {
+        Action::Rebase {
+            source,
+            destination,
+        } => {
+            workspace_command.check_rewritable_expr(
+                &RevsetExpression::commit(source.id().clone()).descendants(),
+            )?;
+            let repo = workspace_command.repo();
+            if repo.index().is_ancestor(source.id(), destination.id())? {
+                return Err(user_error(format!(
+                    "Cannot rebase {} onto its descendant {}",
+                    short_change_hash(source.change_id()),
+                    short_change_hash(destination.change_id())
+                )));
+            }
+            let mut tx = workspace_command.start_transaction();
+            let loc = MoveCommitsLocation {
+                new_parent_ids: vec![destination.id().clone()],
+                new_child_ids: vec![],
+                target: MoveCommitsTarget::Roots(vec![source.id().clone()]),
+            };
+            move_commits(tx.repo_mut(), &loc, &RebaseOptions::default())?;
+            let description = format!("rebase commit {} and descendants", source.id().hex());
+            Ok(Some((tx, description)))
+        }
+        Action::Squash(commit) => {
+            let [parent_id] = commit.parent_ids() else {
+                return Err(user_error("Cannot squash a merge commit"));
+            };
+            workspace_command.check_rewritable([commit.id(), parent_id])?;
+            let text_editor = workspace_command.text_editor()?;
+            let repo = workspace_command.repo().clone();
+            let parent = repo.store().get_commit(parent_id)?;
+            let mut tx = workspace_command.start_transaction();
+            let source = CommitWithSelection {
+                commit: commit.clone(),
+                selected_tree: commit.tree(),
+                parent_tree: commit.parent_tree(repo.as_ref())?,
+            };
+            let Some(squashed) = squash_commits(tx.repo_mut(), &[source], &parent, false)? else {
+                return Ok(None);
+            };
+            let abandoned_commits = squashed.abandoned_commits;
+            let mut commit_builder = squashed.commit_builder.detach();
+            let description = match try_combine_messages(&abandoned_commits, &parent) {
+                Some(description) => description,
+                None => {
+                    let combined = combine_messages_for_editing(
+                        ui,
+                        &tx,
+                        &abandoned_commits,
+                        Some(&parent),
+                        &commit_builder,
+                    )?;
+                    commit_builder.set_description(combined);
+                    let temp_commit = commit_builder.write_hidden()?;
+                    let intro = "Enter a description for the combined commit.";
+                    let template = description_template(ui, &tx, intro, &temp_commit)?;
+                    with_suspended(screen, || edit_description(&text_editor, &template))?
+                }
+            };
+            commit_builder.set_description(description);
+            commit_builder.write(tx.repo_mut())?;
+            let description = format!("squash commits into {}", parent.id().hex());
+            Ok(Some((tx, description)))
+        }
+        Action::Split(commit) => {
+            let args = ResolvedSplitArgs::interactive(ui, workspace_command, commit.clone())?;
+            let text_editor = workspace_command.text_editor()?;
+            let mut tx = workspace_command.start_transaction();
+            with_suspended(screen, || split_commit(ui, &mut tx, &text_editor, args))?;
+            let description = format!("split commit {}", commit.id().hex());
+            Ok(Some((tx, description)))
+        }
+        Action::Describe(commit) => {
+            workspace_command.check_rewritable([commit.id()])?;
+            let text_editor = workspace_command.text_editor()?;
+            let mut tx = workspace_command.start_transaction();
+            let mut commit_builder = tx.repo_mut().rewrite_commit(commit).detach();
+            let description = add_trailers(ui, &tx, &commit_builder)?;
+            commit_builder.set_description(description);
+            let temp_commit = commit_builder.write_hidden()?;
+            let template = description_template(ui, &tx, "", &temp_commit)?;
+            let description = with_suspended(screen, || edit_description(&text_editor, &template))?;
+            if description == commit.description() {
+                return Ok(None);
+            }
+            commit_builder.set_description(description);
+            commit_builder.write(tx.repo_mut())?;
+            let description = format!("describe commit {}", commit.id().hex());
+            Ok(Some((tx, description)))
+        }
+        Action::Abandon(commit) => {
+            workspace_command.check_rewritable([commit.id()])?;
+            let mut tx = workspace_command.start_transaction();
+            tx.repo_mut().record_abandoned_commit(commit);
+            tx.repo_mut().rebase_descendants()?;
+            let description = format!("abandon commit {}", commit.id().hex());
+            Ok(Some((tx, description)))
+        }
+        Action::Edit(commit) => {
+            if workspace_command.get_wc_commit_id() == Some(commit.id()) {
+                return Ok(None);
+            }
+            workspace_command.check_rewritable([commit.id()])?;
+            let mut tx = workspace_command.start_transaction();
+            tx.edit(commit)?;
+            let description = format!("edit commit {}", commit.id().hex());
+            Ok(Some((tx, description)))
+        }
+        Action::New(commit) => {
+            let mut tx = workspace_command.start_transaction();
+            let new_commit = tx
+                .repo_mut()
+                .new_commit(vec![commit.id().clone()], commit.tree())
+                .write()?;
+            tx.edit(&new_commit)?;
+            Ok(Some((tx, "new empty commit".to_owned())))
+        }
+    }
+}
+
+/// Runs `f` with the terminal restored to the normal mode.
+fn with_suspended<T>(
+    screen: &mut dyn Screen,
+    f: impl FnOnce() -> Result<T, CommandError>,
+) -> Result<T, CommandError> {
+    screen.suspend()?;
+    let result = f();
+    screen.resume()?;
+    result
+}
@@ -20,7 +20,7 @@ use jj_lib::file_util;
 use jj_lib::file_util::IoResultExt as _;
 use jj_lib::ref_name::WorkspaceNameBuf;
 use jj_lib::repo::Repo as _;
-use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::rewrite::merge_commit_trees_with_drivers;
 use jj_lib::workspace::Workspace;
 use pollster::FutureExt as _;
 use tracing::instrument;
@@ -202,7 +202,7 @@ pub fn cmd_workspace_add(
             .try_collect()?
     };
 
-    let tree = merge_commit_trees(tx.repo(), &parents).block_on()?;
+    let tree = merge_commit_trees_with_drivers(tx.repo(), &parents).block_on()?;
     let parent_ids = parents.iter().ids().cloned().collect_vec();
     let mut commit_builder = tx.repo_mut().new_commit(parent_ids, tree).detach();
     let mut description = join_message_paragraphs(&args.message_paragraphs);
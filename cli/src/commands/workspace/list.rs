@@ -12,20 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use clap_complete::ArgValueCandidates;
+use jj_lib::commit::Commit;
+use jj_lib::file_util;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::repo::Repo as _;
+use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::workspace::Workspace;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceStore as _;
+use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
 use crate::commit_templater::WorkspaceRef;
+use crate::commit_templater::WorkspaceStatus;
 use crate::complete;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// List workspaces
+///
+/// Workspaces whose directories no longer exist are reported as missing, and
+/// workspaces whose working copy needs `jj workspace update-stale` are
+/// reported as stale. With `--dirty`, workspaces with changes that haven't
+/// been snapshotted yet are reported as dirty.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceListArgs {
+    /// Scan the other workspaces for changes that haven't been snapshotted
+    ///
+    /// The working copies are scanned without recording a snapshot, which
+    /// can be slow if there are many workspaces.
+    #[arg(long)]
+    dirty: bool,
     /// Render each workspace using the given template
     ///
     /// All 0-argument methods of the [`WorkspaceRef` type] are available as
@@ -66,14 +89,146 @@ pub fn cmd_workspace_list(
     };
 
     let repo = workspace_command.repo();
-    let mut formatter = ui.stdout_formatter();
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path())?;
+    let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+    let snapshot_options = if args.dirty {
+        Some(
+            workspace_command
+                .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)?,
+        )
+    } else {
+        None
+    };
 
+    let mut ws_refs = Vec::new();
     for (name, wc_commit_id) in repo.view().wc_commit_ids() {
         let commit = repo.store().get_commit(wc_commit_id)?;
-        let ws_ref = WorkspaceRef::new(name.clone(), commit);
+        let status = if name == workspace_command.workspace_name() {
+            // The current working copy has been snapshotted and updated.
+            WorkspaceStatus {
+                path: Some(workspace_command.workspace_root().to_owned()),
+                missing: false,
+                stale: false,
+                dirty: false,
+                last_snapshot: Some(repo.operation().metadata().time.end),
+            }
+        } else {
+            let path = workspace_store.get_workspace_path(name)?;
+            load_workspace_status(
+                ui,
+                command,
+                &workspace_command,
+                snapshot_options.as_ref(),
+                path,
+                &commit,
+            )?
+        };
+        ws_refs.push(WorkspaceRef::with_status(name.clone(), commit, status));
+    }
 
-        template.format(&ws_ref, formatter.as_mut())?;
+    let mut formatter = ui.stdout_formatter();
+    for ws_ref in &ws_refs {
+        template.format(ws_ref, formatter.as_mut())?;
     }
 
     Ok(())
 }
+
+/// Inspects the workspace at `path` without recording a snapshot.
+///
+/// The working copy is only scanned for changes if `snapshot_options` is
+/// given. Failures to inspect the workspace are reported as warnings.
+fn load_workspace_status(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    snapshot_options: Option<&SnapshotOptions>,
+    path: Option<PathBuf>,
+    wc_commit: &Commit,
+) -> Result<WorkspaceStatus, CommandError> {
+    let Some(path) = path else {
+        // Workspaces created before the workspace store have no recorded path.
+        return Ok(WorkspaceStatus::default());
+    };
+    let path = file_util::normalize_path(&workspace_command.repo_path().join(path));
+    if !path.exists() {
+        return Ok(WorkspaceStatus {
+            path: Some(path),
+            missing: true,
+            ..WorkspaceStatus::default()
+        });
+    }
+    let mut workspace = match command.load_workspace_at(&path, workspace_command.settings()) {
+        Ok(workspace) => workspace,
+        Err(err) => {
+            writeln!(
+                ui.warning_default(),
+                "Failed to load workspace at {}: {}",
+                path.display(),
+                err.error
+            )?;
+            return Ok(WorkspaceStatus {
+                path: Some(path),
+                ..WorkspaceStatus::default()
+            });
+        }
+    };
+    let working_copy = workspace.working_copy();
+    let wc_tree = match working_copy.tree() {
+        Ok(tree) => tree.clone(),
+        Err(err) => {
+            writeln!(
+                ui.warning_default(),
+                "Failed to read working copy at {}: {err}",
+                path.display()
+            )?;
+            return Ok(WorkspaceStatus {
+                path: Some(path),
+                ..WorkspaceStatus::default()
+            });
+        }
+    };
+    let stale = wc_tree.tree_ids_and_labels() != wc_commit.tree().tree_ids_and_labels();
+    let last_snapshot = workspace_command
+        .repo()
+        .loader()
+        .load_operation(working_copy.operation_id())
+        .ok()
+        .map(|op| op.metadata().time.end);
+    let dirty = match snapshot_options {
+        Some(snapshot_options) => match scan_working_copy(&mut workspace, snapshot_options) {
+            Ok(snapshot_tree) => snapshot_tree.tree_ids() != wc_tree.tree_ids(),
+            Err(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to scan working copy at {}: {err}",
+                    path.display()
+                )?;
+                false
+            }
+        },
+        None => false,
+    };
+    Ok(WorkspaceStatus {
+        path: Some(path),
+        missing: false,
+        stale,
+        dirty,
+        last_snapshot,
+    })
+}
+
+/// Snapshots the working copy without recording the snapshot.
+fn scan_working_copy(
+    workspace: &mut Workspace,
+    snapshot_options: &SnapshotOptions,
+) -> Result<MergedTree, Box<dyn std::error::Error + Send + Sync>> {
+    // The locked working copy is dropped without finishing, so the snapshot
+    // isn't recorded in the working-copy state.
+    let mut locked_ws = workspace.start_working_copy_mutation()?;
+    let (snapshot_tree, _stats) = locked_ws
+        .locked_wc()
+        .snapshot(snapshot_options)
+        .block_on()?;
+    Ok(snapshot_tree)
+}
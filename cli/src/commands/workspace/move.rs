@@ -0,0 +1,175 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::ref_name::WorkspaceName;
+use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::repo::Repo as _;
+use jj_lib::workspace::update_workspace_repo_path;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceStore as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Move a workspace to another directory
+///
+/// If the workspace directory still exists, it is moved to the destination.
+/// If it was already moved by other means, only the recorded path is updated.
+///
+/// The current workspace and the workspace that contains the repo cannot be
+/// moved.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspaceMoveArgs {
+    /// Name of the workspace to move
+    #[arg(add = ArgValueCandidates::new(complete::workspaces))]
+    name: WorkspaceNameBuf,
+    /// New location of the workspace
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    destination: String,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_move(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspaceMoveArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let name = &*args.name;
+    if workspace_command
+        .repo()
+        .view()
+        .get_wc_commit_id(name)
+        .is_none()
+    {
+        return Err(user_error(format!(
+            "No such workspace: {}",
+            name.as_symbol()
+        )));
+    }
+    if name == workspace_command.workspace_name() {
+        return Err(user_error("Cannot move the current workspace"));
+    }
+
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path())?;
+    let repo_path = workspace_command.repo_path();
+    let destination_path = file_util::normalize_path(&command.cwd().join(&args.destination));
+    let old_path = workspace_store
+        .get_workspace_path(name)?
+        .map(|path| repo_path.join(path));
+
+    let mut moved_from = None;
+    if let Some(old_path) = old_path.as_ref().filter(|path| path.exists()) {
+        if old_path.join(".jj").join("repo").is_dir() {
+            return Err(user_error(
+                "Cannot move the workspace that contains the repo",
+            ));
+        }
+        if destination_path.exists() {
+            return Err(user_error("Destination path already exists"));
+        }
+        fs::rename(old_path, &destination_path).context(&destination_path)?;
+        moved_from = Some(old_path);
+    } else if !is_moved_workspace(
+        command,
+        &workspace_command,
+        name,
+        &destination_path,
+        old_path.as_deref(),
+    )? {
+        return Err(user_error(format!(
+            "Workspace {} is missing, and it isn't at the destination",
+            name.as_symbol()
+        )));
+    }
+
+    let result = update_workspace_repo_path(&destination_path, repo_path)
+        .map_err(CommandError::from)
+        .and_then(|()| Ok(workspace_store.add(name, &destination_path)?));
+    if let Err(err) = result {
+        // Move the directory back so it matches the recorded path.
+        if let Some(old_path) = moved_from {
+            fs::rename(&destination_path, old_path).context(old_path)?;
+            update_workspace_repo_path(old_path, repo_path)?;
+        }
+        return Err(err);
+    }
+    writeln!(
+        ui.status(),
+        "Moved workspace {} to \"{}\"",
+        name.as_symbol(),
+        file_util::relative_path(command.cwd(), &destination_path).display()
+    )?;
+    Ok(())
+}
+
+/// Returns true if the directory at `path` is the workspace `name` of the
+/// current repo, which may have been moved from `old_path`.
+fn is_moved_workspace(
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    name: &WorkspaceName,
+    path: &Path,
+    old_path: Option<&Path>,
+) -> Result<bool, CommandError> {
+    let jj_dir = path.join(".jj");
+    let repo_file_path = jj_dir.join("repo");
+    if !repo_file_path.is_file() {
+        return Ok(false);
+    }
+    // The repo location is relative to the workspace, so it may point
+    // elsewhere after the workspace was moved.
+    let repo_path = workspace_command.repo_path();
+    let buf = fs::read(&repo_file_path).context(&repo_file_path)?;
+    let Ok(stored_path) = file_util::path_from_bytes(&buf) else {
+        return Ok(false);
+    };
+    let points_to_repo = dunce::canonicalize(jj_dir.join(stored_path))
+        .is_ok_and(|path| path == repo_path)
+        || old_path.is_some_and(|old_path| {
+            file_util::normalize_path(&old_path.join(".jj").join(stored_path)) == repo_path
+        });
+    if !points_to_repo {
+        return Ok(false);
+    }
+    let state_path = jj_dir.join("working_copy");
+    let type_path = state_path.join("type");
+    let working_copy_type = match fs::read_to_string(&type_path) {
+        Ok(working_copy_type) => working_copy_type,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => Err(err).context(&type_path)?,
+    };
+    if working_copy_type != workspace_command.working_copy().name() {
+        return Ok(false);
+    }
+    let working_copy = command.get_working_copy_factory()?.load_working_copy(
+        workspace_command.repo().store().clone(),
+        path.to_owned(),
+        state_path,
+        workspace_command.settings(),
+    )?;
+    Ok(working_copy.workspace_name() == name)
+}
@@ -0,0 +1,88 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceStore as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Forget workspaces whose directories no longer exist
+///
+/// Workspaces created before the workspace store was introduced have no
+/// recorded path, and are never pruned.
+#[derive(clap::Args, Clone, Debug)]
+pub struct WorkspacePruneArgs {
+    /// Only list the workspaces that would be forgotten
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_workspace_prune(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &WorkspacePruneArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path())?;
+
+    let mut prune_ws: Vec<WorkspaceNameBuf> = Vec::new();
+    for name in workspace_command.repo().view().wc_commit_ids().keys() {
+        if name == workspace_command.workspace_name() {
+            continue;
+        }
+        let Some(path) = workspace_store.get_workspace_path(name)? else {
+            continue;
+        };
+        if !workspace_command.repo_path().join(path).exists() {
+            prune_ws.push(name.clone());
+        }
+    }
+    if prune_ws.is_empty() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        let verb = if args.dry_run {
+            "Would forget"
+        } else {
+            "Forgetting"
+        };
+        for name in &prune_ws {
+            writeln!(formatter, "{verb} missing workspace {}", name.as_symbol())?;
+        }
+    }
+    if args.dry_run {
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for name in &prune_ws {
+        tx.repo_mut().remove_wc_commit(name)?;
+    }
+    workspace_store.forget(&prune_ws.iter().map(|x| x.as_ref()).collect_vec())?;
+
+    let description = format!(
+        "prune workspaces {}",
+        prune_ws.iter().map(|ws| ws.as_symbol()).join(", ")
+    );
+    tx.finish(ui, description)?;
+    Ok(())
+}
@@ -15,12 +15,14 @@
 //! Template environment for `jj log`, `jj evolog` and similar.
 
 use std::any::Any;
+use std::cell::Cell;
 use std::cmp::Ordering;
 use std::cmp::max;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
 use std::io;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -32,6 +34,7 @@ use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::conflict_labels::ConflictLabels;
@@ -45,6 +48,7 @@ use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+use jj_lib::gitattributes::GitAttributes;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::id_prefix::IdPrefixIndex;
 use jj_lib::index::IndexResult;
@@ -128,6 +132,13 @@ pub trait CommitTemplateLanguageExtension {
     fn build_cache_extensions(&self, extensions: &mut ExtensionsMap);
 }
 
+/// Function that evaluates the immutable set in place of the immutable
+/// expression. Returns `None` if the expression should be evaluated instead.
+pub type ImmutableRevsetFn<'repo> = dyn FnOnce() -> ImmutableRevsetResult<'repo> + 'repo;
+
+type ImmutableRevsetResult<'repo> =
+    Result<Option<Box<dyn Revset + 'repo>>, Box<dyn std::error::Error + Send + Sync>>;
+
 /// Template environment for `jj log` and `jj evolog`.
 pub struct CommitTemplateLanguage<'repo> {
     repo: &'repo dyn Repo,
@@ -141,6 +152,7 @@ pub struct CommitTemplateLanguage<'repo> {
     revset_parse_context: RevsetParseContext<'repo>,
     id_prefix_context: &'repo IdPrefixContext,
     immutable_expression: Arc<UserRevsetExpression>,
+    immutable_revset_fn: Cell<Option<Box<ImmutableRevsetFn<'repo>>>>,
     conflict_marker_style: ConflictMarkerStyle,
     build_fn_table: CommitTemplateBuildFnTable<'repo>,
     keyword_cache: CommitKeywordCache<'repo>,
@@ -178,12 +190,19 @@ impl<'repo> CommitTemplateLanguage<'repo> {
             revset_parse_context,
             id_prefix_context,
             immutable_expression,
+            immutable_revset_fn: Cell::new(None),
             conflict_marker_style,
             build_fn_table,
             keyword_cache: CommitKeywordCache::default(),
             cache_extensions,
         }
     }
+
+    /// Sets function that evaluates the immutable set. The function will be
+    /// called at most once when the `immutable` keyword is used.
+    pub fn set_immutable_revset_fn(&mut self, f: Box<ImmutableRevsetFn<'repo>>) {
+        self.immutable_revset_fn = Cell::new(Some(f));
+    }
 }
 
 impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
@@ -991,6 +1010,15 @@ impl<'repo> CommitKeywordCache<'repo> {
         // It's usually smaller than the immutable set. The revset engine can also
         // optimize "::<recent_heads>" query to use bitset-based implementation.
         self.is_immutable_fn.get_or_try_init(|| {
+            if let Some(evaluate) = language.immutable_revset_fn.take() {
+                let revset = evaluate().map_err(|err| {
+                    TemplateParseError::expression("Failed to evaluate revset", span)
+                        .with_source(err)
+                })?;
+                if let Some(revset) = revset {
+                    return Ok(revset.containing_fn().into());
+                }
+            }
             let expression = &language.immutable_expression;
             let revset = evaluate_revset_expression(language, span, expression)?;
             Ok(revset.containing_fn().into())
@@ -1732,12 +1760,29 @@ pub struct WorkspaceRef {
     name: WorkspaceNameBuf,
     /// Working-copy commit of this workspace.
     target: Commit,
+    /// State of the workspace on disk, if loaded.
+    #[serde(skip)]
+    status: Option<Rc<WorkspaceStatus>>,
 }
 
 impl WorkspaceRef {
     /// Creates a new workspace reference from the workspace name and commit.
     pub fn new(name: WorkspaceNameBuf, target: Commit) -> Self {
-        Self { name, target }
+        Self {
+            name,
+            target,
+            status: None,
+        }
+    }
+
+    /// Creates a new workspace reference with the state of the workspace on
+    /// disk.
+    pub fn with_status(name: WorkspaceNameBuf, target: Commit, status: WorkspaceStatus) -> Self {
+        Self {
+            name,
+            target,
+            status: Some(Rc::new(status)),
+        }
     }
 
     /// Returns the workspace name symbol.
@@ -1749,6 +1794,28 @@ impl WorkspaceRef {
     pub fn target(&self) -> &Commit {
         &self.target
     }
+
+    fn status(&self) -> Result<&WorkspaceStatus, TemplatePropertyError> {
+        self.status
+            .as_deref()
+            .ok_or_else(|| TemplatePropertyError("Workspace status is not available".into()))
+    }
+}
+
+/// State of a workspace on disk.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceStatus {
+    /// Path to the workspace root recorded in the workspace store.
+    pub path: Option<PathBuf>,
+    /// Whether the workspace root no longer exists.
+    pub missing: bool,
+    /// Whether the working copy needs to be updated to the working-copy
+    /// commit.
+    pub stale: bool,
+    /// Whether the working copy has changes that haven't been snapshotted.
+    pub dirty: bool,
+    /// Time of the operation the working copy was last updated at.
+    pub last_snapshot: Option<Timestamp>,
 }
 
 impl Template for WorkspaceRef {
@@ -1781,6 +1848,57 @@ fn builtin_workspace_ref_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'rep
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "path",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|ws_ref| {
+                let status = ws_ref.status()?;
+                Ok(status
+                    .path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "missing",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|ws_ref| Ok(ws_ref.status()?.missing));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "stale",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|ws_ref| Ok(ws_ref.status()?.stale));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "dirty",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|ws_ref| Ok(ws_ref.status()?.dirty));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "last_snapshot",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|ws_ref| {
+                ws_ref.status()?.last_snapshot.ok_or_else(|| {
+                    TemplatePropertyError("Workspace has no working copy on disk".into())
+                })
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -2300,6 +2418,7 @@ impl TreeDiff {
             &Store,
             BoxStream<CopiesTreeDiffEntry>,
             Diff<&ConflictLabels>,
+            &GitAttributes,
         ) -> Result<(), E>,
         E: Into<TemplatePropertyError>,
     {
@@ -2320,6 +2439,7 @@ where
         &Store,
         BoxStream<CopiesTreeDiffEntry>,
         Diff<&ConflictLabels>,
+        &GitAttributes,
     ) -> Result<(), E>,
     E: Into<TemplatePropertyError>,
 {
@@ -2328,8 +2448,15 @@ where
         let store = self.diff.from_tree.store();
         let tree_diff = self.diff.diff_stream();
         let conflict_labels = Diff::new(self.diff.from_tree.labels(), self.diff.to_tree.labels());
-        show(formatter.as_mut(), store, tree_diff, conflict_labels)
-            .or_else(|err| formatter.handle_error(err.into()))
+        let attributes = GitAttributes::new(self.diff.to_tree.clone());
+        show(
+            formatter.as_mut(),
+            store,
+            tree_diff,
+            conflict_labels,
+            &attributes,
+        )
+        .or_else(|err| formatter.handle_error(err.into()))
     }
 }
 
@@ -2375,18 +2502,21 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     if let Some(context) = context {
                         options.context = context;
                     }
-                    diff.into_formatted(move |formatter, store, tree_diff, conflict_labels| {
-                        diff_util::show_color_words_diff(
-                            formatter,
-                            store,
-                            tree_diff,
-                            conflict_labels,
-                            path_converter,
-                            &options,
-                            conflict_marker_style,
-                        )
-                        .block_on()
-                    })
+                    diff.into_formatted(
+                        move |formatter, store, tree_diff, conflict_labels, attributes| {
+                            diff_util::show_color_words_diff(
+                                formatter,
+                                store,
+                                tree_diff,
+                                conflict_labels,
+                                attributes,
+                                path_converter,
+                                &options,
+                                conflict_marker_style,
+                            )
+                            .block_on()
+                        },
+                    )
                 })
                 .into_template();
             Ok(P::wrap_template(template))
@@ -2418,17 +2548,20 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                     if let Some(context) = context {
                         options.context = context;
                     }
-                    diff.into_formatted(move |formatter, store, trees, tree_diff| {
-                        diff_util::show_git_diff(
-                            formatter,
-                            store,
-                            trees,
-                            tree_diff,
-                            &options,
-                            conflict_marker_style,
-                        )
-                        .block_on()
-                    })
+                    diff.into_formatted(
+                        move |formatter, store, tree_diff, conflict_labels, attributes| {
+                            diff_util::show_git_diff(
+                                formatter,
+                                store,
+                                tree_diff,
+                                conflict_labels,
+                                attributes,
+                                &options,
+                                conflict_marker_style,
+                            )
+                            .block_on()
+                        },
+                    )
                 })
                 .into_template();
             Ok(P::wrap_template(template))
@@ -2449,15 +2582,25 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                 })
                 .transpose()?;
             let path_converter = language.path_converter;
-            // No user configuration exists for diff stat.
-            let options = diff_util::DiffStatOptions::default();
+            let options =
+                diff_util::DiffStatOptions::from_settings(language.settings()).map_err(|err| {
+                    let message = "Failed to load diff settings";
+                    TemplateParseError::expression(message, function.name_span).with_source(err)
+                })?;
             let conflict_marker_style = language.conflict_marker_style;
             // TODO: cache and reuse stats within the current evaluation?
             let out_property = (self_property, width_property).and_then(move |(diff, width)| {
                 let store = diff.from_tree.store();
                 let tree_diff = diff.diff_stream();
-                let stats = DiffStats::calculate(store, tree_diff, &options, conflict_marker_style)
-                    .block_on()?;
+                let attributes = GitAttributes::new(diff.to_tree.clone());
+                let stats = DiffStats::calculate(
+                    store,
+                    tree_diff,
+                    &attributes,
+                    &options,
+                    conflict_marker_style,
+                )
+                .block_on()?;
                 Ok(DiffStatsFormatted {
                     stats,
                     path_converter,
@@ -2475,10 +2618,12 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
             let path_converter = language.path_converter;
             let template = self_property
                 .map(move |diff| {
-                    diff.into_formatted(move |formatter, _store, tree_diff, _conflict_labels| {
-                        diff_util::show_diff_summary(formatter, tree_diff, path_converter)
-                            .block_on()
-                    })
+                    diff.into_formatted(
+                        move |formatter, _store, tree_diff, _conflict_labels, _attributes| {
+                            diff_util::show_diff_summary(formatter, tree_diff, path_converter)
+                                .block_on()
+                        },
+                    )
                 })
                 .into_template();
             Ok(P::wrap_template(template))
@@ -2972,7 +3117,6 @@ fn builtin_trailer_list_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo
 mod tests {
     use std::path::Component;
     use std::path::Path;
-    use std::path::PathBuf;
 
     use jj_lib::config::ConfigLayer;
     use jj_lib::config::ConfigSource;
@@ -3050,6 +3194,7 @@ mod tests {
                 user_email: "test.user@example.com",
                 date_pattern_context: chrono::DateTime::UNIX_EPOCH.fixed_offset().into(),
                 default_ignored_remote: None,
+                default_push_remote: None,
                 use_glob_by_default: true,
                 extensions: &self.revset_extensions,
                 workspace: Some(RevsetWorkspaceContext {
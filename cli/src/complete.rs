@@ -1119,6 +1119,7 @@ fn get_jj_command() -> Result<(JjBuilder, UserSettings), CommandError> {
     if let Ok(loader) = &maybe_cwd_workspace_loader {
         config_env.reset_repo_path(loader.repo_path());
         config_env.reload_repo_config(&ui, &mut raw_config).ok();
+        config_env.reload_tree_config(&ui, &mut raw_config).ok();
         config_env.reset_workspace_path(loader.workspace_root());
         config_env
             .reload_workspace_config(&ui, &mut raw_config)
@@ -1141,6 +1142,7 @@ fn get_jj_command() -> Result<(JjBuilder, UserSettings), CommandError> {
         if let Ok(loader) = DefaultWorkspaceLoaderFactory.create(&cwd.join(&repository)) {
             config_env.reset_repo_path(loader.repo_path());
             config_env.reload_repo_config(&ui, &mut raw_config).ok();
+            config_env.reload_tree_config(&ui, &mut raw_config).ok();
             config_env.reset_workspace_path(loader.workspace_root());
             config_env
                 .reload_workspace_config(&ui, &mut raw_config)
@@ -18,6 +18,9 @@ use std::collections::HashMap;
 use std::env;
 use std::env::split_paths;
 use std::fmt;
+use std::iter;
+use std::mem;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -27,6 +30,8 @@ use std::sync::Mutex;
 
 use etcetera::BaseStrategy as _;
 use itertools::Itertools as _;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
 use jj_lib::config::ConfigFile;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLayer;
@@ -37,18 +42,24 @@ use jj_lib::config::ConfigResolutionContext;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::ConfigValue;
 use jj_lib::config::StackedConfig;
+use jj_lib::config::TREE_CONFIG_PATH;
+use jj_lib::dsl_util::collect_similar;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::secure_config::LoadedSecureConfig;
 use jj_lib::secure_config::SecureConfig;
+use pollster::FutureExt as _;
 use rand::SeedableRng as _;
 use rand_chacha::ChaCha20Rng;
 use regex::Captures;
 use regex::Regex;
 use serde::Serialize as _;
+use tokio::io::AsyncReadExt as _;
 use tracing::instrument;
 
 use crate::command_error::CommandError;
 use crate::command_error::config_error;
 use crate::command_error::config_error_with_message;
+use crate::command_error::user_error;
 use crate::ui::Ui;
 
 // TODO(#879): Consider generating entire schema dynamically vs. static file.
@@ -57,6 +68,23 @@ pub const CONFIG_SCHEMA: &str = include_str!("config-schema.json");
 const REPO_CONFIG_DIR: &str = "repos";
 const WORKSPACE_CONFIG_DIR: &str = "workspaces";
 
+/// Name of the trusted copy of the tree config file, which is stored next to
+/// the repo config file.
+const TRUSTED_TREE_CONFIG_FILE: &str = "tree-config.toml";
+/// Name of the file recording the file ID of the tree config at `trunk()` when
+/// it was last compared with the trusted copy. Stored next to the trusted copy.
+pub const TREE_CONFIG_CHECKED_FILE: &str = "tree-config-checked";
+/// Config names the tree config is allowed to set. Everything else is
+/// ignored, so that a repository can't e.g. change the signing key.
+pub const TREE_CONFIG_ALLOWED_NAMES: &[&[&str]] = &[
+    &["fix", "tools"],
+    &["merge-tools"],
+    // Includes immutable_heads()
+    &["revset-aliases"],
+    &["template-aliases"],
+    &["ui", "merge-editor"],
+];
+
 /// Parses a TOML value expression. Interprets the given value as string if it
 /// can't be parsed and doesn't look like a TOML expression.
 pub fn parse_value_or_bare_string(value_str: &str) -> Result<ConfigValue, toml_edit::TomlError> {
@@ -527,6 +555,37 @@ impl ConfigEnv {
         Ok(())
     }
 
+    /// Returns a path to the existing trusted copy of the tree config file.
+    fn maybe_tree_config_path(&self, ui: &Ui) -> Result<Option<PathBuf>, CommandError> {
+        Ok(self
+            .maybe_repo_config_path(ui)?
+            .map(|path| path.with_file_name(TRUSTED_TREE_CONFIG_FILE)))
+    }
+
+    /// Returns a path to the trusted copy of the tree config file, which may
+    /// not exist. If the repo config directory does not exist, will create a
+    /// new config ID and create a new directory for this.
+    pub fn tree_config_path(&self, ui: &Ui) -> Result<Option<PathBuf>, CommandError> {
+        Ok(self
+            .repo_config_path(ui)?
+            .map(|path| path.with_file_name(TRUSTED_TREE_CONFIG_FILE)))
+    }
+
+    /// Loads the trusted copy of the tree config file into the given `config`.
+    /// The old tree-config layer will be replaced if any.
+    #[instrument(skip(ui))]
+    pub fn reload_tree_config(&self, ui: &Ui, config: &mut RawConfig) -> Result<(), CommandError> {
+        config.as_mut().remove_layers(ConfigSource::Tree);
+        if let Some(path) = self.maybe_tree_config_path(ui)?
+            && path.exists()
+        {
+            let mut layer = ConfigLayer::load_from_file(ConfigSource::Tree, path)?;
+            retain_tree_config_items(&mut layer);
+            config.as_mut().add_layer(layer);
+        }
+        Ok(())
+    }
+
     /// Sets the directory where the workspace-specific config file is stored.
     pub fn reset_workspace_path(&mut self, path: &Path) {
         self.workspace_config = Some(SecureConfig::new_workspace(path.join(".jj")));
@@ -630,6 +689,68 @@ fn config_files_for(
     Ok(files)
 }
 
+/// Reads the config file shared through the tree of the `commit`. Returns
+/// `None` if the file doesn't exist.
+pub fn read_tree_config(commit: &Commit) -> Result<Option<Vec<u8>>, CommandError> {
+    let path = RepoPath::from_internal_string(TREE_CONFIG_PATH).unwrap();
+    match commit.tree().path_value(path)?.into_resolved() {
+        Ok(None) => Ok(None),
+        Ok(Some(TreeValue::File { id, .. })) => {
+            let mut reader = commit.store().read_file(path, &id).block_on()?;
+            let mut content = vec![];
+            reader.read_to_end(&mut content).block_on()?;
+            Ok(Some(content))
+        }
+        Ok(Some(_)) => Err(user_error(format!(
+            "Tree config `{TREE_CONFIG_PATH}` is not a file"
+        ))),
+        Err(_) => Err(user_error(format!(
+            "Tree config `{TREE_CONFIG_PATH}` has conflicts"
+        ))),
+    }
+}
+
+/// Removes the items the tree config isn't allowed to set from the `layer`.
+/// Returns the names of the removed items.
+pub fn retain_tree_config_items(layer: &mut ConfigLayer) -> Vec<ConfigNamePathBuf> {
+    let mut removed = vec![];
+    retain_allowed_items(layer.data.as_table_mut(), &mut vec![], &mut removed);
+    removed
+}
+
+fn retain_allowed_items(
+    table: &mut dyn toml_edit::TableLike,
+    prefix: &mut Vec<String>,
+    removed: &mut Vec<ConfigNamePathBuf>,
+) {
+    let keys = table.iter().map(|(key, _)| key.to_owned()).collect_vec();
+    for key in keys {
+        prefix.push(key);
+        let matches = |names: &[&str]| iter::zip(names, &*prefix).all(|(a, b)| *a == b);
+        let is_allowed = TREE_CONFIG_ALLOWED_NAMES
+            .iter()
+            .any(|names| names.len() <= prefix.len() && matches(names));
+        let has_allowed_children = TREE_CONFIG_ALLOWED_NAMES
+            .iter()
+            .any(|names| names.len() > prefix.len() && matches(names));
+        let key = prefix.last().unwrap();
+        if is_allowed {
+            // Keep the whole item
+        } else if has_allowed_children
+            && let Some(sub_table) = table.get_mut(key).and_then(|item| item.as_table_like_mut())
+        {
+            retain_allowed_items(sub_table, prefix, removed);
+            if sub_table.is_empty() {
+                table.remove(prefix.last().unwrap());
+            }
+        } else {
+            table.remove(key);
+            removed.push(prefix.iter().map(String::as_str).collect());
+        }
+        prefix.pop();
+    }
+}
+
 /// Initializes stacked config with the given `default_layers` and infallible
 /// sources.
 ///
@@ -637,12 +758,13 @@ fn config_files_for(
 /// 1. Default
 /// 2. Base environment variables
 /// 3. [User configs](https://docs.jj-vcs.dev/latest/config/)
-/// 4. Repo config
-/// 5. Workspace config
-/// 6. Override environment variables
-/// 7. Command-line arguments `--config` and `--config-file`
+/// 4. Trusted tree config
+/// 5. Repo config
+/// 6. Workspace config
+/// 7. Override environment variables
+/// 8. Command-line arguments `--config` and `--config-file`
 ///
-/// This function sets up 1, 2, and 6.
+/// This function sets up 1, 2, and 7.
 pub fn config_from_environment(default_layers: impl IntoIterator<Item = ConfigLayer>) -> RawConfig {
     let mut config = StackedConfig::with_defaults();
     config.extend_layers(default_layers);
@@ -839,6 +961,315 @@ For details, see: https://docs.jj-vcs.dev/latest/config/#automatic-tracking-of-b
     ]
 }
 
+static PARSED_CONFIG_SCHEMA: LazyLock<serde_json::Value> =
+    LazyLock::new(|| serde_json::from_str(CONFIG_SCHEMA).expect("config schema should be valid"));
+
+/// Problem found by checking config items against [`CONFIG_SCHEMA`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigSchemaProblem {
+    /// Name of the offending item.
+    pub name: ConfigNamePathBuf,
+    pub kind: ConfigSchemaProblemKind,
+    /// Byte range of the item in the source text, if known.
+    pub span: Option<Range<usize>>,
+}
+
+/// Kind of [`ConfigSchemaProblem`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigSchemaProblemKind {
+    /// The key isn't defined in the schema.
+    UnknownKey {
+        /// Similar names defined in the schema.
+        similar: Vec<ConfigNamePathBuf>,
+    },
+    /// The value doesn't have the type defined in the schema.
+    InvalidType {
+        /// Description of the expected type, such as "a string".
+        expected: String,
+    },
+}
+
+impl ConfigSchemaProblem {
+    /// Returns a "did you mean" hint if there are similar names.
+    pub fn hint(&self) -> Option<String> {
+        match &self.kind {
+            ConfigSchemaProblemKind::UnknownKey { similar } if !similar.is_empty() => {
+                let names = similar.iter().map(|name| format!("`{name}`")).join(", ");
+                Some(format!("Did you mean {names}?"))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConfigSchemaProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ConfigSchemaProblemKind::UnknownKey { .. } => {
+                write!(f, "Unknown config key `{}`", self.name)
+            }
+            ConfigSchemaProblemKind::InvalidType { expected } => {
+                write!(f, "Invalid type for `{}`: expected {expected}", self.name)
+            }
+        }
+    }
+}
+
+/// Checks the items of the config `table` against [`CONFIG_SCHEMA`].
+///
+/// Keys missing from a table that defines its known keys are reported even if
+/// the schema doesn't disallow additional keys. Unknown top-level keys aren't
+/// reported since other tools may store their settings there. Enum values
+/// aren't checked because some of them can be extended, e.g. by custom signing
+/// backends.
+pub fn check_config_schema(table: &toml_edit::Table) -> Vec<ConfigSchemaProblem> {
+    let schema = &*PARSED_CONFIG_SCHEMA;
+    let mut checker = SchemaChecker {
+        root: schema,
+        name: vec![],
+        problems: vec![],
+    };
+    checker.check_table(table, schema);
+    checker.problems
+}
+
+/// Returns true if the layers of the `source` should be checked against
+/// [`CONFIG_SCHEMA`].
+pub fn is_schema_checked_source(source: ConfigSource) -> bool {
+    match source {
+        ConfigSource::Default | ConfigSource::EnvBase | ConfigSource::EnvOverrides => false,
+        ConfigSource::User
+        | ConfigSource::Tree
+        | ConfigSource::Repo
+        | ConfigSource::Workspace
+        | ConfigSource::CommandArg => true,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SchemaNode<'a> {
+    Value(&'a toml_edit::Value),
+    Table(&'a toml_edit::Table),
+    Tables(&'a toml_edit::ArrayOfTables),
+}
+
+impl<'a> SchemaNode<'a> {
+    fn from_item(item: &'a toml_edit::Item) -> Option<Self> {
+        match item {
+            toml_edit::Item::None => None,
+            toml_edit::Item::Value(value) => Some(Self::Value(value)),
+            toml_edit::Item::Table(table) => Some(Self::Table(table)),
+            toml_edit::Item::ArrayOfTables(tables) => Some(Self::Tables(tables)),
+        }
+    }
+
+    /// Returns the JSON schema type name of the node.
+    fn type_name(self) -> &'static str {
+        match self {
+            Self::Value(toml_edit::Value::String(_)) => "string",
+            Self::Value(toml_edit::Value::Integer(_)) => "integer",
+            Self::Value(toml_edit::Value::Float(_)) => "number",
+            Self::Value(toml_edit::Value::Boolean(_)) => "boolean",
+            Self::Value(toml_edit::Value::Datetime(_)) => "datetime",
+            Self::Value(toml_edit::Value::Array(_)) | Self::Tables(_) => "array",
+            Self::Value(toml_edit::Value::InlineTable(_)) | Self::Table(_) => "object",
+        }
+    }
+
+    fn span(self) -> Option<Range<usize>> {
+        match self {
+            Self::Value(value) => value.span(),
+            Self::Table(table) => table.span(),
+            Self::Tables(tables) => tables.span(),
+        }
+    }
+}
+
+struct SchemaChecker<'a> {
+    root: &'a serde_json::Value,
+    name: Vec<String>,
+    problems: Vec<ConfigSchemaProblem>,
+}
+
+impl<'a> SchemaChecker<'a> {
+    /// Follows `$ref` pointers within the schema.
+    fn resolve(&self, mut schema: &'a serde_json::Value) -> &'a serde_json::Value {
+        while let Some(target) = schema
+            .get("$ref")
+            .and_then(|reference| reference.as_str()?.strip_prefix('#'))
+            .and_then(|pointer| self.root.pointer(pointer))
+        {
+            schema = target;
+        }
+        schema
+    }
+
+    fn report(&mut self, kind: ConfigSchemaProblemKind, span: Option<Range<usize>>) {
+        self.problems.push(ConfigSchemaProblem {
+            name: self.name.iter().map(String::as_str).collect(),
+            kind,
+            span,
+        });
+    }
+
+    fn check_node(&mut self, node: SchemaNode<'_>, schema: &'a serde_json::Value) {
+        let schema = self.resolve(schema);
+        if let Some(alternatives) = schema.get("oneOf").and_then(|v| v.as_array()) {
+            if let Some(alt) = alternatives.iter().find(|alt| self.type_matches(node, alt)) {
+                self.check_node(node, alt);
+            } else {
+                let expected = self.expected_type(schema);
+                self.report(
+                    ConfigSchemaProblemKind::InvalidType { expected },
+                    node.span(),
+                );
+            }
+            return;
+        }
+        if !self.type_matches(node, schema) {
+            let expected = self.expected_type(schema);
+            self.report(
+                ConfigSchemaProblemKind::InvalidType { expected },
+                node.span(),
+            );
+            return;
+        }
+        match node {
+            SchemaNode::Value(toml_edit::Value::InlineTable(table)) => {
+                self.check_table(table, schema);
+            }
+            SchemaNode::Table(table) => self.check_table(table, schema),
+            SchemaNode::Value(toml_edit::Value::Array(array)) => {
+                if let Some(items) = schema.get("items") {
+                    for value in array {
+                        self.check_node(SchemaNode::Value(value), items);
+                    }
+                }
+            }
+            SchemaNode::Tables(tables) => {
+                if let Some(items) = schema.get("items") {
+                    for table in tables {
+                        self.check_node(SchemaNode::Table(table), items);
+                    }
+                }
+            }
+            SchemaNode::Value(_) => {}
+        }
+    }
+
+    fn check_table(&mut self, table: &dyn toml_edit::TableLike, schema: &'a serde_json::Value) {
+        let properties = schema.get("properties").and_then(|v| v.as_object());
+        let additional = schema.get("additionalProperties");
+        let is_root = std::ptr::eq(schema, self.root);
+        // Tables without known keys, as well as the root table, are open.
+        let is_closed = properties.is_some()
+            && !is_root
+            && additional.is_none_or(|v| v.as_bool() == Some(false));
+        // Names in scoped tables are relative to the root table.
+        let outer_name = is_root.then(|| mem::take(&mut self.name));
+        for (key, item) in table.iter().filter_map(|(key, _)| table.get_key_value(key)) {
+            let Some(node) = SchemaNode::from_item(item) else {
+                continue;
+            };
+            self.name.push(key.get().to_owned());
+            let property = properties
+                .and_then(|properties| properties.get(key.get()))
+                .or(additional.filter(|v| v.is_object()));
+            if let Some(property) = property {
+                self.check_node(node, property);
+            } else if let Some(properties) = properties.filter(|_| is_closed) {
+                let prefix = &self.name[..self.name.len() - 1];
+                let similar = collect_similar(key.get(), properties.keys())
+                    .into_iter()
+                    .map(|name| {
+                        prefix
+                            .iter()
+                            .map(String::as_str)
+                            .chain([name.as_str()])
+                            .collect()
+                    })
+                    .collect();
+                let kind = ConfigSchemaProblemKind::UnknownKey { similar };
+                self.report(kind, key.span());
+            }
+            self.name.pop();
+        }
+        if let Some(name) = outer_name {
+            self.name = name;
+        }
+    }
+
+    fn type_matches(&self, node: SchemaNode<'_>, schema: &'a serde_json::Value) -> bool {
+        let actual = node.type_name();
+        self.schema_types(schema)
+            .is_none_or(|types| types.iter().any(|&ty| type_includes(ty, actual)))
+    }
+
+    fn expected_type(&self, schema: &'a serde_json::Value) -> String {
+        let Some(types) = self.schema_types(schema) else {
+            return "any value".to_owned();
+        };
+        types
+            .iter()
+            .unique()
+            .map(|ty| match *ty {
+                "array" => "an array",
+                "boolean" => "a boolean",
+                "integer" => "an integer",
+                "number" => "a number",
+                "object" => "a table",
+                "string" => "a string",
+                ty => ty,
+            })
+            .join(" or ")
+    }
+
+    /// Returns the JSON types accepted by the `schema`, or `None` if any type
+    /// is accepted.
+    fn schema_types(&self, schema: &'a serde_json::Value) -> Option<Vec<&'a str>> {
+        let schema = self.resolve(schema);
+        if let Some(alternatives) = schema.get("oneOf").and_then(|v| v.as_array()) {
+            let mut types = vec![];
+            for alt in alternatives {
+                types.extend(self.schema_types(alt)?);
+            }
+            return Some(types);
+        }
+        match schema.get("type") {
+            Some(serde_json::Value::String(ty)) => Some(vec![ty.as_str()]),
+            Some(serde_json::Value::Array(types)) => {
+                Some(types.iter().filter_map(|ty| ty.as_str()).collect())
+            }
+            Some(_) => None,
+            None if schema.get("properties").is_some()
+                || schema.get("additionalProperties").is_some() =>
+            {
+                Some(vec!["object"])
+            }
+            None => {
+                let values = schema.get("enum")?.as_array()?;
+                Some(values.iter().map(json_type_name).collect())
+            }
+        }
+    }
+}
+
+fn type_includes(expected: &str, actual: &str) -> bool {
+    expected == actual || (expected == "number" && actual == "integer")
+}
+
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(number) if number.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
 /// Command name and arguments specified by config.
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
 #[serde(untagged)]
@@ -1164,6 +1595,52 @@ mod tests {
         assert_eq!(args, ["-nw"].as_ref());
     }
 
+    #[test]
+    fn test_check_config_schema() {
+        let check = |text: &str| {
+            let document = toml_edit::Document::parse(text).unwrap();
+            check_config_schema(document.as_table())
+                .iter()
+                .map(|problem| {
+                    let span = problem.span.clone().unwrap();
+                    let hint = problem.hint().unwrap_or_default();
+                    format!("{}: {problem} {hint}", &text[span])
+                        .trim_end()
+                        .to_owned()
+                })
+                .join("\n")
+        };
+
+        // Unknown top-level keys are allowed
+        insta::assert_snapshot!(check(indoc! {"
+            custom-table.foo = 1
+            ui.paginate = 'never'
+            ui.pager = { command = ['less'], env = { LESS = 'FRX' } }
+            signing.backends.custom.key = 1
+        "}), @"");
+
+        insta::assert_snapshot!(check(indoc! {"
+            ui.pagr = 'less'
+            ui.paginate = 0
+            ui.editor = ['vim', 1]
+            colors.error = { fg = 'red', bold = 'yes' }
+            merge-tools.foo.edit-argz = []
+        "}), @r"
+        pagr: Unknown config key `ui.pagr` Did you mean `ui.pager`, `ui.paginate`?
+        0: Invalid type for `ui.paginate`: expected a string
+        1: Invalid type for `ui.editor`: expected a string
+        'yes': Invalid type for `colors.error.bold`: expected a boolean
+        edit-argz: Unknown config key `merge-tools.foo.edit-argz` Did you mean `merge-tools.foo.diff-args`, `merge-tools.foo.edit-args`?
+        ");
+
+        // Names in scoped tables are relative to the root table
+        insta::assert_snapshot!(check(indoc! {"
+            [[--scope]]
+            --when.commands = ['log']
+            ui.colr = 'always'
+        "}), @"colr: Unknown config key `ui.colr` Did you mean `ui.color`, `ui.conflict-marker-style`?");
+    }
+
     #[test]
     fn test_resolved_config_values_empty() {
         let config = StackedConfig::empty();
@@ -19,6 +19,7 @@ use std::iter;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use bstr::BStr;
 use bstr::BString;
@@ -49,6 +50,8 @@ use jj_lib::copies::CopyRecords;
 use jj_lib::diff::ContentDiff;
 use jj_lib::diff::DiffHunk;
 use jj_lib::diff::DiffHunkKind;
+use jj_lib::diff_presentation::DiffAlgorithm;
+use jj_lib::diff_presentation::DiffAlgorithms;
 use jj_lib::diff_presentation::DiffTokenType;
 use jj_lib::diff_presentation::FileContent;
 use jj_lib::diff_presentation::LineCompareMode;
@@ -64,6 +67,8 @@ use jj_lib::files::ConflictDiffHunk;
 use jj_lib::files::DiffLineHunkSide;
 use jj_lib::files::DiffLineIterator;
 use jj_lib::files::DiffLineNumber;
+use jj_lib::gitattributes::GitAttributes;
+use jj_lib::gitattributes::PathAttributes;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
 use jj_lib::merge::Merge;
@@ -256,7 +261,7 @@ impl BuiltinFormatKind {
         match self {
             Self::Summary => Ok(DiffFormat::Summary),
             Self::Stat => {
-                let mut options = DiffStatOptions::default();
+                let mut options = DiffStatOptions::from_settings(settings)?;
                 options.merge_args(args);
                 Ok(DiffFormat::Stat(Box::new(options)))
             }
@@ -475,6 +480,7 @@ impl<'a> DiffRenderer<'a> {
                 .diff_stream_with_copies(trees.after, matcher, copy_records)
         };
         let conflict_labels = trees.map(|tree| tree.labels());
+        let attributes = GitAttributes::new(trees.after.clone());
 
         let store = self.repo.store();
         let path_converter = self.path_converter;
@@ -486,9 +492,14 @@ impl<'a> DiffRenderer<'a> {
                 }
                 DiffFormat::Stat(options) => {
                     let tree_diff = diff_stream();
-                    let stats =
-                        DiffStats::calculate(store, tree_diff, options, self.conflict_marker_style)
-                            .block_on()?;
+                    let stats = DiffStats::calculate(
+                        store,
+                        tree_diff,
+                        &attributes,
+                        options,
+                        self.conflict_marker_style,
+                    )
+                    .block_on()?;
                     show_diff_stats(formatter, &stats, path_converter, width)?;
                 }
                 DiffFormat::Types => {
@@ -506,6 +517,7 @@ impl<'a> DiffRenderer<'a> {
                         store,
                         tree_diff,
                         conflict_labels,
+                        &attributes,
                         options,
                         self.conflict_marker_style,
                     )
@@ -518,6 +530,7 @@ impl<'a> DiffRenderer<'a> {
                         store,
                         tree_diff,
                         conflict_labels,
+                        &attributes,
                         path_converter,
                         options,
                         self.conflict_marker_style,
@@ -683,7 +696,7 @@ impl<'a> DiffRenderer<'a> {
 }
 
 pub fn get_copy_records<'a>(
-    store: &'a Store,
+    store: &'a Arc<Store>,
     root: &CommitId,
     head: &CommitId,
     matcher: &'a dyn Matcher,
@@ -709,10 +722,44 @@ pub enum ConflictDiffMethod {
 pub struct LineDiffOptions {
     /// How equivalence of lines is tested.
     pub compare_mode: LineCompareMode,
+    /// Algorithm to find matching lines in the current file.
+    pub algorithm: DiffAlgorithm,
+    /// Algorithms selected per file by the `diff.algorithms` table.
+    pub algorithms: Arc<DiffAlgorithms>,
     // TODO: add --ignore-blank-lines, etc. which aren't mutually exclusive.
 }
 
 impl LineDiffOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            compare_mode: LineCompareMode::default(),
+            algorithm: DiffAlgorithm::default(),
+            algorithms: Arc::new(DiffAlgorithms::from_settings(settings)?),
+        })
+    }
+
+    /// Returns options with the algorithm selected for the file at `path`.
+    ///
+    /// A `diff=<algorithm>` attribute takes precedence over the
+    /// `diff.algorithms` table.
+    fn for_path(&self, path: &RepoPath, attributes: &PathAttributes) -> Self {
+        let algorithm = attributes
+            .value("diff")
+            .and_then(DiffAlgorithm::from_name)
+            .unwrap_or_else(|| self.algorithms.for_path(path));
+        Self {
+            algorithm,
+            ..self.clone()
+        }
+    }
+
+    fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
+        &self,
+        inputs: impl IntoIterator<Item = &'input T>,
+    ) -> ContentDiff<'input> {
+        diff_by_line(inputs, self.algorithm, &self.compare_mode)
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         self.compare_mode = if args.ignore_all_space {
             LineCompareMode::IgnoreAllSpace
@@ -752,11 +799,18 @@ impl ColorWordsDiffOptions {
         Ok(Self {
             conflict: settings.get("diff.color-words.conflict")?,
             context: settings.get("diff.color-words.context")?,
-            line_diff: LineDiffOptions::default(),
+            line_diff: LineDiffOptions::from_settings(settings)?,
             max_inline_alternation,
         })
     }
 
+    fn for_path(&self, path: &RepoPath, attributes: &PathAttributes) -> Self {
+        Self {
+            line_diff: self.line_diff.for_path(path, attributes),
+            ..self.clone()
+        }
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         if let Some(context) = args.context {
             self.context = context;
@@ -815,10 +869,9 @@ fn show_color_words_conflict_hunks(
     options: &ColorWordsDiffOptions,
 ) -> io::Result<DiffLineNumber> {
     let num_lefts = contents.before.as_slice().len();
-    let line_diff = diff_by_line(
-        itertools::chain(contents.before, contents.after),
-        &options.line_diff.compare_mode,
-    );
+    let line_diff = options
+        .line_diff
+        .diff_by_line(itertools::chain(contents.before, contents.after));
     // Matching entries shouldn't appear consecutively in diff of two inputs.
     // However, if the inputs have conflicts, there may be a hunk that can be
     // resolved, resulting [matching, resolved, matching] sequence.
@@ -949,7 +1002,7 @@ fn show_color_words_resolved_hunks(
     labels: Diff<&str>,
     options: &ColorWordsDiffOptions,
 ) -> io::Result<DiffLineNumber> {
-    let line_diff = diff_by_line(contents.into_array(), &options.line_diff.compare_mode);
+    let line_diff = options.line_diff.diff_by_line(contents.into_array());
     // Matching entries shouldn't appear consecutively in diff of two inputs.
     let mut context: Option<Diff<&BStr>> = None;
     let mut emitted = false;
@@ -1359,11 +1412,19 @@ fn basic_diff_file_type(value: &MaterializedTreeValue) -> &'static str {
     }
 }
 
+/// Returns true if the file should be shown as binary regardless of its
+/// contents because the `diff` attribute is unset.
+fn is_binary_by_attributes(attributes: &PathAttributes) -> bool {
+    attributes.is_unset("diff")
+}
+
+#[expect(clippy::too_many_arguments)]
 pub async fn show_color_words_diff(
     formatter: &mut dyn Formatter,
     store: &Store,
     tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
     conflict_labels: Diff<&ConflictLabels>,
+    attributes: &GitAttributes,
     path_converter: &RepoPathUiConverter,
     options: &ColorWordsDiffOptions,
     marker_style: ConflictMarkerStyle,
@@ -1378,12 +1439,15 @@ pub async fn show_color_words_diff(
     while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
         let left_path = path.source();
         let right_path = path.target();
+        let path_attributes = attributes.get(right_path).await?;
+        let options = &options.for_path(right_path, &path_attributes);
         let left_ui_path = path_converter.format_file_path(left_path);
         let right_ui_path = path_converter.format_file_path(right_path);
         let Diff {
             before: left_value,
             after: right_value,
         } = values?;
+        let binary_by_attributes = is_binary_by_attributes(&path_attributes);
 
         match (&left_value, &right_value) {
             (MaterializedTreeValue::AccessDenied(source), _) => {
@@ -1413,7 +1477,7 @@ pub async fn show_color_words_diff(
             let right_content = diff_content_as_merge(right_path, right_value)?;
             if right_content.contents.is_empty() {
                 writeln!(formatter.labeled("empty"), "    (empty)")?;
-            } else if right_content.is_binary {
+            } else if right_content.is_binary || binary_by_attributes {
                 writeln!(formatter.labeled("binary"), "    (binary)")?;
             } else {
                 show_color_words_diff_hunks(
@@ -1484,7 +1548,7 @@ pub async fn show_color_words_diff(
                     "{description} {right_ui_path} ({left_ui_path} => {right_ui_path}):"
                 )?;
             }
-            if left_content.is_binary || right_content.is_binary {
+            if left_content.is_binary || right_content.is_binary || binary_by_attributes {
                 writeln!(formatter.labeled("binary"), "    (binary)")?;
             } else if left_content.contents != right_content.contents {
                 show_color_words_diff_hunks(
@@ -1510,7 +1574,7 @@ pub async fn show_color_words_diff(
             let left_content = diff_content_as_merge(left_path, left_value)?;
             if left_content.contents.is_empty() {
                 writeln!(formatter.labeled("empty"), "    (empty)")?;
-            } else if left_content.is_binary {
+            } else if left_content.is_binary || binary_by_attributes {
                 writeln!(formatter.labeled("binary"), "    (binary)")?;
             } else {
                 show_color_words_diff_hunks(
@@ -1627,10 +1691,17 @@ impl UnifiedDiffOptions {
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         Ok(Self {
             context: settings.get("diff.git.context")?,
-            line_diff: LineDiffOptions::default(),
+            line_diff: LineDiffOptions::from_settings(settings)?,
         })
     }
 
+    fn for_path(&self, path: &RepoPath, attributes: &PathAttributes) -> Self {
+        Self {
+            line_diff: self.line_diff.for_path(path, attributes),
+            ..self.clone()
+        }
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         if let Some(context) = args.context {
             self.context = context;
@@ -1642,6 +1713,7 @@ impl UnifiedDiffOptions {
 fn show_unified_diff_hunks(
     formatter: &mut dyn Formatter,
     contents: Diff<&BStr>,
+    line_offsets: Diff<usize>,
     options: &UnifiedDiffOptions,
 ) -> io::Result<()> {
     // "If the chunk size is 0, the first number is one lower than one would
@@ -1659,13 +1731,18 @@ fn show_unified_diff_hunks(
         }
     }
 
-    for hunk in unified_diff_hunks(contents, options.context, options.line_diff.compare_mode) {
+    for hunk in unified_diff_hunks(
+        contents,
+        options.context,
+        options.line_diff.algorithm,
+        options.line_diff.compare_mode,
+    ) {
         writeln!(
             formatter.labeled("hunk_header"),
             "@@ -{},{} +{},{} @@",
-            to_line_number(hunk.left_line_range.clone()),
+            to_line_number(hunk.left_line_range.clone()) + line_offsets.before,
             hunk.left_line_range.len(),
-            to_line_number(hunk.right_line_range.clone()),
+            to_line_number(hunk.right_line_range.clone()) + line_offsets.after,
             hunk.right_line_range.len()
         )?;
         for (line_type, tokens) in &hunk.lines {
@@ -1685,6 +1762,41 @@ fn show_unified_diff_hunks(
     Ok(())
 }
 
+/// Shows the changes in a range of lines in the git diff format. The line
+/// numbers in hunk headers are offset by the start of the ranges.
+///
+/// Paths are `None` if the file doesn't exist on that side.
+pub fn show_git_diff_line_range(
+    formatter: &mut dyn Formatter,
+    paths: Diff<Option<&RepoPath>>,
+    ranges: Diff<Range<usize>>,
+    contents: Diff<&BStr>,
+    options: &UnifiedDiffOptions,
+) -> io::Result<()> {
+    {
+        let mut formatter = formatter.labeled("file_header");
+        match paths.before {
+            Some(path) => writeln!(formatter, "--- a/{}", path.as_internal_file_string())?,
+            None => writeln!(formatter, "--- /dev/null")?,
+        }
+        match paths.after {
+            Some(path) => writeln!(formatter, "+++ b/{}", path.as_internal_file_string())?,
+            None => writeln!(formatter, "+++ /dev/null")?,
+        }
+    }
+    // Show all lines in the ranges as context.
+    let options = UnifiedDiffOptions {
+        context: ranges.before.len().max(ranges.after.len()),
+        ..options.clone()
+    };
+    show_unified_diff_hunks(
+        formatter,
+        contents,
+        ranges.map(|range| range.start),
+        &options,
+    )
+}
+
 fn show_diff_line_tokens(
     formatter: &mut dyn Formatter,
     tokens: &[(DiffTokenType, &[u8])],
@@ -1703,6 +1815,7 @@ pub async fn show_git_diff(
     store: &Store,
     tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
     conflict_labels: Diff<&ConflictLabels>,
+    attributes: &GitAttributes,
     options: &UnifiedDiffOptions,
     marker_style: ConflictMarkerStyle,
 ) -> Result<(), DiffRenderError> {
@@ -1715,6 +1828,8 @@ pub async fn show_git_diff(
     while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
         let left_path = path.source();
         let right_path = path.target();
+        let path_attributes = attributes.get(right_path).await?;
+        let options = &options.for_path(right_path, &path_attributes);
         let left_path_string = left_path.as_internal_file_string();
         let right_path_string = right_path.as_internal_file_string();
         let values = values?;
@@ -1775,7 +1890,10 @@ pub async fn show_git_diff(
             Some(_) => format!("b/{right_path_string}"),
             None => "/dev/null".to_owned(),
         };
-        if left_part.content.is_binary || right_part.content.is_binary {
+        if left_part.content.is_binary
+            || right_part.content.is_binary
+            || is_binary_by_attributes(&path_attributes)
+        {
             // TODO: add option to emit Git binary diff
             writeln!(
                 formatter,
@@ -1787,6 +1905,7 @@ pub async fn show_git_diff(
             show_unified_diff_hunks(
                 formatter,
                 Diff::new(&left_part.content.contents, &right_part.content.contents).map(BStr::new),
+                Diff::new(0, 0),
                 options,
             )?;
         }
@@ -1820,7 +1939,12 @@ fn show_git_diff_texts<T: AsRef<[u8]>>(
             materialize_options,
         )),
     });
-    show_unified_diff_hunks(formatter, contents.as_ref().map(Cow::as_ref), options)
+    show_unified_diff_hunks(
+        formatter,
+        contents.as_ref().map(Cow::as_ref),
+        Diff::new(0, 0),
+        options,
+    )
 }
 
 #[instrument(skip_all)]
@@ -1876,6 +2000,12 @@ pub struct DiffStatOptions {
 }
 
 impl DiffStatOptions {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            line_diff: LineDiffOptions::from_settings(settings)?,
+        })
+    }
+
     fn merge_args(&mut self, args: &DiffFormatArgs) {
         self.line_diff.merge_args(args);
     }
@@ -1891,6 +2021,7 @@ impl DiffStats {
     pub async fn calculate(
         store: &Store,
         tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+        attributes: &GitAttributes,
         options: &DiffStatOptions,
         marker_style: ConflictMarkerStyle,
     ) -> BackendResult<Self> {
@@ -1905,16 +2036,24 @@ impl DiffStats {
             tree_diff,
             Diff::new(&conflict_labels, &conflict_labels),
         )
-        .map(|MaterializedTreeDiffEntry { path, values }| {
+        .then(|MaterializedTreeDiffEntry { path, values }| async {
             let values = values?;
             let status =
                 diff_status_inner(&path, values.before.is_present(), values.after.is_present());
-            let left_content = diff_content(path.source(), values.before, &materialize_options)?;
-            let right_content = diff_content(path.target(), values.after, &materialize_options)?;
+            let mut left_content =
+                diff_content(path.source(), values.before, &materialize_options)?;
+            let mut right_content =
+                diff_content(path.target(), values.after, &materialize_options)?;
+            let path_attributes = attributes.get(path.target()).await?;
+            if is_binary_by_attributes(&path_attributes) {
+                left_content.is_binary = true;
+                right_content.is_binary = true;
+            }
             let stat = get_diff_stat_entry(
                 path,
                 status,
                 Diff::new(&left_content, &right_content),
+                &path_attributes,
                 options,
             );
             BackendResult::Ok(stat)
@@ -1991,15 +2130,16 @@ fn get_diff_stat_entry(
     path: CopiesTreeDiffEntryPath,
     status: DiffEntryStatus,
     contents: Diff<&FileContent<BString>>,
+    attributes: &PathAttributes,
     options: &DiffStatOptions,
 ) -> DiffStatEntry {
     let added_removed = if contents.before.is_binary || contents.after.is_binary {
         None
     } else {
-        let diff = diff_by_line(
-            contents.map(|content| &content.contents).into_array(),
-            &options.line_diff.compare_mode,
-        );
+        let diff = options
+            .line_diff
+            .for_path(path.target(), attributes)
+            .diff_by_line(contents.map(|content| &content.contents).into_array());
         let mut added = 0;
         let mut removed = 0;
         for hunk in diff.hunks() {
@@ -18,6 +18,7 @@ use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::io;
+use std::sync::Arc;
 
 use itertools::Itertools as _;
 use jj_lib::extensions_map::ExtensionsMap;
@@ -26,6 +27,11 @@ use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+use jj_lib::signing::SignResult;
+use jj_lib::signing::Verification;
+use jj_lib::store::Store;
 
 use crate::template_builder;
 use crate::template_builder::BuildContext;
@@ -156,6 +162,7 @@ where
     Self: WrapTemplateProperty<'a, Option<Operation>>,
     Self: WrapTemplateProperty<'a, Vec<Operation>>,
     Self: WrapTemplateProperty<'a, OperationId>,
+    Self: WrapTemplateProperty<'a, Option<OperationSignature>>,
 {
 }
 
@@ -165,6 +172,7 @@ pub enum OperationTemplatePropertyKind<'a> {
     OperationOpt(BoxedTemplateProperty<'a, Option<Operation>>),
     OperationList(BoxedTemplateProperty<'a, Vec<Operation>>),
     OperationId(BoxedTemplateProperty<'a, OperationId>),
+    OperationSignatureOpt(BoxedTemplateProperty<'a, Option<OperationSignature>>),
 }
 
 /// Implements `WrapTemplateProperty<type>` for operation property types.
@@ -178,6 +186,7 @@ macro_rules! impl_operation_property_wrappers {
             OperationOpt(Option<jj_lib::operation::Operation>),
             OperationList(Vec<jj_lib::operation::Operation>),
             OperationId(jj_lib::op_store::OperationId),
+            OperationSignatureOpt(Option<$crate::operation_templater::OperationSignature>),
         });
     };
 }
@@ -193,6 +202,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(_) => "Option<Operation>",
             Self::OperationList(_) => "List<Operation>",
             Self::OperationId(_) => "OperationId",
+            Self::OperationSignatureOpt(_) => "Option<OperationSignature>",
         }
     }
 
@@ -202,6 +212,9 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(property) => Some(property.map(|opt| opt.is_some()).into_dyn()),
             Self::OperationList(property) => Some(property.map(|l| !l.is_empty()).into_dyn()),
             Self::OperationId(_) => None,
+            Self::OperationSignatureOpt(property) => {
+                Some(property.map(|sig| sig.is_some()).into_dyn())
+            }
         }
     }
 
@@ -220,6 +233,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(property) => Some(property.into_serialize()),
             Self::OperationList(property) => Some(property.into_serialize()),
             Self::OperationId(property) => Some(property.into_serialize()),
+            Self::OperationSignatureOpt(_) => None,
         }
     }
 
@@ -229,6 +243,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             Self::OperationOpt(_) => None,
             Self::OperationList(_) => None,
             Self::OperationId(property) => Some(property.into_template()),
+            Self::OperationSignatureOpt(_) => None,
         }
     }
 
@@ -238,6 +253,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 
@@ -250,6 +266,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 
@@ -259,6 +276,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 
@@ -271,6 +289,7 @@ impl<'a> OperationTemplatePropertyKind<'a> {
             (Self::OperationOpt(_), _) => None,
             (Self::OperationList(_), _) => None,
             (Self::OperationId(_), _) => None,
+            (Self::OperationSignatureOpt(_), _) => None,
         }
     }
 }
@@ -363,6 +382,7 @@ pub struct OperationTemplateBuildFnTable<'a, L: ?Sized, P = <L as TemplateLangua
     pub operation_methods: TemplateBuildMethodFnMap<'a, L, Operation, P>,
     pub operation_list_methods: TemplateBuildMethodFnMap<'a, L, Vec<Operation>, P>,
     pub operation_id_methods: TemplateBuildMethodFnMap<'a, L, OperationId, P>,
+    pub operation_signature_methods: TemplateBuildMethodFnMap<'a, L, OperationSignature, P>,
 }
 
 impl<L: ?Sized, P> OperationTemplateBuildFnTable<'_, L, P> {
@@ -371,6 +391,7 @@ impl<L: ?Sized, P> OperationTemplateBuildFnTable<'_, L, P> {
             operation_methods: HashMap::new(),
             operation_list_methods: HashMap::new(),
             operation_id_methods: HashMap::new(),
+            operation_signature_methods: HashMap::new(),
         }
     }
 
@@ -379,11 +400,16 @@ impl<L: ?Sized, P> OperationTemplateBuildFnTable<'_, L, P> {
             operation_methods,
             operation_list_methods,
             operation_id_methods,
+            operation_signature_methods,
         } = other;
 
         merge_fn_map(&mut self.operation_methods, operation_methods);
         merge_fn_map(&mut self.operation_list_methods, operation_list_methods);
         merge_fn_map(&mut self.operation_id_methods, operation_id_methods);
+        merge_fn_map(
+            &mut self.operation_signature_methods,
+            operation_signature_methods,
+        );
     }
 }
 
@@ -398,6 +424,7 @@ where
             operation_methods: builtin_operation_methods(),
             operation_list_methods: template_builder::builtin_unformattable_list_methods(),
             operation_id_methods: builtin_operation_id_methods(),
+            operation_signature_methods: builtin_operation_signature_methods(),
         }
     }
 
@@ -435,6 +462,13 @@ where
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            OperationTemplatePropertyKind::OperationSignatureOpt(property) => {
+                let type_name = "OperationSignature";
+                let table = &self.operation_signature_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name).into_dyn();
+                build(language, diagnostics, build_ctx, inner_property, function)
+            }
         }
     }
 }
@@ -565,6 +599,16 @@ where
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "signature",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language.repo_loader().store().clone();
+            let out_property =
+                self_property.map(move |op| OperationSignature::new(op, store.clone()));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -606,3 +650,78 @@ where
     );
     map
 }
+
+#[derive(Debug)]
+pub struct OperationSignature {
+    operation: Operation,
+    store: Arc<Store>,
+}
+
+impl OperationSignature {
+    fn new(operation: Operation, store: Arc<Store>) -> Option<Self> {
+        operation.is_signed().then_some(Self { operation, store })
+    }
+
+    fn verify(&self) -> SignResult<Verification> {
+        self.operation
+            .verification(self.store.signer())
+            .transpose()
+            .expect("must have signature")
+    }
+
+    fn status(&self) -> SignResult<SigStatus> {
+        self.verify().map(|verification| verification.status)
+    }
+
+    /// Defaults to empty string if key is not present.
+    fn key(&self) -> SignResult<String> {
+        self.verify()
+            .map(|verification| verification.key.unwrap_or_default())
+    }
+
+    /// Defaults to empty string if display is not present.
+    fn display(&self) -> SignResult<String> {
+        self.verify()
+            .map(|verification| verification.display.unwrap_or_default())
+    }
+}
+
+fn builtin_operation_signature_methods<'a, L>()
+-> TemplateBuildMethodFnMap<'a, L, OperationSignature>
+where
+    L: TemplateLanguage<'a> + OperationTemplateEnvironment + ?Sized,
+    L::Property: OperationTemplatePropertyVar<'a>,
+{
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, OperationSignature>::new();
+    map.insert(
+        "status",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| match sig.status() {
+                Ok(status) => Ok(status.to_string()),
+                Err(SignError::InvalidSignatureFormat) => Ok("invalid".to_string()),
+                Err(err) => Err(err.into()),
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| Ok(sig.key()?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map.insert(
+        "display",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| Ok(sig.display()?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
+    map
+}
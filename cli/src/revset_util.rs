@@ -15,7 +15,12 @@
 //! Utility for parsing and evaluating user-provided revset expressions.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Write as _;
+use std::process::Stdio;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
@@ -37,6 +42,7 @@ use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetExtensions;
+use jj_lib::revset::RevsetFilterExtension;
 use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::RevsetParseError;
@@ -45,21 +51,24 @@ use jj_lib::revset::SymbolResolver;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::revset::UserRevsetExpression;
 use jj_lib::settings::RemoteSettingsMap;
+use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringExpression;
 use jj_lib::str_util::StringMatcher;
 use thiserror::Error;
 
 use crate::command_error::CommandError;
+use crate::command_error::config_error;
 use crate::command_error::config_error_with_message;
 use crate::command_error::print_parse_diagnostics;
 use crate::command_error::revset_parse_error_hint;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
+use crate::config::CommandNameAndArgs;
 use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
-const USER_IMMUTABLE_HEADS: &str = "immutable_heads";
+pub(crate) const USER_IMMUTABLE_HEADS: &str = "immutable_heads";
 
 #[derive(Debug, Error)]
 pub enum UserRevsetEvaluationError {
@@ -153,6 +162,7 @@ fn warn_user_redefined_builtin(
         ConfigSource::Default => (),
         ConfigSource::EnvBase
         | ConfigSource::User
+        | ConfigSource::Tree
         | ConfigSource::Repo
         | ConfigSource::Workspace
         | ConfigSource::EnvOverrides
@@ -212,6 +222,132 @@ pub fn load_revset_aliases(
     Ok(aliases_map)
 }
 
+/// Error occurred while running external revset filter.
+#[derive(Debug, Error)]
+#[error("Failed to run revset filter `{name}()`")]
+pub struct ExternalRevsetFilterError {
+    name: String,
+    #[source]
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+/// Revset filter that selects commits by external program.
+///
+/// The program receives commit ids through stdin, one per line, and prints the
+/// ids of the matching commits. Results are cached per commit id.
+pub struct ExternalRevsetFilter {
+    name: String,
+    command: CommandNameAndArgs,
+    cache: Mutex<HashMap<CommitId, bool>>,
+}
+
+impl fmt::Debug for ExternalRevsetFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternalRevsetFilter")
+            .field("name", &self.name)
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExternalRevsetFilter {
+    pub fn new(name: String, command: CommandNameAndArgs) -> Self {
+        Self {
+            name,
+            command,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn run_command(
+        &self,
+        commit_ids: &[&CommitId],
+    ) -> Result<HashSet<CommitId>, ExternalRevsetFilterError> {
+        let to_error =
+            |source: Box<dyn std::error::Error + Send + Sync>| ExternalRevsetFilterError {
+                name: self.name.clone(),
+                source,
+            };
+        let mut child = self
+            .command
+            .to_command()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| to_error(err.into()))?;
+        let mut stdin = child.stdin.take().unwrap();
+        let input = commit_ids.iter().map(|id| format!("{id}\n")).join("");
+        let output = std::thread::scope(|s| {
+            s.spawn(move || {
+                // The program may exit without reading all inputs.
+                stdin.write_all(input.as_bytes()).ok();
+            });
+            child.wait_with_output()
+        })
+        .map_err(|err| to_error(err.into()))?;
+        if !output.status.success() {
+            return Err(to_error(
+                format!("Process exited with {}", output.status).into(),
+            ));
+        }
+        let stdout = String::from_utf8(output.stdout).map_err(|err| to_error(err.into()))?;
+        stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                CommitId::try_from_hex(line).ok_or_else(|| {
+                    to_error(format!("Invalid commit id in output: {line:?}").into())
+                })
+            })
+            .try_collect()
+    }
+}
+
+impl RevsetFilterExtension for ExternalRevsetFilter {
+    fn matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError> {
+        let matches = self.matches_commits(std::slice::from_ref(commit))?;
+        Ok(matches == [true])
+    }
+
+    fn matches_commits(&self, commits: &[Commit]) -> Result<Vec<bool>, RevsetEvaluationError> {
+        let mut cache = self.cache.lock().unwrap();
+        let uncached_ids = commits
+            .iter()
+            .map(|commit| commit.id())
+            .filter(|id| !cache.contains_key(id))
+            .unique()
+            .collect_vec();
+        if !uncached_ids.is_empty() {
+            let matched_ids = self
+                .run_command(&uncached_ids)
+                .map_err(|err| RevsetEvaluationError::Other(err.into()))?;
+            for id in uncached_ids {
+                cache.insert(id.clone(), matched_ids.contains(id));
+            }
+        }
+        Ok(commits.iter().map(|commit| cache[commit.id()]).collect())
+    }
+}
+
+/// Registers entries of the `revset-filters` table as revset functions.
+pub fn load_revset_filters(
+    settings: &UserSettings,
+    extensions: &mut RevsetExtensions,
+) -> Result<(), CommandError> {
+    for name in settings.table_keys("revset-filters").sorted() {
+        if extensions.has_function(name) {
+            return Err(config_error(format!(
+                "Revset filter `{name}` conflicts with the built-in function `{name}()`"
+            )));
+        }
+        let command: CommandNameAndArgs = settings.get(["revset-filters", name])?;
+        let filter = ExternalRevsetFilter::new(name.to_owned(), command);
+        extensions.add_filter_function(name.to_owned(), Arc::new(filter));
+    }
+    Ok(())
+}
+
 /// Wraps the given `IdPrefixContext` in `SymbolResolver` to be passed in to
 /// `evaluate()`.
 pub fn default_symbol_resolver<'a>(
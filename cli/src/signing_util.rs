@@ -0,0 +1,127 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Enforcement of the `signing.require` policy, and verification of signed
+//! operations.
+
+use std::fmt;
+
+use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetError;
+use jj_lib::operation::Operation;
+use jj_lib::settings::UserSettings;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+use jj_lib::signing::Signer;
+use jj_lib::signing::Verification;
+
+use crate::cli_util::RevisionArg;
+
+/// Commits that must carry a good signature from one of the allowed keys.
+#[derive(Clone, Debug)]
+pub struct SignaturePolicy {
+    /// Revset of the commits the policy applies to.
+    pub require: RevisionArg,
+    /// Keys accepted for required commits. Any key is accepted if empty.
+    pub allowed_keys: Vec<String>,
+}
+
+/// Why a commit doesn't satisfy the [`SignaturePolicy`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SignatureProblem {
+    NotSigned,
+    Invalid,
+    Bad,
+    Unknown,
+    KeyNotAllowed(Option<String>),
+}
+
+impl SignaturePolicy {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            require: RevisionArg::from(settings.get_string("signing.require")?),
+            allowed_keys: settings.get("signing.allowed-keys")?,
+        })
+    }
+
+    /// Checks the signature of the `commit`, regardless of whether the commit
+    /// is required to be signed.
+    pub fn check(&self, commit: &Commit) -> Result<Option<SignatureProblem>, SignError> {
+        self.check_verification(commit.verification())
+    }
+
+    /// Checks the signature of the `operation`.
+    pub fn check_operation(
+        &self,
+        operation: &Operation,
+        signer: &Signer,
+    ) -> Result<Option<SignatureProblem>, SignError> {
+        self.check_verification(operation.verification(signer))
+    }
+
+    fn check_verification(
+        &self,
+        verification: Result<Option<Verification>, SignError>,
+    ) -> Result<Option<SignatureProblem>, SignError> {
+        let verification = match verification {
+            Ok(Some(verification)) => verification,
+            Ok(None) => return Ok(Some(SignatureProblem::NotSigned)),
+            Err(SignError::InvalidSignatureFormat) => return Ok(Some(SignatureProblem::Invalid)),
+            Err(err) => return Err(err),
+        };
+        let problem = match verification.status {
+            SigStatus::Good => {
+                let allowed = self.allowed_keys.is_empty()
+                    || verification
+                        .key
+                        .as_ref()
+                        .is_some_and(|key| self.allowed_keys.contains(key));
+                (!allowed).then_some(SignatureProblem::KeyNotAllowed(verification.key))
+            }
+            SigStatus::Unknown => Some(SignatureProblem::Unknown),
+            SigStatus::Bad => Some(SignatureProblem::Bad),
+        };
+        Ok(problem)
+    }
+}
+
+impl SignatureProblem {
+    /// Describes the problem as the reason a commit is rejected, such as "it is
+    /// not signed".
+    pub fn reason(&self) -> String {
+        match self {
+            Self::NotSigned => "it is not signed".to_owned(),
+            Self::Invalid => "its signature is invalid".to_owned(),
+            Self::Bad => "its signature is bad".to_owned(),
+            Self::Unknown => "its signature could not be verified".to_owned(),
+            Self::KeyNotAllowed(Some(key)) => {
+                format!("it is signed with a key that is not allowed ({key})")
+            }
+            Self::KeyNotAllowed(None) => "it is signed with an unidentified key".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for SignatureProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSigned => write!(f, "not signed"),
+            Self::Invalid => write!(f, "invalid signature"),
+            Self::Bad => write!(f, "bad signature"),
+            Self::Unknown => write!(f, "unknown signature"),
+            Self::KeyNotAllowed(Some(key)) => write!(f, "key {key} not allowed"),
+            Self::KeyNotAllowed(None) => write!(f, "unidentified key"),
+        }
+    }
+}
@@ -28,6 +28,8 @@ use std::mem;
 use std::process::Child;
 use std::process::ChildStdin;
 use std::process::Stdio;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
 use std::thread;
 use std::thread::JoinHandle;
 
@@ -61,6 +63,8 @@ enum UiOutput {
         err_wr: PipeWriter,
         pager_thread: JoinHandle<streampager::Result<()>>,
     },
+    /// Both stdout and stderr are collected in memory.
+    Captured(Mutex<Vec<u8>>),
     Null,
 }
 
@@ -154,6 +158,7 @@ impl UiOutput {
                     }
                 }
             }
+            Self::Captured(_) => {}
             Self::Null => {}
         }
     }
@@ -163,6 +168,7 @@ pub enum UiStdout<'a> {
     Terminal(StdoutLock<'static>),
     Paged(&'a ChildStdin),
     Builtin(&'a PipeWriter),
+    Captured(CapturedOutput<'a>),
     Null(io::Sink),
 }
 
@@ -170,6 +176,7 @@ pub enum UiStderr<'a> {
     Terminal(StderrLock<'static>),
     Paged(&'a ChildStdin),
     Builtin(&'a PipeWriter),
+    Captured(CapturedOutput<'a>),
     Null(io::Sink),
 }
 
@@ -179,6 +186,7 @@ macro_rules! for_outputs {
             $ty::Terminal($pat) => $expr,
             $ty::Paged($pat) => $expr,
             $ty::Builtin($pat) => $expr,
+            $ty::Captured($pat) => $expr,
             $ty::Null($pat) => $expr,
         }
     };
@@ -212,6 +220,19 @@ impl Write for UiStderr<'_> {
     }
 }
 
+/// Writer to the in-memory buffer of a `Ui` created by [`Ui::captured()`].
+pub struct CapturedOutput<'a>(MutexGuard<'a, Vec<u8>>);
+
+impl Write for CapturedOutput<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 pub struct Ui {
     quiet: bool,
     pager: PagerConfig,
@@ -351,6 +372,27 @@ impl Ui {
         }
     }
 
+    /// Creates a `Ui` that collects its output in memory instead of printing
+    /// it. The output can be obtained by [`Ui::take_captured_output()`].
+    pub fn captured(&self) -> Self {
+        Self {
+            quiet: self.quiet,
+            pager: PagerConfig::Disabled,
+            progress_indicator: false,
+            formatter_factory: FormatterFactory::plain_text(),
+            output: UiOutput::Captured(Mutex::new(vec![])),
+        }
+    }
+
+    /// Takes the output collected so far by a `Ui` created by
+    /// [`Ui::captured()`]. Returns an empty buffer for other `Ui`s.
+    pub fn take_captured_output(&self) -> Vec<u8> {
+        match &self.output {
+            UiOutput::Captured(buffer) => mem::take(&mut *buffer.lock().unwrap()),
+            _ => vec![],
+        }
+    }
+
     pub fn with_config(config: &StackedConfig) -> Result<Self, CommandError> {
         let formatter_factory = prepare_formatter_factory(config, &io::stdout())?;
         Ok(Self {
@@ -431,6 +473,9 @@ impl Ui {
             UiOutput::Terminal { stdout, .. } => UiStdout::Terminal(stdout.lock()),
             UiOutput::Paged { child_stdin, .. } => UiStdout::Paged(child_stdin),
             UiOutput::BuiltinPaged { out_wr, .. } => UiStdout::Builtin(out_wr),
+            UiOutput::Captured(buffer) => {
+                UiStdout::Captured(CapturedOutput(buffer.lock().unwrap()))
+            }
             UiOutput::Null => UiStdout::Null(io::sink()),
         }
     }
@@ -449,6 +494,9 @@ impl Ui {
             UiOutput::Terminal { stderr, .. } => UiStderr::Terminal(stderr.lock()),
             UiOutput::Paged { child_stdin, .. } => UiStderr::Paged(child_stdin),
             UiOutput::BuiltinPaged { err_wr, .. } => UiStderr::Builtin(err_wr),
+            UiOutput::Captured(buffer) => {
+                UiStderr::Captured(CapturedOutput(buffer.lock().unwrap()))
+            }
             UiOutput::Null => UiStderr::Null(io::sink()),
         }
     }
@@ -464,7 +512,7 @@ impl Ui {
             UiOutput::Terminal { .. } => Ok(Stdio::inherit()),
             UiOutput::Paged { child_stdin, .. } => Ok(duplicate_child_stdin(child_stdin)?.into()),
             UiOutput::BuiltinPaged { err_wr, .. } => Ok(err_wr.try_clone()?.into()),
-            UiOutput::Null => Ok(Stdio::null()),
+            UiOutput::Captured(_) | UiOutput::Null => Ok(Stdio::null()),
         }
     }
 
@@ -477,6 +525,7 @@ impl Ui {
             }
             UiOutput::Paged { .. } => false,
             UiOutput::BuiltinPaged { .. } => false,
+            UiOutput::Captured(_) => false,
             UiOutput::Null => false,
         }
     }
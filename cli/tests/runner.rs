@@ -34,6 +34,8 @@ mod test_evolog_command;
 mod test_file_annotate_command;
 mod test_file_chmod_command;
 mod test_file_list_command;
+mod test_file_log_command;
+mod test_file_mv_cp_commands;
 mod test_file_search_command;
 mod test_file_show_command;
 mod test_file_track_untrack_commands;
@@ -80,6 +82,7 @@ mod test_squash_command;
 mod test_status_command;
 mod test_tag_command;
 mod test_templater;
+mod test_ui_command;
 mod test_undo_redo_commands;
 mod test_util_command;
 mod test_working_copy;
@@ -83,6 +83,8 @@ fn test_absorb_simple() {
     let output = work_dir.run_jj(["absorb"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
+    Hunks left in the source revision:
+      file1:4: inserted between lines from different revisions
     Nothing changed.
     [EOF]
     ");
@@ -715,6 +717,8 @@ fn test_absorb_from_into() {
     let output = work_dir.run_jj(["absorb", "--into=@-"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
+    Hunks left in the source revision:
+      file1:5: modified lines weren't introduced by the destination revisions
     Absorbed changes into 1 revisions:
       kkmpptxz cae507ef 2
     Rebased 1 descendant commits.
@@ -891,6 +895,8 @@ fn test_absorb_immutable() {
     let output = work_dir.run_jj(["absorb"]);
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
+    Hunks left in the source revision:
+      file1:1: modified lines weren't introduced by the destination revisions
     Absorbed changes into 1 revisions:
       kkmpptxz e68cc3e2 2
     Rebased 1 descendant commits.
@@ -951,6 +957,199 @@ fn test_absorb_immutable() {
     ");
 }
 
+#[test]
+fn test_absorb_heuristics() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m0"]).success();
+    work_dir.write_file("file1", "");
+    work_dir.write_file("file2", "a\nb\nc\n");
+
+    work_dir.run_jj(["new", "-m1"]).success();
+    work_dir.write_file("file1", "1a\n1b\n");
+    work_dir.write_file("file2", "a\nB\nc\n");
+
+    work_dir.run_jj(["new", "-m2"]).success();
+    work_dir.write_file("file1", "1a\n1b\n2a\n2b\n");
+
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "1a\n1b\nX\n2a\n2b\n");
+    work_dir.write_file("file2", "A\nB\nc\nD\n");
+
+    // Without heuristics, nothing can be absorbed
+    let output = work_dir.run_jj(["absorb", "--into=@--|@-"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Hunks left in the source revision:
+      file1:3: inserted between lines from different revisions
+      file2:1: modified lines weren't introduced by the destination revisions
+      file2:4: modified lines weren't introduced by the destination revisions
+    Nothing changed.
+    [EOF]
+    ");
+
+    // The inserted line goes to the descendant, and the inserted line in file2
+    // goes to the only revision which modified the file. The modified line in
+    // file2 came from the immutable revision, so it stays.
+    let output = work_dir.run_jj([
+        "absorb",
+        "--into=@--|@-",
+        "--heuristic=context",
+        "--heuristic=file",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Hunks left in the source revision:
+      file2:1: modified lines weren't introduced by the destination revisions
+    Absorbed changes into 2 revisions:
+      zsuskuln 3e9cd8a2 2
+      kkmpptxz fa96857b 1
+    Rebased 1 descendant commits.
+    Working copy  (@) now at: mzvwutvl 61b988a3 (no description set)
+    Parent commit (@-)      : zsuskuln 3e9cd8a2 2
+    Remaining changes:
+    M file2
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_diffs(&work_dir, "@--|@-"), @"
+    ○  zsuskuln 3e9cd8a2 2
+    │  diff --git a/file1 b/file1
+    │  index 8c5268f893..337fbd2b52 100644
+    │  --- a/file1
+    │  +++ b/file1
+    │  @@ -1,2 +1,5 @@
+    │   1a
+    │   1b
+    │  +X
+    │  +2a
+    │  +2b
+    ○  kkmpptxz fa96857b 1
+    │  diff --git a/file1 b/file1
+    ~  index e69de29bb2..8c5268f893 100644
+       --- a/file1
+       +++ b/file1
+       @@ -0,0 +1,2 @@
+       +1a
+       +1b
+       diff --git a/file2 b/file2
+       index de980441c3..bb79aecde9 100644
+       --- a/file2
+       +++ b/file2
+       @@ -1,3 +1,4 @@
+        a
+       -b
+       +B
+        c
+       +D
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_absorb_interactive() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_diff_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m0"]).success();
+    work_dir.write_file("file1", "");
+
+    work_dir.run_jj(["new", "-m1"]).success();
+    work_dir.write_file("file1", "1a\n1b\n");
+
+    work_dir.run_jj(["new", "-m2"]).success();
+    work_dir.write_file("file1", "1a\n1b\n2a\n2b\n");
+
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "1A\n1b\nX\n2a\n2B\n");
+
+    // Absorb the first and the last hunks. The ambiguous hunk is offered to
+    // both revisions, and left in the source revision.
+    std::fs::write(
+        &edit_script,
+        "write file1\n1A\n1b\n2a\n2B\n\0dump JJ-INSTRUCTIONS instrs",
+    )
+    .unwrap();
+    let output = work_dir.run_jj(["absorb", "--interactive"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Absorbed changes into 2 revisions:
+      zsuskuln 59176b9c 2
+      kkmpptxz 722d87de 1
+    Rebased 1 descendant commits.
+    Working copy  (@) now at: mzvwutvl 7afa0962 (no description set)
+    Parent commit (@-)      : zsuskuln 59176b9c 2
+    Remaining changes:
+    M file1
+    [EOF]
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("instrs")).unwrap(), @"
+    You are absorbing changes from: mzvwutvl 663838f6 (no description set)
+    into commit: zsuskuln 36fad385 2
+
+    The left side of the diff shows the contents of the parent commit. The
+    right side initially shows the changes that can be absorbed into the
+    destination commit:
+      file1:3
+      file1:5 (proposed)
+
+    Adjust the right side until the diff shows the changes you want to absorb
+    into the destination. Changes that aren't absorbed will be offered to the
+    next commit that modified the nearby lines, or left in the source commit.
+    Changes are absorbed by whole hunks; partially edited hunks are skipped.
+    ");
+    insta::assert_snapshot!(get_diffs(&work_dir, "mutable()"), @"
+    @  mzvwutvl 7afa0962 (no description set)
+    │  diff --git a/file1 b/file1
+    │  index 428796ca20..062feb4994 100644
+    │  --- a/file1
+    │  +++ b/file1
+    │  @@ -1,4 +1,5 @@
+    │   1A
+    │   1b
+    │  +X
+    │   2a
+    │   2B
+    ○  zsuskuln 59176b9c 2
+    │  diff --git a/file1 b/file1
+    │  index 7860a02b65..428796ca20 100644
+    │  --- a/file1
+    │  +++ b/file1
+    │  @@ -1,2 +1,4 @@
+    │   1A
+    │   1b
+    │  +2a
+    │  +2B
+    ○  kkmpptxz 722d87de 1
+    │  diff --git a/file1 b/file1
+    │  index e69de29bb2..7860a02b65 100644
+    │  --- a/file1
+    │  +++ b/file1
+    │  @@ -0,0 +1,2 @@
+    │  +1A
+    │  +1b
+    ○  qpvuntsm 6a446874 0
+    │  diff --git a/file1 b/file1
+    ~  new file mode 100644
+       index 0000000000..e69de29bb2
+    [EOF]
+    ");
+
+    // Skip all hunks
+    work_dir.run_jj(["undo"]).success();
+    std::fs::write(&edit_script, "reset file1").unwrap();
+    let output = work_dir.run_jj(["absorb", "--tool=fake-diff-editor"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_diffs(work_dir: &TestWorkDir, revision: &str) -> CommandOutput {
     let template = r#"format_commit_summary_with_refs(self, "") ++ "\n""#;
@@ -561,6 +561,97 @@ fn test_bisect_run_jj_command() {
     ");
 }
 
+#[test]
+fn test_bisect_manual() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["b"]);
+    create_commit(&work_dir, "d", &["c"]);
+    create_commit(&work_dir, "e", &["d"]);
+    create_commit(&work_dir, "f", &["e"]);
+
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "good"]), @r"
+    ------- stderr -------
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "start", "--range=.."]), @r"
+    Now evaluating: royxmykx dffaa0d4 c | c
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: kxryzmor 076636e4 (empty) (no description set)
+    Parent commit (@-)      : royxmykx dffaa0d4 c | c
+    Added 0 files, modified 0 files, removed 3 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "start", "--range=.."]), @r"
+    ------- stderr -------
+    Error: A bisection is already in progress
+    Hint: Use `jj bisect reset` to end it first.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The heads of the range are bad
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "good", "-r=f"]), @r"
+    ------- stderr -------
+    Error: Revision 8b67af288466 is already marked as bad
+    [EOF]
+    [exit status: 1]
+    ");
+
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "bad"]).success().stdout, @r"
+    Now evaluating: rlvkpnrz 7d980be7 a | a
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "good"]).success().stdout, @r"
+    Now evaluating: zsuskuln 123b4d91 b | b
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "bad"]), @r"
+    The first bad revision is: zsuskuln 123b4d91 b | b
+    [EOF]
+    ------- stderr -------
+    Hint: Use `jj bisect reset` to return to the original working-copy commit.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "log"]), @r"
+    bad: royxmykx dffaa0d4 c | c
+    good: rlvkpnrz 7d980be7 a | a
+    bad: zsuskuln 123b4d91 b | b
+    [EOF]
+    ");
+
+    // Reset returns to the original working-copy commit
+    work_dir.run_jj(["bisect", "reset"]).success();
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=@", "-T=description"]);
+    insta::assert_snapshot!(output, @r"
+    f
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "log"]), @r"
+    ------- stderr -------
+    Error: No bisection in progress
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The range is stored by its roots and heads, so it can't have gaps
+    insta::assert_snapshot!(work_dir.run_jj(["bisect", "start", "--range=a|c"]), @"
+    ------- stderr -------
+    Error: The range to bisect must include all revisions between its roots and heads
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"separate(" ",
@@ -519,21 +519,21 @@ fn test_default_command_is_resolved(shell: Shell) {
         Shell::Bash => {
             insta::assert_snapshot!(output, @r"
             --revisions
-            --limit
+            --follow
             [EOF]
             ");
         }
         Shell::Zsh => {
             insta::assert_snapshot!(output, @r"
             --revisions:Which revisions to show
-            --limit:Limit number of revisions to show
+            --follow:Follow the history of a file across renames and copies
             [EOF]
             ");
         }
         Shell::Fish => {
             insta::assert_snapshot!(output, @r"
             --revisions	Which revisions to show
-            --limit	Limit number of revisions to show
+            --follow	Follow the history of a file across renames and copies
             [EOF]
             ");
         }
@@ -710,11 +710,13 @@ fn test_command_completion_short_name() {
     // Short command names should be omitted
     let output = test_env.complete_fish(["config", ""]);
     insta::assert_snapshot!(output, @"
+    check	Check config files for unknown keys and values of invalid types
     edit	Start an editor on a jj config file
     get	Get the value of a given config option.
     list	List variables set in config files, along with their values
     path	Print the paths to the config files
     set	Update a config file to set the given option to a given value
+    trust	Review and trust the config file committed to the repository
     unset	Update a config file to unset the given option
     --repository	Path to repository to operate on
     --ignore-working-copy	Don't snapshot the working copy, and don't update it
@@ -18,6 +18,7 @@ use std::path::PathBuf;
 use indoc::indoc;
 use itertools::Itertools as _;
 use regex::Regex;
+use testutils::git;
 
 use crate::common::TestEnvironment;
 use crate::common::default_config_from_schema;
@@ -1773,6 +1774,220 @@ fn test_config_show_paths() {
     ");
 }
 
+#[test]
+fn test_config_check() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["config", "check"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No problems found in config
+    [EOF]
+    ");
+
+    test_env.add_config(indoc! {"
+        custom-key = 'unchecked'
+        [ui]
+        pagr = 'less'
+        diff-editor = 1
+    "});
+
+    // Problems aren't reported when the config is loaded
+    let output = work_dir.run_jj(["log", "--no-graph", "-r@", "-T=change_id.short()"]);
+    insta::assert_snapshot!(output, @"qpvuntsmwlqt[EOF]");
+
+    // `jj config check` reports them with their locations
+    let output = work_dir.run_jj(["config", "check", "--config=ui.graph.stlye=ascii"]);
+    insta::assert_snapshot!(output, @r"
+    $TEST_ENV/config/config0002.toml:3:1: Unknown config key `ui.pagr`
+      Did you mean `ui.pager`, `ui.paginate`?
+    $TEST_ENV/config/config0002.toml:4:15: Invalid type for `ui.diff-editor`: expected a string or an array or a table
+    cli config: Unknown config key `ui.graph.stlye`
+      Did you mean `ui.graph.style`?
+    [EOF]
+    ------- stderr -------
+    Error: Found 3 problems in config
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_config_trust() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    test_env.add_config(r#"revset-aliases."trunk()" = "main""#);
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".git"));
+    // jj doesn't snapshot files in .jj directories, so commit the file in Git
+    let config_commit = git::add_commit(
+        &git_repo,
+        "refs/heads/main",
+        ".jj/config.toml",
+        indoc! {b"
+            revset-aliases.'mine()' = 'none()'
+            revset-aliases.'immutable_heads()' = 'trunk() | subject(frozen)'
+            ui.merge-editor = 'vimdiff'
+            ui.pagr = 'evil'
+        "},
+        "add config",
+        &[],
+    );
+    // The file is kept in the tree, but isn't checked out
+    let output = work_dir.run_jj(["new", "main"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: Failed to resolve `revset-aliases.trunk()`: Revision `main` doesn't exist
+    The `trunk()` alias is temporarily set to `root()`.
+    Hint: Use `jj config edit --repo` to adjust the `trunk()` alias.
+    Done importing changes from the underlying Git repo.
+    Working copy  (@) now at: rlvkpnrz 82ef7a9c (empty) (no description set)
+    Parent commit (@-)      : puxurzok 724f3968 main | add config
+    [EOF]
+    ");
+    assert!(!work_dir.root().join(".jj/config.toml").exists());
+    let output = work_dir.run_jj(["diff", "--summary", "--from=root()"]);
+    insta::assert_snapshot!(output, @r"
+    A .jj/config.toml
+    [EOF]
+    ");
+
+    // Not loaded until trusted
+    let output = work_dir.run_jj(["config", "list", "revset-aliases.'mine()'"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Warning: No matching config key for revset-aliases.'mine()'
+    [EOF]
+    ");
+
+    // Declined
+    let output = work_dir.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .args(["config", "trust"])
+            .write_stdin("n\n")
+    });
+    insta::assert_snapshot!(output, @r"
+    revset-aliases.'mine()' = 'none()'
+    revset-aliases.'immutable_heads()' = 'trunk() | subject(frozen)'
+    ui.merge-editor = 'vimdiff'
+    ui.pagr = 'evil'
+    [EOF]
+    ------- stderr -------
+    Warning: `ui.pagr` is ignored because it can't be set by the tree config
+    Hint: The tree config can only set `fix.tools`, `merge-tools`, `revset-aliases`, `template-aliases`, `ui.merge-editor`.
+    Trust this config file? (yN): Error: Config file not trusted
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj_with(|cmd| {
+        force_interactive(cmd)
+            .args(["config", "trust"])
+            .write_stdin("y\n")
+    });
+    insta::assert_snapshot!(output.stderr, @r"
+    Warning: `ui.pagr` is ignored because it can't be set by the tree config
+    Hint: The tree config can only set `fix.tools`, `merge-tools`, `revset-aliases`, `template-aliases`, `ui.merge-editor`.
+    Trust this config file? (yN): The tree config is now trusted
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["config", "trust"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    The tree config is already trusted
+    [EOF]
+    ");
+
+    // Only the allowed items are loaded, and their origin is shown
+    let output = work_dir.run_jj(["config", "list", "revset-aliases"]);
+    insta::assert_snapshot!(output, @r#"
+    revset-aliases."trunk()" = "main"
+    revset-aliases."mine()" = 'none()' # tree
+    revset-aliases."immutable_heads()" = 'trunk() | subject(frozen)' # tree
+    [EOF]
+    "#);
+    let output = work_dir.run_jj([
+        "config",
+        "list",
+        "-Tbuiltin_config_list_detailed",
+        "revset-aliases.'mine()'",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    revset-aliases.'mine()' = 'none()' # tree $TEST_ENV/home/.config/jj/repos/d043564ef93650b06a70/tree-config.toml
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["config", "get", "ui.merge-editor"]);
+    insta::assert_snapshot!(output, @r"
+    vimdiff
+    [EOF]
+    ");
+    // The ignored items aren't checked
+    let output = work_dir.run_jj(["config", "check"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    No problems found in config
+    [EOF]
+    ");
+
+    // Immutable heads can be shared
+    work_dir.run_jj(["new", "-m", "frozen"]).success();
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj(["describe", "subject(frozen)", "-m", "thawed"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Commit 8a5921097604 is immutable
+    Hint: Could not modify commit: kmkuslsw 8a592109 (empty) frozen
+    Hint: Immutable commits are used to protect shared history.
+    Hint: For more information, see:
+          - https://docs.jj-vcs.dev/latest/config/#set-of-immutable-commits
+          - `jj help -k config`, "Set of immutable commits"
+    Hint: This operation would rewrite 1 immutable commits.
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Changes to the file at trunk() aren't applied until trusted, and the
+    // warning is printed once per change
+    git::add_commit(
+        &git_repo,
+        "refs/heads/main",
+        ".jj/config.toml",
+        b"revset-aliases.'mine()' = 'all()'\n",
+        "update config",
+        &[config_commit.commit_id],
+    );
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=mine()", "-T=description"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Done importing changes from the underlying Git repo.
+    Warning: The tree config `.jj/config.toml` at trunk() differs from the trusted copy
+    Hint: Run `jj config trust` to review and trust the changes.
+    [EOF]
+    ");
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=mine()", "-T=description"]);
+    insta::assert_snapshot!(output, @"");
+
+    let output = work_dir.run_jj(["config", "trust", "--revoke"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    The tree config is no longer trusted
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["config", "get", "revset-aliases.'mine()'"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Config error: Value not found for revset-aliases.'mine()'
+    For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_config_author_change_warning() {
     let test_env = TestEnvironment::default();
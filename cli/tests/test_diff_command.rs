@@ -3863,6 +3863,133 @@ fn test_diff_binary() {
     ");
 }
 
+#[test]
+fn test_diff_binary_by_gitattributes() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file(".gitattributes", "*.dat -diff\n");
+    work_dir.write_file("file.dat", "foo\n");
+    work_dir.write_file("file.txt", "foo\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file.dat", "bar\n");
+    work_dir.write_file("file.txt", "bar\n");
+
+    let output = work_dir.run_jj(["diff"]);
+    insta::assert_snapshot!(output, @"
+    Modified regular file file.dat:
+        (binary)
+    Modified regular file file.txt:
+       1    1: foobar
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @"
+    diff --git a/file.dat b/file.dat
+    index 257cc5642c..5716ca5987 100644
+    Binary files a/file.dat and b/file.dat differ
+    diff --git a/file.txt b/file.txt
+    index 257cc5642c..5716ca5987 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1,1 +1,1 @@
+    -foo
+    +bar
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--stat"]);
+    insta::assert_snapshot!(output, @"
+    file.dat | (binary)
+    file.txt | 2 +-
+    2 files changed, 1 insertion(+), 1 deletion(-)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_block_algorithm() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"diff.algorithms.block = ["glob:'*.rs'"]"#);
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let content1 = "fn a() {\n    x\n}\n";
+    let content2 = "fn a() {\n    y\n}\n\nfn b() {\n    x\n}\n";
+    work_dir.write_file("file.rs", content1);
+    work_dir.write_file("file.txt", content1);
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file.rs", content2);
+    work_dir.write_file("file.txt", content2);
+
+    // The function a() is compared with the previous version in file.rs
+    let output = work_dir.run_jj(["diff", "--git"]);
+    insta::assert_snapshot!(output, @"
+    diff --git a/file.rs b/file.rs
+    index 1c00f9ca6c..ff052a35d0 100644
+    --- a/file.rs
+    +++ b/file.rs
+    @@ -1,3 +1,7 @@
+     fn a() {
+    -    x
+    +    y
+     }
+    +
+    +fn b() {
+    +    x
+    +}
+    diff --git a/file.txt b/file.txt
+    index 1c00f9ca6c..ff052a35d0 100644
+    --- a/file.txt
+    +++ b/file.txt
+    @@ -1,3 +1,7 @@
+     fn a() {
+    +    y
+    +}
+    +
+    +fn b() {
+         x
+     }
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["diff", "--stat"]);
+    insta::assert_snapshot!(output, @"
+    file.rs  | 6 +++++-
+    file.txt | 4 ++++
+    2 files changed, 9 insertions(+), 1 deletion(-)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_diff_algorithm_by_gitattributes() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"diff.algorithms.block = ["glob:'*.rs'"]"#);
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let content1 = "fn a() {\n    x\n}\n";
+    let content2 = "fn a() {\n    y\n}\n\nfn b() {\n    x\n}\n";
+    work_dir.write_file(".gitattributes", "*.rs diff=line\n*.txt diff=block\n");
+    work_dir.write_file("file.rs", content1);
+    work_dir.write_file("file.txt", content1);
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file.rs", content2);
+    work_dir.write_file("file.txt", content2);
+
+    // The attribute takes precedence over the diff.algorithms table
+    let output = work_dir.run_jj(["diff", "--stat"]);
+    insta::assert_snapshot!(output, @"
+    file.rs  | 4 ++++
+    file.txt | 6 +++++-
+    2 files changed, 9 insertions(+), 1 deletion(-)
+    [EOF]
+    ");
+}
+
 /// Test diff --stat output width for diffs that have different cases of right
 /// side text: solely "(binary)", a mixture of text and binary diffs, and binary
 /// size changes.
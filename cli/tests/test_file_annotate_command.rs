@@ -303,3 +303,146 @@ fn test_annotate_with_template() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_annotate_line_range_and_ignore_revs() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "a\nb\nc\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    work_dir.write_file("file.txt", "A\nB\nc\n");
+    work_dir.run_jj(["commit", "-m=fmt"]).success();
+    work_dir.write_file("file.txt", "A\nB\nC\nd\n");
+    work_dir.run_jj(["describe", "-m=change"]).success();
+
+    let template = r#"commit.description().first_line() ++ " " ++ line_number ++ ": " ++ content"#;
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    fmt 1: A
+    fmt 2: B
+    change 3: C
+    change 4: d
+    [EOF]
+    ");
+
+    // Only the requested lines are shown
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "-T",
+        template,
+        "--lines=2:3",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    fmt 2: B
+    change 3: C
+    [EOF]
+    ");
+
+    // The end of the range is clamped to the end of the file
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "-T",
+        template,
+        "--lines=4:10",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    change 4: d
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "--lines=5:6"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Line 5 is out of range: file.txt has 4 lines
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "--lines=3:2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: invalid value '3:2' for '--lines <START:END>': The range 3:2 is empty
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+
+    // Lines changed by ignored revisions are attributed to the parent
+    test_env.add_config(r#"annotate.ignore-revs = 'description(substring:"fmt")'"#);
+    let output = work_dir.run_jj(["file", "annotate", "file.txt", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    initial 1: A
+    initial 2: B
+    change 3: C
+    change 4: d
+    [EOF]
+    ");
+
+    // The command-line option overrides the config
+    let output = work_dir.run_jj([
+        "file",
+        "annotate",
+        "file.txt",
+        "-T",
+        template,
+        "--ignore-revs=none()",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    fmt 1: A
+    fmt 2: B
+    change 3: C
+    change 4: d
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_annotate_split_file() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.txt", "a\nb\nc\nd\ne\nf\ng\nh\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    work_dir.write_file("file.txt", "a\nb\nc\nd\ne\n");
+    work_dir.write_file("part.txt", "f\ng\nh\ni\n");
+    work_dir.run_jj(["commit", "-m=split"]).success();
+    work_dir.write_file("part.txt", "f\ng\nh\ni\nj\n");
+    work_dir.run_jj(["describe", "-m=change"]).success();
+
+    let template = r#"commit.description().first_line() ++ " " ++ line_number ++ ": " ++ content"#;
+    let output = work_dir.run_jj(["file", "annotate", "part.txt", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    split 1: f
+    split 2: g
+    split 3: h
+    split 4: i
+    change 5: j
+    [EOF]
+    ");
+
+    // Lines split out of another file are followed once detected
+    test_env.add_config("copies.detect = true");
+    let output = work_dir.run_jj(["diff", "--summary", "-r@-"]);
+    insta::assert_snapshot!(output, @r"
+    M file.txt
+    C {file.txt => part.txt}
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "annotate", "part.txt", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    initial 1: f
+    initial 2: g
+    initial 3: h
+    split 4: i
+    change 5: j
+    [EOF]
+    ");
+}
@@ -0,0 +1,86 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_file_log_line_range() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("templates.commit_summary = 'description.first_line()'");
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file.rs", "fn a() {\n    1\n}\n\nfn b() {\n    2\n}\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+    work_dir.write_file("file.rs", "fn a() {\n    1\n}\n\nfn b() {\n    3\n}\n");
+    work_dir.run_jj(["commit", "-m=change b"]).success();
+    work_dir.remove_file("file.rs");
+    work_dir.write_file("lib.rs", "fn a() {\n    1\n}\n\nfn b() {\n    3\n}\n");
+    work_dir.run_jj(["commit", "-m=rename"]).success();
+    work_dir.write_file("lib.rs", "fn a() {\n    4\n}\n\nfn b() {\n    3\n}\n");
+    work_dir.run_jj(["describe", "-m=change a"]).success();
+
+    // The function is followed across the rename
+    let output = work_dir.run_jj(["file", "log", "lib.rs", "-L", ":fn b"]);
+    insta::assert_snapshot!(output, @r"
+    change b
+    --- a/file.rs
+    +++ b/file.rs
+    @@ -5,3 +5,3 @@
+     fn b() {
+    -    2
+    +    3
+     }
+    initial
+    --- /dev/null
+    +++ b/file.rs
+    @@ -0,0 +5,3 @@
+    +fn b() {
+    +    2
+    +}
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "log", "lib.rs", "-L", "2:2"]);
+    insta::assert_snapshot!(output, @r"
+    change a
+    --- a/lib.rs
+    +++ b/lib.rs
+    @@ -2,1 +2,1 @@
+    -    1
+    +    4
+    initial
+    --- /dev/null
+    +++ b/file.rs
+    @@ -0,0 +2,1 @@
+    +    1
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "log", "lib.rs", "-L", "10:12"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Line 10 is out of range: lib.rs has 7 lines
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["file", "log", "lib.rs", "-L", ":fn c"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: No line in lib.rs matches the regular expression "fn c"
+    [EOF]
+    [exit status: 1]
+    "#);
+}
@@ -0,0 +1,195 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+use crate::common::create_commit_with_files;
+
+#[test]
+fn test_file_mv() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\nb\nc\n");
+    work_dir.write_file("dir/other", "other\n");
+    work_dir.run_jj(["new"]).success();
+
+    work_dir.run_jj(["file", "mv", "file", "renamed"]).success();
+    assert!(!work_dir.root().join("file").exists());
+    assert_eq!(work_dir.read_file("renamed"), "a\nb\nc\n");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    R {file => renamed}
+    [EOF]
+    ");
+
+    // The rename is still recorded after the content is rewritten, even though
+    // the files are no longer similar.
+    work_dir.write_file("renamed", "x\ny\nz\n");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    R {file => renamed}
+    [EOF]
+    ");
+
+    // Moving a file into a directory keeps its name, and moving it again
+    // records a single rename from the original path
+    work_dir.run_jj(["file", "mv", "renamed", "dir"]).success();
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    R {file => dir/renamed}
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["file", "mv", "nonexistent", "new"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No such path: nonexistent
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["file", "mv", "dir", "new"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Path exists but is not a regular file: dir
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["file", "mv", "dir/renamed", "dir/other"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Destination already exists: dir/other
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_file_cp() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\nb\nc\n");
+    work_dir.run_jj(["commit", "-m=initial"]).success();
+
+    work_dir.run_jj(["file", "cp", "file", "copy"]).success();
+    work_dir.run_jj(["describe", "-m=copy"]).success();
+    assert_eq!(work_dir.read_file("file"), "a\nb\nc\n");
+    assert_eq!(work_dir.read_file("copy"), "a\nb\nc\n");
+    work_dir.write_file("copy", "a\nb\nc\nd\n");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    C {file => copy}
+    [EOF]
+    ");
+
+    // Lines of the copy are attributed to the revision that added them to the
+    // source file
+    let template = r#"commit.description().first_line() ++ " " ++ line_number ++ ": " ++ content"#;
+    let output = work_dir.run_jj(["file", "annotate", "copy", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    initial 1: a
+    initial 2: b
+    initial 3: c
+    copy 4: d
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_file_mv_other_revision() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "a", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "b", &["a"], &[("other", "b\n")]);
+
+    work_dir
+        .run_jj(["file", "mv", "-r", "a", "file", "renamed"])
+        .success();
+    let output = work_dir.run_jj(["diff", "--summary", "-r", "a"]);
+    insta::assert_snapshot!(output, @r"
+    A renamed
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    other
+    renamed
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_file_mv_rebase() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "1\n2\n3\n")]);
+    create_commit_with_files(
+        &work_dir,
+        "edit",
+        &["base"],
+        &[("file", "1\n2\n3 edited\n")],
+    );
+    work_dir.run_jj(["new", "base", "-m", "rename"]).success();
+    work_dir.run_jj(["file", "mv", "file", "renamed"]).success();
+    work_dir.write_file("renamed", "1 renamed\n2\n3\n");
+
+    // The edit is applied to the renamed file when the rename is rebased onto
+    // it
+    work_dir
+        .run_jj(["rebase", "-r", "@", "-o", "edit"])
+        .success();
+    let output = work_dir.run_jj(["file", "list"]);
+    insta::assert_snapshot!(output, @r"
+    renamed
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("renamed"), @r"
+    1 renamed
+    2
+    3 edited
+    ");
+}
+
+#[test]
+fn test_snapshot_record_copies() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    test_env.add_config("snapshot.record-copies = true");
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "1\n2\n3\n4\n");
+    work_dir.run_jj(["new"]).success();
+
+    work_dir.remove_file("file");
+    work_dir.write_file("renamed", "1\n2\n3\n4 changed\n");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    R {file => renamed}
+    [EOF]
+    ");
+
+    // The recorded rename is kept after the file is changed beyond recognition
+    work_dir.write_file("renamed", "x\n");
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    R {file => renamed}
+    [EOF]
+    ");
+}
@@ -148,6 +148,52 @@ fn test_git_fetch_default_remote() {
     ");
 }
 
+#[test]
+fn test_git_fetch_require_signatures() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    remotes.origin.auto-track-bookmarks = '*'
+    signing.require = "remote_bookmarks()"
+    signing.enforce-on-fetch = true
+    templates.commit_summary = "description.first_line()"
+    "#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    add_git_remote(&test_env, &work_dir, "origin");
+
+    // Unsigned commits are not imported
+    let output = work_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't import 1 fetched commits since they are not signed as required
+    Hint: Rejected commit (not signed): message
+    Hint: Configured signing.require: 'remote_bookmarks()'
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"");
+
+    // The rejected refs aren't left behind in Git to be imported later
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"");
+
+    work_dir
+        .run_jj(["git", "fetch", "--config=signing.enforce-on-fetch=false"])
+        .success();
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @r"
+    origin: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_fetch_single_remote() {
     let test_env = TestEnvironment::default();
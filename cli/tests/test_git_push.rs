@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use indoc::indoc;
+use regex::Regex;
 use testutils::git;
 
 use crate::common::CommandOutput;
@@ -96,6 +97,135 @@ fn test_git_push_nothing() {
     ");
 }
 
+#[test]
+fn test_git_push_status_revsets() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "local"])
+        .success();
+    let work_dir = test_env.work_dir("local");
+    work_dir.run_jj(["describe", "-m=description 1"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "bookmark1"])
+        .success();
+    work_dir
+        .run_jj(["new", "root()", "-m=description 2"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "bookmark2"])
+        .success();
+
+    // Set up remote bookmarks without fetching from the remote
+    git::add_remote(work_dir.root(), "origin", "http://example.com/repo");
+    let git_repo = git::open(work_dir.root());
+    for name in ["bookmark1", "bookmark2"] {
+        let oid = git_repo
+            .find_reference(&format!("refs/heads/{name}"))
+            .unwrap()
+            .id();
+        git_repo
+            .reference(
+                format!("refs/remotes/origin/{name}"),
+                oid.detach(),
+                gix::refs::transaction::PreviousValue::MustNotExist,
+                "create remote ref",
+            )
+            .unwrap();
+    }
+    work_dir.run_jj(["git", "import"]).success();
+    work_dir
+        .run_jj(["bookmark", "track", "bookmark1@origin", "bookmark2@origin"])
+        .success();
+
+    work_dir
+        .run_jj(["new", "bookmark2", "-m", "other"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "bookmark3"])
+        .success();
+    work_dir
+        .run_jj(["new", "bookmark1", "-m", "local 1"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "move", "bookmark1", "--to=@"])
+        .success();
+    work_dir.run_jj(["new", "-m", "local 2"]).success();
+
+    let template = r#"description.first_line() ++ " " ++ bookmarks ++ "\n""#;
+    let log = |revset: &str| work_dir.run_jj(["log", "--no-graph", "-T", template, "-r", revset]);
+    insta::assert_snapshot!(log("pushed() ~ root()"), @"
+    description 2 bookmark2
+    description 1 bookmark1@origin
+    [EOF]
+    ");
+    insta::assert_snapshot!(log("pushed(remote=origin) ~ root()"), @"
+    description 2 bookmark2
+    description 1 bookmark1@origin
+    [EOF]
+    ");
+    insta::assert_snapshot!(log("pushed(remote=unknown)"), @"");
+    insta::assert_snapshot!(log("unpushed()"), @"
+    local 1 bookmark1*
+    other bookmark3
+    [EOF]
+    ");
+
+    // Same as the bookmarks pushed by default
+    insta::assert_snapshot!(log("push_pending()"), @"
+    local 1 bookmark1*
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "push", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from 9b2e76de3920 to f840f3a82cc4
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+
+    // git.push setting is respected
+    let output = work_dir.run_jj([
+        "log",
+        "--config=git.push=unknown",
+        "--no-graph",
+        "-T",
+        template,
+        "-r=push_pending() ~ root()",
+    ]);
+    insta::assert_snapshot!(output, @"
+    local 1 bookmark1*
+    description 1 bookmark1@origin
+    [EOF]
+    ");
+
+    // git.push setting is only looked up if needed
+    let output = work_dir.run_jj(["log", "--config=git.push=1", "-T", template, "-r=@-"]);
+    insta::assert_snapshot!(output, @r"
+    ○  local 1 bookmark1*
+    │
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "--config=git.push=1", "-r=push_pending()"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to parse revset: Failed to look up the default push remote
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | push_pending()
+      | ^----------^
+      |
+      = Failed to look up the default push remote
+    2: Invalid type or value for git.push
+    3: invalid type: integer `1`, expected a string
+
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_git_push_default_remote_selection() {
     let test_env = TestEnvironment::default();
@@ -2578,6 +2708,115 @@ fn test_git_push_sign_on_push() {
     ");
 }
 
+#[test]
+fn test_git_push_require_signatures() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    test_env.add_config(
+        r#"
+    signing.backend = "test"
+    signing.require = "::bookmark2"
+    signing.allowed-keys = ["trusted"]
+    templates.commit_summary = "description.first_line()"
+    "#,
+    );
+    let normalize_commit_hash = |s: String| {
+        Regex::new("commit [0-9a-f]{12}")
+            .unwrap()
+            .replace_all(&s, "commit <hash>")
+            .into_owned()
+    };
+    work_dir
+        .run_jj(["new", "bookmark2", "-m", "must be signed"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+    work_dir
+        .run_jj(["new", "bookmark1", "-m", "need not be signed"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark1", "-r@"])
+        .success();
+
+    // Commits outside of signing.require can be pushed unsigned
+    work_dir
+        .run_jj(["git", "push", "--dry-run", "-b", "bookmark1"])
+        .success();
+
+    let output = work_dir
+        .run_jj(["git", "push", "-b", "bookmark2"])
+        .normalize_stderr_with(normalize_commit_hash);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't push commit <hash> since it is not signed
+    Hint: Rejected commit: must be signed
+    Hint: Configured signing.require: '::bookmark2'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir
+        .run_jj(["sign", "-r", "bookmark2", "--key", "other"])
+        .success();
+    let output = work_dir
+        .run_jj(["git", "push", "-b", "bookmark2"])
+        .normalize_stderr_with(normalize_commit_hash);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't push commit <hash> since it is signed with a key that is not allowed (other)
+    Hint: Rejected commit: must be signed
+    Hint: Configured signing.require: '::bookmark2'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir
+        .run_jj(["sign", "-r", "bookmark2", "--key", "trusted"])
+        .success();
+    work_dir
+        .run_jj(["git", "push", "-b", "bookmark2"])
+        .success();
+
+    // Commits signed on push satisfy the requirement
+    test_env.add_config(
+        r#"
+    signing.key = "trusted"
+    git.sign-on-push = true
+    "#,
+    );
+    work_dir
+        .run_jj(["new", "bookmark2", "-m", "signed on push"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "-r@"])
+        .success();
+
+    // Commits signed on push are checked against the allowed keys
+    let output = work_dir
+        .run_jj([
+            "git",
+            "push",
+            "-b",
+            "bookmark2",
+            "--config=signing.key=other",
+        ])
+        .normalize_stderr_with(normalize_commit_hash);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Won't push commit <hash> since it is signed with a key that is not allowed (other)
+    Hint: Rejected commit: signed on push
+    Hint: Configured signing.require: '::bookmark2'
+    [EOF]
+    [exit status: 1]
+    ");
+
+    work_dir
+        .run_jj(["git", "push", "-b", "bookmark2"])
+        .success();
+}
+
 #[test]
 fn test_git_push_rejected_by_remote() {
     let test_env = TestEnvironment::default();
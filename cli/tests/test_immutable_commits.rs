@@ -138,6 +138,139 @@ fn test_rewrite_immutable_generic() {
     ");
 }
 
+#[test]
+fn test_immutable_revset_cache() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config("index.revset-cache = true");
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "present(main)""#);
+    work_dir.run_jj(["describe", "-m=a"]).success();
+    work_dir.run_jj(["new", "-m=b"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    work_dir.run_jj(["new", "-m=c"]).success();
+    let output = work_dir.run_jj(["log"]);
+    insta::assert_snapshot!(output, @"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:11 b7f55ebb
+    │  (empty) c
+    ○  kkmpptxz test.user@example.com 2001-02-03 08:05:09 74bc8072
+    │  (empty) b
+    ◆  qpvuntsm test.user@example.com 2001-02-03 08:05:08 main b86e28cd
+    │  (empty) a
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+    assert!(work_dir.root().join(".jj/repo/index/revset_cache").is_dir());
+    // Cached sets are reused at the same operation
+    let output = work_dir.run_jj(["log"]);
+    insta::assert_snapshot!(output, @"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:11 b7f55ebb
+    │  (empty) c
+    ○  kkmpptxz test.user@example.com 2001-02-03 08:05:09 74bc8072
+    │  (empty) b
+    ◆  qpvuntsm test.user@example.com 2001-02-03 08:05:08 main b86e28cd
+    │  (empty) a
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+
+    // The cached set is extended to the new heads
+    work_dir
+        .run_jj(["bookmark", "set", "-r@-", "main"])
+        .success();
+    let output = work_dir.run_jj(["log"]);
+    insta::assert_snapshot!(output, @"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:11 b7f55ebb
+    │  (empty) c
+    ◆  kkmpptxz test.user@example.com 2001-02-03 08:05:09 main 74bc8072
+    │  (empty) b
+    ~  (elided revisions)
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["describe", "-r@-", "-m=b2"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Commit 74bc80726640 is immutable
+    Hint: Could not modify commit: kkmpptxz 74bc8072 main | (empty) b
+    Hint: Immutable commits are used to protect shared history.
+    Hint: For more information, see:
+          - https://docs.jj-vcs.dev/latest/config/#set-of-immutable-commits
+          - `jj help -k config`, "Set of immutable commits"
+    Hint: This operation would rewrite 1 immutable commits.
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // The cached set is evaluated from scratch if the old heads are no longer
+    // reachable
+    work_dir
+        .run_jj([
+            "bookmark",
+            "set",
+            "--allow-backwards",
+            "-rsubject(a)",
+            "main",
+        ])
+        .success();
+    let output = work_dir.run_jj(["log"]);
+    insta::assert_snapshot!(output, @"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:11 b7f55ebb
+    │  (empty) c
+    ○  kkmpptxz test.user@example.com 2001-02-03 08:05:09 74bc8072
+    │  (empty) b
+    ◆  qpvuntsm test.user@example.com 2001-02-03 08:05:08 main b86e28cd
+    │  (empty) a
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+    work_dir.run_jj(["describe", "-r@-", "-m=b2"]).success();
+
+    // Sets with date filters aren't cached
+    let cache_dir = work_dir.root().join(".jj/repo/index/revset_cache");
+    let count_entries = || std::fs::read_dir(&cache_dir).unwrap().count();
+    let num_entries = count_entries();
+    let output = work_dir.run_jj([
+        "log",
+        "--config=revsets.log='committer_date(after:\"1 year ago\")'",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:19 14d7fc9c
+    │  (empty) c
+    ○  kkmpptxz test.user@example.com 2001-02-03 08:05:19 51ad01f3
+    │  (empty) b2
+    ◆  qpvuntsm test.user@example.com 2001-02-03 08:05:08 main b86e28cd
+    │  (empty) a
+    ~
+    [EOF]
+    ");
+    assert_eq!(count_entries(), num_entries);
+
+    // Failure to update the cache isn't fatal
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+    std::fs::write(&cache_dir, "").unwrap();
+    let output = work_dir.run_jj(["log"]);
+    insta::assert_snapshot!(output, @r"
+    @  mzvwutvl test.user@example.com 2001-02-03 08:05:19 14d7fc9c
+    │  (empty) c
+    ○  kkmpptxz test.user@example.com 2001-02-03 08:05:19 51ad01f3
+    │  (empty) b2
+    ◆  qpvuntsm test.user@example.com 2001-02-03 08:05:08 main b86e28cd
+    │  (empty) a
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to update revset cache
+    Caused by:
+    1: Failed to write revset cache
+    2: Cannot access $TEST_ENV/repo/.jj/repo/index/revset_cache
+    3: File exists (os error 17)
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_new_wc_commit_when_wc_immutable() {
     let test_env = TestEnvironment::default();
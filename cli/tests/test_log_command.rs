@@ -12,6 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::process::Child;
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
 use crate::common::TestEnvironment;
 use crate::common::to_toml_value;
 
@@ -1009,6 +1017,57 @@ fn test_log_filtered_by_path() {
     ");
 }
 
+#[test]
+fn test_log_follow() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "a\nb\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "a\nb\n");
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    work_dir.write_file("file2", "a\nb\nc\n");
+    work_dir.run_jj(["describe", "-m", "third"]).success();
+
+    let output = work_dir.run_jj(["log", "-T", "description", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    @  third
+    ○  second
+    │
+    ~
+    [EOF]
+    ");
+
+    // The history of the file is followed across the rename
+    let output = work_dir.run_jj(["log", "-T", "description", "--follow", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    @  third
+    ○  second
+    ○  first
+    │
+    ~
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--follow", "file1", "file2"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --follow requires a single file path
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["log", "--follow", "file1"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: --follow requires a file, but file1 is not a file in the working copy
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_log_limit() {
     let test_env = TestEnvironment::default();
@@ -1089,6 +1148,236 @@ fn test_log_limit() {
     ");
 }
 
+#[test]
+fn test_log_sort() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir
+        .run_jj(["describe", "-m", "a", "--author", "Zoe <zoe@example.com>"])
+        .success();
+    work_dir.run_jj(["new"]).success();
+    work_dir
+        .run_jj(["describe", "-m", "b", "--author", "Amy <amy@example.com>"])
+        .success();
+    work_dir.run_jj(["new", "root()"]).success();
+    work_dir
+        .run_jj(["describe", "-m", "c", "--author", "Mia <mia@example.com>"])
+        .success();
+
+    let template = r#"description.first_line() ++ " " ++ author.name() ++ "\n""#;
+    let output = work_dir.run_jj([
+        "log",
+        "-r=~root()",
+        "-T",
+        template,
+        "--no-graph",
+        "--sort=author-name",
+    ]);
+    insta::assert_snapshot!(output, @"
+    b Amy
+    c Mia
+    a Zoe
+    [EOF]
+    ");
+
+    // Sort in descending order
+    let output = work_dir.run_jj([
+        "log",
+        "-r=~root()",
+        "-T",
+        template,
+        "--no-graph",
+        "--sort=author-email-",
+    ]);
+    insta::assert_snapshot!(output, @"
+    a Zoe
+    c Mia
+    b Amy
+    [EOF]
+    ");
+
+    // Limit is applied after sorting
+    let output = work_dir.run_jj([
+        "log",
+        "-r=~root()",
+        "-T",
+        template,
+        "--no-graph",
+        "--sort=committer-date",
+        "--limit=2",
+    ]);
+    insta::assert_snapshot!(output, @"
+    a Zoe
+    b Amy
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-r=~root()",
+        "-T",
+        template,
+        "--no-graph",
+        "--sort=committer-date",
+        "--limit=2",
+        "--reversed",
+    ]);
+    insta::assert_snapshot!(output, @"
+    b Amy
+    a Zoe
+    [EOF]
+    ");
+
+    // Sorting isn't supported in graph mode
+    let output = work_dir.run_jj(["log", "--sort=author-name"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      --no-graph
+
+    Usage: jj log --no-graph --sort <SORT_KEY> [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_log_prioritize() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir.run_jj(["new", "-m", "b", "root()"]).success();
+    work_dir.run_jj(["new", "-m", "c", "root()"]).success();
+    work_dir.run_jj(["new", "-m", "d", "subject(a)"]).success();
+
+    let output = work_dir.run_jj(["log", "-T", "description"]);
+    insta::assert_snapshot!(output, @"
+    @  d
+    ○  a
+    │ ○  c
+    ├─╯
+    │ ○  b
+    ├─╯
+    ◆
+    [EOF]
+    ");
+
+    // Branches of the earlier revsets are emitted first
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        "description",
+        "--prioritize=subject(b)",
+        "--prioritize=subject(c)",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ○  b
+    │ ○  c
+    ├─╯
+    │ @  d
+    │ ○  a
+    ├─╯
+    ◆
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "-T",
+        "description",
+        "--prioritize=subject(c)",
+        "--prioritize=subject(b)",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ○  c
+    │ ○  b
+    ├─╯
+    │ @  d
+    │ ○  a
+    ├─╯
+    ◆
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_watch() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+
+    /// Kills the watching process even if the test fails.
+    struct KillOnDrop(Child);
+
+    impl Drop for KillOnDrop {
+        fn drop(&mut self) {
+            self.0.kill().ok();
+            self.0.wait().ok();
+        }
+    }
+
+    let base_cmd = test_env.new_jj_cmd();
+    let mut child = std::process::Command::new(base_cmd.get_program())
+        .current_dir(work_dir.root())
+        .env_clear()
+        .envs(base_cmd.get_envs().filter_map(|(k, v)| Some((k, v?))))
+        .args(["log", "--watch", "--color=debug"])
+        .args(["-T", r#"if(root, "(root)", description)"#])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let _child = KillOnDrop(child);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if tx.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    // Each redraw ends with the root commit. The timeout only guards against
+    // hangs, so it's much longer than the polling interval.
+    let read_redraw = || {
+        let mut lines = vec![];
+        loop {
+            let line = rx.recv_timeout(Duration::from_secs(60)).unwrap();
+            let is_last = line.contains("(root)");
+            lines.push(line);
+            if is_last {
+                return lines.join("\n");
+            }
+        }
+    };
+
+    insta::assert_snapshot!(read_redraw(), @"
+    [1m[38;5;2m<<log commit node working_copy mutable::@>>[0m  <<log commit description::a>>
+    [1m[38;5;14m<<log commit node immutable::◆>>[0m  <<log commit::(root)>>
+    ");
+
+    // New operation
+    work_dir.run_jj(["new", "-m", "b"]).success();
+    insta::assert_snapshot!(read_redraw(), @"
+    [1m[38;5;2m<<log commit node working_copy mutable::@>>[0m  [4m<<changed log commit description::b>>[24m
+    <<log commit node mutable::○>>  <<log commit description::a>>
+    [1m[38;5;14m<<log commit node immutable::◆>>[0m  <<log commit::(root)>>
+    ");
+
+    // Working-copy change
+    work_dir.write_file("file", "");
+    insta::assert_snapshot!(read_redraw(), @"
+    [1m[38;5;2m<<log commit node working_copy mutable::@>>[0m  [4m<<changed log commit description::b>>[24m
+    <<log commit node mutable::○>>  <<log commit description::a>>
+    [1m[38;5;14m<<log commit node immutable::◆>>[0m  <<log commit::(root)>>
+    ");
+}
+
 #[test]
 fn test_log_warn_path_might_be_revset() {
     let test_env = TestEnvironment::default();
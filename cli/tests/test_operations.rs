@@ -3129,6 +3129,118 @@ fn test_op_log_anonymize() {
     ");
 }
 
+#[test]
+fn test_op_signatures() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+[signing]
+backend = "test"
+allowed-keys = ["trusted"]
+"#,
+    );
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    test_env.add_config("signing.sign-operations = true");
+    work_dir
+        .run_jj(["new", "--config=signing.key=other"])
+        .success();
+    work_dir
+        .run_jj(["new", "--config=signing.key=trusted"])
+        .success();
+
+    let template = r#"separate(" ",
+        description.first_line(),
+        if(signature, signature.status() ++ " " ++ signature.key(), "unsigned"),
+    ) ++ "\n""#;
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    new empty commit good trusted
+    new empty commit good other
+    add workspace 'default' unsigned
+    unsigned
+    [EOF]
+    ");
+
+    let regex = Regex::new(r"\b[0-9a-f]{12}\b").unwrap();
+    let output = work_dir.run_jj(["op", "verify"]);
+    insta::assert_snapshot!(
+        output.normalize_stdout_with(|s| regex.replace_all(&s, "[OP_ID]").into_owned()), @r"
+    key other not allowed: [OP_ID] new empty commit
+    not signed: [OP_ID] add workspace 'default'
+    [EOF]
+    ------- stderr -------
+    Error: 2 of 3 operations are not signed as required
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["op", "verify", "--since=@-"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    All 1 operations are signed as required
+    [EOF]
+    ");
+
+    // Reparented operations are signed again if operations are to be signed
+    let output = work_dir.run_jj(["op", "abandon", "@-", "--config=signing.key=trusted"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 1 operations and reparented 1 descendant operations.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    new empty commit good trusted
+    add workspace 'default' unsigned
+    unsigned
+    [EOF]
+    ");
+
+    // Otherwise, their signatures are dropped
+    let output = work_dir.run_jj([
+        "op",
+        "abandon",
+        "@-",
+        "--config=signing.sign-operations=false",
+    ]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Abandoned 1 operations and reparented 1 descendant operations.
+    Warning: Dropped the signatures of 1 reparented operations.
+    Hint: Set `signing.sign-operations = true` to sign reparented operations again.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    new empty commit unsigned
+    unsigned
+    [EOF]
+    ");
+
+    // Operations can't be recorded if they can't be signed
+    let output = work_dir.run_jj(["new", "--config=signing.backend=none"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Failed to sign operation
+    Caused by: No signing backend configured
+    Hint: For configuring a signing backend, see https://docs.jj-vcs.dev/latest/config/#commit-signing
+    [EOF]
+    [exit status: 1]
+    ");
+    work_dir
+        .run_jj(["new", "--config=signing.sign-operations=false"])
+        .success();
+    let output = work_dir.run_jj(["op", "abandon", "@-", "--config=signing.backend=none"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: No signing backend configured
+    Hint: For configuring a signing backend, see https://docs.jj-vcs.dev/latest/config/#commit-signing
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 fn init_bare_git_repo(git_repo_path: &Path) -> gix::Repository {
     let git_repo = git::init_bare(git_repo_path);
     let commit_result = git::add_commit(
@@ -12,11 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use indoc::formatdoc;
+
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
 use crate::common::create_commit;
 use crate::common::create_commit_with_files;
+use crate::common::fake_editor_path;
+use crate::common::to_toml_value;
 
 #[test]
 fn test_rebase_invalid() {
@@ -32,9 +36,9 @@ fn test_rebase_invalid() {
     insta::assert_snapshot!(output, @r"
     ------- stderr -------
     error: the following required arguments were not provided:
-      <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+      <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--onto-latest>
 
-    Usage: jj rebase <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--onto-latest>
 
     For more information, try '--help'.
     [EOF]
@@ -47,7 +51,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--revisions <REVSETS>' cannot be used with '--source <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--onto-latest>
 
     For more information, try '--help'.
     [EOF]
@@ -60,7 +64,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--branch <REVSETS>' cannot be used with '--source <REVSETS>'
 
-    Usage: jj rebase --branch <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --branch <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--onto-latest>
 
     For more information, try '--help'.
     [EOF]
@@ -73,7 +77,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--onto <REVSETS>' cannot be used with '--insert-after <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--onto-latest>
 
     For more information, try '--help'.
     [EOF]
@@ -86,7 +90,7 @@ fn test_rebase_invalid() {
     ------- stderr -------
     error: the argument '--onto <REVSETS>' cannot be used with '--insert-before <REVSETS>'
 
-    Usage: jj rebase --revisions <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+    Usage: jj rebase --revisions <REVSETS> <--onto <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>|--onto-latest>
 
     For more information, try '--help'.
     [EOF]
@@ -3079,6 +3083,194 @@ fn test_rebase_simplify_parents() {
     ");
 }
 
+#[test]
+fn test_rebase_with_merge_driver() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    let editor_path = to_toml_value(fake_editor_path());
+    test_env.add_config(formatdoc! {r#"
+        [merge-drivers.changelog]
+        command = [{editor_path}, "$output"]
+        patterns = ["CHANGELOG"]
+    "#});
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let files = |content| [("CHANGELOG", content), ("file", content)];
+    create_commit_with_files(&work_dir, "base", &[], &files("base\n"));
+    create_commit_with_files(&work_dir, "a", &["base"], &files("a\n"));
+    create_commit_with_files(&work_dir, "b", &["base"], &files("b\n"));
+    let setup_opid = work_dir.current_operation_id();
+
+    // The driver resolves CHANGELOG, but the other file is still conflicted
+    std::fs::write(&edit_script, "expect\na\n\0write\na\nb\n").unwrap();
+    work_dir.run_jj(["rebase", "-r", "b", "-o", "a"]).success();
+    let output = work_dir.run_jj(["resolve", "--list", "-r", "b"]);
+    insta::assert_snapshot!(output, @"
+    file    2-sided conflict
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["file", "show", "-r", "b", "CHANGELOG"]);
+    insta::assert_snapshot!(output, @"
+    a
+    b
+    [EOF]
+    ");
+
+    // The conflict is left if the driver fails. Its stderr is shown, and the
+    // user is warned about the failure
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    std::fs::write(&edit_script, "expect\nunexpected\n").unwrap();
+    let output = work_dir.run_jj(["rebase", "-r", "b", "-o", "a"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    fake-editor: Unexpected content.
+
+    EXPECTED: <unexpected
+    >
+    RECEIVED: <a
+    >
+    Rebased 1 commits to destination
+    Warning: Failed to merge CHANGELOG by using the merge driver. The file was left conflicted.
+    Caused by: Merge driver changelog exited with exit status: 1
+    Working copy  (@) now at: royxmykx 8d642c8f b | (conflict) b
+    Parent commit (@-)      : zsuskuln adc0a609 a | a
+    Added 0 files, modified 2 files, removed 0 files
+    Warning: There are unresolved conflicts at these paths:
+    CHANGELOG    2-sided conflict
+    file         2-sided conflict
+    New conflicts appeared in 1 commits:
+      royxmykx 8d642c8f b | (conflict) b
+    Hint: To resolve the conflicts, start by creating a commit on top of
+    the conflicted commit:
+      jj new royxmykx
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you can inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["resolve", "--list", "-r", "b"]);
+    insta::assert_snapshot!(output, @"
+    CHANGELOG    2-sided conflict
+    file         2-sided conflict
+    [EOF]
+    ");
+
+    // The driver is run when merging the parents of a new commit
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    std::fs::write(&edit_script, "expect\na\n\0write\na\nb\n").unwrap();
+    work_dir.run_jj(["new", "a", "b"]).success();
+    let output = work_dir.run_jj(["resolve", "--list"]);
+    insta::assert_snapshot!(output, @r"
+    file    2-sided conflict
+    [EOF]
+    ");
+
+    // The driver isn't run when merges are only computed for display, so the
+    // resolved file is shown as modified
+    std::fs::write(&edit_script, "dump-path driver-ran").unwrap();
+    let output = work_dir.run_jj(["diff", "--summary"]);
+    insta::assert_snapshot!(output, @r"
+    M CHANGELOG
+    [EOF]
+    ");
+    assert!(!test_env.env_root().join("driver-ran").exists());
+
+    // The driver is run when squashing changes
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+    std::fs::write(&edit_script, "expect\na\n\0write\na\nb\n").unwrap();
+    work_dir
+        .run_jj(["squash", "--from", "b", "--into", "a", "CHANGELOG"])
+        .success();
+    let output = work_dir.run_jj(["file", "show", "-r", "a", "CHANGELOG"]);
+    insta::assert_snapshot!(output, @r"
+    a
+    b
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_rebase_onto_latest_rewritten_parent() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    let output = work_dir
+        .run_jj(["log", "--no-graph", "-r=a", "-T=commit_id"])
+        .success();
+    let old_a_id = output.stdout.into_raw();
+    work_dir.run_jj(["describe", "-r=a", "-m=a2"]).success();
+    // Make the obsolete version of "a" visible again
+    create_commit(&work_dir, "c", &[&old_a_id]);
+
+    work_dir
+        .run_jj(["rebase", "-s=c", "--onto-latest"])
+        .success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @r"
+    @  c: a
+    │ ○  b: a
+    ├─╯
+    ○  a
+    │ ○
+    ├─╯
+    ◆
+    [EOF]
+    ");
+
+    // The parent is already the newest version
+    let output = work_dir.run_jj(["rebase", "-s=c", "--onto-latest"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_rebase_onto_latest_moved_remote_bookmark() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "origin"]).success();
+    let origin_dir = test_env.work_dir("origin");
+    origin_dir.run_jj(["describe", "-m=trunk1"]).success();
+    origin_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    origin_dir.run_jj(["git", "export"]).success();
+
+    let mut origin_git_path = origin_dir.root().to_owned();
+    origin_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env
+        .run_jj_in(
+            ".",
+            ["git", "clone", origin_git_path.to_str().unwrap(), "local"],
+        )
+        .success();
+    let local_dir = test_env.work_dir("local");
+    create_commit(&local_dir, "mine", &["main@origin"]);
+
+    // The local change is squashed into the new trunk upstream
+    origin_dir.run_jj(["new", "-m=trunk2"]).success();
+    origin_dir.write_file("mine", "mine\n");
+    origin_dir
+        .run_jj(["bookmark", "set", "-r@", "main"])
+        .success();
+    origin_dir.run_jj(["git", "export"]).success();
+    local_dir.run_jj(["git", "fetch"]).success();
+
+    local_dir.run_jj(["rebase", "--onto-latest"]).success();
+    let template = r#"if(description, description.first_line(), "(no description)") ++ "\n""#;
+    let output = local_dir.run_jj(["log", "--no-graph", "-r=::@ ~ root()", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    (no description)
+    trunk2
+    trunk1
+    [EOF]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     let template = "bookmarks ++ surround(': ', '', parents.map(|c| c.bookmarks()))";
@@ -809,6 +809,133 @@ fn test_bad_alias_decl() {
     "#);
 }
 
+#[test]
+fn test_external_filter() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["new", "-mA", "root()"]).success();
+    work_dir.run_jj(["new", "-mB"]).success();
+    let commit_a_id = work_dir
+        .run_jj(["log", "--no-graph", "-rsubject(A)", "-Tcommit_id"])
+        .success()
+        .stdout
+        .into_raw();
+
+    let filter_path = assert_cmd::cargo::cargo_bin!("fake-formatter");
+    let set_up_filter = |name: &str, args: &[&str]| {
+        let command = [filter_path.to_str().unwrap()]
+            .into_iter()
+            .chain(args.iter().copied());
+        format!(
+            "--config=revset-filters.{name}={}",
+            toml_edit::Value::from_iter(command)
+        )
+    };
+
+    // The program echoes all candidates
+    let tee_path = test_env.env_root().join("filter-input");
+    let echo_all = set_up_filter("echo_all", &["--tee", tee_path.to_str().unwrap()]);
+    let output = work_dir.run_jj(["log", "-r", "echo_all()", &echo_all]);
+    insta::assert_snapshot!(output, @"
+    @  kkmpptxz test.user@example.com 2001-02-03 08:05:09 5f104233
+    │  (empty) B
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:08 095dbd02
+    │  (empty) A
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+    // Candidates are sent in batch
+    insta::assert_snapshot!(std::fs::read_to_string(&tee_path).unwrap(), @"
+    5f10423343e49aaafd78d90743d9562013f02da4
+    095dbd02b1abfefa95c743f84bb8575d41d0efcd
+    0000000000000000000000000000000000000000
+    ");
+
+    // Candidates tested by predicate are also sent in batch, but hidden
+    // commits which aren't candidates aren't
+    std::fs::remove_file(&tee_path).unwrap();
+    let output = work_dir.run_jj(["log", "-r", "echo_all() | subject(A)", &echo_all]);
+    insta::assert_snapshot!(output, @r"
+    @  kkmpptxz test.user@example.com 2001-02-03 08:05:09 5f104233
+    │  (empty) B
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:08 095dbd02
+    │  (empty) A
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(&tee_path).unwrap(), @r"
+    5f10423343e49aaafd78d90743d9562013f02da4
+    095dbd02b1abfefa95c743f84bb8575d41d0efcd
+    0000000000000000000000000000000000000000
+    ");
+
+    // The program selects a subset of the candidates
+    let select_a = set_up_filter("select_a", &["--stdout", &format!("{commit_a_id}\n")]);
+    let output = work_dir.run_jj(["log", "-r", "select_a()", &select_a]);
+    insta::assert_snapshot!(output, @"
+    ○  rlvkpnrz test.user@example.com 2001-02-03 08:05:08 095dbd02
+    │  (empty) A
+    ~
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r", "~select_a() & ~root()", &select_a]);
+    insta::assert_snapshot!(output, @"
+    @  kkmpptxz test.user@example.com 2001-02-03 08:05:09 5f104233
+    │  (empty) B
+    ~
+    [EOF]
+    ");
+
+    // Arguments are not allowed
+    let output = work_dir.run_jj(["log", "-r", "select_a(x)", &select_a]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Failed to parse revset: Function `select_a`: Expected 0 arguments
+    Caused by:  --> 1:10
+      |
+    1 | select_a(x)
+      |          ^
+      |
+      = Function `select_a`: Expected 0 arguments
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The program fails
+    let fail = set_up_filter("fail", &["--fail"]);
+    let output = work_dir.run_jj(["log", "-r", "fail()", &fail]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Failed to run revset filter `fail()`
+    Caused by: Process exited with exit status: 1
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The program prints garbage
+    let garbage = set_up_filter("garbage", &["--stdout", "garbage"]);
+    let output = work_dir.run_jj(["log", "-r", "garbage()", &garbage]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Failed to run revset filter `garbage()`
+    Caused by: Invalid commit id in output: "garbage"
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // Built-in functions can't be overridden
+    let all = set_up_filter("all", &[]);
+    let output = work_dir.run_jj(["log", "-r", "all()", &all]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Config error: Revset filter `all` conflicts with the built-in function `all()`
+    For help, see https://docs.jj-vcs.dev/latest/config/ or use `jj help -k config`.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 /// Verifies that the committer_date revset honors the local time zone.
 /// This test cannot run on Windows because The TZ env var does not control
 /// chrono::Local on that platform.
@@ -134,6 +134,64 @@ backend = "test"
     ");
 }
 
+#[test]
+fn test_sign_verify() {
+    let test_env = TestEnvironment::default();
+
+    test_env.add_config(
+        r#"
+templates.commit_summary = "description.first_line()"
+
+[signing]
+behavior = "keep"
+backend = "test"
+allowed-keys = ["trusted"]
+"#,
+    );
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "one"]).success();
+    work_dir.run_jj(["commit", "-m", "two"]).success();
+    work_dir.run_jj(["commit", "-m", "three"]).success();
+    work_dir
+        .run_jj(["sign", "-r", "subject(one)", "--key", "trusted"])
+        .success();
+    work_dir
+        .run_jj(["sign", "-r", "subject(two)", "--key", "other"])
+        .success();
+
+    let output = work_dir.run_jj(["sign", "--verify", "..@-"]);
+    insta::assert_snapshot!(output, @r"
+    not signed: three
+    key other not allowed: two
+    [EOF]
+    ------- stderr -------
+    Error: 2 of 3 commits are not signed as required
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["sign", "--verify", "subject(one)"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    All 1 commits are signed as required
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["sign", "--verify", "@", "-r", "@"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    error: the argument '--verify <REVSETS>' cannot be used with '--revisions <REVSETS>'
+
+    Usage: jj sign --verify <REVSETS>
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_sign_with_key() {
     let test_env = TestEnvironment::default();
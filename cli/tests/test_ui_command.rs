@@ -0,0 +1,430 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::CommandOutput;
+use crate::common::TestEnvironment;
+use crate::common::TestWorkDir;
+use crate::common::create_commit;
+
+const LOG_TEMPLATE: &str =
+    r#"templates.log='separate(" ", change_id.short(), empty, description.first_line()) ++ "\n"'"#;
+
+#[must_use]
+fn run_ui(work_dir: &TestWorkDir, keys: &str) -> CommandOutput {
+    work_dir.run_jj(["ui", "--config", LOG_TEMPLATE, "--debug-keys", keys])
+}
+
+#[must_use]
+fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
+    let template = r#"separate(" ", change_id.short(), empty, description)"#;
+    work_dir.run_jj(["log", "-T", template])
+}
+
+#[test]
+fn test_ui_not_terminal() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["ui"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: `jj ui` must be run in a terminal
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["ui", "--debug-keys", "j Foo"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Invalid key: Foo
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_ui_navigate_and_quit() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+
+    let output = run_ui(&work_dir, "j j k");
+    insta::assert_snapshot!(output, @"
+    @  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ");
+
+    // Keys after quitting are ignored
+    let output = run_ui(&work_dir, "q a Enter");
+    insta::assert_snapshot!(output, @"
+    @  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_ui_rebase() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    create_commit(&work_dir, "c", &["a"]);
+
+    // Preview the rebase, then discard it
+    let output = run_ui(&work_dir, "r j Enter");
+    insta::assert_snapshot!(output, @"
+    @  royxmykxtrkr false c
+    ○  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+    Preview: rebase royxmykxtrkr and descendants onto zsuskulnrvyr
+    j/k:scroll Enter:apply Esc:discard
+    [EOF]
+    ");
+    let output = run_ui(&work_dir, "r j Enter Esc");
+    insta::assert_snapshot!(output, @"
+    @  royxmykxtrkr false c
+    │ ○  zsuskulnrvyr false b
+    ├─╯
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+    Discarded: rebase royxmykxtrkr and descendants onto zsuskulnrvyr
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ");
+
+    // Apply the rebase
+    let output = run_ui(&work_dir, "r j Enter Enter");
+    insta::assert_snapshot!(output, @"
+    @  royxmykxtrkr false c
+    ○  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+    Applied: rebase royxmykxtrkr and descendants onto zsuskulnrvyr. Working copy  (@) now at: royxmykx 0
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: royxmykx 07f247d5 c | c
+    Parent commit (@-)      : zsuskuln 123b4d91 b | b
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  royxmykxtrkr false c
+    ○  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+    [EOF]
+    ");
+
+    // Cannot rebase onto descendant
+    let output = run_ui(&work_dir, "j j r k k Enter");
+    insta::assert_snapshot!(output, @"
+    @  royxmykxtrkr false c
+    ○  zsuskulnrvyr false b
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+    Error: Cannot rebase rlvkpnrzqnoo onto its descendant royxmykxtrkr
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_ui_abandon_and_new() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+
+    let output = run_ui(&work_dir, "a Enter j n Enter");
+    insta::assert_snapshot!(output, @"
+    @  nppvrztzynpl true
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+    Applied: new revision on top of rlvkpnrzqnoo. Working copy  (@) now at: nppvrztz 4cc24078 (empty) (n
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: royxmykx 2144134b (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz 7d980be7 a b | a
+    Added 0 files, modified 0 files, removed 1 files
+    Working copy  (@) now at: nppvrztz 4cc24078 (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz 7d980be7 a b | a
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  nppvrztzynpl true
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+    [EOF]
+    ");
+
+    // Root commit is immutable
+    let output = run_ui(&work_dir, "G a");
+    insta::assert_snapshot!(output, @"
+    @  nppvrztzynpl true
+    ○  rlvkpnrzqnoo false a
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+    Error: The root commit 000000000000 is immutable
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_ui_squash_and_describe() {
+    let mut test_env = TestEnvironment::default();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+
+    std::fs::write(&edit_script, "write\ncombined").unwrap();
+    let output = run_ui(&work_dir, "s Enter");
+    insta::assert_snapshot!(output, @"
+    @  royxmykxtrkr true
+    ○  rlvkpnrzqnoo false combined
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+    Applied: squash zsuskulnrvyr into its parent. Working copy  (@) now at: royxmykx faa6ffe9 (empty) (n
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: royxmykx faa6ffe9 (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz eec1dffe a b | combined
+    [EOF]
+    ");
+
+    std::fs::write(&edit_script, "write\ndescribed").unwrap();
+    let output = run_ui(&work_dir, "d Enter");
+    insta::assert_snapshot!(output, @"
+    @  royxmykxtrkr true described
+    ○  rlvkpnrzqnoo false combined
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+    Applied: describe royxmykxtrkr. Working copy  (@) now at: royxmykx 6b40a42c (empty) described
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: royxmykx 6b40a42c (empty) described
+    Parent commit (@-)      : rlvkpnrz eec1dffe a b | combined
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  royxmykxtrkr true described
+    ○  rlvkpnrzqnoo false combined
+    ◆  zzzzzzzzzzzz true
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_ui_split() {
+    let mut test_env = TestEnvironment::default();
+    let diff_editor = test_env.set_up_fake_diff_editor();
+    let edit_script = test_env.set_up_fake_editor();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "foo\n");
+
+    // Same as `jj split`, descriptions are asked for both commits
+    std::fs::write(diff_editor, "rm file2").unwrap();
+    std::fs::write(
+        edit_script,
+        ["write\nselected", "next invocation\n", "write\nremaining"].join("\0"),
+    )
+    .unwrap();
+    let output = run_ui(&work_dir, "S Enter");
+    insta::assert_snapshot!(output, @"
+    @  kkmpptxzrspx false remaining
+    ○  qpvuntsmwlqt false selected
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+
+    Applied: split qpvuntsmwlqt. Working copy  (@) now at: kkmpptxz 9899bb2a remaining
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: kkmpptxz 9899bb2a remaining
+    Parent commit (@-)      : qpvuntsm eacdccc3 selected
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  kkmpptxzrspx false remaining
+    ○  qpvuntsmwlqt false selected
+    ◆  zzzzzzzzzzzz true
+    [EOF]
+    ");
+
+    // Empty commit cannot be split
+    let output = run_ui(&work_dir, "n Enter k S");
+    insta::assert_snapshot!(output, @"
+    @  mzvwutvlkqwt true
+    ○  kkmpptxzrspx false remaining
+    ○  qpvuntsmwlqt false selected
+    ◆  zzzzzzzzzzzz true
+
+
+
+
+
+
+
+
+
+
+    Error: Refusing to split empty commit e2fd0120369036b62a117598fc745c7e0f22a5a1.
+    j/k:move r:rebase s:squash S:split d:describe a:abandon e:edit n:new q:quit
+    [EOF]
+    ------- stderr -------
+    Working copy  (@) now at: mzvwutvl e2fd0120 (empty) (no description set)
+    Parent commit (@-)      : kkmpptxz 9899bb2a remaining
+    [EOF]
+    ");
+}
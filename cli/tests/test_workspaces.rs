@@ -1604,6 +1604,197 @@ fn test_list_workspaces_template() {
     ");
 }
 
+#[test]
+fn test_list_workspaces_status() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+
+    main_dir.write_file("file", "contents\n");
+    main_dir.run_jj(["new"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    main_dir.run_jj(["workspace", "add", "../third"]).success();
+    main_dir.run_jj(["workspace", "add", "../fourth"]).success();
+
+    // Rewrite the working-copy commit of the secondary workspace
+    main_dir.write_file("file", "changed\n");
+    main_dir
+        .run_jj(["squash", "--into", "secondary@"])
+        .success();
+    // Delete the third workspace
+    std::fs::remove_dir_all(test_env.env_root().join("third")).unwrap();
+    // Modify the fourth workspace without snapshotting it
+    test_env.work_dir("fourth").write_file("file", "modified\n");
+
+    // Other working copies are only scanned for changes with --dirty
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: royxmykx 1830f56c (empty) (no description set)
+    fourth: zxsnswpr 7bccc386 (empty) (no description set)
+    secondary: pmmvwywv 56e5085f (no description set) (stale)
+    third: rzvqmyuk 49d1a560 (empty) (no description set) (missing)
+    [EOF]
+    ");
+    let output = main_dir.run_jj(["workspace", "list", "--dirty"]);
+    insta::assert_snapshot!(output, @r"
+    default: royxmykx 1830f56c (empty) (no description set)
+    fourth: zxsnswpr 7bccc386 (empty) (no description set) (dirty)
+    secondary: pmmvwywv 56e5085f (no description set) (stale)
+    third: rzvqmyuk 49d1a560 (empty) (no description set) (missing)
+    [EOF]
+    ");
+
+    // The changes aren't snapshotted by listing
+    let template = r#"name ++ ": " ++ path ++ " " ++ last_snapshot.ago() ++ "\n""#;
+    let output = main_dir.run_jj(["workspace", "list", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    default: $TEST_ENV/main 25 years ago
+    fourth: $TEST_ENV/fourth 25 years ago
+    secondary: $TEST_ENV/secondary 25 years ago
+    third: $TEST_ENV/third <Error: Workspace has no working copy on disk>
+    [EOF]
+    ");
+    let output = main_dir.run_jj(["workspace", "list", "--dirty"]);
+    insta::assert_snapshot!(output, @"
+    default: royxmykx 1830f56c (empty) (no description set)
+    fourth: zxsnswpr 7bccc386 (empty) (no description set) (dirty)
+    secondary: pmmvwywv 56e5085f (no description set) (stale)
+    third: rzvqmyuk 49d1a560 (empty) (no description set) (missing)
+    [EOF]
+    ");
+
+    // Status isn't available outside of `jj workspace list`
+    let output = main_dir.run_jj([
+        "log",
+        "-r=@",
+        "--no-graph",
+        "-T=working_copies.map(|ws| ws.stale())",
+    ]);
+    insta::assert_snapshot!(output, @"<Error: Workspace status is not available>[EOF]");
+}
+
+#[test]
+fn test_workspaces_prune() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    main_dir.run_jj(["workspace", "add", "../third"]).success();
+
+    let output = main_dir.run_jj(["workspace", "prune"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    std::fs::remove_dir_all(test_env.env_root().join("secondary")).unwrap();
+    let output = main_dir.run_jj(["workspace", "prune", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Would forget missing workspace secondary
+    [EOF]
+    ");
+    let output = main_dir.run_jj(["workspace", "prune"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Forgetting missing workspace secondary
+    [EOF]
+    ");
+
+    let output = main_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    third: pmmvwywv 337ba39f (empty) (no description set)
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_workspaces_move() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "main"]).success();
+    let main_dir = test_env.work_dir("main");
+
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+
+    let output = main_dir.run_jj(["workspace", "move", "default", "../elsewhere"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Cannot move the current workspace
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = main_dir.run_jj(["workspace", "move", "nonexistent", "../elsewhere"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No such workspace: nonexistent
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // The destination path is normalized
+    let output = main_dir.run_jj(["workspace", "move", "secondary", "../main/../moved"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace secondary to "../moved"
+    [EOF]
+    "#);
+    let output = main_dir.run_jj(["workspace", "root", "--name", "secondary"]);
+    insta::assert_snapshot!(output, @"
+    $TEST_ENV/moved
+    [EOF]
+    ");
+    let moved_dir = test_env.work_dir("moved");
+    moved_dir.run_jj(["status"]).success();
+
+    // Update the recorded path of a workspace that was moved by other means
+    std::fs::rename(
+        test_env.env_root().join("moved"),
+        test_env.env_root().join("moved-again"),
+    )
+    .unwrap();
+    // Other workspaces of the repo and workspaces of other repos aren't taken
+    // over
+    main_dir.run_jj(["workspace", "add", "../third"]).success();
+    test_env.run_jj_in(".", ["git", "init", "other"]).success();
+    let output = main_dir.run_jj(["workspace", "move", "secondary", "../third"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Workspace secondary is missing, and it isn't at the destination
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = main_dir.run_jj(["workspace", "move", "secondary", "../other"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    Error: Workspace secondary is missing, and it isn't at the destination
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = main_dir.run_jj(["workspace", "move", "secondary", "../moved-again"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Moved workspace secondary to "../moved-again"
+    [EOF]
+    "#);
+    let moved_dir = test_env.work_dir("moved-again");
+    let output = moved_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @r"
+    default: qpvuntsm e8849ae1 (empty) (no description set)
+    secondary: uuqppmxq 94f41578 (empty) (no description set)
+    third: nuwvvtmy a5c05b8e (empty) (no description set)
+    [EOF]
+    ");
+}
+
 /// Test getting the workspace root from primary and secondary workspaces
 #[test]
 fn test_workspaces_root() {
@@ -30,15 +30,23 @@ use crate::annotate::FileAnnotator;
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
+use crate::backend::CopyId;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::commit::conflict_label_for_commits;
 use crate::conflicts::MaterializedFileValue;
 use crate::conflicts::MaterializedTreeValue;
+use crate::conflicts::materialize_tree_value;
 use crate::conflicts::materialized_diff_stream;
 use crate::copies::CopyRecords;
 use crate::diff::ContentDiff;
 use crate::diff::DiffHunkKind;
+use crate::diff_presentation::DiffAlgorithm;
+use crate::diff_presentation::DiffAlgorithms;
+use crate::diff_presentation::LineCompareMode;
+use crate::diff_presentation::diff_by_line;
+use crate::fileset::FilesetExpression;
+use crate::index::IndexError;
 use crate::matchers::Matcher;
 use crate::merge::Diff;
 use crate::merge::Merge;
@@ -46,9 +54,13 @@ use crate::merged_tree::MergedTree;
 use crate::merged_tree_builder::MergedTreeBuilder;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
+use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
 use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+use crate::revset::RevsetFilterPredicate;
+use crate::store::Store;
 
 /// The source commit to absorb into its ancestry.
 #[derive(Clone, Debug)]
@@ -80,6 +92,15 @@ pub enum AbsorbError {
     /// Error resolving commit ancestry.
     #[error(transparent)]
     RevsetEvaluation(#[from] RevsetEvaluationError),
+    /// Error while querying the index.
+    #[error(transparent)]
+    Index(#[from] IndexError),
+    /// Hunks mapped by line annotation don't match the diff of the file.
+    #[error("Failed to map changes of {} to destination commits", path.as_internal_file_string())]
+    UnmappedHunks {
+        /// Path to the file.
+        path: RepoPathBuf,
+    },
 }
 
 /// An absorb 'plan' indicating which commits should be modified and what they
@@ -92,15 +113,238 @@ pub struct SelectedTrees {
     pub skipped_paths: Vec<(RepoPathBuf, String)>,
 }
 
+/// Hunk of the source commit, and the commits it can be absorbed into.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbsorbHunk {
+    /// Byte range of the hunk in the source parent (= left) content.
+    pub left_range: Range<usize>,
+    /// Byte range of the hunk in the source (= right) content.
+    pub right_range: Range<usize>,
+    /// Commit the hunk will be absorbed into, or `None` if the hunk should be
+    /// left in the source commit.
+    pub destination: Option<CommitId>,
+    /// Commits which modified the lines at or adjacent to the hunk. The
+    /// proposed destination comes first if any.
+    pub candidates: Vec<CommitId>,
+    /// How the destination was chosen, or why the hunk isn't absorbed.
+    pub reason: AbsorbHunkReason,
+}
+
+/// How the destination of [`AbsorbHunk`] was chosen, or why the hunk isn't
+/// absorbed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AbsorbHunkReason {
+    /// The modified lines were last modified by the destination commit.
+    Annotation,
+    /// The inserted lines were attributed to the commit which last modified
+    /// the surrounding lines.
+    Context,
+    /// The destination is the only commit which modified the file.
+    File,
+    /// The modified lines came from multiple commits.
+    MultipleCommits,
+    /// The lines were inserted between lines from different commits.
+    AmbiguousInsertion,
+    /// (Some of) the modified lines weren't introduced by the destination
+    /// commits.
+    NotInDestinations,
+    /// The hunk was excluded by the caller.
+    Excluded,
+}
+
+/// Optional heuristics to choose destinations of hunks which can't be
+/// attributed unambiguously by line annotation.
+#[derive(Clone, Debug, Default)]
+pub struct AbsorbHeuristics {
+    /// Attribute lines inserted between lines from different commits to the
+    /// commit which last modified the surrounding lines.
+    pub context: bool,
+    /// Attribute remaining hunks to the destination commit if it's the only
+    /// one which modified the file. Hunks modifying lines which weren't
+    /// introduced by the destination commits are left unattributed.
+    pub file: bool,
+}
+
+/// Changes to a file in the source commit, split into hunks.
+#[derive(Clone, Debug)]
+pub struct AbsorbFileHunks {
+    /// Path to the file.
+    pub path: RepoPathBuf,
+    /// Content of the file in the source parent.
+    pub left_text: BString,
+    /// Content of the file in the source commit.
+    pub right_text: BString,
+    /// Hunks in forward order.
+    pub hunks: Vec<AbsorbHunk>,
+    executable: bool,
+    copy_id: CopyId,
+    deleted: bool,
+    diff_algorithm: DiffAlgorithm,
+}
+
+impl AbsorbFileHunks {
+    /// Returns the indices of the hunks whose changes are included in the file
+    /// at `tree`. Hunks which were applied partially or modified aren't
+    /// included.
+    pub async fn find_hunks_in_tree(
+        &self,
+        store: &Store,
+        tree: &MergedTree,
+    ) -> BackendResult<Vec<usize>> {
+        let value = tree.path_value_async(&self.path).await?;
+        let value = materialize_tree_value(store, &self.path, value, tree.labels()).await?;
+        let text = match to_file_value(value) {
+            Ok(Some(mut value)) => value.read_all(&self.path).await?,
+            Ok(None) => vec![],
+            // Not a file, so no hunks can be found
+            Err(_) => return Ok(vec![]),
+        };
+        Ok(self.find_hunks_in_text(&text))
+    }
+
+    fn find_hunks_in_text(&self, text: &[u8]) -> Vec<usize> {
+        let diff = diff_by_line(
+            [self.left_text.as_slice(), text],
+            self.diff_algorithm,
+            &LineCompareMode::Exact,
+        );
+        let mut found = Vec::new();
+        for hunk in diff
+            .hunk_ranges()
+            .filter(|hunk| hunk.kind == DiffHunkKind::Different)
+        {
+            let [left_range, right_range]: &[_; 2] = hunk.ranges[..].try_into().unwrap();
+            // Deletion may be split into multiple hunks of contiguous ranges.
+            let Some(first) = self
+                .hunks
+                .iter()
+                .position(|cur| cur.left_range.start == left_range.start)
+            else {
+                continue;
+            };
+            let mut end = first;
+            let mut left_end = left_range.start;
+            let mut new_text = BString::default();
+            while let Some(cur) = self.hunks.get(end)
+                && cur.left_range.start == left_end
+                && cur.left_range.end <= left_range.end
+            {
+                new_text.extend_from_slice(&self.right_text[cur.right_range.clone()]);
+                left_end = cur.left_range.end;
+                end += 1;
+                if left_end == left_range.end {
+                    break;
+                }
+            }
+            if left_end == left_range.end && new_text == text[right_range.clone()] {
+                found.extend(first..end);
+            }
+        }
+        found
+    }
+}
+
+/// Hunks of the source commit and their destinations, which can be adjusted
+/// before building trees by [`AbsorbPlan::to_selected_trees()`].
+#[derive(Clone, Debug, Default)]
+pub struct AbsorbPlan {
+    /// Modified files in the source commit.
+    pub files: Vec<AbsorbFileHunks>,
+    /// Paths that were not absorbed for various error reasons.
+    pub skipped_paths: Vec<(RepoPathBuf, String)>,
+}
+
+impl AbsorbPlan {
+    /// Builds trees to be merged into destination commits from the hunks
+    /// mapped to destinations.
+    pub async fn to_selected_trees(
+        &self,
+        repo: &dyn Repo,
+        source: &AbsorbSource,
+    ) -> BackendResult<SelectedTrees> {
+        let mut selected_trees = SelectedTrees {
+            target_commits: HashMap::new(),
+            skipped_paths: self.skipped_paths.clone(),
+        };
+        for file in &self.files {
+            let mut selected_ranges: HashMap<&CommitId, Vec<SelectedRange>> = HashMap::new();
+            for hunk in &file.hunks {
+                if let Some(commit_id) = &hunk.destination {
+                    let ranges = selected_ranges.entry(commit_id).or_default();
+                    ranges.push((hunk.left_range.clone(), hunk.right_range.clone()));
+                }
+            }
+            // Build trees containing parent (= left) contents + selected hunks
+            for (commit_id, ranges) in selected_ranges {
+                let tree_builder = selected_trees
+                    .target_commits
+                    .entry(commit_id.clone())
+                    .or_insert_with(|| MergedTreeBuilder::new(source.parent_tree.clone()));
+                let new_text = combine_texts(&file.left_text, &file.right_text, &ranges);
+                // Since changes to be absorbed are represented as diffs relative
+                // to the source parent, we can propagate file deletion only if
+                // the whole file content is deleted at a single destination
+                // commit.
+                let new_tree_value = if new_text.is_empty() && file.deleted {
+                    Merge::absent()
+                } else {
+                    let id = repo
+                        .store()
+                        .write_file(&file.path, &mut new_text.as_slice())
+                        .await?;
+                    Merge::normal(TreeValue::File {
+                        id,
+                        executable: file.executable,
+                        copy_id: file.copy_id.clone(),
+                    })
+                };
+                tree_builder.set_or_remove(file.path.clone(), new_tree_value);
+            }
+        }
+        Ok(selected_trees)
+    }
+}
+
 /// Builds trees to be merged into destination commits by splitting source
 /// changes based on file annotation.
+///
+/// Changed lines are matched by the algorithm selected per file by
+/// `diff_algorithms`.
 pub async fn split_hunks_to_trees(
     repo: &dyn Repo,
     source: &AbsorbSource,
     destinations: &Arc<ResolvedRevsetExpression>,
     matcher: &dyn Matcher,
+    diff_algorithms: &DiffAlgorithms,
 ) -> Result<SelectedTrees, AbsorbError> {
-    let mut selected_trees = SelectedTrees::default();
+    let heuristics = AbsorbHeuristics::default();
+    let plan = plan_absorb(
+        repo,
+        source,
+        destinations,
+        matcher,
+        diff_algorithms,
+        &heuristics,
+    )
+    .await?;
+    Ok(plan.to_selected_trees(repo, source).await?)
+}
+
+/// Splits source changes into hunks, and maps each hunk to the destination
+/// commit based on file annotation. Hunks which can't be mapped by annotation
+/// may be mapped by the enabled `heuristics`.
+///
+/// Changed lines are matched by the algorithm selected per file by
+/// `diff_algorithms`.
+pub async fn plan_absorb(
+    repo: &dyn Repo,
+    source: &AbsorbSource,
+    destinations: &Arc<ResolvedRevsetExpression>,
+    matcher: &dyn Matcher,
+    diff_algorithms: &DiffAlgorithms,
+    heuristics: &AbsorbHeuristics,
+) -> Result<AbsorbPlan, AbsorbError> {
+    let mut plan = AbsorbPlan::default();
 
     let left_tree = &source.parent_tree;
     let right_tree = source.commit.tree();
@@ -125,9 +369,7 @@ pub async fn split_hunks_to_trees(
             // New file should have no destinations
             Ok(None) => continue,
             Err(reason) => {
-                selected_trees
-                    .skipped_paths
-                    .push((left_path.to_owned(), reason));
+                plan.skipped_paths.push((left_path.to_owned(), reason));
                 continue;
             }
         };
@@ -135,52 +377,72 @@ pub async fn split_hunks_to_trees(
             Ok(Some(mut value)) => (value.read_all(right_path).await?, false),
             Ok(None) => (vec![], true),
             Err(reason) => {
-                selected_trees
-                    .skipped_paths
-                    .push((right_path.to_owned(), reason));
+                plan.skipped_paths.push((right_path.to_owned(), reason));
                 continue;
             }
         };
 
         // Compute annotation of parent (= left) content to map right hunks
+        let diff_algorithm = diff_algorithms.for_path(right_path);
         let mut annotator =
             FileAnnotator::with_file_content(source.commit.id(), left_path, left_text.clone());
+        annotator.set_diff_algorithm(diff_algorithm);
         annotator.compute(repo, destinations)?;
         let annotation = annotator.to_annotation();
         let annotation_ranges = annotation
             .compact_line_ranges()
             .filter_map(|(commit_id, range)| Some((commit_id.ok()?, range)))
             .collect_vec();
-        let diff = ContentDiff::by_line([&left_text, &right_text]);
-        let selected_ranges = split_file_hunks(&annotation_ranges, &diff);
-        // Build trees containing parent (= left) contents + selected hunks
-        for (&commit_id, ranges) in &selected_ranges {
-            let tree_builder = selected_trees
-                .target_commits
-                .entry(commit_id.clone())
-                .or_insert_with(|| MergedTreeBuilder::new(left_tree.clone()));
-            let new_text = combine_texts(&left_text, &right_text, ranges);
-            // Since changes to be absorbed are represented as diffs relative to
-            // the source parent, we can propagate file deletion only if the
-            // whole file content is deleted at a single destination commit.
-            let new_tree_value = if new_text.is_empty() && deleted {
-                Merge::absent()
-            } else {
-                let id = repo
-                    .store()
-                    .write_file(left_path, &mut new_text.as_slice())
-                    .await?;
-                Merge::normal(TreeValue::File {
-                    id,
-                    executable,
-                    copy_id: copy_id.clone(),
-                })
-            };
-            tree_builder.set_or_remove(left_path.to_owned(), new_tree_value);
+        let diff = diff_by_line(
+            [&left_text, &right_text],
+            diff_algorithm,
+            &LineCompareMode::Exact,
+        );
+        let mut hunks = collect_file_hunks(&annotation_ranges, &diff).ok_or_else(|| {
+            AbsorbError::UnmappedHunks {
+                path: left_path.to_owned(),
+            }
+        })?;
+        drop(diff);
+        if heuristics.context {
+            attribute_insertions_by_context(repo, &mut hunks)?;
+        }
+        // Lines which weren't introduced by the destinations shouldn't be
+        // modified, but new lines can be inserted anywhere.
+        let is_absorbable_by_file = |hunk: &AbsorbHunk| match hunk.reason {
+            AbsorbHunkReason::MultipleCommits | AbsorbHunkReason::AmbiguousInsertion => true,
+            AbsorbHunkReason::NotInDestinations => hunk.left_range.is_empty(),
+            AbsorbHunkReason::Annotation
+            | AbsorbHunkReason::Context
+            | AbsorbHunkReason::File
+            | AbsorbHunkReason::Excluded => false,
+        };
+        if heuristics.file
+            && hunks.iter().any(is_absorbable_by_file)
+            && let Some(commit_id) =
+                sole_commit_modifying_file(repo, source, destinations, left_path)?
+        {
+            for hunk in hunks.iter_mut().filter(|hunk| is_absorbable_by_file(hunk)) {
+                if !hunk.candidates.contains(&commit_id) {
+                    hunk.candidates.insert(0, commit_id.clone());
+                }
+                hunk.destination = Some(commit_id.clone());
+                hunk.reason = AbsorbHunkReason::File;
+            }
         }
+        plan.files.push(AbsorbFileHunks {
+            path: left_path.to_owned(),
+            left_text: left_text.into(),
+            right_text: right_text.into(),
+            hunks,
+            executable,
+            copy_id,
+            deleted,
+            diff_algorithm,
+        });
     }
 
-    Ok(selected_trees)
+    Ok(plan)
 }
 
 type SelectedRange = (Range<usize>, Range<usize>);
@@ -263,6 +525,153 @@ fn split_file_hunks<'a>(
     selected_ranges
 }
 
+/// Lists `diff` hunks with the destinations mapped by [`split_file_hunks()`].
+/// Hunks which can't be mapped unambiguously are included without
+/// destination. Returns `None` if any of the mapped ranges doesn't belong to
+/// the `diff` hunks.
+fn collect_file_hunks(
+    annotation_ranges: &[(&CommitId, Range<usize>)],
+    diff: &ContentDiff,
+) -> Option<Vec<AbsorbHunk>> {
+    let mut selected_ranges = split_file_hunks(annotation_ranges, diff)
+        .into_iter()
+        .flat_map(|(commit_id, ranges)| {
+            ranges
+                .into_iter()
+                .map(move |(left_range, right_range)| (left_range, right_range, commit_id))
+        })
+        .collect_vec();
+    selected_ranges
+        .sort_unstable_by_key(|(left_range, right_range, _)| (right_range.start, left_range.start));
+    let mut selected_ranges = selected_ranges.into_iter().peekable();
+    // Commits which modified lines overlapping or adjacent to the range
+    let candidates_near = |range: &Range<usize>, first: Option<&CommitId>| {
+        itertools::chain(
+            first,
+            annotation_ranges
+                .iter()
+                .filter(|(_, cur)| cur.start <= range.end && range.start <= cur.end)
+                .map(|(commit_id, _)| *commit_id),
+        )
+        .unique()
+        .cloned()
+        .collect_vec()
+    };
+
+    let mut hunks = Vec::new();
+    for hunk in diff
+        .hunk_ranges()
+        .filter(|hunk| hunk.kind == DiffHunkKind::Different)
+    {
+        let [left_range, right_range]: &[_; 2] = hunk.ranges[..].try_into().unwrap();
+        let num_hunks = hunks.len();
+        while let Some((sub_left_range, sub_right_range, commit_id)) =
+            selected_ranges.next_if(|(sub_left_range, sub_right_range, _)| {
+                sub_right_range == right_range
+                    && left_range.start <= sub_left_range.start
+                    && sub_left_range.end <= left_range.end
+            })
+        {
+            hunks.push(AbsorbHunk {
+                candidates: candidates_near(&sub_left_range, Some(commit_id)),
+                left_range: sub_left_range,
+                right_range: sub_right_range,
+                destination: Some(commit_id.clone()),
+                reason: AbsorbHunkReason::Annotation,
+            });
+        }
+        if hunks.len() == num_hunks {
+            let candidates = candidates_near(left_range, None);
+            let reason = if left_range.is_empty() {
+                // Insertion adjacent to a single commit's lines should have
+                // been mapped.
+                if candidates.len() > 1 {
+                    AbsorbHunkReason::AmbiguousInsertion
+                } else {
+                    AbsorbHunkReason::NotInDestinations
+                }
+            } else {
+                let num_overlapped_commits = annotation_ranges
+                    .iter()
+                    .filter(|(_, cur)| cur.start < left_range.end && left_range.start < cur.end)
+                    .map(|(commit_id, _)| commit_id)
+                    .unique()
+                    .count();
+                if num_overlapped_commits > 1 {
+                    AbsorbHunkReason::MultipleCommits
+                } else {
+                    AbsorbHunkReason::NotInDestinations
+                }
+            };
+            hunks.push(AbsorbHunk {
+                left_range: left_range.clone(),
+                right_range: right_range.clone(),
+                destination: None,
+                candidates,
+                reason,
+            });
+        }
+    }
+    if selected_ranges.next().is_some() {
+        return None;
+    }
+    Some(hunks)
+}
+
+/// Maps ambiguous insertions to the descendant-most commit of the commits
+/// which modified the surrounding lines.
+fn attribute_insertions_by_context(
+    repo: &dyn Repo,
+    hunks: &mut [AbsorbHunk],
+) -> Result<(), IndexError> {
+    let index = repo.index();
+    for hunk in hunks
+        .iter_mut()
+        .filter(|hunk| hunk.reason == AbsorbHunkReason::AmbiguousInsertion)
+    {
+        for (pos, commit_id) in hunk.candidates.iter().enumerate() {
+            // Unrelated commits can't be ordered.
+            let mut is_latest = true;
+            for other_id in &hunk.candidates {
+                if other_id != commit_id && !index.is_ancestor(other_id, commit_id)? {
+                    is_latest = false;
+                    break;
+                }
+            }
+            if is_latest {
+                hunk.destination = Some(commit_id.clone());
+                hunk.reason = AbsorbHunkReason::Context;
+                // The destination should come first.
+                hunk.candidates[..=pos].rotate_right(1);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the destination commit if it's the only one which modified the
+/// file in the ancestors of the source.
+fn sole_commit_modifying_file(
+    repo: &dyn Repo,
+    source: &AbsorbSource,
+    destinations: &Arc<ResolvedRevsetExpression>,
+    path: &RepoPath,
+) -> Result<Option<CommitId>, RevsetEvaluationError> {
+    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(path.to_owned()));
+    let parent_ids = source.parents.iter().map(|commit| commit.id().clone());
+    let revset = RevsetExpression::commits(parent_ids.collect())
+        .ancestors()
+        .intersection(destinations)
+        .filtered(predicate)
+        .evaluate(repo)?;
+    let commit_ids: Vec<_> = revset.iter().take(2).try_collect()?;
+    match <[_; 1]>::try_from(commit_ids) {
+        Ok([commit_id]) => Ok(Some(commit_id)),
+        Err(_) => Ok(None),
+    }
+}
+
 /// Constructs new text by replacing `text1` range with `text2` range for each
 /// selected `(range1, range2)` pairs.
 fn combine_texts(text1: &[u8], text2: &[u8], selected_ranges: &[SelectedRange]) -> BString {
@@ -1121,6 +1530,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_collect_file_hunks() {
+        let commit_id1 = &CommitId::from_hex("111111");
+        let commit_id2 = &CommitId::from_hex("222222");
+        let commit_id3 = &CommitId::from_hex("333333");
+
+        let hunks = collect_file_hunks(
+            &[
+                (commit_id1, 0..3),   // 1a       => 1A
+                (commit_id2, 3..6),   // 2a       => 2a
+                (commit_id1, 6..15),  // 1b 1c 1d => 1B 1d
+                (commit_id3, 15..21), // 3a 3b    => 3a 3b
+                (commit_id2, 21..24), // 2b       => 2b 2X
+                (commit_id1, 24..27), // 1e       => 1e
+            ],
+            &ContentDiff::by_line([
+                "1a\n2a\n1b\n1c\n1d\n3a\n3b\n2b\n1e\n",
+                "1A\n2a\n1B\n1d\n3a\n3b\n2b\n2X\n1e\n",
+            ]),
+        );
+        assert_eq!(
+            hunks,
+            Some(vec![
+                AbsorbHunk {
+                    left_range: 0..3,
+                    right_range: 0..3,
+                    destination: Some(commit_id1.clone()),
+                    candidates: vec![commit_id1.clone(), commit_id2.clone()],
+                    reason: AbsorbHunkReason::Annotation,
+                },
+                AbsorbHunk {
+                    left_range: 6..12,
+                    right_range: 6..9,
+                    destination: Some(commit_id1.clone()),
+                    candidates: vec![commit_id1.clone(), commit_id2.clone()],
+                    reason: AbsorbHunkReason::Annotation,
+                },
+                // Insertion between two ranges is ambiguous
+                AbsorbHunk {
+                    left_range: 24..24,
+                    right_range: 21..24,
+                    destination: None,
+                    candidates: vec![commit_id2.clone(), commit_id1.clone()],
+                    reason: AbsorbHunkReason::AmbiguousInsertion,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_find_hunks_in_text() {
+        let commit_id1 = &CommitId::from_hex("111111");
+        let commit_id2 = &CommitId::from_hex("222222");
+
+        let left_text = "1a\n1b\n2a\n2b\n1c\n";
+        let right_text = "1A\n1b\n1c\nX\n";
+        let hunks = collect_file_hunks(
+            &[
+                (commit_id1, 0..6),   // 1a 1b => 1A 1b
+                (commit_id2, 6..12),  // 2a 2b =>
+                (commit_id1, 12..15), // 1c    => 1c X
+            ],
+            &ContentDiff::by_line([left_text, right_text]),
+        )
+        .unwrap();
+        assert_eq!(hunks.len(), 3);
+        let file = AbsorbFileHunks {
+            path: RepoPathBuf::from_internal_string("file").unwrap(),
+            left_text: left_text.into(),
+            right_text: right_text.into(),
+            hunks,
+            executable: false,
+            copy_id: CopyId::placeholder(),
+            deleted: false,
+            diff_algorithm: DiffAlgorithm::default(),
+        };
+
+        assert_eq!(file.find_hunks_in_text(left_text.as_bytes()), vec![]);
+        assert_eq!(
+            file.find_hunks_in_text(right_text.as_bytes()),
+            vec![0, 1, 2]
+        );
+        assert_eq!(file.find_hunks_in_text(b"1A\n1b\n2a\n2b\n1c\n"), vec![0]);
+        assert_eq!(file.find_hunks_in_text(b"1a\n1b\n1c\nX\n"), vec![1, 2]);
+        // Modified hunks aren't included
+        assert_eq!(file.find_hunks_in_text(b"1B\n1b\n1c\nX\n"), vec![1, 2]);
+        assert_eq!(file.find_hunks_in_text(b"1a\n1b\n2a\n1c\n"), vec![]);
+    }
+
     #[test]
     fn test_combine_texts() {
         assert_eq!(combine_texts(b"", b"", &[]), "");
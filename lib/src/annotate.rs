@@ -19,8 +19,10 @@
 //! Like commit metadata and more.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::hash_map;
 use std::iter;
+use std::mem;
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -38,17 +40,23 @@ use crate::conflicts::ConflictMaterializeOptions;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::materialize_merge_result_to_bytes;
 use crate::conflicts::materialize_tree_value;
-use crate::diff::ContentDiff;
+use crate::copies::find_copy_source;
+use crate::copies::has_file_in_parents;
 use crate::diff::DiffHunkKind;
+use crate::diff_presentation::DiffAlgorithm;
+use crate::diff_presentation::LineCompareMode;
+use crate::diff_presentation::diff_by_line;
 use crate::files::FileMergeHunkLevel;
 use crate::fileset::FilesetExpression;
 use crate::graph::GraphEdge;
 use crate::merge::SameChange;
+use crate::merge_driver::MergeDrivers;
 use crate::merged_tree::MergedTree;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
 use crate::revset::RevsetFilterPredicate;
@@ -152,8 +160,6 @@ impl FileAnnotation {
 /// Annotation process for a specific file.
 #[derive(Clone, Debug)]
 pub struct FileAnnotator {
-    // If we add copy-tracing support, file_path might be tracked by state.
-    file_path: RepoPathBuf,
     starting_text: BString,
     state: AnnotationState,
 }
@@ -164,7 +170,7 @@ impl FileAnnotator {
     /// If the file is not found, the result would be empty.
     pub fn from_commit(starting_commit: &Commit, file_path: &RepoPath) -> BackendResult<Self> {
         let source = Source::load(starting_commit, file_path)?;
-        Ok(Self::with_source(starting_commit.id(), file_path, source))
+        Ok(Self::with_source(starting_commit.id(), source))
     }
 
     /// Initializes annotator for a specific file path starting with the given
@@ -178,15 +184,11 @@ impl FileAnnotator {
         file_path: &RepoPath,
         starting_text: impl Into<Vec<u8>>,
     ) -> Self {
-        let source = Source::new(BString::new(starting_text.into()));
-        Self::with_source(starting_commit_id, file_path, source)
+        let source = Source::new(file_path.to_owned(), BString::new(starting_text.into()));
+        Self::with_source(starting_commit_id, source)
     }
 
-    fn with_source(
-        starting_commit_id: &CommitId,
-        file_path: &RepoPath,
-        mut source: Source,
-    ) -> Self {
+    fn with_source(starting_commit_id: &CommitId, mut source: Source) -> Self {
         source.fill_line_map();
         let starting_text = source.text.clone();
         let state = AnnotationState {
@@ -199,15 +201,54 @@ impl FileAnnotator {
                 })
                 .collect(),
             commit_source_map: HashMap::from([(starting_commit_id.clone(), source)]),
-            num_unresolved_roots: 0,
+            diff_algorithm: DiffAlgorithm::default(),
+            ignored_revisions: RevsetExpression::none(),
+            follow_copies: false,
         };
         Self {
-            file_path: file_path.to_owned(),
             starting_text,
             state,
         }
     }
 
+    /// Sets the algorithm to find lines in common with parent commits.
+    pub fn set_diff_algorithm(&mut self, algorithm: DiffAlgorithm) {
+        self.state.diff_algorithm = algorithm;
+    }
+
+    /// Restricts annotation to the given 0-based line range of the starting
+    /// file.
+    ///
+    /// Lines outside of the range are left unresolved, and the search stops
+    /// once all lines in the range are attributed. This should be called
+    /// before [`Self::compute()`].
+    pub fn set_line_range(&mut self, range: Range<usize>) {
+        for source in self.state.commit_source_map.values_mut() {
+            source
+                .line_map
+                .retain(|(_, starting_line_number)| range.contains(starting_line_number));
+        }
+    }
+
+    /// Sets revisions to look through.
+    ///
+    /// Lines changed by these revisions are attributed to the most similar
+    /// lines in their parents if possible, so e.g. reformatting commits don't
+    /// hide the previous authors. Lines added by these revisions (without
+    /// similar counterpart in the parents) are still attributed to them.
+    pub fn set_ignored_revisions(&mut self, expression: Arc<ResolvedRevsetExpression>) {
+        self.state.ignored_revisions = expression;
+    }
+
+    /// Sets whether to follow lines to the files the annotated file was copied
+    /// or renamed from.
+    ///
+    /// Copies are looked up by [`Store::get_copy_records()`] if none of the
+    /// parents has the file.
+    pub fn set_follow_copies(&mut self, follow: bool) {
+        self.state.follow_copies = follow;
+    }
+
     /// Computes line-by-line annotation within the `domain`.
     ///
     /// The `domain` expression narrows the range of ancestors to search. It
@@ -218,7 +259,7 @@ impl FileAnnotator {
         repo: &dyn Repo,
         domain: &Arc<ResolvedRevsetExpression>,
     ) -> Result<(), RevsetEvaluationError> {
-        process_commits(repo, &mut self.state, domain, &self.file_path)
+        process_commits(repo, &mut self.state, domain)
     }
 
     /// Remaining commit ids to visit from.
@@ -244,13 +285,19 @@ struct AnnotationState {
     original_line_map: OriginalLineMap,
     /// Commits to file line mappings and contents.
     commit_source_map: HashMap<CommitId, Source>,
-    /// Number of unresolved root commits in `commit_source_map`.
-    num_unresolved_roots: usize,
+    /// Algorithm to find lines in common between commits.
+    diff_algorithm: DiffAlgorithm,
+    /// Revisions whose changed lines should be attributed to their parents.
+    ignored_revisions: Arc<ResolvedRevsetExpression>,
+    /// Whether to follow lines to the sources of copied files.
+    follow_copies: bool,
 }
 
 /// Line mapping and file content at a certain commit.
 #[derive(Clone, Debug)]
 struct Source {
+    /// Path of the file at the current commit.
+    path: RepoPathBuf,
     /// Mapping of line numbers in the file at the current commit to the
     /// starting file, sorted by the line numbers at the current commit.
     line_map: Vec<(usize, usize)>,
@@ -259,8 +306,9 @@ struct Source {
 }
 
 impl Source {
-    fn new(text: BString) -> Self {
+    fn new(path: RepoPathBuf, text: BString) -> Self {
         Self {
+            path,
             line_map: Vec::new(),
             text,
         }
@@ -269,7 +317,7 @@ impl Source {
     fn load(commit: &Commit, file_path: &RepoPath) -> Result<Self, BackendError> {
         let tree = commit.tree();
         let text = get_file_contents(commit.store(), file_path, &tree).block_on()?;
-        Ok(Self::new(text))
+        Ok(Self::new(file_path.to_owned(), text))
     }
 
     fn fill_line_map(&mut self) {
@@ -297,24 +345,83 @@ fn process_commits(
     repo: &dyn Repo,
     state: &mut AnnotationState,
     domain: &Arc<ResolvedRevsetExpression>,
-    file_name: &RepoPath,
 ) -> Result<(), RevsetEvaluationError> {
-    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
+    let ignored_revset = state.ignored_revisions.clone().evaluate(repo)?;
+    let is_ignored = ignored_revset.containing_fn();
+    // Commits outside of the domain, where the lines are left unresolved.
+    let mut unresolved_roots = HashSet::new();
+    // Walk history of one path at a time. Sources of copied files are walked
+    // after the history of the copy.
+    while let Some(file_path) = state
+        .commit_source_map
+        .iter()
+        .find(|(commit_id, _)| !unresolved_roots.contains(*commit_id))
+        .map(|(_, source)| source.path.clone())
+    {
+        process_commits_for_path(
+            repo,
+            state,
+            domain,
+            &*is_ignored,
+            &file_path,
+            &mut unresolved_roots,
+        )?;
+    }
+    Ok(())
+}
+
+fn process_commits_for_path(
+    repo: &dyn Repo,
+    state: &mut AnnotationState,
+    domain: &Arc<ResolvedRevsetExpression>,
+    is_ignored: &RevsetContainingFn,
+    file_path: &RepoPath,
+    unresolved_roots: &mut HashSet<CommitId>,
+) -> Result<(), RevsetEvaluationError> {
+    let is_pending =
+        |unresolved_roots: &HashSet<CommitId>, commit_id: &CommitId, source: &Source| {
+            source.path.as_ref() == file_path && !unresolved_roots.contains(commit_id)
+        };
+    let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_path.to_owned()));
     // TODO: If the domain isn't a contiguous range, changes masked out by it
     // might not be caught by the closest ancestor revision. For example,
     // domain=merges() would pick up almost nothing because merge revisions
     // are usually empty. Perhaps, we want to query `files(file_path,
     // within_sub_graph=domain)`, not `domain & files(file_path)`.
-    let heads = RevsetExpression::commits(state.commit_source_map.keys().cloned().collect());
-    let revset = heads
-        .union(&domain.intersection(&heads.ancestors()).filtered(predicate))
-        .evaluate(repo)?;
+    let heads = RevsetExpression::commits(
+        state
+            .commit_source_map
+            .iter()
+            .filter(|(commit_id, source)| is_pending(unresolved_roots, commit_id, source))
+            .map(|(commit_id, _)| commit_id.clone())
+            .collect(),
+    );
+    let candidates = domain.intersection(&heads.ancestors()).filtered(predicate);
+    let revset = heads.union(&candidates).evaluate(repo)?;
 
-    state.num_unresolved_roots = 0;
     for node in revset.iter_graph() {
         let (commit_id, edge_list) = node?;
-        process_commit(repo, file_name, state, &commit_id, &edge_list)?;
-        if state.commit_source_map.len() == state.num_unresolved_roots {
+        if !state
+            .commit_source_map
+            .get(&commit_id)
+            .is_some_and(|source| is_pending(unresolved_roots, &commit_id, source))
+        {
+            continue;
+        }
+        let look_through = is_ignored(&commit_id)?;
+        process_commit(
+            repo,
+            state,
+            &commit_id,
+            &edge_list,
+            look_through,
+            unresolved_roots,
+        )?;
+        if !state
+            .commit_source_map
+            .iter()
+            .any(|(commit_id, source)| is_pending(unresolved_roots, commit_id, source))
+        {
             // No more lines to propagate to ancestors.
             break;
         }
@@ -325,12 +432,16 @@ fn process_commits(
 /// For a given commit, for each parent, we compare the version in the parent
 /// tree with the current version, updating the mappings for any lines in
 /// common. If the parent doesn't have the file, we skip it.
+///
+/// If `look_through` is set, changed lines are also mapped to the most similar
+/// parent lines within the changed hunk.
 fn process_commit(
     repo: &dyn Repo,
-    file_name: &RepoPath,
     state: &mut AnnotationState,
     current_commit_id: &CommitId,
     edges: &[GraphEdge<CommitId>],
+    look_through: bool,
+    unresolved_roots: &mut HashSet<CommitId>,
 ) -> Result<(), BackendError> {
     let Some(mut current_source) = state.commit_source_map.remove(current_commit_id) else {
         return Ok(());
@@ -342,42 +453,19 @@ fn process_commit(
             hash_map::Entry::Occupied(entry) => entry.into_mut(),
             hash_map::Entry::Vacant(entry) => {
                 let commit = repo.store().get_commit(entry.key())?;
-                entry.insert(Source::load(&commit, file_name)?)
+                entry.insert(Source::load(&commit, &current_source.path)?)
             }
         };
-
-        // For two versions of the same file, for all the lines in common,
-        // overwrite the new mapping in the results for the new commit. Let's
-        // say I have a file in commit A and commit B. We know that according to
-        // local line_map, in commit A, line 3 corresponds to line 7 of the
-        // starting file. Now, line 3 in Commit A corresponds to line 6 in
-        // commit B. Then, we update local line_map to say that "Commit B line 6
-        // goes to line 7 of the starting file". We repeat this for all lines in
-        // common in the two commits.
-        let mut current_lines = current_source.line_map.iter().copied().peekable();
-        let mut new_current_line_map = Vec::new();
-        let mut new_parent_line_map = Vec::new();
-        copy_same_lines_with(
-            &current_source.text,
-            &parent_source.text,
-            |current_start, parent_start, count| {
-                new_current_line_map
-                    .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
-                while let Some((current, starting)) =
-                    current_lines.next_if(|&(cur, _)| cur < current_start + count)
-                {
-                    let parent = parent_start + (current - current_start);
-                    new_parent_line_map.push((parent, starting));
-                }
-            },
+        if parent_source.path != current_source.path {
+            // The parent is pending as the source of another copied file.
+            continue;
+        }
+        map_lines_to_parent(
+            &mut current_source,
+            parent_source,
+            state.diff_algorithm,
+            look_through,
         );
-        new_current_line_map.extend(current_lines);
-        current_source.line_map = new_current_line_map;
-        parent_source.line_map = if parent_source.line_map.is_empty() {
-            new_parent_line_map
-        } else {
-            itertools::merge(parent_source.line_map.iter().copied(), new_parent_line_map).collect()
-        };
         if parent_source.line_map.is_empty() {
             state.commit_source_map.remove(parent_commit_id);
         } else if parent_edge.is_missing() {
@@ -390,10 +478,20 @@ fn process_commit(
                     line_number: parent_line_number,
                 });
             }
-            state.num_unresolved_roots += 1;
+            unresolved_roots.insert(parent_commit_id.clone());
         }
     }
 
+    if state.follow_copies && !current_source.line_map.is_empty() {
+        map_lines_to_copy_source(
+            repo,
+            state,
+            current_commit_id,
+            &mut current_source,
+            look_through,
+        )?;
+    }
+
     // Once we've looked at all parents of a commit, any leftover lines must be
     // original to the current commit, so we save this information in
     // original_line_map.
@@ -407,14 +505,362 @@ fn process_commit(
     Ok(())
 }
 
+/// Moves the lines in common with the parent from the current line map to the
+/// parent line map.
+fn map_lines_to_parent(
+    current_source: &mut Source,
+    parent_source: &mut Source,
+    diff_algorithm: DiffAlgorithm,
+    look_through: bool,
+) {
+    // For two versions of the same file, for all the lines in common,
+    // overwrite the new mapping in the results for the new commit. Let's
+    // say I have a file in commit A and commit B. We know that according to
+    // local line_map, in commit A, line 3 corresponds to line 7 of the
+    // starting file. Now, line 3 in Commit A corresponds to line 6 in
+    // commit B. Then, we update local line_map to say that "Commit B line 6
+    // goes to line 7 of the starting file". We repeat this for all lines in
+    // common in the two commits.
+    let mut current_lines = current_source.line_map.iter().copied().peekable();
+    let mut new_current_line_map = Vec::new();
+    let mut new_parent_line_map = Vec::new();
+    copy_same_lines_with(
+        &current_source.text,
+        &parent_source.text,
+        diff_algorithm,
+        look_through,
+        |current_start, parent_start, count| {
+            new_current_line_map
+                .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
+            while let Some((current, starting)) =
+                current_lines.next_if(|&(cur, _)| cur < current_start + count)
+            {
+                let parent = parent_start + (current - current_start);
+                new_parent_line_map.push((parent, starting));
+            }
+        },
+    );
+    new_current_line_map.extend(current_lines);
+    current_source.line_map = new_current_line_map;
+    parent_source.line_map = if parent_source.line_map.is_empty() {
+        new_parent_line_map
+    } else {
+        itertools::merge(parent_source.line_map.iter().copied(), new_parent_line_map).collect()
+    };
+}
+
+/// If none of the parents has the current file, maps the remaining lines to the
+/// file in the first parent the current file was copied from.
+fn map_lines_to_copy_source(
+    repo: &dyn Repo,
+    state: &mut AnnotationState,
+    current_commit_id: &CommitId,
+    current_source: &mut Source,
+    look_through: bool,
+) -> Result<(), BackendError> {
+    let commit = repo.store().get_commit(current_commit_id)?;
+    let Some(parent_commit_id) = commit.parent_ids().first() else {
+        return Ok(());
+    };
+    if has_file_in_parents(&commit, &current_source.path)? {
+        return Ok(());
+    }
+    let Some(source_path) = find_copy_source(
+        repo.store(),
+        parent_commit_id,
+        current_commit_id,
+        &current_source.path,
+    )?
+    else {
+        return Ok(());
+    };
+    if source_path == current_source.path {
+        return Ok(());
+    }
+    let parent_source = match state.commit_source_map.entry(parent_commit_id.clone()) {
+        hash_map::Entry::Occupied(entry) if entry.get().path == source_path => entry.into_mut(),
+        hash_map::Entry::Occupied(_) => return Ok(()),
+        hash_map::Entry::Vacant(entry) => {
+            let commit = repo.store().get_commit(entry.key())?;
+            entry.insert(Source::load(&commit, &source_path)?)
+        }
+    };
+    map_lines_to_parent(
+        current_source,
+        parent_source,
+        state.diff_algorithm,
+        look_through,
+    );
+    if parent_source.line_map.is_empty() {
+        state.commit_source_map.remove(parent_commit_id);
+    }
+    Ok(())
+}
+
+/// Version of a traced line range at a certain commit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineRangeVersion {
+    /// Commit ID where the lines were found.
+    pub commit_id: CommitId,
+    /// Path of the file in the commit.
+    pub path: RepoPathBuf,
+    /// 0-based line range in the file.
+    pub range: Range<usize>,
+    /// Content of the lines in the range.
+    pub text: BString,
+}
+
+/// Commit that changed a traced line range.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineRangeChange {
+    /// Lines after the change.
+    pub current: LineRangeVersion,
+    /// Corresponding lines in the first parent commit, or `None` if the file
+    /// was added by the commit. The range is empty if all lines were added.
+    pub parent: Option<LineRangeVersion>,
+}
+
+/// Traces the `range` of lines in the file at the `starting_commit` back
+/// through history, returning the commits that changed the lines.
+///
+/// Lines are mapped to the parent commits in the same way as
+/// [`FileAnnotator`]. The range in the parent spans all lines corresponding to
+/// the range, including the lines replaced within it. If the file was renamed,
+/// the lines are followed to the source path recorded by the backend.
+///
+/// The `domain` expression narrows the range of ancestors to search. Changes
+/// are returned in topological order for each path.
+pub fn trace_line_range(
+    repo: &dyn Repo,
+    starting_commit: &Commit,
+    file_path: &RepoPath,
+    range: Range<usize>,
+    algorithm: DiffAlgorithm,
+    domain: &Arc<ResolvedRevsetExpression>,
+) -> Result<Vec<LineRangeChange>, RevsetEvaluationError> {
+    let text = get_file_contents(repo.store(), file_path, &starting_commit.tree()).block_on()?;
+    let mut pending = HashMap::from([(
+        starting_commit.id().clone(),
+        TracedRange {
+            path: file_path.to_owned(),
+            range,
+            text,
+        },
+    )]);
+    let mut changes = Vec::new();
+    // Walk history of one path at a time. Ranges in renamed files are
+    // deferred to later walks.
+    while let Some(file_path) = pending.values().next().map(|traced| traced.path.clone()) {
+        let mut deferred: HashMap<_, _>;
+        (pending, deferred) = mem::take(&mut pending)
+            .into_iter()
+            .partition(|(_, traced)| traced.path == file_path);
+        let predicate =
+            RevsetFilterPredicate::File(FilesetExpression::file_path(file_path.clone()));
+        let heads = RevsetExpression::commits(pending.keys().cloned().collect());
+        let revset = heads
+            .union(&domain.intersection(&heads.ancestors()).filtered(predicate))
+            .evaluate(repo)?;
+        for node in revset.iter_graph() {
+            let (commit_id, edges) = node?;
+            let Some(current) = pending.remove(&commit_id) else {
+                continue;
+            };
+            trace_commit(
+                repo,
+                algorithm,
+                &commit_id,
+                &edges,
+                current,
+                &mut pending,
+                &mut deferred,
+                &mut changes,
+            )?;
+            if pending.is_empty() {
+                break;
+            }
+        }
+        // Ranges left in pending are outside of the domain.
+        pending = deferred;
+    }
+    Ok(changes)
+}
+
+/// Line range to be traced from a certain commit.
+#[derive(Clone, Debug)]
+struct TracedRange {
+    path: RepoPathBuf,
+    range: Range<usize>,
+    /// Whole file content at the commit.
+    text: BString,
+}
+
+impl TracedRange {
+    fn to_version(&self, commit_id: &CommitId) -> LineRangeVersion {
+        let text = self
+            .text
+            .split_inclusive(|b| *b == b'\n')
+            .skip(self.range.start)
+            .take(self.range.len())
+            .flatten()
+            .copied()
+            .collect();
+        LineRangeVersion {
+            commit_id: commit_id.clone(),
+            path: self.path.clone(),
+            range: self.range.clone(),
+            text,
+        }
+    }
+
+    /// Extends this range to cover the lines of the other range.
+    fn extend(&mut self, other: &Self) {
+        self.range = self.range.start.min(other.range.start)..self.range.end.max(other.range.end);
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn trace_commit(
+    repo: &dyn Repo,
+    algorithm: DiffAlgorithm,
+    commit_id: &CommitId,
+    edges: &[GraphEdge<CommitId>],
+    current: TracedRange,
+    pending: &mut HashMap<CommitId, TracedRange>,
+    deferred: &mut HashMap<CommitId, TracedRange>,
+    changes: &mut Vec<LineRangeChange>,
+) -> Result<(), RevsetEvaluationError> {
+    let store = repo.store();
+    let commit = store.get_commit(commit_id)?;
+    let mut parent_sources = Vec::new();
+    if has_file_in_parents(&commit, &current.path)? {
+        for edge in edges {
+            parent_sources.push((edge.target.clone(), current.path.clone()));
+        }
+    } else if let Some(parent_id) = commit.parent_ids().first()
+        && let Some(source_path) =
+            find_copy_source(repo.store(), parent_id, commit_id, &current.path)?
+    {
+        parent_sources.push((parent_id.clone(), source_path));
+    }
+
+    let mut parents = Vec::new();
+    let mut unchanged = false;
+    for (parent_id, path) in parent_sources {
+        let parent_commit = store.get_commit(&parent_id)?;
+        let text = get_file_contents(store, &path, &parent_commit.tree()).block_on()?;
+        let (range, same_lines) = map_line_range(&current.text, &text, algorithm, &current.range);
+        let traced = TracedRange { path, range, text };
+        if same_lines {
+            // The history of the other parents doesn't matter if the lines
+            // came from this parent as is.
+            parents = vec![(parent_id, traced)];
+            unchanged = true;
+            break;
+        }
+        parents.push((parent_id, traced));
+    }
+    if !unchanged {
+        changes.push(LineRangeChange {
+            current: current.to_version(commit_id),
+            parent: parents
+                .first()
+                .map(|(parent_id, traced)| traced.to_version(parent_id)),
+        });
+    }
+    for (parent_id, traced) in parents {
+        if traced.range.is_empty() {
+            continue;
+        }
+        let queue = if traced.path == current.path {
+            &mut *pending
+        } else {
+            &mut *deferred
+        };
+        match queue.entry(parent_id) {
+            hash_map::Entry::Occupied(mut entry) => entry.get_mut().extend(&traced),
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(traced);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Maps the `range` of lines in the current file to the lines in the parent
+/// file. Returns the range in the parent, and whether the lines are unchanged.
+fn map_line_range(
+    current_contents: &[u8],
+    parent_contents: &[u8],
+    algorithm: DiffAlgorithm,
+    range: &Range<usize>,
+) -> (Range<usize>, bool) {
+    let diff = diff_by_line(
+        [current_contents, parent_contents],
+        algorithm,
+        &LineCompareMode::Exact,
+    );
+    let mut parent_range: Option<Range<usize>> = None;
+    let mut unchanged = true;
+    let mut current_line_counter: usize = 0;
+    let mut parent_line_counter: usize = 0;
+    for hunk in diff.hunks() {
+        let current_count = hunk.contents[0].split_inclusive(|b| *b == b'\n').count();
+        let parent_count = hunk.contents[1].split_inclusive(|b| *b == b'\n').count();
+        let current_start = current_line_counter;
+        let current_end = current_line_counter + current_count;
+        let mapped = match hunk.kind {
+            DiffHunkKind::Matching => {
+                let start = range.start.max(current_start);
+                let end = range.end.min(current_end);
+                (start < end).then(|| {
+                    let parent_start = parent_line_counter + (start - current_start);
+                    parent_start..parent_start + (end - start)
+                })
+            }
+            DiffHunkKind::Different => {
+                // Lines deleted within the range also change it.
+                let overlaps = if current_count == 0 {
+                    range.start < current_start && current_start < range.end
+                } else {
+                    range.start < current_end && current_start < range.end
+                };
+                if overlaps {
+                    unchanged = false;
+                }
+                (overlaps && parent_count > 0)
+                    .then(|| parent_line_counter..parent_line_counter + parent_count)
+            }
+        };
+        if let Some(mapped) = mapped {
+            parent_range = Some(match parent_range {
+                Some(acc) => acc.start..mapped.end,
+                None => mapped,
+            });
+        }
+        current_line_counter = current_end;
+        parent_line_counter += parent_count;
+    }
+    (parent_range.unwrap_or(0..0), unchanged)
+}
+
 /// For two files, calls `copy(current_start, parent_start, count)` for each
 /// range of contiguous lines in common (e.g. line 8-10 maps to line 9-11.)
+///
+/// If `map_changed_lines` is set, lines in changed hunks are paired up by
+/// position, and the excess lines of the longer side are left unmapped.
 fn copy_same_lines_with(
     current_contents: &[u8],
     parent_contents: &[u8],
+    algorithm: DiffAlgorithm,
+    map_changed_lines: bool,
     mut copy: impl FnMut(usize, usize, usize),
 ) {
-    let diff = ContentDiff::by_line([current_contents, parent_contents]);
+    let diff = diff_by_line(
+        [current_contents, parent_contents],
+        algorithm,
+        &LineCompareMode::Exact,
+    );
     let mut current_line_counter: usize = 0;
     let mut parent_line_counter: usize = 0;
     for hunk in diff.hunks() {
@@ -426,15 +872,103 @@ fn copy_same_lines_with(
                 parent_line_counter += count;
             }
             DiffHunkKind::Different => {
-                let current_output = hunk.contents[0];
-                let parent_output = hunk.contents[1];
-                current_line_counter += current_output.split_inclusive(|b| *b == b'\n').count();
-                parent_line_counter += parent_output.split_inclusive(|b| *b == b'\n').count();
+                let current_count = hunk.contents[0].split_inclusive(|b| *b == b'\n').count();
+                let parent_count = hunk.contents[1].split_inclusive(|b| *b == b'\n').count();
+                if map_changed_lines {
+                    for (current, parent) in align_similar_lines(hunk.contents[0], hunk.contents[1])
+                    {
+                        copy(
+                            current_line_counter + current,
+                            parent_line_counter + parent,
+                            1,
+                        );
+                    }
+                }
+                current_line_counter += current_count;
+                parent_line_counter += parent_count;
             }
         }
     }
 }
 
+/// Minimum similarity (in per mille) of lines to be aligned by
+/// [`align_similar_lines()`].
+const LINE_SIMILARITY_THRESHOLD: u32 = 500;
+
+/// Maximum number of line pairs to compare by [`align_similar_lines()`].
+const MAX_ALIGNED_LINE_PAIRS: usize = 1 << 20;
+
+/// Pairs up similar lines of the changed `current_contents` and
+/// `parent_contents` in order. Returns pairs of 0-based line numbers.
+///
+/// Lines are compared ignoring whitespace and ASCII case, and pairs are chosen
+/// to maximize the total similarity.
+fn align_similar_lines(current_contents: &[u8], parent_contents: &[u8]) -> Vec<(usize, usize)> {
+    let fingerprint = |line: &[u8]| {
+        let mut bytes = line
+            .iter()
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(u8::to_ascii_lowercase)
+            .collect_vec();
+        bytes.sort_unstable();
+        bytes
+    };
+    let current_lines = current_contents
+        .split_inclusive(|b| *b == b'\n')
+        .map(fingerprint)
+        .collect_vec();
+    let parent_lines = parent_contents
+        .split_inclusive(|b| *b == b'\n')
+        .map(fingerprint)
+        .collect_vec();
+    let (n, m) = (current_lines.len(), parent_lines.len());
+    if n == 0 || m == 0 || n * m > MAX_ALIGNED_LINE_PAIRS {
+        return vec![];
+    }
+    let similarity = |i: usize, j: usize| {
+        let score = line_similarity(&current_lines[i], &parent_lines[j]);
+        (score >= LINE_SIMILARITY_THRESHOLD).then_some(score)
+    };
+    // scores[i * (m + 1) + j] is the best total similarity of the alignment
+    // of current_lines[i..] and parent_lines[j..].
+    let index = |i: usize, j: usize| i * (m + 1) + j;
+    let mut scores = vec![0_u32; (n + 1) * (m + 1)];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            let skip = scores[index(i + 1, j)].max(scores[index(i, j + 1)]);
+            let pair = similarity(i, j).map_or(0, |score| scores[index(i + 1, j + 1)] + score);
+            scores[index(i, j)] = skip.max(pair);
+        }
+    }
+    let mut pairs = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if similarity(i, j)
+            .is_some_and(|score| scores[index(i, j)] == scores[index(i + 1, j + 1)] + score)
+        {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if scores[index(i, j)] == scores[index(i + 1, j)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Returns the similarity of the sorted line fingerprints in per mille.
+fn line_similarity(a: &[u8], b: &[u8]) -> u32 {
+    if a.is_empty() && b.is_empty() {
+        return 1000;
+    }
+    let common = itertools::merge_join_by(a, b, Ord::cmp)
+        .filter(|either| either.is_both())
+        .count();
+    u32::try_from(2000 * common / (a.len() + b.len())).unwrap()
+}
+
 async fn get_file_contents(
     store: &Store,
     path: &RepoPath,
@@ -453,6 +987,7 @@ async fn get_file_contents(
                 merge: MergeOptions {
                     hunk_level: FileMergeHunkLevel::Line,
                     same_change: SameChange::Accept,
+                    drivers: MergeDrivers::default(),
                 },
             };
             Ok(materialize_merge_result_to_bytes(
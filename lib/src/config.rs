@@ -277,6 +277,12 @@ impl ToConfigNamePath for &[&str] {
     }
 }
 
+/// Path to the config file which can be shared through the repository tree.
+///
+/// The file is stored in the reserved `.jj` directory, so it is never checked
+/// out to nor snapshotted from the working copy.
+pub const TREE_CONFIG_PATH: &str = ".jj/config.toml";
+
 /// Source of configuration variables in order of precedence.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum ConfigSource {
@@ -286,6 +292,8 @@ pub enum ConfigSource {
     EnvBase,
     /// User configuration files.
     User,
+    /// Config file committed to the repository and trusted by the user.
+    Tree,
     /// Repo configuration files.
     Repo,
     /// Workspace configuration files.
@@ -302,6 +310,7 @@ impl Display for ConfigSource {
         let c = match self {
             Default => "default",
             User => "user",
+            Tree => "tree",
             Repo => "repo",
             Workspace => "workspace",
             CommandArg => "cli",
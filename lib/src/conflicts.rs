@@ -1134,6 +1134,7 @@ mod tests {
 
     use super::*;
     use crate::files::FileMergeHunkLevel;
+    use crate::merge_driver::MergeDrivers;
 
     #[test]
     fn test_resolve_file_executable() {
@@ -1363,6 +1364,7 @@ mod tests {
             merge: MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                drivers: MergeDrivers::default(),
             },
         };
         let actual_contents = String::from_utf8(
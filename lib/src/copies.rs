@@ -15,21 +15,53 @@
 //! Code for working with copies and renames.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::iter;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
 use std::task::ready;
 
 use futures::Stream;
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
+use pollster::FutureExt as _;
+use tokio::io::AsyncReadExt as _;
 
+use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::backend::CopyHistory;
+use crate::backend::CopyId;
 use crate::backend::CopyRecord;
+use crate::backend::FileId;
+use crate::backend::TreeValue;
+use crate::commit::Commit;
+use crate::config::ConfigGetError;
+use crate::dag_walk;
+use crate::fileset::FilesetExpression;
+use crate::matchers::EverythingMatcher;
+use crate::matchers::FilesMatcher;
+use crate::matchers::Matcher;
 use crate::merge::Diff;
+use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
+use crate::merge::SameChange;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::TreeDiffStream;
+use crate::merged_tree_builder::MergedTreeBuilder;
+use crate::object_id::ObjectId as _;
+use crate::repo::Repo;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+use crate::revset::RevsetFilterPredicate;
+use crate::revset::RevsetIteratorExt as _;
+use crate::settings::UserSettings;
+use crate::store::Store;
 
 /// A collection of CopyRecords.
 #[derive(Default, Debug)]
@@ -223,3 +255,568 @@ impl Stream for CopiesTreeDiffStream<'_> {
         Poll::Ready(None)
     }
 }
+
+/// Options for detecting copies and renames by content similarity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CopyDetectionOptions {
+    /// Whether to detect copies in addition to the ones reported by the
+    /// backend.
+    pub enabled: bool,
+    /// Minimum percentage of lines in the added file that must come from the
+    /// source file.
+    pub threshold: u32,
+    /// Maximum number of added files and source candidates to compare.
+    /// Detection is skipped if there are more.
+    pub max_candidates: usize,
+}
+
+impl Default for CopyDetectionOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 50,
+            max_candidates: 100,
+        }
+    }
+}
+
+impl CopyDetectionOptions {
+    /// Loads copy detection options from `settings`.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(Self {
+            enabled: settings.get("copies.detect")?,
+            threshold: settings.get("copies.similarity-threshold")?,
+            max_candidates: settings.get("copies.max-candidates")?,
+        })
+    }
+}
+
+/// Detects copies and renames between the `root` and `head` commits by
+/// comparing the contents of added files with files that were deleted or
+/// modified.
+///
+/// For a source file that still exists in `head`, only the lines removed from
+/// it are compared, so files split out of another file are detected as
+/// copies. The source with the highest share of the added file's lines is
+/// chosen if the share reaches the threshold. Blank lines are ignored.
+///
+/// Targets in `known_records` and targets not in `paths` (if specified) are
+/// skipped.
+pub async fn detect_copies(
+    store: &Arc<Store>,
+    root: &CommitId,
+    head: &CommitId,
+    paths: Option<&[RepoPathBuf]>,
+    known_records: &[CopyRecord],
+    options: &CopyDetectionOptions,
+) -> BackendResult<Vec<CopyRecord>> {
+    let known_targets: HashSet<&RepoPath> = known_records
+        .iter()
+        .map(|record| record.target.as_ref())
+        .collect();
+    let root_tree = store.get_commit_async(root).await?.tree();
+    let head_tree = store.get_commit_async(head).await?.tree();
+    let copies = detect_copies_in_trees(
+        store,
+        &root_tree,
+        &head_tree,
+        paths,
+        &known_targets,
+        options,
+    )
+    .await?;
+    Ok(copies
+        .into_iter()
+        .map(|(source, target, source_file)| CopyRecord {
+            target,
+            target_commit: head.clone(),
+            source,
+            source_file,
+            source_commit: root.clone(),
+        })
+        .collect())
+}
+
+/// Like [`detect_copies()`], but between two trees. Returns the source and
+/// target paths of the detected copies along with the file IDs of the sources.
+async fn detect_copies_in_trees(
+    store: &Store,
+    root_tree: &MergedTree,
+    head_tree: &MergedTree,
+    paths: Option<&[RepoPathBuf]>,
+    known_targets: &HashSet<&RepoPath>,
+    options: &CopyDetectionOptions,
+) -> BackendResult<Vec<(RepoPathBuf, RepoPathBuf, FileId)>> {
+    // Look for added files in the requested paths first, so the whole trees
+    // don't have to be compared if nothing was added there.
+    let mut targets = Vec::new();
+    let target_matcher: Box<dyn Matcher> = match paths {
+        Some(paths) => Box::new(FilesMatcher::new(paths)),
+        None => Box::new(EverythingMatcher),
+    };
+    let mut diff_stream = root_tree.diff_stream(head_tree, target_matcher.as_ref());
+    while let Some(entry) = diff_stream.next().await {
+        let values = entry.values?;
+        if !values.before.is_absent() || known_targets.contains(entry.path.as_ref()) {
+            continue;
+        }
+        if let Some(after_id) = resolved_file_id(&values.after) {
+            targets.push((entry.path, after_id.clone()));
+        }
+    }
+    if targets.is_empty() || targets.len() > options.max_candidates {
+        return Ok(vec![]);
+    }
+
+    let mut sources = Vec::new();
+    let mut diff_stream = root_tree.diff_stream(head_tree, &EverythingMatcher);
+    while let Some(entry) = diff_stream.next().await {
+        let values = entry.values?;
+        if let Some(before_id) = resolved_file_id(&values.before) {
+            let after_id = resolved_file_id(&values.after).cloned();
+            sources.push((entry.path, before_id.clone(), after_id));
+        }
+    }
+    if sources.is_empty() || sources.len() > options.max_candidates {
+        return Ok(vec![]);
+    }
+
+    // Lines that were removed from each source.
+    let mut source_lines = Vec::new();
+    for (path, before_id, after_id) in &sources {
+        let before = read_file_content(store, path, before_id).await?;
+        let mut lines = count_lines(&before);
+        if let Some(after_id) = after_id {
+            let after = read_file_content(store, path, after_id).await?;
+            for (line, count) in count_lines(&after) {
+                if let Some(remaining) = lines.get_mut(line) {
+                    *remaining = remaining.saturating_sub(count);
+                }
+            }
+            lines.retain(|_, count| *count > 0);
+        }
+        source_lines.push(
+            lines
+                .into_iter()
+                .map(|(line, count)| (line.to_vec(), count))
+                .collect::<HashMap<_, _>>(),
+        );
+    }
+
+    let mut copies = Vec::new();
+    for (target_path, target_id) in targets {
+        let content = read_file_content(store, &target_path, &target_id).await?;
+        let target_lines = count_lines(&content);
+        let num_target_lines: usize = target_lines.values().sum();
+        if num_target_lines == 0 {
+            continue;
+        }
+        let best = iter::zip(&sources, &source_lines)
+            .map(|(source, lines)| {
+                let common: usize = target_lines
+                    .iter()
+                    .map(|(line, count)| (*count).min(lines.get(*line).copied().unwrap_or(0)))
+                    .sum();
+                (source, common)
+            })
+            .filter(|(_, common)| *common * 100 >= num_target_lines * options.threshold as usize)
+            .max_by_key(|(_, common)| *common);
+        if let Some(((source_path, source_id, _), _)) = best {
+            copies.push((source_path.clone(), target_path, source_id.clone()));
+        }
+    }
+    Ok(copies)
+}
+
+/// Returns the tree with the file at `source` copied to `target`, and removed
+/// from `source` if `rename` is set. The copy is recorded in the copy ID of the
+/// target file.
+pub async fn record_copy(
+    tree: &MergedTree,
+    source: &RepoPath,
+    target: &RepoPath,
+    rename: bool,
+) -> BackendResult<MergedTree> {
+    let value = tree.path_value_async(source).await?;
+    let source_copy_id = value
+        .to_copy_id_merge()
+        .and_then(|copy_ids| {
+            copy_ids
+                .resolve_trivial(SameChange::Accept)
+                .cloned()
+                .flatten()
+        })
+        .unwrap_or_else(CopyId::placeholder);
+    let target_value =
+        with_copy_source(tree.store(), &value, target, source, &source_copy_id).await?;
+    let mut builder = MergedTreeBuilder::new(tree.clone());
+    builder.set_or_remove(target.to_owned(), target_value);
+    if rename {
+        builder.set_or_remove(source.to_owned(), Merge::absent());
+    }
+    builder.write_tree()
+}
+
+/// Records the copies detected by content similarity between the
+/// `parent_tree` and the `tree` in the copy IDs of the target files. Files that
+/// already have a recorded copy are left alone.
+pub async fn record_detected_copies(
+    parent_tree: &MergedTree,
+    tree: &MergedTree,
+    options: &CopyDetectionOptions,
+) -> BackendResult<MergedTree> {
+    let store = tree.store();
+    let copies =
+        detect_copies_in_trees(store, parent_tree, tree, None, &HashSet::new(), options).await?;
+    let mut builder = MergedTreeBuilder::new(tree.clone());
+    let mut changed = false;
+    for (source, target, _) in copies {
+        let value = tree.path_value_async(&target).await?;
+        if file_copy_id(&value).is_none_or(is_recorded) {
+            continue;
+        }
+        let source_value = parent_tree.path_value_async(&source).await?;
+        let Some(source_copy_id) = file_copy_id(&source_value) else {
+            continue;
+        };
+        let value = with_copy_source(store, &value, &target, &source, source_copy_id).await?;
+        builder.set_or_remove(target, value);
+        changed = true;
+    }
+    if changed {
+        builder.write_tree()
+    } else {
+        Ok(tree.clone())
+    }
+}
+
+/// Returns the copies recorded in the copy IDs of the files added between the
+/// `root` and `head` commits.
+///
+/// The copy history of each added file is followed back to the closest file
+/// that exists in `root`. Only targets in `paths` are returned if specified.
+pub async fn get_recorded_copies(
+    store: &Arc<Store>,
+    root: &CommitId,
+    head: &CommitId,
+    paths: Option<&[RepoPathBuf]>,
+) -> BackendResult<Vec<CopyRecord>> {
+    let root_tree = store.get_commit_async(root).await?.tree();
+    let head_tree = store.get_commit_async(head).await?.tree();
+    let matcher: Box<dyn Matcher> = match paths {
+        Some(paths) => Box::new(FilesMatcher::new(paths)),
+        None => Box::new(EverythingMatcher),
+    };
+    let mut targets = vec![];
+    let mut diff_stream = root_tree.diff_stream(&head_tree, matcher.as_ref());
+    while let Some(entry) = diff_stream.next().await {
+        let values = entry.values?;
+        if !values.before.is_absent() {
+            continue;
+        }
+        if let Some(copy_id) = file_copy_id(&values.after).filter(|id| is_recorded(id)) {
+            targets.push((entry.path, copy_id.clone()));
+        }
+    }
+
+    let mut records = vec![];
+    for (target, copy_id) in targets {
+        if let Some((source, source_file)) =
+            find_recorded_copy_source(store, &root_tree, &copy_id).await?
+        {
+            records.push(CopyRecord {
+                target,
+                target_commit: head.clone(),
+                source,
+                source_file,
+                source_commit: root.clone(),
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// Returns the path the file at `path` in `from_tree` was renamed or copied to
+/// in `to_tree`, following the recorded copy history. Paths that exist in
+/// `from_tree` aren't considered.
+pub async fn find_recorded_copy_target(
+    from_tree: &MergedTree,
+    path: &RepoPath,
+    to_tree: &MergedTree,
+) -> BackendResult<Option<RepoPathBuf>> {
+    let store = from_tree.store();
+    let value = from_tree.path_value_async(path).await?;
+    let Some(copy_id) = file_copy_id(&value) else {
+        return Ok(None);
+    };
+    let related = match copy_history_id(store, path, copy_id).await {
+        Ok(source_id) => store.get_related_copies(&source_id).await,
+        Err(err) => Err(err),
+    };
+    let related = match related {
+        Ok(related) => related,
+        // Nothing can be recorded if the backend doesn't support copies.
+        Err(BackendError::Unsupported(_)) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    for history in related {
+        let target = &history.current_path;
+        if target.as_ref() == path || !from_tree.path_value_async(target).await?.is_absent() {
+            continue;
+        }
+        let target_value = to_tree.path_value_async(target).await?;
+        let Some(target_copy_id) = file_copy_id(&target_value).filter(|id| is_recorded(id)) else {
+            continue;
+        };
+        if let Some((source, _)) =
+            find_recorded_copy_source(store, from_tree, target_copy_id).await?
+            && source.as_ref() == path
+        {
+            return Ok(Some(target.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// Follows the copy history of the file with the `copy_id` back to the closest
+/// file in `tree`. Returns the path and ID of the file.
+async fn find_recorded_copy_source(
+    store: &Store,
+    tree: &MergedTree,
+    copy_id: &CopyId,
+) -> BackendResult<Option<(RepoPathBuf, FileId)>> {
+    let mut visited = HashSet::new();
+    let mut pending: VecDeque<_> = store.read_copy(copy_id).await?.parents.into();
+    while let Some(id) = pending.pop_front() {
+        if !visited.insert(id.clone()) {
+            continue;
+        }
+        let history = store.read_copy(&id).await?;
+        let value = tree.path_value_async(&history.current_path).await?;
+        if let Some(Some(TreeValue::File {
+            id: file_id,
+            copy_id: found_id,
+            ..
+        })) = value.as_resolved()
+        {
+            // A file without a recorded copy matches the history of a new file
+            // at its path.
+            let is_new_file = history.parents.is_empty() && history.salt.is_empty();
+            if *found_id == id || (!is_recorded(found_id) && is_new_file) {
+                return Ok(Some((history.current_path, file_id.clone())));
+            }
+        }
+        pending.extend(history.parents);
+    }
+    Ok(None)
+}
+
+/// Returns the `value` with the copy ID of a new copy of the `source` file at
+/// `target`.
+async fn with_copy_source(
+    store: &Store,
+    value: &MergedTreeValue,
+    target: &RepoPath,
+    source: &RepoPath,
+    source_copy_id: &CopyId,
+) -> BackendResult<MergedTreeValue> {
+    let history = CopyHistory {
+        current_path: target.to_owned(),
+        parents: vec![copy_history_id(store, source, source_copy_id).await?],
+        salt: vec![],
+    };
+    let copy_id = store.write_copy(&history).await?;
+    Ok(value.map(|term| match term {
+        Some(TreeValue::File { id, executable, .. }) => Some(TreeValue::File {
+            id: id.clone(),
+            executable: *executable,
+            copy_id: copy_id.clone(),
+        }),
+        term => term.clone(),
+    }))
+}
+
+/// Returns the ID of the copy history of the file at `path`. A file without a
+/// recorded copy is identified by the history of a new file at the path.
+async fn copy_history_id(
+    store: &Store,
+    path: &RepoPath,
+    copy_id: &CopyId,
+) -> BackendResult<CopyId> {
+    if is_recorded(copy_id) {
+        return Ok(copy_id.clone());
+    }
+    let history = CopyHistory {
+        current_path: path.to_owned(),
+        parents: vec![],
+        salt: vec![],
+    };
+    store.write_copy(&history).await
+}
+
+/// Returns the copy histories in `copies` that are related to `copy_id`, in the
+/// order required by [`Backend::get_related_copies()`].
+///
+/// [`Backend::get_related_copies()`]: crate::backend::Backend::get_related_copies
+pub(crate) fn collect_related_copies(
+    copies: &HashMap<CopyId, CopyHistory>,
+    copy_id: &CopyId,
+) -> BackendResult<Vec<CopyHistory>> {
+    if !copies.contains_key(copy_id) {
+        return Err(BackendError::ObjectNotFound {
+            object_type: copy_id.object_type(),
+            hash: copy_id.hex(),
+            source: "copy history not found".into(),
+        });
+    }
+    let mut children: HashMap<&CopyId, Vec<&CopyId>> = HashMap::new();
+    for (id, history) in copies {
+        for parent in &history.parents {
+            children.entry(parent).or_default().push(id);
+        }
+    }
+    let ancestors = dag_walk::dfs(
+        [copy_id],
+        |id| *id,
+        |id| {
+            copies
+                .get(*id)
+                .into_iter()
+                .flat_map(|history| &history.parents)
+        },
+    );
+    let related = dag_walk::dfs(
+        ancestors,
+        |id| *id,
+        |id| children.get(id).into_iter().flatten().copied(),
+    );
+    let sorted = dag_walk::topo_order_reverse(
+        related.filter(|id| copies.contains_key(*id)),
+        |id| *id,
+        |id| {
+            copies[*id]
+                .parents
+                .iter()
+                .filter(|id| copies.contains_key(*id))
+        },
+        |id| BackendError::Other(format!("Cycle in copy history {}", id.hex()).into()),
+    )?;
+    Ok(sorted.into_iter().map(|id| copies[id].clone()).collect())
+}
+
+fn is_recorded(copy_id: &CopyId) -> bool {
+    *copy_id != CopyId::placeholder()
+}
+
+fn file_copy_id(value: &MergedTreeValue) -> Option<&CopyId> {
+    match value.as_resolved() {
+        Some(Some(TreeValue::File { copy_id, .. })) => Some(copy_id),
+        _ => None,
+    }
+}
+
+/// Traces the renames and copies of the file at `path` back from the `head`
+/// commit.
+///
+/// Returns the paths the file had along with the commits up to which the file
+/// had that path, starting with `(path, head)`. The history of the file is
+/// `files(path) & ::commit` for each of the returned pairs.
+pub fn trace_renames(
+    repo: &dyn Repo,
+    head: &CommitId,
+    path: &RepoPath,
+) -> Result<Vec<(RepoPathBuf, CommitId)>, RevsetEvaluationError> {
+    let mut segments = vec![(path.to_owned(), head.clone())];
+    let mut visited = HashSet::new();
+    while let Some((path, commit_id)) = segments.last().cloned() {
+        if !visited.insert(path.clone()) {
+            // Renamed back to a path we've already followed.
+            segments.pop();
+            break;
+        }
+        let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(path.clone()));
+        let revset = RevsetExpression::commit(commit_id)
+            .ancestors()
+            .filtered(predicate)
+            .evaluate(repo)?;
+        let mut copy_source = None;
+        for commit in revset.iter().commits(repo.store()) {
+            let commit = commit?;
+            if commit.tree().path_value(&path)?.is_absent() || has_file_in_parents(&commit, &path)?
+            {
+                continue;
+            }
+            let Some(parent_id) = commit.parent_ids().first() else {
+                continue;
+            };
+            if let Some(source) = find_copy_source(repo.store(), parent_id, commit.id(), &path)? {
+                copy_source = Some((source, parent_id.clone()));
+                break;
+            }
+        }
+        match copy_source {
+            Some(segment) => segments.push(segment),
+            None => break,
+        }
+    }
+    Ok(segments)
+}
+
+pub(crate) fn has_file_in_parents(commit: &Commit, path: &RepoPath) -> BackendResult<bool> {
+    for parent in commit.parents() {
+        if !parent?.tree().path_value(path)?.is_absent() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Looks up the path the file at `path` was copied from between the commits.
+pub(crate) fn find_copy_source(
+    store: &Arc<Store>,
+    parent_id: &CommitId,
+    commit_id: &CommitId,
+    path: &RepoPath,
+) -> BackendResult<Option<RepoPathBuf>> {
+    let paths = [path.to_owned()];
+    let records: Vec<_> = store
+        .get_copy_records(Some(&paths[..]), parent_id, commit_id)?
+        .try_collect()
+        .block_on()?;
+    Ok(records
+        .into_iter()
+        .find(|record| record.target.as_ref() == path)
+        .map(|record| record.source))
+}
+
+fn resolved_file_id(value: &MergedTreeValue) -> Option<&FileId> {
+    match value.as_resolved() {
+        Some(Some(TreeValue::File { id, .. })) => Some(id),
+        _ => None,
+    }
+}
+
+async fn read_file_content(store: &Store, path: &RepoPath, id: &FileId) -> BackendResult<Vec<u8>> {
+    let mut reader = store.read_file(path, id).await?;
+    let mut content = vec![];
+    reader
+        .read_to_end(&mut content)
+        .await
+        .map_err(|err| BackendError::ReadFile {
+            path: path.to_owned(),
+            id: id.clone(),
+            source: err.into(),
+        })?;
+    Ok(content)
+}
+
+/// Counts occurrences of non-blank lines.
+fn count_lines(content: &[u8]) -> HashMap<&[u8], usize> {
+    let mut counts = HashMap::new();
+    for line in content.split(|b| *b == b'\n') {
+        if !line.trim_ascii().is_empty() {
+            *counts.entry(line).or_default() += 1;
+        }
+    }
+    counts
+}
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use std::cmp::min;
+use std::iter;
+use std::sync::Arc;
 
 use super::composite::CompositeCommitIndex;
 use super::entry::GlobalCommitPosition;
@@ -120,6 +122,58 @@ impl PositionsBitSet {
         *word |= bit;
         old
     }
+
+    /// Returns the capacity in number of positions.
+    pub fn capacity(&self) -> u32 {
+        self.bitset_len * u64::BITS
+    }
+
+    /// Returns the allocated words, starting from the one containing the
+    /// largest positions.
+    pub fn words(&self) -> &[u64] {
+        &self.data
+    }
+
+    /// Creates bit set from the words returned by [`Self::words()`].
+    ///
+    /// Returns `None` if the `words` exceed the capacity.
+    pub fn from_words(len: u32, words: Vec<u64>) -> Option<Self> {
+        let mut set = Self::with_capacity(len);
+        if words.len() > usize::try_from(set.bitset_len).unwrap() {
+            return None;
+        }
+        set.data = words;
+        Some(set)
+    }
+
+    /// Extends the capacity to the specified length, preserving the set bits.
+    pub fn grow(&mut self, len: u32) {
+        let new_bitset_len = u32::div_ceil(len, u64::BITS);
+        let extra_len = new_bitset_len.saturating_sub(self.bitset_len);
+        if extra_len == 0 {
+            return;
+        }
+        if !self.data.is_empty() {
+            self.data
+                .splice(0..0, iter::repeat_n(0, usize::try_from(extra_len).unwrap()));
+        }
+        self.bitset_len = new_bitset_len;
+    }
+
+    /// Iterates set positions in descending order.
+    pub fn iter_desc(self: Arc<Self>) -> impl Iterator<Item = GlobalCommitPosition> {
+        let mut bitset_pos = 0;
+        let mut unvisited_bits = self.data.first().copied().unwrap_or(0);
+        iter::from_fn(move || {
+            while unvisited_bits == 0 {
+                bitset_pos += 1;
+                unvisited_bits = *self.data.get(usize::try_from(bitset_pos).unwrap())?;
+            }
+            let bit_pos = u64::BITS - unvisited_bits.leading_zeros() - 1; // from MSB
+            unvisited_bits ^= 1_u64 << bit_pos;
+            Some(self.to_global_pos((bitset_pos, bit_pos)))
+        })
+    }
 }
 
 /// Computes ancestors set lazily.
@@ -201,6 +255,8 @@ impl AncestorsBitSet {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools as _;
+
     use super::super::composite::AsCompositeIndex as _;
     use super::super::mutable::DefaultMutableIndex;
     use super::super::readonly::FieldLengths;
@@ -292,6 +348,49 @@ mod tests {
         assert!(set.get(GlobalCommitPosition(PAGE_SIZE_IN_BITS * 2)));
     }
 
+    #[test]
+    fn test_positions_bit_set_words() {
+        let collect_positions = |set: &PositionsBitSet| {
+            Arc::new(set.clone())
+                .iter_desc()
+                .map(|pos| pos.0)
+                .collect_vec()
+        };
+
+        let set = PositionsBitSet::with_capacity(200);
+        assert!(set.words().is_empty());
+        assert_eq!(collect_positions(&set), [] as [u32; 0]);
+
+        let mut set = PositionsBitSet::with_capacity(200);
+        set.set(GlobalCommitPosition(199));
+        set.set(GlobalCommitPosition(64));
+        set.set(GlobalCommitPosition(63));
+        set.set(GlobalCommitPosition(0));
+        assert_eq!(collect_positions(&set), [199, 64, 63, 0]);
+
+        // Round trip
+        let set = PositionsBitSet::from_words(200, set.words().to_vec()).unwrap();
+        assert_eq!(collect_positions(&set), [199, 64, 63, 0]);
+        assert!(PositionsBitSet::from_words(64, set.words().to_vec()).is_none());
+
+        // Grow capacity
+        let mut set = set;
+        set.grow(100);
+        assert_eq!(set.capacity(), 256);
+        set.grow(1000);
+        assert_eq!(set.capacity(), 1024);
+        assert_eq!(collect_positions(&set), [199, 64, 63, 0]);
+        set.set(GlobalCommitPosition(999));
+        assert_eq!(collect_positions(&set), [999, 199, 64, 63, 0]);
+
+        // Grow empty set
+        let mut set = PositionsBitSet::with_capacity(10);
+        set.grow(100);
+        assert!(set.words().is_empty());
+        set.set(GlobalCommitPosition(99));
+        assert_eq!(collect_positions(&set), [99]);
+    }
+
     #[test]
     fn test_ancestors_bit_set() {
         let mut new_commit_id = commit_id_generator();
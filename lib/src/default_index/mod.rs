@@ -27,6 +27,7 @@ mod mutable;
 mod readonly;
 mod rev_walk;
 mod rev_walk_queue;
+mod revset_cache;
 mod revset_engine;
 mod revset_graph_iterator;
 mod store;
@@ -38,6 +39,8 @@ pub use self::readonly::DefaultReadonlyIndex;
 pub use self::readonly::DefaultReadonlyIndexRevset;
 pub use self::readonly::IndexStats;
 pub use self::readonly::ReadonlyIndexLoadError;
+pub use self::revset_cache::RevsetCache;
+pub use self::revset_cache::RevsetCacheError;
 pub use self::store::DefaultIndexStore;
 pub use self::store::DefaultIndexStoreError;
 pub use self::store::DefaultIndexStoreInitError;
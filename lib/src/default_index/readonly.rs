@@ -30,6 +30,7 @@ use itertools::Itertools as _;
 use smallvec::smallvec;
 use thiserror::Error;
 
+use super::bit_set::PositionsBitSet;
 use super::changed_path::CompositeChangedPathIndex;
 use super::composite::AsCompositeIndex;
 use super::composite::ChangeIdIndexImpl;
@@ -704,6 +705,14 @@ impl DefaultReadonlyIndex {
         Ok(DefaultReadonlyIndexRevset { inner })
     }
 
+    pub(super) fn revset_from_bit_set(
+        &self,
+        bitset: PositionsBitSet,
+    ) -> DefaultReadonlyIndexRevset {
+        let inner = revset_engine::revset_from_bit_set(bitset, self.clone());
+        DefaultReadonlyIndexRevset { inner }
+    }
+
     pub(super) fn start_modification(&self) -> DefaultMutableIndex {
         DefaultMutableIndex::incremental(self)
     }
@@ -0,0 +1,285 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persistent cache of evaluated revsets.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write as _;
+use std::iter;
+use std::path::PathBuf;
+
+use blake2::Blake2b512;
+use blake2::Digest as _;
+use itertools::Itertools as _;
+use prost::Message as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use super::bit_set::PositionsBitSet;
+use super::composite::AsCompositeIndex as _;
+use super::composite::CommitIndexSegment as _;
+use super::composite::CommitIndexSegmentId;
+use super::entry::GlobalCommitPosition;
+use super::readonly::DefaultReadonlyIndex;
+use crate::backend::CommitId;
+use crate::file_util;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::file_util::persist_temp_file;
+use crate::hex_util;
+use crate::object_id::ObjectId as _;
+use crate::op_store::OperationId;
+use crate::revset::Revset;
+
+/// Error that may occur while evaluating or saving cached revset.
+#[derive(Debug, Error)]
+pub enum RevsetCacheError {
+    /// The given commit isn't indexed.
+    #[error("Commit ID {} not found in index", .0.hex())]
+    CommitNotFound(CommitId),
+    /// Failed to save cache entry.
+    #[error("Failed to write revset cache")]
+    Write(#[source] PathError),
+}
+
+/// Persistent cache of revsets evaluated against the default index.
+///
+/// Sets are stored as bit sets of index positions, keyed by caller-provided
+/// expression string. Cached sets are looked up by operation id. Ancestors
+/// sets can also be updated incrementally if the index of the new operation
+/// is a descendant of the cached one.
+#[derive(Clone, Debug)]
+pub struct RevsetCache {
+    dir: PathBuf,
+}
+
+impl RevsetCache {
+    pub(super) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Looks up the set cached for the `key` at the operation `op_id`.
+    ///
+    /// Unreadable cache entry is treated as missing.
+    pub fn get(
+        &self,
+        index: &DefaultReadonlyIndex,
+        key: &str,
+        op_id: &OperationId,
+    ) -> Option<Box<dyn Revset>> {
+        let entry = self.load_entry(key)?;
+        if entry.op_id != op_id.as_bytes() {
+            return None;
+        }
+        let segment = index.readonly_commits();
+        if entry.commit_segment_id != segment.id().as_bytes()
+            || entry.num_commits != index.num_commits()
+        {
+            return None;
+        }
+        let bitset = PositionsBitSet::from_words(entry.num_commits, entry.bitset_words)?;
+        Some(index.revset_from_bit_set(bitset).into_inner())
+    }
+
+    /// Stores the set of `commit_ids` evaluated for the `key` at the operation
+    /// `op_id`. Returns the stored set.
+    pub fn insert<'a>(
+        &self,
+        index: &DefaultReadonlyIndex,
+        key: &str,
+        op_id: &OperationId,
+        commit_ids: impl IntoIterator<Item = &'a CommitId>,
+    ) -> Result<Box<dyn Revset>, RevsetCacheError> {
+        let mut bitset = PositionsBitSet::with_capacity(index.num_commits());
+        for id in commit_ids {
+            bitset.set(commit_id_to_pos(index, id)?);
+        }
+        let entry = new_entry(index, op_id, &bitset);
+        self.save_entry(key, &entry)?;
+        Ok(index.revset_from_bit_set(bitset).into_inner())
+    }
+
+    /// Evaluates ancestors of the `heads`, and stores the result for the `key`
+    /// at the operation `op_id`.
+    ///
+    /// If the set cached for the `key` is reusable, only the commits that
+    /// aren't in the cached set will be visited. The cached set is reusable
+    /// if its index segment is an ancestor of the current index, and if its
+    /// heads are ancestors of the new `heads`.
+    pub fn ancestors(
+        &self,
+        index: &DefaultReadonlyIndex,
+        key: &str,
+        op_id: &OperationId,
+        heads: &[CommitId],
+    ) -> Result<Box<dyn Revset>, RevsetCacheError> {
+        let head_positions: Vec<_> = heads
+            .iter()
+            .map(|id| commit_id_to_pos(index, id))
+            .try_collect()?;
+        let cached = self
+            .load_entry(key)
+            .filter(|entry| entry.ancestors)
+            .and_then(|entry| {
+                let bitset = reusable_ancestors_bit_set(index, &head_positions, &entry)?;
+                let unchanged = entry.op_id == op_id.as_bytes()
+                    && entry.num_commits == index.num_commits()
+                    && entry
+                        .heads
+                        .iter()
+                        .map(Vec::as_slice)
+                        .sorted()
+                        .eq(heads.iter().map(CommitId::as_bytes).sorted());
+                Some((bitset, unchanged))
+            });
+        let (mut bitset, unchanged) =
+            cached.unwrap_or_else(|| (PositionsBitSet::with_capacity(index.num_commits()), false));
+
+        // Visit new ancestors. The cached set is closed under ancestors, so the
+        // walk can stop at any commit in the set.
+        let commits = index.as_composite().commits();
+        let mut to_visit = head_positions;
+        while let Some(pos) = to_visit.pop() {
+            if bitset.get_set(pos) {
+                continue;
+            }
+            to_visit.extend(commits.entry_by_pos(pos).parent_positions());
+        }
+
+        if !unchanged {
+            let mut entry = new_entry(index, op_id, &bitset);
+            entry.ancestors = true;
+            entry.heads = heads.iter().map(|id| id.to_bytes()).collect();
+            self.save_entry(key, &entry)?;
+        }
+        Ok(index.revset_from_bit_set(bitset).into_inner())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let hash = Blake2b512::digest(key.as_bytes());
+        self.dir.join(hex_util::encode_hex(&hash))
+    }
+
+    fn load_entry(&self, key: &str) -> Option<crate::protos::default_index::RevsetCacheEntry> {
+        let data = fs::read(self.entry_path(key)).ok()?;
+        crate::protos::default_index::RevsetCacheEntry::decode(&*data).ok()
+    }
+
+    fn save_entry(
+        &self,
+        key: &str,
+        entry: &crate::protos::default_index::RevsetCacheEntry,
+    ) -> Result<(), RevsetCacheError> {
+        file_util::create_or_reuse_dir(&self.dir)
+            .context(&self.dir)
+            .map_err(RevsetCacheError::Write)?;
+        let mut temp_file = NamedTempFile::new_in(&self.dir)
+            .context(&self.dir)
+            .map_err(RevsetCacheError::Write)?;
+        temp_file
+            .as_file_mut()
+            .write_all(&entry.encode_to_vec())
+            .context(temp_file.path())
+            .map_err(RevsetCacheError::Write)?;
+        let path = self.entry_path(key);
+        persist_temp_file(temp_file, &path)
+            .context(&path)
+            .map_err(RevsetCacheError::Write)?;
+        Ok(())
+    }
+}
+
+fn commit_id_to_pos(
+    index: &DefaultReadonlyIndex,
+    commit_id: &CommitId,
+) -> Result<GlobalCommitPosition, RevsetCacheError> {
+    index
+        .as_composite()
+        .commits()
+        .commit_id_to_pos(commit_id)
+        .ok_or_else(|| RevsetCacheError::CommitNotFound(commit_id.clone()))
+}
+
+fn new_entry(
+    index: &DefaultReadonlyIndex,
+    op_id: &OperationId,
+    bitset: &PositionsBitSet,
+) -> crate::protos::default_index::RevsetCacheEntry {
+    crate::protos::default_index::RevsetCacheEntry {
+        op_id: op_id.to_bytes(),
+        commit_segment_id: index.readonly_commits().id().to_bytes(),
+        num_commits: index.num_commits(),
+        ancestors: false,
+        heads: vec![],
+        bitset_words: bitset.words().to_vec(),
+    }
+}
+
+/// Loads the cached ancestors set if it can be extended to the ancestors of
+/// the new `head_positions`.
+fn reusable_ancestors_bit_set(
+    index: &DefaultReadonlyIndex,
+    head_positions: &[GlobalCommitPosition],
+    entry: &crate::protos::default_index::RevsetCacheEntry,
+) -> Option<PositionsBitSet> {
+    // Positions are stable within the chain of index segments.
+    let segment = index.readonly_commits();
+    let cached_segment_id = CommitIndexSegmentId::new(entry.commit_segment_id.clone());
+    let cached_segment = iter::once(segment)
+        .chain(segment.as_composite().ancestor_files_without_local())
+        .find(|segment| *segment.id() == cached_segment_id)?;
+    let num_cached_commits =
+        cached_segment.num_parent_commits() + cached_segment.num_local_commits();
+    if num_cached_commits != entry.num_commits {
+        return None;
+    }
+
+    // The cached set can be reused only if the old heads are ancestors of the
+    // new heads. In other words, heads(new_heads | old_heads) must be a subset
+    // of the new heads.
+    let commits = index.as_composite().commits();
+    let new_heads: HashSet<_> = head_positions.iter().copied().collect();
+    let mut removed_heads = Vec::new();
+    for id in &entry.heads {
+        let pos = commits.commit_id_to_pos(&CommitId::new(id.clone()))?;
+        if pos.0 >= num_cached_commits {
+            return None;
+        }
+        if !new_heads.contains(&pos) {
+            removed_heads.push(pos);
+        }
+    }
+    if !removed_heads.is_empty() {
+        let mut candidates = new_heads
+            .iter()
+            .chain(&removed_heads)
+            .copied()
+            .collect_vec();
+        candidates.sort_unstable_by_key(|&pos| Reverse(pos));
+        candidates.dedup();
+        if commits
+            .heads_pos(candidates)
+            .iter()
+            .any(|pos| !new_heads.contains(pos))
+        {
+            return None;
+        }
+    }
+
+    let mut bitset = PositionsBitSet::from_words(entry.num_commits, entry.bitset_words.clone())?;
+    bitset.grow(index.num_commits());
+    Some(bitset)
+}
@@ -17,11 +17,13 @@ use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::convert::Infallible;
 use std::fmt;
 use std::iter;
 use std::ops::Range;
 use std::rc::Rc;
+use std::slice;
 use std::sync::Arc;
 
 use bstr::BString;
@@ -46,6 +48,7 @@ use crate::conflict_labels::ConflictLabels;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::materialize_tree_value;
 use crate::default_index::bit_set::AncestorsBitSet;
+use crate::default_index::bit_set::PositionsBitSet;
 use crate::diff::ContentDiff;
 use crate::diff::DiffHunkKind;
 use crate::files;
@@ -64,6 +67,7 @@ use crate::revset::ResolvedPredicateExpression;
 use crate::revset::Revset;
 use crate::revset::RevsetContainingFn;
 use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetFilterExtension;
 use crate::revset::RevsetFilterPredicate;
 use crate::rewrite;
 use crate::store::Store;
@@ -97,6 +101,15 @@ impl<T: ToPredicateFn + ?Sized> ToPredicateFn for Box<T> {
     }
 }
 
+impl<T: ToPredicateFn + ?Sized> ToPredicateFn for Rc<T> {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        <T as ToPredicateFn>::to_predicate_fn(self)
+    }
+}
+
 trait InternalRevset: fmt::Debug + ToPredicateFn {
     // All revsets currently iterate in order of descending index position
     fn positions<'a>(&self) -> BoxedRevWalk<'a>
@@ -113,6 +126,15 @@ impl<T: InternalRevset + ?Sized> InternalRevset for Box<T> {
     }
 }
 
+impl<T: InternalRevset + ?Sized> InternalRevset for Rc<T> {
+    fn positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        <T as InternalRevset>::positions(self)
+    }
+}
+
 pub(super) struct RevsetImpl<I> {
     inner: Box<dyn InternalRevset>,
     index: I,
@@ -316,6 +338,32 @@ impl ToPredicateFn for EagerRevset {
     }
 }
 
+/// Adapter for precomputed bit set of `GlobalCommitPosition`s.
+#[derive(Debug)]
+struct BitSetRevset {
+    bitset: Arc<PositionsBitSet>,
+}
+
+impl InternalRevset for BitSetRevset {
+    fn positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        let walk = EagerRevWalk::new(self.bitset.clone().iter_desc());
+        Box::new(walk.map(|_index, pos| Ok(pos)))
+    }
+}
+
+impl ToPredicateFn for BitSetRevset {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        let bitset = self.bitset.clone();
+        Box::new(move |_index, pos| Ok(pos.0 < bitset.capacity() && bitset.get(pos)))
+    }
+}
+
 /// Adapter for infallible `RevWalk` of `GlobalCommitPosition`s.
 struct RevWalkRevset<W> {
     walk: W,
@@ -402,6 +450,133 @@ where
     }
 }
 
+/// Number of candidate commits to be tested by extension filter at once.
+const EXTENSION_FILTER_BATCH_SIZE: usize = 100;
+
+/// Like [`FilterRevset`], but tests candidates by extension filter in batch.
+#[derive(Debug)]
+struct ExtensionFilterRevset<S> {
+    candidates: S,
+    store: Arc<Store>,
+    extension: Arc<dyn RevsetFilterExtension>,
+}
+
+impl<S: InternalRevset> InternalRevset for ExtensionFilterRevset<S> {
+    fn positions<'a>(&self) -> BoxedRevWalk<'a>
+    where
+        Self: 'a,
+    {
+        Box::new(ExtensionFilterRevWalk {
+            candidates: self.candidates.positions(),
+            store: self.store.clone(),
+            extension: self.extension.clone(),
+            queued: VecDeque::new(),
+        })
+    }
+}
+
+impl<S: InternalRevset> ToPredicateFn for ExtensionFilterRevset<S> {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        // The predicate is evaluated in descending position order, so the
+        // candidates can be tested in batch by walking the filtered set.
+        let mut walk = self.positions().peekable();
+        Box::new(move |index, entry_pos| {
+            while let Some(item) = walk.next_if(index, |item| {
+                item.as_ref().map_or(true, |&pos| pos > entry_pos)
+            }) {
+                item?;
+            }
+            Ok(walk
+                .next_if(index, |item| {
+                    item.as_ref().is_ok_and(|&pos| pos == entry_pos)
+                })
+                .is_some())
+        })
+    }
+}
+
+/// Predicate of extension filter, which tests commits one by one.
+///
+/// This is used where the candidates aren't known. Use
+/// [`ExtensionFilterRevset`] to test candidates in batch.
+#[derive(Debug)]
+struct ExtensionPredicate {
+    store: Arc<Store>,
+    extension: Arc<dyn RevsetFilterExtension>,
+}
+
+impl ToPredicateFn for ExtensionPredicate {
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        let store = self.store.clone();
+        let extension = self.extension.clone();
+        Box::new(move |index, pos| {
+            let entry = index.commits().entry_by_pos(pos);
+            let commit = store.get_commit(&entry.commit_id())?;
+            let matches = extension.matches_commits(slice::from_ref(&commit))?;
+            assert_eq!(matches.len(), 1);
+            Ok(matches[0])
+        })
+    }
+}
+
+struct ExtensionFilterRevWalk<'a> {
+    candidates: BoxedRevWalk<'a>,
+    store: Arc<Store>,
+    extension: Arc<dyn RevsetFilterExtension>,
+    queued: VecDeque<GlobalCommitPosition>,
+}
+
+impl ExtensionFilterRevWalk<'_> {
+    /// Tests next batch of candidates. Returns false if there are no more
+    /// candidates.
+    fn fill_queue(&mut self, index: &CompositeIndex) -> Result<bool, RevsetEvaluationError> {
+        let mut positions = Vec::with_capacity(EXTENSION_FILTER_BATCH_SIZE);
+        while positions.len() < EXTENSION_FILTER_BATCH_SIZE {
+            match self.candidates.next(index).transpose()? {
+                Some(pos) => positions.push(pos),
+                None => break,
+            }
+        }
+        if positions.is_empty() {
+            return Ok(false);
+        }
+        let commits: Vec<_> = positions
+            .iter()
+            .map(|&pos| {
+                let entry = index.commits().entry_by_pos(pos);
+                self.store.get_commit(&entry.commit_id())
+            })
+            .try_collect()?;
+        let matches = self.extension.matches_commits(&commits)?;
+        assert_eq!(matches.len(), positions.len());
+        self.queued.extend(
+            iter::zip(positions, matches).filter_map(|(pos, matched)| matched.then_some(pos)),
+        );
+        Ok(true)
+    }
+}
+
+impl RevWalk<CompositeIndex> for ExtensionFilterRevWalk<'_> {
+    type Item = Result<GlobalCommitPosition, RevsetEvaluationError>;
+
+    fn next(&mut self, index: &CompositeIndex) -> Option<Self::Item> {
+        while self.queued.is_empty() {
+            match self.fill_queue(index) {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.queued.pop_front().map(Ok)
+    }
+}
+
 #[derive(Debug)]
 struct NotInPredicate<S>(S);
 
@@ -730,6 +905,17 @@ pub(super) fn evaluate<I: AsCompositeIndex + Clone>(
     Ok(RevsetImpl::new(internal_revset, index))
 }
 
+/// Builds revset from the precomputed set of positions in the `index`.
+pub(super) fn revset_from_bit_set<I: AsCompositeIndex + Clone>(
+    bitset: PositionsBitSet,
+    index: I,
+) -> RevsetImpl<I> {
+    let inner = BitSetRevset {
+        bitset: Arc::new(bitset),
+    };
+    RevsetImpl::new(Box::new(inner), index)
+}
+
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
@@ -930,7 +1116,7 @@ impl EvaluationContext<'_> {
                 .attach(index)
                 .try_collect()?;
                 let positions = if let Some(filter) = filter {
-                    let mut filter = self.evaluate_predicate(filter)?.to_predicate_fn();
+                    let mut filter = self.evaluate_predicate(filter, None)?.to_predicate_fn();
                     index.commits().heads_from_range_and_filter(
                         root_positions,
                         head_positions,
@@ -993,6 +1179,21 @@ impl EvaluationContext<'_> {
                 };
                 Ok(Box::new(EagerRevset { positions }))
             }
+            ResolvedExpression::Evolution {
+                candidates,
+                direction,
+                generation,
+                graph,
+            } => {
+                let candidate_set = self.evaluate(candidates)?;
+                let start_ids: Vec<_> = candidate_set
+                    .positions()
+                    .attach(index)
+                    .map_ok(|pos| index.commits().entry_by_pos(pos).commit_id())
+                    .try_collect()?;
+                let commit_ids = graph.walk(&start_ids, *direction, generation.clone());
+                Ok(Box::new(self.revset_for_commit_ids(&commit_ids)?))
+            }
             ResolvedExpression::Latest { candidates, count } => {
                 let candidate_set = self.evaluate(candidates)?;
                 Ok(Box::new(self.take_latest_revset(&*candidate_set, *count)?))
@@ -1037,11 +1238,24 @@ impl EvaluationContext<'_> {
             }
             ResolvedExpression::FilterWithin {
                 candidates,
-                predicate,
-            } => Ok(Box::new(FilterRevset {
+                predicate:
+                    ResolvedPredicateExpression::Filter(RevsetFilterPredicate::Extension(extension)),
+            } => Ok(Box::new(ExtensionFilterRevset {
                 candidates: self.evaluate(candidates)?,
-                predicate: self.evaluate_predicate(predicate)?,
+                store: self.store.clone(),
+                extension: extension.clone(),
             })),
+            ResolvedExpression::FilterWithin {
+                candidates,
+                predicate,
+            } => {
+                let candidates: Rc<dyn InternalRevset> = self.evaluate(candidates)?.into();
+                let predicate = self.evaluate_predicate(predicate, Some(&candidates))?;
+                Ok(Box::new(FilterRevset {
+                    candidates,
+                    predicate,
+                }))
+            }
             ResolvedExpression::Intersection(expression1, expression2) => {
                 let set1 = self.evaluate(expression1)?;
                 let set2 = self.evaluate(expression2)?;
@@ -1055,12 +1269,26 @@ impl EvaluationContext<'_> {
         }
     }
 
+    /// Evaluates the predicate `expression`.
+    ///
+    /// If the predicate is only tested against `candidates`, extension
+    /// filters test the candidates in batch.
     fn evaluate_predicate(
         &self,
         expression: &ResolvedPredicateExpression,
+        candidates: Option<&Rc<dyn InternalRevset>>,
     ) -> Result<Box<dyn ToPredicateFn>, RevsetEvaluationError> {
         match expression {
             ResolvedPredicateExpression::Filter(predicate) => {
+                if let RevsetFilterPredicate::Extension(extension) = predicate
+                    && let Some(candidates) = candidates
+                {
+                    return Ok(Box::new(ExtensionFilterRevset {
+                        candidates: candidates.clone(),
+                        store: self.store.clone(),
+                        extension: extension.clone(),
+                    }));
+                }
                 Ok(build_predicate_fn(self.store.clone(), predicate))
             }
             ResolvedPredicateExpression::Divergent { visible_heads } => {
@@ -1097,17 +1325,17 @@ impl EvaluationContext<'_> {
             }
             ResolvedPredicateExpression::Set(expression) => Ok(self.evaluate(expression)?),
             ResolvedPredicateExpression::NotIn(complement) => {
-                let set = self.evaluate_predicate(complement)?;
+                let set = self.evaluate_predicate(complement, candidates)?;
                 Ok(Box::new(NotInPredicate(set)))
             }
             ResolvedPredicateExpression::Union(expression1, expression2) => {
-                let set1 = self.evaluate_predicate(expression1)?;
-                let set2 = self.evaluate_predicate(expression2)?;
+                let set1 = self.evaluate_predicate(expression1, candidates)?;
+                let set2 = self.evaluate_predicate(expression2, candidates)?;
                 Ok(Box::new(UnionRevset { set1, set2 }))
             }
             ResolvedPredicateExpression::Intersection(expression1, expression2) => {
-                let set1 = self.evaluate_predicate(expression1)?;
-                let set2 = self.evaluate_predicate(expression2)?;
+                let set1 = self.evaluate_predicate(expression1, candidates)?;
+                let set2 = self.evaluate_predicate(expression2, candidates)?;
                 Ok(Box::new(IntersectionRevset { set1, set2 }))
             }
         }
@@ -1351,14 +1579,10 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.is_signed())
         }),
-        RevsetFilterPredicate::Extension(ext) => {
-            let ext = ext.clone();
-            box_pure_predicate_fn(move |index, pos| {
-                let entry = index.commits().entry_by_pos(pos);
-                let commit = store.get_commit(&entry.commit_id())?;
-                Ok(ext.matches_commit(&commit))
-            })
-        }
+        RevsetFilterPredicate::Extension(ext) => Box::new(ExtensionPredicate {
+            store,
+            extension: ext.clone(),
+        }),
     }
 }
 
@@ -1492,6 +1716,7 @@ mod tests {
     use crate::default_index::readonly::FieldLengths;
     use crate::files::FileMergeHunkLevel;
     use crate::merge::SameChange;
+    use crate::merge_driver::MergeDrivers;
     use crate::str_util::StringPattern;
 
     const TEST_FIELD_LENGTHS: FieldLengths = FieldLengths {
@@ -1883,6 +2108,7 @@ mod tests {
             let options = MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                drivers: MergeDrivers::default(),
             };
             diff_match_lines(&left1, &left2, &matcher, &options).unwrap()
         };
@@ -1908,6 +2134,7 @@ mod tests {
             let options = MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                drivers: MergeDrivers::default(),
             };
             diff_match_lines(&conflict1, &conflict2, &matcher, &options).unwrap()
         };
@@ -1937,6 +2164,7 @@ mod tests {
             let options = MergeOptions {
                 hunk_level: FileMergeHunkLevel::Line,
                 same_change: SameChange::Accept,
+                drivers: MergeDrivers::default(),
             };
             diff_match_lines(&base, &conflict2, &matcher, &options).unwrap()
         };
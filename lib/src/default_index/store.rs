@@ -41,6 +41,7 @@ use super::readonly::DefaultReadonlyIndex;
 use super::readonly::FieldLengths;
 use super::readonly::ReadonlyCommitIndexSegment;
 use super::readonly::ReadonlyIndexLoadError;
+use super::revset_cache::RevsetCache;
 use crate::backend::BackendError;
 use crate::backend::BackendInitError;
 use crate::backend::CommitId;
@@ -132,6 +133,11 @@ impl DefaultIndexStore {
         // will be created by the other process.
         file_util::remove_dir_contents(&self.commit_segments_dir())?;
         file_util::remove_dir_contents(&self.changed_path_segments_dir())?;
+        // Cached positions are invalidated by reindexing.
+        let revset_cache_dir = self.revset_cache_dir();
+        if revset_cache_dir.exists() {
+            file_util::remove_dir_contents(&revset_cache_dir)?;
+        }
         // jj <= 0.14 created segment files in the top directory
         for entry in self.dir.read_dir().context(&self.dir)? {
             let entry = entry.context(&self.dir)?;
@@ -177,6 +183,16 @@ impl DefaultIndexStore {
         self.dir.join("changed_paths")
     }
 
+    /// Directory for cached revsets.
+    fn revset_cache_dir(&self) -> PathBuf {
+        self.dir.join("revset_cache")
+    }
+
+    /// Returns persistent cache of revsets evaluated against this index.
+    pub fn revset_cache(&self) -> RevsetCache {
+        RevsetCache::new(self.revset_cache_dir())
+    }
+
     fn load_index_at_operation(
         &self,
         op_id: &OperationId,
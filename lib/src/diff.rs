@@ -39,6 +39,37 @@ pub fn find_line_ranges(text: &[u8]) -> Vec<Range<usize>> {
         .collect()
 }
 
+/// Finds ranges of top-level blocks such as function or class definitions.
+///
+/// A block starts at a line without indentation, and extends over the
+/// following indented, blank, and closing-bracket lines. Consecutive lines
+/// without indentation (e.g. comments and attributes preceding a function)
+/// belong to the same block.
+pub fn find_block_ranges(text: &[u8]) -> Vec<Range<usize>> {
+    #[derive(Clone, Copy, Eq, PartialEq)]
+    enum LineKind {
+        Head,
+        Body,
+    }
+    let line_kind = |line: &[u8]| match line.first() {
+        Some(b' ' | b'\t' | b'\r' | b'\n' | b')' | b']' | b'}') | None => LineKind::Body,
+        Some(_) => LineKind::Head,
+    };
+    let mut block_ranges: Vec<Range<usize>> = vec![];
+    let mut prev_kind = LineKind::Body;
+    for line_range in find_line_ranges(text) {
+        let kind = line_kind(&text[line_range.clone()]);
+        match block_ranges.last_mut() {
+            Some(block_range) if kind == LineKind::Body || prev_kind == LineKind::Head => {
+                block_range.end = line_range.end;
+            }
+            _ => block_ranges.push(line_range),
+        }
+        prev_kind = kind;
+    }
+    block_ranges
+}
+
 fn is_word_byte(b: u8) -> bool {
     // TODO: Make this configurable (probably higher up in the call stack)
     matches!(
@@ -183,6 +214,24 @@ impl CompareBytes for CompareBytesIgnoreWhitespaceAmount {
     }
 }
 
+/// Compares byte sequences by their first lines.
+#[derive(Clone, Debug)]
+struct CompareFirstLine<C>(C);
+
+impl<C: CompareBytes> CompareBytes for CompareFirstLine<C> {
+    fn eq(&self, left: &[u8], right: &[u8]) -> bool {
+        self.0.eq(first_line(left), first_line(right))
+    }
+
+    fn hash<H: Hasher>(&self, text: &[u8], state: &mut H) {
+        self.0.hash(first_line(text), state);
+    }
+}
+
+fn first_line(text: &[u8]) -> &[u8] {
+    text.split_inclusive(|b| *b == b'\n').next().unwrap_or(text)
+}
+
 // Not implementing Eq because the text should be compared by WordComparator.
 #[derive(Clone, Copy, Debug)]
 struct HashedWord<'input> {
@@ -592,6 +641,20 @@ impl UnchangedRange {
     fn is_all_empty(&self) -> bool {
         self.base.is_empty() && self.others.iter().all(|r| r.is_empty())
     }
+
+    /// Moves ranges by the given offsets.
+    fn translated(
+        &self,
+        base_offset: usize,
+        other_offsets: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        Self {
+            base: (self.base.start + base_offset)..(self.base.end + base_offset),
+            others: iter::zip(&self.others, other_offsets)
+                .map(|(range, offset)| (range.start + offset)..(range.end + offset))
+                .collect(),
+        }
+    }
 }
 
 /// Takes any number of inputs and finds regions that are them same between all
@@ -612,6 +675,18 @@ impl<'input> ContentDiff<'input> {
         inputs: impl IntoIterator<Item = &'input T>,
         tokenizer: impl Fn(&[u8]) -> Vec<Range<usize>>,
         compare: impl CompareBytes,
+    ) -> Self {
+        let mut diff = Self::for_tokenizer_uncompacted(inputs, tokenizer, compare);
+        diff.compact_unchanged_regions();
+        diff
+    }
+
+    /// Like [`Self::for_tokenizer()`], but keeps each unchanged token as a
+    /// separate region.
+    fn for_tokenizer_uncompacted<T: AsRef<[u8]> + ?Sized + 'input>(
+        inputs: impl IntoIterator<Item = &'input T>,
+        tokenizer: impl Fn(&[u8]) -> Vec<Range<usize>>,
+        compare: impl CompareBytes,
     ) -> Self {
         let mut inputs = inputs.into_iter().map(BStr::new);
         let base_input = inputs.next().expect("inputs must not be empty");
@@ -733,13 +808,11 @@ impl<'input> ContentDiff<'input> {
             }
         };
 
-        let mut diff = Self {
+        Self {
             base_input,
             other_inputs,
             unchanged_regions,
-        };
-        diff.compact_unchanged_regions();
-        diff
+        }
     }
 
     pub fn unrefined<T: AsRef<[u8]> + ?Sized + 'input>(
@@ -767,6 +840,70 @@ impl<'input> ContentDiff<'input> {
         diff
     }
 
+    /// Compares `inputs` block by block, then line by line within the blocks.
+    ///
+    /// Blocks (see [`find_block_ranges()`]) are matched by their first lines,
+    /// so that a function definition is compared with the previous version of
+    /// the same function even if its body has changed. Lines in the matched
+    /// blocks and in the unmatched regions between them are then compared by
+    /// the `compare` function.
+    pub fn by_block<T: AsRef<[u8]> + ?Sized + 'input>(
+        inputs: impl IntoIterator<Item = &'input T>,
+        compare: impl CompareBytes,
+    ) -> Self {
+        let block_diff = ContentDiff::for_tokenizer_uncompacted(
+            inputs,
+            find_block_ranges,
+            CompareFirstLine(&compare),
+        );
+        // Translate unchanged lines to the positions in the inputs. Empty
+        // ranges at the start/end of the line diffs are excluded so adjacent
+        // changed lines will be merged into one hunk.
+        let (first_region, last_region) = block_diff
+            .unchanged_regions
+            .first()
+            .zip(block_diff.unchanged_regions.last())
+            .unwrap();
+        let mut unchanged_regions = vec![first_region.clone()];
+        for window in block_diff.unchanged_regions.windows(2) {
+            let [previous, current]: &[_; 2] = window.try_into().unwrap();
+            let changed_diff = ContentDiff::for_tokenizer(
+                block_diff.hunk_between(previous, current),
+                find_line_ranges,
+                &compare,
+            );
+            unchanged_regions.extend(
+                changed_diff
+                    .unchanged_regions
+                    .iter()
+                    .filter(|refined| !refined.is_all_empty())
+                    .map(|refined| {
+                        refined.translated(previous.base.end, previous.others.iter().map(|r| r.end))
+                    }),
+            );
+            let matched_diff =
+                ContentDiff::for_tokenizer(block_diff.hunk_at(current), find_line_ranges, &compare);
+            unchanged_regions.extend(
+                matched_diff
+                    .unchanged_regions
+                    .iter()
+                    .filter(|refined| !refined.is_all_empty())
+                    .map(|refined| {
+                        refined
+                            .translated(current.base.start, current.others.iter().map(|r| r.start))
+                    }),
+            );
+        }
+        unchanged_regions.push(last_region.clone());
+        let mut diff = Self {
+            base_input: block_diff.base_input,
+            other_inputs: block_diff.other_inputs,
+            unchanged_regions,
+        };
+        diff.compact_unchanged_regions();
+        diff
+    }
+
     /// Returns iterator over matching and different texts.
     pub fn hunks(&self) -> DiffHunkIterator<'_, 'input> {
         let ranges = self.hunk_ranges();
@@ -1052,6 +1189,26 @@ mod tests {
         assert_eq!(find_line_ranges(b"a\nbb\nccc\n"), vec![0..2, 2..5, 5..9]);
     }
 
+    #[test]
+    fn test_find_block_ranges() {
+        assert_eq!(find_block_ranges(b""), no_ranges());
+        assert_eq!(find_block_ranges(b"\n\n"), vec![0..2]);
+        assert_eq!(
+            find_block_ranges(b"fn a() {\n    1\n}\n\nfn b() {}\n"),
+            vec![0..18, 18..28]
+        );
+        // Leading comments and attributes belong to the following block
+        assert_eq!(
+            find_block_ranges(b"x\n\n// b\n#[b]\nfn b() {\n}\n"),
+            vec![0..3, 3..24]
+        );
+        // Blocks without closing brackets
+        assert_eq!(
+            find_block_ranges(b"def a():\n  1\ndef b():\n  2"),
+            vec![0..13, 13..25]
+        );
+    }
+
     #[test]
     fn test_find_word_ranges_empty() {
         assert_eq!(find_word_ranges(b""), no_ranges());
@@ -1486,6 +1643,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_diff_by_block() {
+        fn diff(inputs: [&str; 2]) -> Vec<DiffHunk<'_>> {
+            let diff = ContentDiff::by_block(inputs, CompareBytesExactly);
+            diff.hunks().collect()
+        }
+
+        assert_eq!(diff(["", ""]), vec![]);
+        assert_eq!(diff(["", "a\n"]), vec![DiffHunk::different(["", "a\n"])]);
+
+        // The function is compared with the function of the same name, not
+        // with the new function which has the same body.
+        assert_eq!(
+            diff([
+                "fn a() {\n    x\n}\n",
+                "fn b() {\n    x\n}\n\nfn a() {\n    y\n}\n",
+            ]),
+            vec![
+                DiffHunk::different(["", "fn b() {\n    x\n}\n\n"]),
+                DiffHunk::matching(["fn a() {\n", "fn a() {\n"]),
+                DiffHunk::different(["    x\n", "    y\n"]),
+                DiffHunk::matching(["}\n", "}\n"]),
+            ]
+        );
+
+        // Changed lines between matched functions are merged into one hunk.
+        assert_eq!(
+            diff(["fn a() {}\n", "fn a() {}\n\nfn b() {}\n"]),
+            vec![
+                DiffHunk::matching(["fn a() {}\n", "fn a() {}\n"]),
+                DiffHunk::different(["", "\nfn b() {}\n"]),
+            ]
+        );
+    }
+
     #[test]
     fn test_diff_ignore_all_whitespace() {
         fn diff(inputs: [&str; 2]) -> Vec<DiffHunk<'_>> {
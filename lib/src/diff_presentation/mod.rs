@@ -17,14 +17,19 @@
 #![expect(missing_docs)]
 
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::mem;
+use std::sync::Arc;
 
 use bstr::BString;
 use itertools::Itertools as _;
 use pollster::FutureExt as _;
 
 use crate::backend::BackendResult;
+use crate::config::ConfigGetError;
+use crate::config::ConfigGetResultExt as _;
 use crate::conflicts::MaterializedFileValue;
+use crate::diff::CompareBytes;
 use crate::diff::CompareBytesExactly;
 use crate::diff::CompareBytesIgnoreAllWhitespace;
 use crate::diff::CompareBytesIgnoreWhitespaceAmount;
@@ -32,8 +37,11 @@ use crate::diff::ContentDiff;
 use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::diff::find_line_ranges;
+use crate::fileset;
+use crate::matchers::Matcher;
 use crate::merge::Diff;
 use crate::repo_path::RepoPath;
+use crate::settings::UserSettings;
 
 pub mod unified;
 // TODO: colored_diffs utils should also be moved from `jj_cli::diff_utils` to
@@ -85,8 +93,90 @@ pub enum LineCompareMode {
     IgnoreSpaceChange,
 }
 
+/// Algorithm to find matching lines between file contents.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffAlgorithm {
+    /// Compares lines individually.
+    #[default]
+    Line,
+    /// Matches top-level blocks (such as function definitions) by their
+    /// first lines, then compares lines within the blocks.
+    Block,
+}
+
+impl DiffAlgorithm {
+    /// Looks up the algorithm by its config name, e.g. `block`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "line" => Some(Self::Line),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
+}
+
+/// Diff algorithms selected per file by the `diff.algorithms` table.
+#[derive(Clone, Debug, Default)]
+pub struct DiffAlgorithms {
+    rules: Vec<DiffAlgorithmRule>,
+}
+
+#[derive(Clone, Debug)]
+struct DiffAlgorithmRule {
+    algorithm: DiffAlgorithm,
+    patterns: Vec<String>,
+    matcher: Arc<dyn Matcher>,
+}
+
+impl DiffAlgorithms {
+    /// Loads the `diff.algorithms` table from `settings`.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let name = "diff.algorithms";
+        let table: BTreeMap<DiffAlgorithm, Vec<String>> =
+            settings.get(name).optional()?.unwrap_or_default();
+        let rules = table
+            .into_iter()
+            .map(|(algorithm, patterns)| {
+                let matcher = fileset::parse_config_patterns(&patterns)
+                    .map_err(|err| ConfigGetError::Type {
+                        name: name.to_owned(),
+                        error: err.into(),
+                        source_path: None,
+                    })?
+                    .to_matcher()
+                    .into();
+                Ok(DiffAlgorithmRule {
+                    algorithm,
+                    patterns,
+                    matcher,
+                })
+            })
+            .try_collect()?;
+        Ok(Self { rules })
+    }
+
+    /// Returns the algorithm to use for the file at `path`.
+    pub fn for_path(&self, path: &RepoPath) -> DiffAlgorithm {
+        self.rules
+            .iter()
+            .find(|rule| rule.matcher.matches(path))
+            .map_or(DiffAlgorithm::Line, |rule| rule.algorithm)
+    }
+}
+
+impl PartialEq for DiffAlgorithms {
+    fn eq(&self, other: &Self) -> bool {
+        let key = |rule: &DiffAlgorithmRule| (rule.algorithm, rule.patterns.clone());
+        self.rules.iter().map(key).eq(other.rules.iter().map(key))
+    }
+}
+
+impl Eq for DiffAlgorithms {}
+
 pub fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
     inputs: impl IntoIterator<Item = &'input T>,
+    algorithm: DiffAlgorithm,
     options: &LineCompareMode,
 ) -> ContentDiff<'input> {
     // TODO: If we add --ignore-blank-lines, its tokenizer will have to attach
@@ -94,18 +184,27 @@ pub fn diff_by_line<'input, T: AsRef<[u8]> + ?Sized + 'input>(
     // post-process (similar to refine_changed_regions()) that expands unchanged
     // regions across blank lines.
     match options {
-        LineCompareMode::Exact => {
-            ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesExactly)
-        }
+        LineCompareMode::Exact => diff_with_algorithm(inputs, algorithm, CompareBytesExactly),
         LineCompareMode::IgnoreAllSpace => {
-            ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreAllWhitespace)
+            diff_with_algorithm(inputs, algorithm, CompareBytesIgnoreAllWhitespace)
         }
         LineCompareMode::IgnoreSpaceChange => {
-            ContentDiff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreWhitespaceAmount)
+            diff_with_algorithm(inputs, algorithm, CompareBytesIgnoreWhitespaceAmount)
         }
     }
 }
 
+fn diff_with_algorithm<'input, T: AsRef<[u8]> + ?Sized + 'input>(
+    inputs: impl IntoIterator<Item = &'input T>,
+    algorithm: DiffAlgorithm,
+    compare: impl CompareBytes,
+) -> ContentDiff<'input> {
+    match algorithm {
+        DiffAlgorithm::Line => ContentDiff::for_tokenizer(inputs, find_line_ranges, compare),
+        DiffAlgorithm::Block => ContentDiff::by_block(inputs, compare),
+    }
+}
+
 /// Splits `[left, right]` hunk pairs into `[left_lines, right_lines]`.
 pub fn unzip_diff_hunks_to_lines<'content, I>(diff_hunks: I) -> Diff<Vec<DiffTokenVec<'content>>>
 where
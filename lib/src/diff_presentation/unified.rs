@@ -20,6 +20,7 @@ use bstr::BStr;
 use bstr::BString;
 use thiserror::Error;
 
+use super::DiffAlgorithm;
 use super::DiffTokenType;
 use super::DiffTokenVec;
 use super::FileContent;
@@ -182,6 +183,7 @@ impl<'content> UnifiedDiffHunk<'content> {
 pub fn unified_diff_hunks(
     contents: Diff<&BStr>,
     context: usize,
+    algorithm: DiffAlgorithm,
     options: LineCompareMode,
 ) -> Vec<UnifiedDiffHunk<'_>> {
     let mut hunks = vec![];
@@ -190,7 +192,7 @@ pub fn unified_diff_hunks(
         right_line_range: 0..0,
         lines: vec![],
     };
-    let diff = diff_by_line(contents.into_array(), &options);
+    let diff = diff_by_line(contents.into_array(), algorithm, &options);
     let mut diff_hunks = diff.hunks().peekable();
     while let Some(hunk) = diff_hunks.next() {
         match hunk.kind {
@@ -19,6 +19,8 @@ use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt as _;
 
 use crate::config::ConfigGetError;
+use crate::gitattributes::AttributeState;
+use crate::gitattributes::PathAttributes;
 use crate::settings::UserSettings;
 
 fn is_binary(bytes: &[u8]) -> bool {
@@ -83,18 +85,55 @@ impl TargetEolStrategy {
         Ok(is_binary(slice_to_check))
     }
 
-    pub(crate) async fn convert_eol_for_snapshot<'a>(
-        &self,
+    /// Decides how to convert the file contents when writing to the backend
+    /// store from the file system.
+    fn target_eol_for_snapshot(&self, attributes: &PathAttributes) -> EolDecision {
+        match text_attribute(attributes) {
+            TextAttribute::Unset => EolDecision::PassThrough,
+            TextAttribute::Set => EolDecision::Convert(TargetEol::Lf),
+            TextAttribute::Auto => EolDecision::ConvertUnlessBinary(TargetEol::Lf),
+            TextAttribute::Unspecified => match self.eol_conversion_mode {
+                EolConversionMode::None => EolDecision::PassThrough,
+                EolConversionMode::Input | EolConversionMode::InputOutput => {
+                    EolDecision::ConvertUnlessBinary(TargetEol::Lf)
+                }
+            },
+        }
+    }
+
+    /// Decides how to convert the file contents when writing to the file
+    /// system from the backend store.
+    fn target_eol_for_update(&self, attributes: &PathAttributes) -> EolDecision {
+        let crlf = match attributes.value("eol") {
+            Some("crlf") => true,
+            Some("lf") => false,
+            _ => self.eol_conversion_mode == EolConversionMode::InputOutput,
+        };
+        match text_attribute(attributes) {
+            TextAttribute::Unset => EolDecision::PassThrough,
+            _ if !crlf => EolDecision::PassThrough,
+            TextAttribute::Set => EolDecision::Convert(TargetEol::Crlf),
+            TextAttribute::Auto => EolDecision::ConvertUnlessBinary(TargetEol::Crlf),
+            TextAttribute::Unspecified => match self.eol_conversion_mode {
+                EolConversionMode::None | EolConversionMode::Input => EolDecision::PassThrough,
+                EolConversionMode::InputOutput => EolDecision::ConvertUnlessBinary(TargetEol::Crlf),
+            },
+        }
+    }
+
+    async fn apply_decision<'a>(
+        decision: EolDecision,
         mut contents: impl AsyncRead + Send + Unpin + 'a,
     ) -> Result<Box<dyn AsyncRead + Send + Unpin + 'a>, std::io::Error> {
-        match self.eol_conversion_mode {
-            EolConversionMode::None => Ok(Box::new(contents)),
-            EolConversionMode::Input | EolConversionMode::InputOutput => {
+        match decision {
+            EolDecision::PassThrough => Ok(Box::new(contents)),
+            EolDecision::Convert(target_eol) => convert_eol(contents, target_eol).await,
+            EolDecision::ConvertUnlessBinary(target_eol) => {
                 let mut peek = vec![];
                 let target_eol = if Self::probe_for_binary(&mut contents, &mut peek).await? {
                     TargetEol::PassThrough
                 } else {
-                    TargetEol::Lf
+                    target_eol
                 };
                 let peek = Cursor::new(peek);
                 let contents = peek.chain(contents);
@@ -103,27 +142,60 @@ impl TargetEolStrategy {
         }
     }
 
+    /// Converts EOL of the file contents to be written to the backend store.
+    ///
+    /// The `text` and `eol` attributes of the file take precedence over the
+    /// configured conversion mode.
+    pub(crate) async fn convert_eol_for_snapshot<'a>(
+        &self,
+        attributes: &PathAttributes,
+        contents: impl AsyncRead + Send + Unpin + 'a,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin + 'a>, std::io::Error> {
+        let decision = self.target_eol_for_snapshot(attributes);
+        Self::apply_decision(decision, contents).await
+    }
+
+    /// Converts EOL of the file contents to be written to the file system.
+    ///
+    /// The `text` and `eol` attributes of the file take precedence over the
+    /// configured conversion mode.
     pub(crate) async fn convert_eol_for_update<'a>(
         &self,
-        mut contents: impl AsyncRead + Send + Unpin + 'a,
+        attributes: &PathAttributes,
+        contents: impl AsyncRead + Send + Unpin + 'a,
     ) -> Result<Box<dyn AsyncRead + Send + Unpin + 'a>, std::io::Error> {
-        match self.eol_conversion_mode {
-            EolConversionMode::None | EolConversionMode::Input => Ok(Box::new(contents)),
-            EolConversionMode::InputOutput => {
-                let mut peek = vec![];
-                let target_eol = if Self::probe_for_binary(&mut contents, &mut peek).await? {
-                    TargetEol::PassThrough
-                } else {
-                    TargetEol::Crlf
-                };
-                let peek = Cursor::new(peek);
-                let contents = peek.chain(contents);
-                convert_eol(contents, target_eol).await
-            }
-        }
+        let decision = self.target_eol_for_update(attributes);
+        Self::apply_decision(decision, contents).await
+    }
+}
+
+/// Effective `text` attribute of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TextAttribute {
+    Set,
+    Unset,
+    Auto,
+    Unspecified,
+}
+
+fn text_attribute(attributes: &PathAttributes) -> TextAttribute {
+    match attributes.get("text") {
+        Some(AttributeState::Set) => TextAttribute::Set,
+        Some(AttributeState::Unset) => TextAttribute::Unset,
+        Some(AttributeState::Value(value)) if value == "auto" => TextAttribute::Auto,
+        // Setting the eol attribute implies that the file is text.
+        _ if attributes.value("eol").is_some() => TextAttribute::Set,
+        _ => TextAttribute::Unspecified,
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EolDecision {
+    PassThrough,
+    Convert(TargetEol),
+    ConvertUnlessBinary(TargetEol),
+}
+
 /// Configuring auto-converting CRLF line endings into LF when you add a file to
 /// the backend, and vice versa when it checks out code onto your filesystem.
 #[derive(Debug, PartialEq, Eq, Copy, Clone, serde::Deserialize)]
@@ -199,6 +271,7 @@ mod tests {
     use test_case::test_case;
 
     use super::*;
+    use crate::gitattributes::GitAttributesFile;
 
     #[tokio::main(flavor = "current_thread")]
     #[test_case(b"a\n", TargetEol::PassThrough, b"a\n"; "LF text with no EOL conversion")]
@@ -324,7 +397,7 @@ mod tests {
     ) {
         let mut actual_output = vec![];
         strategy
-            .convert_eol_for_snapshot(contents)
+            .convert_eol_for_snapshot(&PathAttributes::default(), contents)
             .await
             .unwrap()
             .read_to_end(&mut actual_output)
@@ -356,7 +429,63 @@ mod tests {
     ) {
         let mut actual_output = vec![];
         strategy
-            .convert_eol_for_update(contents)
+            .convert_eol_for_update(&PathAttributes::default(), contents)
+            .await
+            .unwrap()
+            .read_to_end(&mut actual_output)
+            .await
+            .unwrap();
+        assert_eq!(actual_output, expected_output);
+    }
+
+    fn attributes(input: &str) -> PathAttributes {
+        GitAttributesFile::empty()
+            .chain("", input.as_bytes())
+            .attributes("file")
+    }
+
+    #[tokio::main(flavor = "current_thread")]
+    #[test_case(EolConversionMode::Input, "file -text", b"a\r\n", b"a\r\n"; "unset text")]
+    #[test_case(EolConversionMode::None, "file text", b"a\r\n", b"a\n"; "set text")]
+    #[test_case(EolConversionMode::None, "file text", b"\0\r\n", b"\0\n"; "set text binary")]
+    #[test_case(EolConversionMode::None, "file text=auto", b"\0\r\n", b"\0\r\n"; "auto text binary")]
+    #[test_case(EolConversionMode::None, "file eol=crlf", b"a\r\n", b"a\n"; "eol implies text")]
+    #[test_case(EolConversionMode::Input, "file binary", b"a\r\n", b"a\r\n"; "binary")]
+    async fn test_eol_strategy_convert_eol_for_snapshot_with_attributes(
+        eol_conversion_mode: EolConversionMode,
+        gitattributes: &str,
+        contents: &[u8],
+        expected_output: &[u8],
+    ) {
+        let strategy = TargetEolStrategy::new(eol_conversion_mode);
+        let mut actual_output = vec![];
+        strategy
+            .convert_eol_for_snapshot(&attributes(gitattributes), contents)
+            .await
+            .unwrap()
+            .read_to_end(&mut actual_output)
+            .await
+            .unwrap();
+        assert_eq!(actual_output, expected_output);
+    }
+
+    #[tokio::main(flavor = "current_thread")]
+    #[test_case(EolConversionMode::InputOutput, "file -text", b"a\n", b"a\n"; "unset text")]
+    #[test_case(EolConversionMode::None, "file eol=crlf", b"a\n", b"a\r\n"; "crlf eol")]
+    #[test_case(EolConversionMode::InputOutput, "file eol=lf", b"a\n", b"a\n"; "lf eol")]
+    #[test_case(EolConversionMode::None, "file text", b"a\n", b"a\n"; "set text without crlf")]
+    #[test_case(EolConversionMode::InputOutput, "file text", b"\0\n", b"\0\r\n"; "set text binary")]
+    #[test_case(EolConversionMode::None, "file text=auto eol=crlf", b"\0\n", b"\0\n"; "auto text binary")]
+    async fn test_eol_strategy_convert_eol_for_update_with_attributes(
+        eol_conversion_mode: EolConversionMode,
+        gitattributes: &str,
+        contents: &[u8],
+        expected_output: &[u8],
+    ) {
+        let strategy = TargetEolStrategy::new(eol_conversion_mode);
+        let mut actual_output = vec![];
+        strategy
+            .convert_eol_for_update(&attributes(gitattributes), contents)
             .await
             .unwrap()
             .read_to_end(&mut actual_output)
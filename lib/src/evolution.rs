@@ -16,25 +16,36 @@
 
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::collections::hash_map::Entry;
+use std::iter::Peekable;
+use std::ops::Range;
 use std::slice;
+use std::sync::Arc;
 
+use indexmap::IndexSet;
 use itertools::Itertools as _;
 use thiserror::Error;
 
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
+use crate::backend::Timestamp;
 use crate::commit::Commit;
+use crate::commit::CommitByCommitterTimestamp;
 use crate::dag_walk;
 use crate::index::IndexError;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_walk;
 use crate::operation::Operation;
+use crate::ref_name::RemoteRefSymbolBuf;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
+use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
 
 /// Commit with predecessor information.
 #[derive(Clone, Debug, serde::Serialize)]
@@ -252,6 +263,78 @@ where
     }
 }
 
+/// Direction to follow in the commit evolution graph.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EvolutionDirection {
+    /// Follows commit to its predecessors.
+    Predecessors,
+    /// Follows commit to its successors.
+    Successors,
+}
+
+/// Commit evolution graph reconstructed from operation history.
+#[derive(Clone, Debug, Default)]
+pub struct EvolutionGraph {
+    predecessors: BTreeMap<CommitId, Vec<CommitId>>,
+    successors: BTreeMap<CommitId, Vec<CommitId>>,
+}
+
+impl EvolutionGraph {
+    /// Collects predecessor records from `op` and its ancestors.
+    ///
+    /// Operations recorded without commit predecessors (and their ancestors)
+    /// aren't taken into account.
+    pub fn from_operation(op: &Operation) -> OpStoreResult<Self> {
+        let mut predecessors = BTreeMap::new();
+        try_collect_predecessors_into(
+            &mut predecessors,
+            op_walk::walk_ancestors(slice::from_ref(op)),
+        )?;
+        let successors = reverse_edges(predecessors.clone());
+        Ok(Self {
+            predecessors,
+            successors,
+        })
+    }
+
+    /// Walks the graph from `start` commits in the given `direction`, and
+    /// returns commits whose distance from `start` is within the
+    /// `generation` range.
+    ///
+    /// A `start` commit reachable from another `start` commit is included if
+    /// its distance is within the range, even if the range excludes 0.
+    pub fn walk(
+        &self,
+        start: &[CommitId],
+        direction: EvolutionDirection,
+        generation: Range<u64>,
+    ) -> Vec<CommitId> {
+        let graph = match direction {
+            EvolutionDirection::Predecessors => &self.predecessors,
+            EvolutionDirection::Successors => &self.successors,
+        };
+        // Start commits aren't marked as visited until they're reached from
+        // another commit, so they can be found at non-zero distance.
+        let mut visited: HashSet<&CommitId> = HashSet::new();
+        let mut found: IndexSet<&CommitId> = IndexSet::new();
+        let mut current = start.iter().unique().collect_vec();
+        let mut depth = 0;
+        while !current.is_empty() && depth < generation.end {
+            if generation.contains(&depth) {
+                found.extend(current.iter().copied());
+            }
+            current = current
+                .iter()
+                .filter_map(|&id| graph.get(id))
+                .flatten()
+                .filter(|&id| visited.insert(id))
+                .collect();
+            depth += 1;
+        }
+        found.into_iter().cloned().collect()
+    }
+}
+
 /// Collects predecessor records from `new_ops` to `old_ops`, and resolves
 /// transitive entries.
 ///
@@ -299,6 +382,196 @@ pub fn accumulate_predecessors(
         .map_err(|id| WalkPredecessorsError::CycleDetected(id.clone()))
 }
 
+/// Error that may occur during [`find_latest_versions()`].
+#[expect(missing_docs)]
+#[derive(Debug, Error)]
+pub enum FindLatestVersionsError {
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error(transparent)]
+    Index(#[from] IndexError),
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+}
+
+/// Finds the newest visible version of each of the `commit_ids`.
+///
+/// A commit is superseded by its visible successors in the evolution graph. If
+/// there are no such successors, it's superseded by newer visible commits with
+/// the same change ID (which is how rewrites fetched from a remote show up),
+/// and by the current target of a remote bookmark that used to point to the
+/// commit and has since moved away. Superseding commits are followed
+/// transitively. If there are several candidates, the most recently committed
+/// head among them is picked. Commits in `excluded` are never picked.
+///
+/// Only the operations recorded after the commits were created are scanned.
+///
+/// The returned map only contains entries for superseded commits.
+pub fn find_latest_versions(
+    repo: &ReadonlyRepo,
+    commit_ids: &[CommitId],
+    excluded: &Arc<ResolvedRevsetExpression>,
+) -> Result<HashMap<CommitId, CommitId>, FindLatestVersionsError> {
+    let mut history = OpLogHistory::new(repo.operation());
+    let visible_heads = |candidate_ids: Vec<CommitId>| -> Result<Vec<_>, FindLatestVersionsError> {
+        let head_ids: Vec<_> = RevsetExpression::commits(candidate_ids)
+            .intersection(&RevsetExpression::visible_heads().ancestors())
+            .minus(excluded)
+            .heads()
+            .evaluate(repo)?
+            .iter()
+            .try_collect()?;
+        let heads = head_ids
+            .iter()
+            .map(|id| repo.store().get_commit(id).map(CommitByCommitterTimestamp))
+            .try_collect()?;
+        Ok(heads)
+    };
+    let mut latest_versions = HashMap::new();
+    for commit_id in commit_ids {
+        let mut current = repo.store().get_commit(commit_id)?;
+        let mut visited = HashSet::from([commit_id.clone()]);
+        loop {
+            // The commit can't be rewritten or pointed to by bookmarks before
+            // it was created.
+            history.scan_back_to(&current.committer().timestamp)?;
+            let mut heads = visible_heads(history.walk_successors(current.id()))?;
+            if heads.is_empty() {
+                let mut candidate_ids = vec![];
+                if let Some(targets) = repo.resolve_change_id(current.change_id())? {
+                    for (_, id) in targets.visible_with_offsets() {
+                        if id == current.id() {
+                            continue;
+                        }
+                        let commit = repo.store().get_commit(id)?;
+                        if commit.committer().timestamp.timestamp
+                            > current.committer().timestamp.timestamp
+                        {
+                            candidate_ids.push(id.clone());
+                        }
+                    }
+                }
+                for symbol in history.symbols_pointing_to(current.id()) {
+                    let remote_ref = repo.view().get_remote_bookmark(symbol.as_ref());
+                    let Some(target_id) = remote_ref.target.as_normal() else {
+                        continue;
+                    };
+                    // Bookmarks moved backwards don't supersede the commit.
+                    if target_id == current.id()
+                        || repo.index().is_ancestor(target_id, current.id())?
+                    {
+                        continue;
+                    }
+                    candidate_ids.push(target_id.clone());
+                }
+                heads = visible_heads(candidate_ids)?;
+            }
+            let Some(CommitByCommitterTimestamp(newest)) = heads.into_iter().max() else {
+                break;
+            };
+            if !visited.insert(newest.id().clone()) {
+                break;
+            }
+            current = newest;
+        }
+        if current.id() != commit_id {
+            latest_versions.insert(commit_id.clone(), current.id().clone());
+        }
+    }
+    Ok(latest_versions)
+}
+
+/// Commit successors and remote bookmark targets collected lazily from the
+/// operation log, newest operations first.
+struct OpLogHistory {
+    op_ancestors: Peekable<Box<dyn Iterator<Item = OpStoreResult<Operation>>>>,
+    /// Whether an operation without commit predecessors has been seen. Older
+    /// operations aren't taken into account for successors.
+    predecessors_truncated: bool,
+    successors: HashMap<CommitId, Vec<CommitId>>,
+    bookmark_targets: HashMap<CommitId, Vec<RemoteRefSymbolBuf>>,
+}
+
+impl OpLogHistory {
+    fn new(head_op: &Operation) -> Self {
+        let op_ancestors: Box<dyn Iterator<Item = _>> =
+            Box::new(op_walk::walk_ancestors(slice::from_ref(head_op)));
+        Self {
+            op_ancestors: op_ancestors.peekable(),
+            predecessors_truncated: false,
+            successors: HashMap::new(),
+            bookmark_targets: HashMap::new(),
+        }
+    }
+
+    /// Scans operations back to the first operation which ended before the
+    /// `timestamp`.
+    fn scan_back_to(&mut self, timestamp: &Timestamp) -> OpStoreResult<()> {
+        while let Some(op) = self.op_ancestors.next_if(|op| {
+            op.as_ref().map_or(true, |op| {
+                op.metadata().time.end.timestamp >= timestamp.timestamp
+            })
+        }) {
+            let op = op?;
+            if let Some(map) = &op.store_operation().commit_predecessors {
+                if !self.predecessors_truncated {
+                    for (new_id, old_ids) in map {
+                        for old_id in old_ids {
+                            self.successors
+                                .entry(old_id.clone())
+                                .or_default()
+                                .push(new_id.clone());
+                        }
+                    }
+                }
+            } else {
+                self.predecessors_truncated = true;
+            }
+            let view = op.view()?;
+            for (symbol, remote_ref) in view.all_remote_bookmarks() {
+                // The Git-tracking bookmarks just mirror local bookmarks.
+                #[cfg(feature = "git")]
+                if symbol.remote == crate::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+                    continue;
+                }
+                for id in remote_ref.target.added_ids() {
+                    let symbols = self.bookmark_targets.entry(id.clone()).or_default();
+                    if !symbols.iter().any(|s| s == symbol) {
+                        symbols.push(symbol.to_owned());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns transitive successors of `commit_id` found so far.
+    fn walk_successors(&self, commit_id: &CommitId) -> Vec<CommitId> {
+        let mut visited = HashSet::from([commit_id]);
+        let mut found = vec![];
+        let mut pending = vec![commit_id];
+        while let Some(id) = pending.pop() {
+            for successor_id in self.successors.get(id).into_iter().flatten() {
+                if visited.insert(successor_id) {
+                    found.push(successor_id.clone());
+                    pending.push(successor_id);
+                }
+            }
+        }
+        found
+    }
+
+    /// Returns remote bookmarks that pointed to `commit_id` in the operations
+    /// scanned so far.
+    fn symbols_pointing_to(&self, commit_id: &CommitId) -> &[RemoteRefSymbolBuf] {
+        self.bookmark_targets
+            .get(commit_id)
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
 fn try_collect_predecessors_into(
     collected: &mut BTreeMap<CommitId, Vec<CommitId>>,
     ops: impl IntoIterator<Item = OpStoreResult<Operation>>,
@@ -306,6 +306,82 @@ pub fn try_merge<T: AsRef<[u8]>>(inputs: &Merge<T>, options: &MergeOptions) -> O
     merge_inner(inputs, options)
 }
 
+/// Built-in strategy to resolve hunks which the standard merge leaves
+/// conflicted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// Takes the lines from all sides in order.
+    Union,
+    /// Takes the lines from the first side.
+    Ours,
+    /// Takes the lines from the last side.
+    Theirs,
+    /// Takes the lines from all sides in order if all sides added lines at the
+    /// same position. Other conflicts are left unresolved.
+    AdjacentAdditions,
+}
+
+impl MergeStrategy {
+    /// All built-in strategies.
+    pub const ALL: [Self; 4] = [
+        Self::Union,
+        Self::Ours,
+        Self::Theirs,
+        Self::AdjacentAdditions,
+    ];
+
+    /// Name of the strategy as used in config and attributes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Union => "union",
+            Self::Ours => "ours",
+            Self::Theirs => "theirs",
+            Self::AdjacentAdditions => "adjacent-additions",
+        }
+    }
+
+    fn resolve_hunk(self, hunk: &Merge<BString>) -> Option<BString> {
+        match self {
+            Self::Union => Some(hunk.adds().flat_map(|add| add.iter().copied()).collect()),
+            Self::Ours => Some(hunk.first().clone()),
+            Self::Theirs => hunk.adds().last().cloned(),
+            Self::AdjacentAdditions => {
+                if hunk.removes().all(|remove| remove.is_empty()) {
+                    Self::Union.resolve_hunk(hunk)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Splits `inputs` into hunks like [`try_merge()`], then resolves the
+/// conflicting hunks by the given `strategy`.
+///
+/// Returns the merged content if all hunks can be resolved.
+pub fn try_merge_with_strategy<T: AsRef<[u8]>>(
+    inputs: &Merge<T>,
+    options: &MergeOptions,
+    strategy: MergeStrategy,
+) -> Option<BString> {
+    match merge_hunks(inputs, options) {
+        MergeResult::Resolved(content) => Some(content),
+        MergeResult::Conflict(hunks) => {
+            let mut content = BString::default();
+            for hunk in &hunks {
+                if let Some(resolved) = hunk.as_resolved() {
+                    content.extend_from_slice(resolved);
+                } else {
+                    content.extend_from_slice(&strategy.resolve_hunk(hunk)?);
+                }
+            }
+            Some(content)
+        }
+    }
+}
+
 fn merge_inner<'input, T, B>(inputs: &'input Merge<T>, options: &MergeOptions) -> B
 where
     T: AsRef<[u8]>,
@@ -492,6 +568,7 @@ mod tests {
     use indoc::indoc;
 
     use super::*;
+    use crate::merge_driver::MergeDrivers;
 
     fn conflict<const N: usize>(values: [&[u8]; N]) -> Merge<BString> {
         Merge::from_vec(values.map(hunk).to_vec())
@@ -855,6 +932,7 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            drivers: MergeDrivers::default(),
         };
         let merge_hunks = |inputs: &_| merge_hunks(inputs, &options);
         // Unchanged and empty on all sides
@@ -971,6 +1049,7 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            drivers: MergeDrivers::default(),
         };
         let merge_hunks = |inputs: &_| merge_hunks(inputs, &options);
         let merge = |inputs: &_| merge(inputs, &options);
@@ -1083,6 +1162,7 @@ mod tests {
         let options = MergeOptions {
             hunk_level: FileMergeHunkLevel::Word,
             same_change: SameChange::Accept,
+            drivers: MergeDrivers::default(),
         };
         let merge = |inputs: &_| merge(inputs, &options);
         // No context line in between, but "\n" is a context word
@@ -1106,4 +1186,58 @@ mod tests {
             conflict([b"a b", b"a", b"x a c"])
         );
     }
+
+    #[test]
+    fn test_try_merge_with_strategy() {
+        let options = MergeOptions {
+            hunk_level: FileMergeHunkLevel::Line,
+            same_change: SameChange::Accept,
+            drivers: MergeDrivers::default(),
+        };
+        let try_merge = |inputs: &_, strategy| try_merge_with_strategy(inputs, &options, strategy);
+        // Both sides added lines at the same position
+        let added = conflict([b"a\nb\n", b"a\n", b"a\nc\n"]);
+        // Both sides modified the same line
+        let modified = conflict([b"a\nb\nd\n", b"a\nx\nd\n", b"a\nc\nd\n"]);
+
+        assert_eq!(
+            try_merge(&added, MergeStrategy::Union),
+            Some(hunk(b"a\nb\nc\n"))
+        );
+        assert_eq!(
+            try_merge(&modified, MergeStrategy::Union),
+            Some(hunk(b"a\nb\nc\nd\n"))
+        );
+
+        assert_eq!(
+            try_merge(&added, MergeStrategy::Ours),
+            Some(hunk(b"a\nb\n"))
+        );
+        assert_eq!(
+            try_merge(&modified, MergeStrategy::Ours),
+            Some(hunk(b"a\nb\nd\n"))
+        );
+
+        assert_eq!(
+            try_merge(&added, MergeStrategy::Theirs),
+            Some(hunk(b"a\nc\n"))
+        );
+        assert_eq!(
+            try_merge(&modified, MergeStrategy::Theirs),
+            Some(hunk(b"a\nc\nd\n"))
+        );
+
+        assert_eq!(
+            try_merge(&added, MergeStrategy::AdjacentAdditions),
+            Some(hunk(b"a\nb\nc\n"))
+        );
+        assert_eq!(try_merge(&modified, MergeStrategy::AdjacentAdditions), None);
+
+        // Changes which don't conflict are merged as usual
+        let inputs = conflict([b"a2\nb\nc\n", b"a\nb\nc\n", b"a\nb\nc2\n"]);
+        assert_eq!(
+            try_merge(&inputs, MergeStrategy::Ours),
+            Some(hunk(b"a2\nb\nc2\n"))
+        );
+    }
 }
@@ -618,6 +618,22 @@ pub fn parse_maybe_bare(
     resolve_expression(diagnostics, path_converter, &node)
 }
 
+/// Parses fileset `patterns` specified in config into a union expression.
+///
+/// Patterns are relative to the workspace root.
+pub(crate) fn parse_config_patterns(patterns: &[String]) -> FilesetParseResult<FilesetExpression> {
+    let path_converter = RepoPathUiConverter::Fs {
+        cwd: "".into(),
+        base: "".into(),
+    };
+    let mut diagnostics = FilesetDiagnostics::new();
+    let expressions: Vec<_> = patterns
+        .iter()
+        .map(|pattern| parse(&mut diagnostics, pattern, &path_converter))
+        .try_collect()?;
+    Ok(FilesetExpression::union_all(expressions))
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -43,6 +43,7 @@ use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::git_backend::GitBackend;
+use crate::git_backend::to_git_tree_id;
 use crate::git_subprocess::GitFetchStatus;
 pub use crate::git_subprocess::GitProgress;
 pub use crate::git_subprocess::GitSidebandLineTerminator;
@@ -1649,7 +1650,7 @@ fn reset_index(
             // If the parent tree is resolved, we can use gix's `index_from_tree` method.
             // This is more efficient than iterating over the tree and adding each entry.
             git_repo
-                .index_from_tree(&gix::ObjectId::from_bytes_or_panic(tree_id.as_bytes()))
+                .index_from_tree(&to_git_tree_id(tree_id)?)
                 .map_err(GitResetHeadError::from_git)?
         }
     } else {
@@ -2914,6 +2915,60 @@ impl<'a> GitFetch<'a> {
     }
 }
 
+/// Restores the Git refs changed by an import to the targets they had before,
+/// so the imported commits won't be imported again by a later import.
+///
+/// This should be called if the import (typically of fetched refs) is rejected
+/// and the transaction is discarded. Refs that were conflicted in jj are left
+/// unchanged.
+#[tracing::instrument(skip_all)]
+pub fn revert_imported_git_refs(
+    store: &Store,
+    import_stats: &GitImportStats,
+) -> Result<(), GitImportError> {
+    let git_repo = get_git_repo(store)?;
+    let bookmarks =
+        import_stats
+            .changed_remote_bookmarks
+            .iter()
+            .map(|(symbol, (old_remote_ref, _))| {
+                let full_name = to_git_ref_name(GitRefKind::Bookmark, symbol.as_ref());
+                (full_name, &old_remote_ref.target)
+            });
+    let tags = import_stats
+        .changed_remote_tags
+        .iter()
+        .map(|(symbol, (old_remote_ref, _))| {
+            // Tags fetched from real remotes are stored in our own namespace.
+            let full_name = to_git_ref_name(GitRefKind::Tag, symbol.as_ref()).or_else(|| {
+                let name = symbol.name.as_str();
+                let remote = symbol.remote.as_str();
+                Some(format!("{REMOTE_TAG_REF_NAMESPACE}{remote}/{name}").into())
+            });
+            (full_name, &old_remote_ref.target)
+        });
+    for (full_name, old_target) in bookmarks.chain(tags) {
+        let Some(full_name) = full_name else {
+            continue;
+        };
+        if old_target.is_absent() {
+            if let Some(git_ref) = git_repo
+                .try_find_reference(full_name.as_str())
+                .map_err(GitImportError::from_git)?
+            {
+                git_ref.delete().map_err(GitImportError::from_git)?;
+            }
+        } else if let Some(id) = old_target.as_normal() {
+            let oid = gix::ObjectId::from_bytes_or_panic(id.as_bytes());
+            let constraint = gix::refs::transaction::PreviousValue::Any;
+            git_repo
+                .reference(full_name.as_str(), oid, constraint, "revert fetch by jj")
+                .map_err(GitImportError::from_git)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum GitPushError {
     #[error("No git remote named '{}'", .0.as_symbol())]
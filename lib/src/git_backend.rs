@@ -14,7 +14,9 @@
 
 #![expect(missing_docs)]
 
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::hash_map;
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::fmt::Error;
@@ -22,6 +24,7 @@ use std::fmt::Formatter;
 use std::fs;
 use std::io;
 use std::io::Cursor;
+use std::io::Write as _;
 use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -43,6 +46,7 @@ use once_cell::sync::OnceCell as OnceLock;
 use pollster::FutureExt as _;
 use prost::Message as _;
 use smallvec::SmallVec;
+use tempfile::NamedTempFile;
 use thiserror::Error;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt as _;
@@ -70,16 +74,20 @@ use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::backend::make_root_commit;
 use crate::config::ConfigGetError;
+use crate::content_hash::blake2b_hash;
+use crate::copies::collect_related_copies;
 use crate::file_util;
 use crate::file_util::BadPathEncoding;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::git::GitSettings;
+use crate::hex_util;
 use crate::index::Index;
 use crate::lock::FileLock;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::object_id::ObjectId;
+use crate::repo_path::InvalidNewRepoPathError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponentBuf;
@@ -91,6 +99,8 @@ use crate::stacked_table::TableStore;
 use crate::stacked_table::TableStoreError;
 
 const HASH_LENGTH: usize = 20;
+/// Length of the hash of the copy IDs of the entries of a tree.
+const TREE_COPY_IDS_HASH_LENGTH: usize = 64;
 const CHANGE_ID_LENGTH: usize = 16;
 /// Ref namespace used only for preventing GC.
 const NO_GC_REF_NAMESPACE: &str = "refs/jj/keep/";
@@ -173,6 +183,13 @@ pub struct GitBackend {
     shallow_root_ids: OnceLock<Vec<CommitId>>,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
+    /// Directory of copy histories, keyed by copy ID.
+    copies_path: PathBuf,
+    /// Directory of the IDs of the copy histories descending from each root
+    /// copy history, keyed by the root copy ID.
+    copy_index_path: PathBuf,
+    /// Directory of the copy IDs of the entries of trees, keyed by their hash.
+    tree_copies_path: PathBuf,
     git_executable: PathBuf,
     write_change_id_header: bool,
 }
@@ -184,6 +201,7 @@ impl GitBackend {
 
     fn new(
         base_repo: gix::ThreadSafeRepository,
+        store_path: &Path,
         extra_metadata_store: TableStore,
         git_settings: GitSettings,
     ) -> Self {
@@ -200,6 +218,9 @@ impl GitBackend {
             shallow_root_ids: OnceLock::new(),
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
+            copies_path: store_path.join("copies"),
+            copy_index_path: store_path.join("copy_index"),
+            tree_copies_path: store_path.join("tree_copies"),
             git_executable: git_settings.executable_path,
             write_change_id_header: git_settings.write_change_id_header,
         }
@@ -298,7 +319,12 @@ impl GitBackend {
             .context(&target_path)
             .map_err(GitBackendInitError::Path)?;
         let extra_metadata_store = TableStore::init(extra_path, HASH_LENGTH);
-        Ok(Self::new(repo, extra_metadata_store, git_settings))
+        Ok(Self::new(
+            repo,
+            store_path,
+            extra_metadata_store,
+            git_settings,
+        ))
     }
 
     pub fn load(
@@ -325,7 +351,12 @@ impl GitBackend {
         let extra_metadata_store = TableStore::load(store_path.join("extra"), HASH_LENGTH);
         let git_settings =
             GitSettings::from_settings(settings).map_err(GitBackendLoadError::Config)?;
-        Ok(Self::new(repo, extra_metadata_store, git_settings))
+        Ok(Self::new(
+            repo,
+            store_path,
+            extra_metadata_store,
+            git_settings,
+        ))
     }
 
     fn lock_git_repo(&self) -> MutexGuard<'_, gix::Repository> {
@@ -457,6 +488,60 @@ impl GitBackend {
         Ok(blob.take_data())
     }
 
+    /// Reads the copy IDs of the entries of the tree with the given ID.
+    fn read_tree_copy_ids(
+        &self,
+        id: &TreeId,
+        hash: &[u8],
+    ) -> BackendResult<HashMap<String, crate::protos::git_store::tree_copy_ids::Entry>> {
+        let path = self.tree_copies_path.join(hex_util::encode_hex(hash));
+        let buf = fs::read(path).map_err(|err| map_missing_file_err(err, id))?;
+        let proto = crate::protos::git_store::TreeCopyIds::decode(&*buf)
+            .map_err(|err| to_read_object_err(err, id))?;
+        Ok(proto
+            .entries
+            .into_iter()
+            .map(|entry| (entry.name.clone(), entry))
+            .collect())
+    }
+
+    /// Writes the copy IDs of the entries of a tree, and returns their hash.
+    fn write_tree_copy_ids(
+        &self,
+        entries: Vec<crate::protos::git_store::tree_copy_ids::Entry>,
+    ) -> BackendResult<Vec<u8>> {
+        let bytes = crate::protos::git_store::TreeCopyIds { entries }.encode_to_vec();
+        let hash = blake2b_hash(&bytes).to_vec();
+        let path = self.tree_copies_path.join(hex_util::encode_hex(&hash));
+        write_metadata_file(&self.tree_copies_path, &path, &bytes).map_err(|err| {
+            BackendError::WriteObject {
+                object_type: "tree copy IDs",
+                source: Box::new(err),
+            }
+        })?;
+        Ok(hash)
+    }
+
+    /// Returns the IDs of the root copy histories the copy history with the
+    /// given ID descends from, including itself if it has no parents.
+    async fn find_root_copy_ids(&self, id: &CopyId) -> BackendResult<Vec<CopyId>> {
+        let mut roots = vec![];
+        let mut visited = HashSet::new();
+        let mut pending = vec![id.clone()];
+        while let Some(id) = pending.pop() {
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            let parents = self.read_copy(&id).await?.parents;
+            if parents.is_empty() {
+                roots.push(id);
+            } else {
+                pending.extend(parents);
+            }
+        }
+        Ok(roots)
+    }
+
     fn new_diff_platform(&self) -> BackendResult<gix::diff::blob::Platform> {
         let attributes = gix::worktree::Stack::new(
             Path::new(""),
@@ -492,7 +577,7 @@ impl GitBackend {
         let tree = self.read_commit(id).block_on()?.root_tree;
         // TODO(kfm): probably want to do something here if it is a merge
         let tree_id = tree.first().clone();
-        let gix_id = validate_git_object_id(&tree_id)?;
+        let gix_id = to_git_tree_id(&tree_id)?;
         repo.find_object(gix_id)
             .map_err(|err| map_not_found_err(err, &tree_id))?
             .try_into_tree()
@@ -742,6 +827,13 @@ fn serialize_extras(commit: &Commit) -> Vec<u8> {
         // future, we can stop doing it.
         proto.root_tree = commit.root_tree.iter().map(|r| r.to_bytes()).collect();
     }
+    if commit
+        .root_tree
+        .iter()
+        .any(|id| id.as_bytes().len() != HASH_LENGTH)
+    {
+        proto.root_tree_with_copy_ids = commit.root_tree.iter().map(|r| r.to_bytes()).collect();
+    }
     for predecessor in &commit.predecessors {
         proto.predecessors.push(predecessor.to_bytes());
     }
@@ -764,6 +856,14 @@ fn deserialize_extras(commit: &mut Commit, bytes: &[u8]) {
             .collect();
         commit.root_tree = merge_builder.build();
     }
+    if !proto.root_tree_with_copy_ids.is_empty() {
+        let merge_builder: MergeBuilder<_> = proto
+            .root_tree_with_copy_ids
+            .iter()
+            .map(|id_bytes| TreeId::from_bytes(id_bytes))
+            .collect();
+        commit.root_tree = merge_builder.build();
+    }
     for predecessor in &proto.predecessors {
         commit.predecessors.push(CommitId::from_bytes(predecessor));
     }
@@ -892,6 +992,70 @@ fn run_git_gc(program: &OsStr, git_dir: &Path, keep_newer: SystemTime) -> Result
     Ok(())
 }
 
+/// Writes a content-addressed file of metadata stored next to the Git repo,
+/// creating the directory if needed.
+fn write_metadata_file(dir: &Path, path: &Path, contents: &[u8]) -> Result<(), PathError> {
+    fs::create_dir_all(dir).context(dir)?;
+    let temp_file = NamedTempFile::new_in(dir).context(dir)?;
+    temp_file
+        .as_file()
+        .write_all(contents)
+        .context(temp_file.path())?;
+    file_util::persist_content_addressed_temp_file(temp_file, path).context(path)?;
+    Ok(())
+}
+
+fn map_missing_file_err(err: io::Error, id: &impl ObjectId) -> BackendError {
+    if err.kind() == io::ErrorKind::NotFound {
+        BackendError::ObjectNotFound {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: Box::new(err),
+        }
+    } else {
+        to_read_object_err(err, id)
+    }
+}
+
+fn copy_history_to_proto(copy: &CopyHistory) -> crate::protos::git_store::CopyHistory {
+    crate::protos::git_store::CopyHistory {
+        current_path: copy.current_path.as_internal_file_string().to_owned(),
+        parents: copy.parents.iter().map(|id| id.to_bytes()).collect(),
+        salt: copy.salt.clone(),
+    }
+}
+
+fn copy_history_from_proto(
+    proto: crate::protos::git_store::CopyHistory,
+) -> Result<CopyHistory, InvalidNewRepoPathError> {
+    Ok(CopyHistory {
+        current_path: RepoPathBuf::from_internal_string(proto.current_path)?,
+        parents: proto.parents.into_iter().map(CopyId::new).collect(),
+        salt: proto.salt,
+    })
+}
+
+/// Splits the ID of a tree into the ID of the Git tree and the hash of the copy
+/// IDs of its entries, if any.
+///
+/// Git trees can't store copy IDs, so the ID of a tree with files that have
+/// copy IDs, or with such subtrees, is the Git tree ID followed by the hash of
+/// the copy IDs of its entries.
+fn split_tree_id(id: &TreeId) -> BackendResult<(gix::ObjectId, Option<&[u8]>)> {
+    let bytes = id.as_bytes();
+    if bytes.len() == HASH_LENGTH + TREE_COPY_IDS_HASH_LENGTH {
+        let (git_id, hash) = bytes.split_at(HASH_LENGTH);
+        Ok((gix::ObjectId::from_bytes_or_panic(git_id), Some(hash)))
+    } else {
+        Ok((validate_git_object_id(id)?, None))
+    }
+}
+
+/// Returns the ID of the Git tree of the tree with the given ID.
+pub(crate) fn to_git_tree_id(id: &TreeId) -> BackendResult<gix::ObjectId> {
+    split_tree_id(id).map(|(git_id, _)| git_id)
+}
+
 fn validate_git_object_id(id: &impl ObjectId) -> BackendResult<gix::ObjectId> {
     if id.as_bytes().len() != HASH_LENGTH {
         return Err(BackendError::InvalidHashLength {
@@ -1056,29 +1220,57 @@ impl Backend for GitBackend {
         Ok(SymlinkId::new(oid.as_bytes().to_vec()))
     }
 
-    async fn read_copy(&self, _id: &CopyId) -> BackendResult<CopyHistory> {
-        Err(BackendError::Unsupported(
-            "The Git backend doesn't support tracked copies yet".to_string(),
-        ))
-    }
-
-    async fn write_copy(&self, _contents: &CopyHistory) -> BackendResult<CopyId> {
-        Err(BackendError::Unsupported(
-            "The Git backend doesn't support tracked copies yet".to_string(),
-        ))
+    async fn read_copy(&self, id: &CopyId) -> BackendResult<CopyHistory> {
+        let path = self.copies_path.join(id.hex());
+        let buf = fs::read(path).map_err(|err| map_missing_file_err(err, id))?;
+        let proto = crate::protos::git_store::CopyHistory::decode(&*buf)
+            .map_err(|err| to_read_object_err(err, id))?;
+        copy_history_from_proto(proto).map_err(|err| to_read_object_err(err, id))
     }
 
-    async fn get_related_copies(&self, _copy_id: &CopyId) -> BackendResult<Vec<CopyHistory>> {
-        Err(BackendError::Unsupported(
-            "The Git backend doesn't support tracked copies yet".to_string(),
-        ))
+    async fn write_copy(&self, contents: &CopyHistory) -> BackendResult<CopyId> {
+        let to_write_err = |err: PathError| BackendError::WriteObject {
+            object_type: "copy",
+            source: Box::new(err),
+        };
+        let id = CopyId::new(blake2b_hash(contents).to_vec());
+        let proto = copy_history_to_proto(contents);
+        let path = self.copies_path.join(id.hex());
+        write_metadata_file(&self.copies_path, &path, &proto.encode_to_vec())
+            .map_err(to_write_err)?;
+        // Index the copy history by its roots so the related copy histories
+        // can be found without reading all of them.
+        for root_id in self.find_root_copy_ids(&id).await? {
+            let dir = self.copy_index_path.join(root_id.hex());
+            write_metadata_file(&dir, &dir.join(id.hex()), &[]).map_err(to_write_err)?;
+        }
+        Ok(id)
+    }
+
+    async fn get_related_copies(&self, copy_id: &CopyId) -> BackendResult<Vec<CopyHistory>> {
+        let mut copies = HashMap::new();
+        for root_id in self.find_root_copy_ids(copy_id).await? {
+            let dir = self.copy_index_path.join(root_id.hex());
+            let entries = fs::read_dir(&dir).map_err(|err| map_missing_file_err(err, &root_id))?;
+            for entry in entries {
+                let entry = entry.map_err(|err| to_read_object_err(err, &root_id))?;
+                let Some(id) = entry.file_name().to_str().and_then(CopyId::try_from_hex) else {
+                    continue;
+                };
+                if let hash_map::Entry::Vacant(entry) = copies.entry(id) {
+                    let copy = self.read_copy(entry.key()).await?;
+                    entry.insert(copy);
+                }
+            }
+        }
+        collect_related_copies(&copies, copy_id)
     }
 
     async fn read_tree(&self, _path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
         if id == &self.empty_tree_id {
             return Ok(Tree::default());
         }
-        let git_tree_id = validate_git_object_id(id)?;
+        let (git_tree_id, copy_ids_hash) = split_tree_id(id)?;
 
         let locked_repo = self.lock_git_repo();
         let git_tree = locked_repo
@@ -1127,6 +1319,26 @@ impl Backend for GitBackend {
                 Ok((name, value))
             })
             .try_collect()?;
+        drop(git_tree);
+        drop(locked_repo);
+        if let Some(hash) = copy_ids_hash {
+            let copy_ids = self.read_tree_copy_ids(id, hash)?;
+            for (name, value) in &mut entries {
+                let Some(entry) = copy_ids.get(name.as_internal_str()) else {
+                    continue;
+                };
+                match value {
+                    TreeValue::File { copy_id, .. } if !entry.copy_id.is_empty() => {
+                        *copy_id = CopyId::new(entry.copy_id.clone());
+                    }
+                    // The subtree ID must refer to the same Git tree.
+                    TreeValue::Tree(tree_id) if entry.tree_id.starts_with(tree_id.as_bytes()) => {
+                        *tree_id = TreeId::new(entry.tree_id.clone());
+                    }
+                    _ => return Err(to_read_object_err("Invalid tree copy IDs", id)),
+                }
+            }
+        }
         // While Git tree entries are sorted, the rule is slightly different.
         // Directory names are sorted as if they had trailing "/".
         if !entries.is_sorted_by_key(|(name, _)| name) {
@@ -1146,7 +1358,7 @@ impl Backend for GitBackend {
                     TreeValue::File {
                         id,
                         executable: false,
-                        copy_id: _, // TODO: Use the value
+                        copy_id: _,
                     } => gix::objs::tree::Entry {
                         mode: gix::object::tree::EntryKind::Blob.into(),
                         filename,
@@ -1155,7 +1367,7 @@ impl Backend for GitBackend {
                     TreeValue::File {
                         id,
                         executable: true,
-                        copy_id: _, // TODO: Use the value
+                        copy_id: _,
                     } => gix::objs::tree::Entry {
                         mode: gix::object::tree::EntryKind::BlobExecutable.into(),
                         filename,
@@ -1169,7 +1381,7 @@ impl Backend for GitBackend {
                     TreeValue::Tree(id) => gix::objs::tree::Entry {
                         mode: gix::object::tree::EntryKind::Tree.into(),
                         filename,
-                        oid: gix::ObjectId::from_bytes_or_panic(id.as_bytes()),
+                        oid: gix::ObjectId::from_bytes_or_panic(&id.as_bytes()[..HASH_LENGTH]),
                     },
                     TreeValue::GitSubmodule(id) => gix::objs::tree::Entry {
                         mode: gix::object::tree::EntryKind::Commit.into(),
@@ -1186,8 +1398,34 @@ impl Backend for GitBackend {
             .map_err(|err| BackendError::WriteObject {
                 object_type: "tree",
                 source: Box::new(err),
-            })?;
-        Ok(TreeId::from_bytes(oid.as_bytes()))
+            })?
+            .detach();
+        drop(locked_repo);
+        let copy_ids = contents
+            .entries()
+            .filter_map(|entry| {
+                let (copy_id, tree_id) = match entry.value() {
+                    TreeValue::File { copy_id, .. } if *copy_id != CopyId::placeholder() => {
+                        (copy_id.to_bytes(), vec![])
+                    }
+                    TreeValue::Tree(id) if id.as_bytes().len() != HASH_LENGTH => {
+                        (vec![], id.to_bytes())
+                    }
+                    _ => return None,
+                };
+                Some(crate::protos::git_store::tree_copy_ids::Entry {
+                    name: entry.name().as_internal_str().to_owned(),
+                    copy_id,
+                    tree_id,
+                })
+            })
+            .collect_vec();
+        if copy_ids.is_empty() {
+            Ok(TreeId::from_bytes(oid.as_bytes()))
+        } else {
+            let hash = self.write_tree_copy_ids(copy_ids)?;
+            Ok(TreeId::new([oid.as_bytes(), &hash].concat()))
+        }
     }
 
     #[tracing::instrument(skip(self))]
@@ -1239,7 +1477,7 @@ impl Backend for GitBackend {
         let locked_repo = self.lock_git_repo();
         let tree_ids = &contents.root_tree;
         let git_tree_id = match tree_ids.as_resolved() {
-            Some(tree_id) => validate_git_object_id(tree_id)?,
+            Some(tree_id) => to_git_tree_id(tree_id)?,
             None => write_tree_conflict(&locked_repo, tree_ids)?,
         };
         let author = signature_to_git(&contents.author);
@@ -1285,7 +1523,10 @@ impl Backend for GitBackend {
             ));
         }
         if !tree_ids.is_resolved() {
-            let value = tree_ids.iter().map(|id| id.hex()).join(" ");
+            let value = tree_ids
+                .iter()
+                .map(|id| to_git_tree_id(id).map(|id| id.to_hex().to_string()))
+                .process_results(|mut ids| ids.join(" "))?;
             extra_headers.push((JJ_TREES_COMMIT_HEADER.into(), value.into()));
         }
         if self.write_change_id_header {
@@ -1513,12 +1754,14 @@ fn write_tree_conflict(
             .enumerate()
             .map(|(i, tree_id)| (format!(".jjconflict-side-{i}"), tree_id)),
     )
-    .map(|(name, tree_id)| gix::objs::tree::Entry {
-        mode: gix::object::tree::EntryKind::Tree.into(),
-        filename: name.into(),
-        oid: gix::ObjectId::from_bytes_or_panic(tree_id.as_bytes()),
+    .map(|(name, tree_id)| {
+        Ok(gix::objs::tree::Entry {
+            mode: gix::object::tree::EntryKind::Tree.into(),
+            filename: name.into(),
+            oid: to_git_tree_id(tree_id)?,
+        })
     })
-    .collect_vec();
+    .collect::<BackendResult<Vec<_>>>()?;
     let readme_id = repo
         .write_blob(
             r#"This commit was made by jj, https://jj-vcs.dev/.
@@ -1563,8 +1806,6 @@ mod tests {
 
     use super::*;
     use crate::config::StackedConfig;
-    use crate::content_hash::blake2b_hash;
-    use crate::hex_util;
     use crate::tests::new_temp_dir;
 
     const GIT_USER: &str = "Someone";
@@ -2100,6 +2341,140 @@ mod tests {
         );
     }
 
+    #[test]
+    fn round_trip_copies() {
+        let settings = user_settings();
+        let temp_dir = new_temp_dir();
+        let store_path = temp_dir.path();
+        let git_repo_path = temp_dir.path().join("git");
+        let git_repo = git_init(&git_repo_path);
+
+        let backend = GitBackend::init_external(&settings, store_path, git_repo.path()).unwrap();
+        let file_id = backend
+            .write_file(RepoPath::root(), &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+
+        let source = CopyHistory {
+            current_path: RepoPathBuf::from_internal_string("source").unwrap(),
+            parents: vec![],
+            salt: vec![],
+        };
+        let source_id = backend.write_copy(&source).block_on().unwrap();
+        let target = CopyHistory {
+            current_path: RepoPathBuf::from_internal_string("target").unwrap(),
+            parents: vec![source_id.clone()],
+            salt: vec![],
+        };
+        let target_id = backend.write_copy(&target).block_on().unwrap();
+        assert_eq!(backend.read_copy(&target_id).block_on().unwrap(), target);
+        assert_eq!(
+            backend.get_related_copies(&source_id).block_on().unwrap(),
+            vec![target.clone(), source.clone()]
+        );
+
+        // Unrelated copy histories aren't returned.
+        let other = CopyHistory {
+            current_path: RepoPathBuf::from_internal_string("other").unwrap(),
+            parents: vec![],
+            salt: vec![],
+        };
+        backend.write_copy(&other).block_on().unwrap();
+        assert_eq!(
+            backend.get_related_copies(&target_id).block_on().unwrap(),
+            vec![target.clone(), source.clone()]
+        );
+
+        // The copy IDs aren't part of the Git tree, so trees written with and
+        // without them share the Git tree but have different IDs.
+        let file_value = |copy_id: CopyId| TreeValue::File {
+            id: file_id.clone(),
+            executable: false,
+            copy_id,
+        };
+        let tree_with_copy_id = |copy_id: CopyId| {
+            Tree::from_sorted_entries(vec![
+                (
+                    RepoPathComponentBuf::new("other").unwrap(),
+                    file_value(CopyId::placeholder()),
+                ),
+                (
+                    RepoPathComponentBuf::new("target").unwrap(),
+                    file_value(copy_id),
+                ),
+            ])
+        };
+        let plain_tree = tree_with_copy_id(CopyId::placeholder());
+        let tree = tree_with_copy_id(target_id);
+        let plain_tree_id = backend
+            .write_tree(RepoPath::root(), &plain_tree)
+            .block_on()
+            .unwrap();
+        let tree_id = backend
+            .write_tree(RepoPath::root(), &tree)
+            .block_on()
+            .unwrap();
+        assert_ne!(tree_id, plain_tree_id);
+        assert_eq!(
+            to_git_tree_id(&tree_id).unwrap(),
+            to_git_tree_id(&plain_tree_id).unwrap()
+        );
+        assert_eq!(
+            backend
+                .read_tree(RepoPath::root(), &plain_tree_id)
+                .block_on()
+                .unwrap(),
+            plain_tree
+        );
+        assert_eq!(
+            backend
+                .read_tree(RepoPath::root(), &tree_id)
+                .block_on()
+                .unwrap(),
+            tree
+        );
+
+        // The IDs of subtrees with copy IDs are kept in the parent tree.
+        let root_tree = Tree::from_sorted_entries(vec![(
+            RepoPathComponentBuf::new("dir").unwrap(),
+            TreeValue::Tree(tree_id.clone()),
+        )]);
+        let root_tree_id = backend
+            .write_tree(RepoPath::root(), &root_tree)
+            .block_on()
+            .unwrap();
+        assert_eq!(
+            backend
+                .read_tree(RepoPath::root(), &root_tree_id)
+                .block_on()
+                .unwrap(),
+            root_tree
+        );
+
+        // The root tree ID is stored in the commit's extra metadata.
+        let commit = Commit {
+            parents: vec![backend.root_commit_id().clone()],
+            predecessors: vec![],
+            root_tree: Merge::resolved(root_tree_id.clone()),
+            conflict_labels: Merge::resolved(String::new()),
+            change_id: ChangeId::from_hex("abc123"),
+            description: "".to_string(),
+            author: create_signature(),
+            committer: create_signature(),
+            secure_sig: None,
+        };
+        let (commit_id, _) = backend.write_commit(commit, None).block_on().unwrap();
+        let commit = backend.read_commit(&commit_id).block_on().unwrap();
+        assert_eq!(commit.root_tree, Merge::resolved(root_tree_id.clone()));
+        let git_commit = git_repo
+            .find_commit(gix::ObjectId::from_bytes_or_panic(commit_id.as_bytes()))
+            .unwrap();
+        assert_eq!(
+            git_commit.tree_id().unwrap().detach(),
+            to_git_tree_id(&root_tree_id).unwrap()
+        );
+    }
+
     #[test]
     fn write_tree_conflicts() {
         let settings = user_settings();
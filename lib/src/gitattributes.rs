@@ -0,0 +1,411 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-path attributes read from `.gitattributes` files.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::iter;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use ignore::gitignore;
+use tokio::io::AsyncReadExt as _;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::TreeValue;
+use crate::file_util::PathError;
+use crate::merged_tree::MergedTree;
+use crate::object_id::ObjectId as _;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::repo_path::RepoPathComponent;
+
+/// File name of the attributes file in each directory.
+pub const GIT_ATTRIBUTES_FILE_NAME: &str = ".gitattributes";
+
+/// State of an attribute for a path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeState {
+    /// The attribute is set, e.g. `text`.
+    Set,
+    /// The attribute is unset, e.g. `-text`.
+    Unset,
+    /// The attribute is set to a value, e.g. `eol=crlf`.
+    Value(String),
+}
+
+/// Attributes which apply to a single path.
+///
+/// Attributes which aren't listed are unspecified.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PathAttributes {
+    states: HashMap<String, AttributeState>,
+}
+
+impl PathAttributes {
+    /// Returns the state of the attribute `name`, or `None` if unspecified.
+    pub fn get(&self, name: &str) -> Option<&AttributeState> {
+        self.states.get(name)
+    }
+
+    /// Returns the value of the attribute `name` if it is set to a value.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(AttributeState::Value(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the attribute `name` is explicitly unset.
+    pub fn is_unset(&self, name: &str) -> bool {
+        self.get(name) == Some(&AttributeState::Unset)
+    }
+
+    fn apply(&mut self, name: &str, state: Option<&AttributeState>) {
+        match state {
+            Some(state) => {
+                self.states.insert(name.to_owned(), state.clone());
+            }
+            None => {
+                self.states.remove(name);
+            }
+        }
+    }
+}
+
+/// A single line of a `.gitattributes` file.
+#[derive(Debug)]
+struct AttributesLine {
+    matcher: gitignore::Gitignore,
+    /// Attributes in the order they appear. `None` means unspecified (`!attr`).
+    attributes: Vec<(String, Option<AttributeState>)>,
+}
+
+/// Models the effective contents of multiple `.gitattributes` files.
+///
+/// Like `.gitignore` files, files in subdirectories take precedence over
+/// files in parent directories, and later lines take precedence over earlier
+/// lines.
+#[derive(Debug)]
+pub struct GitAttributesFile {
+    parent: Option<Arc<Self>>,
+    lines: Vec<AttributesLine>,
+}
+
+impl GitAttributesFile {
+    /// Returns an empty set of attributes.
+    pub fn empty() -> Arc<Self> {
+        Arc::new(Self {
+            parent: None,
+            lines: vec![],
+        })
+    }
+
+    /// Concatenates new `.gitattributes` content at the `prefix` directory.
+    ///
+    /// The `prefix` should be a slash-separated path relative to the workspace
+    /// root. Lines which can't be parsed are ignored, as Git does.
+    pub fn chain(self: &Arc<Self>, prefix: &str, input: &[u8]) -> Arc<Self> {
+        let lines = input
+            .split(|b| *b == b'\n')
+            .filter_map(|line| str::from_utf8(line).ok())
+            .filter_map(|line| parse_line(prefix, line))
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            return self.clone();
+        }
+        let parent = if self.lines.is_empty() {
+            self.parent.clone() // omit the empty root
+        } else {
+            Some(self.clone())
+        };
+        Arc::new(Self { parent, lines })
+    }
+
+    /// Returns the attributes which apply to the file at `path`.
+    ///
+    /// The `path` should be a slash-separated path relative to the workspace
+    /// root.
+    pub fn attributes(&self, path: &str) -> PathAttributes {
+        let files = iter::successors(Some(self), |file| file.parent.as_deref()).collect::<Vec<_>>();
+        let mut attributes = PathAttributes::default();
+        for file in files.iter().rev() {
+            for line in &file.lines {
+                if line.matcher.matched(path, false).is_ignore() {
+                    for (name, state) in &line.attributes {
+                        attributes.apply(name, state.as_ref());
+                    }
+                }
+            }
+        }
+        attributes
+    }
+}
+
+fn parse_line(prefix: &str, line: &str) -> Option<AttributesLine> {
+    let line = line.trim_matches([' ', '\t', '\r']);
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (pattern, rest) = split_pattern(line)?;
+    // Negative patterns are forbidden, and macro definitions are only
+    // recognized in the top-level file by Git. We don't support either.
+    if pattern.starts_with('!') || pattern.starts_with("[attr]") {
+        return None;
+    }
+    let mut builder = gitignore::GitignoreBuilder::new(prefix);
+    builder.add_line(None, &pattern).ok()?;
+    let matcher = builder.build().ok()?;
+    let mut attributes = vec![];
+    for word in rest.split([' ', '\t']).filter(|word| !word.is_empty()) {
+        let (name, state) = if let Some(name) = word.strip_prefix('-') {
+            (name, Some(AttributeState::Unset))
+        } else if let Some(name) = word.strip_prefix('!') {
+            (name, None)
+        } else if let Some((name, value)) = word.split_once('=') {
+            (name, Some(AttributeState::Value(value.to_owned())))
+        } else {
+            (word, Some(AttributeState::Set))
+        };
+        if name == "binary" && state == Some(AttributeState::Set) {
+            // The built-in "binary" macro.
+            for name in ["diff", "merge", "text"] {
+                attributes.push((name.to_owned(), Some(AttributeState::Unset)));
+            }
+        }
+        attributes.push((name.to_owned(), state));
+    }
+    Some(AttributesLine {
+        matcher,
+        attributes,
+    })
+}
+
+/// Splits the pattern from the attributes, unquoting the pattern if needed.
+fn split_pattern(line: &str) -> Option<(String, &str)> {
+    if let Some(quoted) = line.strip_prefix('"') {
+        let mut pattern = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Some((pattern, &quoted[i + 1..])),
+                '\\' => match chars.next()?.1 {
+                    'n' => pattern.push('\n'),
+                    't' => pattern.push('\t'),
+                    c => pattern.push(c),
+                },
+                c => pattern.push(c),
+            }
+        }
+        None
+    } else {
+        let (pattern, rest) = line.split_once([' ', '\t']).unwrap_or((line, ""));
+        Some((pattern.to_owned(), rest))
+    }
+}
+
+/// Looks up attributes from the `.gitattributes` files in a tree or in the
+/// working copy.
+///
+/// Attribute files are loaded lazily and cached per directory. Conflicted
+/// attribute files are ignored.
+#[derive(Debug)]
+pub struct GitAttributes {
+    source: AttributesSource,
+    dirs: Mutex<HashMap<RepoPathBuf, Arc<GitAttributesFile>>>,
+}
+
+#[derive(Debug)]
+enum AttributesSource {
+    Tree(MergedTree),
+    Disk(PathBuf),
+}
+
+impl GitAttributes {
+    /// Creates attributes lookup for the given `tree`.
+    pub fn new(tree: MergedTree) -> Self {
+        Self {
+            source: AttributesSource::Tree(tree),
+            dirs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates attributes lookup for the files on disk under the
+    /// `working_copy_path` directory.
+    pub fn from_working_copy(working_copy_path: PathBuf) -> Self {
+        Self {
+            source: AttributesSource::Disk(working_copy_path),
+            dirs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the attributes which apply to the file at `path`.
+    pub async fn get(&self, path: &RepoPath) -> BackendResult<PathAttributes> {
+        let Some(dir) = path.parent() else {
+            return Ok(PathAttributes::default());
+        };
+        let file = self.file_for_dir(dir).await?;
+        Ok(file.attributes(path.as_internal_file_string()))
+    }
+
+    async fn file_for_dir(&self, dir: &RepoPath) -> BackendResult<Arc<GitAttributesFile>> {
+        if let Some(file) = self.dirs.lock().unwrap().get(dir) {
+            return Ok(file.clone());
+        }
+        let mut file = GitAttributesFile::empty();
+        let ancestors = dir.ancestors().collect::<Vec<_>>();
+        for ancestor in ancestors.into_iter().rev() {
+            if let Some(cached) = self.dirs.lock().unwrap().get(ancestor) {
+                file = cached.clone();
+                continue;
+            }
+            if let Some(content) = self.read_attributes_file(ancestor).await? {
+                file = file.chain(&ancestor.to_internal_dir_string(), &content);
+            }
+            self.dirs
+                .lock()
+                .unwrap()
+                .insert(ancestor.to_owned(), file.clone());
+        }
+        Ok(file)
+    }
+
+    async fn read_attributes_file(&self, dir: &RepoPath) -> BackendResult<Option<Vec<u8>>> {
+        let name = RepoPathComponent::new(GIT_ATTRIBUTES_FILE_NAME).unwrap();
+        let path = dir.join(name);
+        let tree = match &self.source {
+            AttributesSource::Tree(tree) => tree,
+            AttributesSource::Disk(working_copy_path) => {
+                return read_attributes_file_from_disk(&path, working_copy_path);
+            }
+        };
+        let value = tree.path_value_async(&path).await?;
+        let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+            return Ok(None);
+        };
+        let mut content = vec![];
+        let mut reader = tree.store().read_file(&path, id).await?;
+        reader
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| BackendError::ReadObject {
+                object_type: id.object_type(),
+                hash: id.hex(),
+                source: err.into(),
+            })?;
+        Ok(Some(content))
+    }
+}
+
+fn read_attributes_file_from_disk(
+    path: &RepoPath,
+    working_copy_path: &Path,
+) -> BackendResult<Option<Vec<u8>>> {
+    let Ok(disk_path) = path.to_fs_path(working_copy_path) else {
+        return Ok(None);
+    };
+    match fs::read(&disk_path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound || disk_path.is_dir() => Ok(None),
+        Err(source) => Err(BackendError::Other(
+            PathError {
+                path: disk_path,
+                source,
+            }
+            .into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(input: &str, path: &str) -> PathAttributes {
+        GitAttributesFile::empty()
+            .chain("", input.as_bytes())
+            .attributes(path)
+    }
+
+    #[test]
+    fn test_gitattributes_states() {
+        let attrs = attributes("*.txt text -diff eol=crlf !merge\n", "foo.txt");
+        assert_eq!(attrs.get("text"), Some(&AttributeState::Set));
+        assert_eq!(attrs.get("diff"), Some(&AttributeState::Unset));
+        assert_eq!(attrs.value("eol"), Some("crlf"));
+        assert_eq!(attrs.get("merge"), None);
+        assert_eq!(
+            attributes("*.txt text\n", "foo.rs"),
+            PathAttributes::default()
+        );
+    }
+
+    #[test]
+    fn test_gitattributes_later_line_overrides() {
+        let input = "* text=auto\n*.bat eol=crlf\n*.png binary\n*.bat -text\n";
+        let attrs = attributes(input, "dir/run.bat");
+        assert!(attrs.is_unset("text"));
+        assert_eq!(attrs.value("eol"), Some("crlf"));
+        let attrs = attributes(input, "img.png");
+        assert!(attrs.is_unset("text"));
+        assert!(attrs.is_unset("diff"));
+        assert!(attrs.is_unset("merge"));
+        assert_eq!(attrs.get("binary"), Some(&AttributeState::Set));
+        let attrs = attributes(input, "src/lib.rs");
+        assert_eq!(attrs.value("text"), Some("auto"));
+    }
+
+    #[test]
+    fn test_gitattributes_unspecify() {
+        let input = "* -text\nfoo !text\n";
+        assert_eq!(attributes(input, "foo").get("text"), None);
+        assert!(attributes(input, "bar").is_unset("text"));
+    }
+
+    #[test]
+    fn test_gitattributes_chained() {
+        let base = GitAttributesFile::empty().chain("", b"*.txt text\n/top.md -text\n");
+        assert!(base.attributes("top.md").is_unset("text"));
+        let child = base.chain("dir/", b"*.txt -text\ntop.md eol=lf\n");
+        // Files in subdirectories take precedence.
+        assert!(child.attributes("dir/a.txt").is_unset("text"));
+        // Anchored patterns are relative to the directory of the file.
+        assert_eq!(child.attributes("dir/top.md").get("text"), None);
+        assert_eq!(child.attributes("dir/top.md").value("eol"), Some("lf"));
+        // Patterns without a slash match at any level.
+        assert_eq!(child.attributes("dir/sub/top.md").value("eol"), Some("lf"));
+    }
+
+    #[test]
+    fn test_gitattributes_ignored_lines() {
+        let input = "# comment\n\n!foo text\n[attr]mine text\n\"a b.txt\" -text\n";
+        let file = GitAttributesFile::empty().chain("", input.as_bytes());
+        assert!(file.attributes("a b.txt").is_unset("text"));
+        assert_eq!(file.attributes("foo"), PathAttributes::default());
+        assert_eq!(file.attributes("[attr]mine"), PathAttributes::default());
+    }
+
+    #[test]
+    fn test_gitattributes_directory_patterns() {
+        // Attributes of a directory don't apply to the files in it.
+        let input = "dir -text\nother/** -text\n";
+        assert_eq!(attributes(input, "dir/file").get("text"), None);
+        assert!(attributes(input, "other/file").is_unset("text"));
+    }
+}
@@ -68,6 +68,7 @@ use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::config::ConfigGetError;
+use crate::config::TREE_CONFIG_PATH;
 use crate::conflict_labels::ConflictLabels;
 use crate::conflicts;
 use crate::conflicts::ConflictMarkerStyle;
@@ -90,6 +91,8 @@ use crate::fsmonitor::FsmonitorSettings;
 use crate::fsmonitor::WatchmanConfig;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
+use crate::gitattributes::GitAttributes;
+use crate::gitattributes::PathAttributes;
 use crate::gitignore::GitIgnoreFile;
 use crate::lock::FileLock;
 use crate::matchers::DifferenceMatcher;
@@ -1300,6 +1303,7 @@ impl TreeState {
             let snapshotter = FileSnapshotter {
                 tree_state: self,
                 current_tree: &self.tree,
+                git_attributes: GitAttributes::from_working_copy(self.working_copy_path.clone()),
                 matcher: &matcher,
                 start_tracking_matcher,
                 force_tracking_matcher,
@@ -1470,6 +1474,7 @@ struct PresentDirEntries {
 struct FileSnapshotter<'a> {
     tree_state: &'a TreeState,
     current_tree: &'a MergedTree,
+    git_attributes: GitAttributes,
     matcher: &'a dyn Matcher,
     start_tracking_matcher: &'a dyn Matcher,
     force_tracking_matcher: &'a dyn Matcher,
@@ -1761,6 +1766,8 @@ impl FileSnapshotter<'_> {
                 PresentDirEntryKind::File => !present_entries.files.contains(name),
             })
             .flat_map(|(_, chunk)| chunk)
+            // The tree config file isn't materialized, so it can't be deleted
+            .filter(|(path, _)| path.as_internal_file_string() != TREE_CONFIG_PATH)
             // Whether or not the entry exists, submodule should be ignored
             .filter(|(_, state)| state.file_type != FileType::GitSubmodule)
             .filter(|(path, _)| self.matcher.matches(path))
@@ -1840,8 +1847,11 @@ impl FileSnapshotter<'_> {
         exec_bit: ExecBit,
         materialized_conflict_data: Option<MaterializedConflictData>,
     ) -> Result<MergedTreeValue, SnapshotError> {
+        let attributes = self.git_attributes.get(repo_path).await?;
         if let Some(current_tree_value) = current_tree_values.as_resolved() {
-            let id = self.write_file_to_store(repo_path, disk_path).await?;
+            let id = self
+                .write_file_to_store(repo_path, disk_path, &attributes)
+                .await?;
             // On Windows, we preserve the executable bit from the current tree.
             let executable = exec_bit.for_tree_value(self.tree_state.exec_policy, || {
                 if let Some(TreeValue::File {
@@ -1888,7 +1898,7 @@ impl FileSnapshotter<'_> {
             })?;
             self.tree_state
                 .target_eol_strategy
-                .convert_eol_for_snapshot(BlockingAsyncReader::new(file))
+                .convert_eol_for_snapshot(&attributes, BlockingAsyncReader::new(file))
                 .await
                 .map_err(|err| SnapshotError::Other {
                     message: "Failed to convert the EOL".to_string(),
@@ -1945,6 +1955,7 @@ impl FileSnapshotter<'_> {
         &self,
         path: &RepoPath,
         disk_path: &Path,
+        attributes: &PathAttributes,
     ) -> Result<FileId, SnapshotError> {
         let file = File::open(disk_path).map_err(|err| SnapshotError::Other {
             message: format!("Failed to open file {}", disk_path.display()),
@@ -1953,7 +1964,7 @@ impl FileSnapshotter<'_> {
         let mut contents = self
             .tree_state
             .target_eol_strategy
-            .convert_eol_for_snapshot(BlockingAsyncReader::new(file))
+            .convert_eol_for_snapshot(attributes, BlockingAsyncReader::new(file))
             .await
             .map_err(|err| SnapshotError::Other {
                 message: "Failed to convert the EOL".to_string(),
@@ -2006,7 +2017,7 @@ impl TreeState {
         disk_path: &Path,
         contents: impl AsyncRead + Send + Unpin,
         exec_bit: ExecBit,
-        apply_eol_conversion: bool,
+        eol_attributes: Option<&PathAttributes>,
     ) -> Result<FileState, CheckoutError> {
         let mut file = File::options()
             .write(true)
@@ -2016,9 +2027,9 @@ impl TreeState {
                 message: format!("Failed to open file {} for writing", disk_path.display()),
                 err: err.into(),
             })?;
-        let contents = if apply_eol_conversion {
+        let contents = if let Some(attributes) = eol_attributes {
             self.target_eol_strategy
-                .convert_eol_for_update(contents)
+                .convert_eol_for_update(attributes, contents)
                 .await
                 .map_err(|err| CheckoutError::Other {
                     message: "Failed to convert the EOL for the content".to_string(),
@@ -2092,10 +2103,11 @@ impl TreeState {
         disk_path: &Path,
         contents: &[u8],
         exec_bit: ExecBit,
+        attributes: &PathAttributes,
     ) -> Result<FileState, CheckoutError> {
         let contents = self
             .target_eol_strategy
-            .convert_eol_for_update(contents)
+            .convert_eol_for_update(attributes, contents)
             .await
             .map_err(|err| CheckoutError::Other {
                 message: "Failed to convert the EOL when writing a merge conflict".to_string(),
@@ -2178,11 +2190,25 @@ impl TreeState {
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
         let mut prev_created_path: RepoPathBuf = RepoPathBuf::root();
+        let git_attributes = GitAttributes::new(new_tree.clone());
 
         let mut process_diff_entry = async |path: RepoPathBuf,
                                             before: MergedTreeValue,
                                             after: MaterializedTreeValue|
                -> Result<(), CheckoutError> {
+            // The tree config file is kept in the tree, but it isn't
+            // materialized since it would be written to the reserved .jj
+            // directory. It isn't counted in the stats, since the working
+            // copy doesn't change.
+            if path.as_internal_file_string() == TREE_CONFIG_PATH {
+                if after.is_absent() {
+                    deleted_files.insert(path);
+                } else {
+                    changed_file_states.push((path, FileState::placeholder()));
+                }
+                return Ok(());
+            }
+
             if after.is_absent() {
                 stats.removed_files += 1;
             } else if before.is_absent() {
@@ -2292,7 +2318,8 @@ impl TreeState {
                 MaterializedTreeValue::File(file) => {
                     let exec_bit =
                         ExecBit::new_from_repo(file.executable, self.exec_policy, get_prev_exec);
-                    self.write_file(&disk_path, file.reader, exec_bit, true)
+                    let attributes = git_attributes.get(&path).await?;
+                    self.write_file(&disk_path, file.reader, exec_bit, Some(&attributes))
                         .await?
                 }
                 MaterializedTreeValue::Symlink { id: _, target } => {
@@ -2300,7 +2327,7 @@ impl TreeState {
                         self.write_symlink(&disk_path, target)?
                     } else {
                         // The fake symlink file shouldn't be executable.
-                        self.write_file(&disk_path, target.as_bytes(), ExecBit(false), false)
+                        self.write_file(&disk_path, target.as_bytes(), ExecBit(false), None)
                             .await?
                     }
                 }
@@ -2326,8 +2353,10 @@ impl TreeState {
                     );
                     let contents =
                         materialize_merge_result_to_bytes(&file.contents, &file.labels, &options);
-                    let mut file_state =
-                        self.write_conflict(&disk_path, &contents, exec_bit).await?;
+                    let attributes = git_attributes.get(&path).await?;
+                    let mut file_state = self
+                        .write_conflict(&disk_path, &contents, exec_bit, &attributes)
+                        .await?;
                     file_state.materialized_conflict_data = Some(MaterializedConflictData {
                         conflict_marker_len: conflict_marker_len.try_into().unwrap_or(u32::MAX),
                     });
@@ -2338,8 +2367,14 @@ impl TreeState {
                     // better than trying to describe the merge.
                     let contents = id.describe(&labels);
                     // Since this is a dummy file, it shouldn't be executable.
-                    self.write_conflict(&disk_path, contents.as_bytes(), ExecBit(false))
-                        .await?
+                    let attributes = git_attributes.get(&path).await?;
+                    self.write_conflict(
+                        &disk_path,
+                        contents.as_bytes(),
+                        ExecBit(false),
+                        &attributes,
+                    )
+                    .await?
                 }
             };
             changed_file_states.push((path, file_state));
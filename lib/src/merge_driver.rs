@@ -0,0 +1,511 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merge drivers which resolve file conflicts without user interaction.
+
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use bstr::BString;
+use futures::channel::oneshot;
+use itertools::Itertools as _;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config::ConfigGetError;
+use crate::files;
+use crate::files::MergeStrategy;
+use crate::fileset;
+use crate::gitattributes::PathAttributes;
+use crate::matchers::Matcher;
+use crate::matchers::NothingMatcher;
+use crate::merge::Merge;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::settings::UserSettings;
+use crate::tree_merge::MergeOptions;
+
+/// Error that may occur while running a merge driver.
+#[derive(Debug, Error)]
+pub enum MergeDriverError {
+    /// Failed to set up the input files or to start the program.
+    #[error("Failed to run merge driver {name}")]
+    Io {
+        /// Name of the driver.
+        name: String,
+        /// Source error.
+        #[source]
+        source: io::Error,
+    },
+    /// The program exited with non-zero status.
+    #[error("Merge driver {name} exited with {status}")]
+    ExitStatus {
+        /// Name of the driver.
+        name: String,
+        /// Exit status of the program.
+        status: std::process::ExitStatus,
+    },
+}
+
+/// Merge driver error recorded while merging trees. The file is left
+/// conflicted.
+#[derive(Debug)]
+pub struct MergeDriverFailure {
+    /// Path of the file which couldn't be merged.
+    pub path: RepoPathBuf,
+    /// Error returned by the driver.
+    pub error: MergeDriverError,
+}
+
+/// Pool of threads running external merge drivers. The number of programs
+/// running concurrently is limited to the number of threads, which defaults to
+/// the number of CPUs.
+static DRIVER_THREAD_POOL: LazyLock<Option<rayon::ThreadPool>> = LazyLock::new(|| {
+    rayon::ThreadPoolBuilder::new()
+        .thread_name(|i| format!("jj-merge-driver-{i}"))
+        .build()
+        .inspect_err(|err| tracing::warn!(?err, "failed to create merge driver thread pool"))
+        .ok()
+});
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RawMergeDriverConfig {
+    command: Option<Vec<String>>,
+    builtin: Option<MergeStrategy>,
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+enum MergeDriverKind {
+    External(Vec<String>),
+    Builtin(MergeStrategy),
+}
+
+/// Merges file contents by an external program or a built-in strategy.
+#[derive(Clone, Debug)]
+pub struct MergeDriver {
+    name: String,
+    kind: MergeDriverKind,
+    matcher: Arc<dyn Matcher>,
+}
+
+impl MergeDriver {
+    /// Name of the driver as configured in `merge-drivers.<name>`, or the
+    /// name of the built-in strategy.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns true if the driver runs an external program.
+    pub fn is_external(&self) -> bool {
+        matches!(self.kind, MergeDriverKind::External(_))
+    }
+
+    /// Merges the `contents` of the file at `path`.
+    ///
+    /// Returns `Ok(None)` if the contents can't be merged. External programs
+    /// only support 2-sided conflicts, so conflicts with more sides are merged
+    /// by the standard algorithm instead.
+    pub async fn merge(
+        &self,
+        path: &RepoPath,
+        contents: &Merge<Vec<u8>>,
+        options: &MergeOptions,
+    ) -> Result<Option<BString>, MergeDriverError> {
+        match &self.kind {
+            MergeDriverKind::External(command) => match contents.as_slice() {
+                [left, base, right] => {
+                    let Some(pool) = DRIVER_THREAD_POOL.as_ref() else {
+                        let merged = run_external(&self.name, command, path, base, left, right)?;
+                        return Ok(Some(merged.into()));
+                    };
+                    // Run the program in the thread pool so other files can be
+                    // merged concurrently.
+                    let (sender, receiver) = oneshot::channel();
+                    let name = self.name.clone();
+                    let command = command.clone();
+                    let path = path.to_owned();
+                    let [base, left, right] = [base, left, right].map(|content| content.clone());
+                    pool.spawn(move || {
+                        let result = run_external(&name, &command, &path, &base, &left, &right);
+                        sender.send(result).ok();
+                    });
+                    let merged = receiver
+                        .await
+                        .expect("merge driver thread shouldn't panic")?;
+                    Ok(Some(merged.into()))
+                }
+                _ => Ok(files::try_merge(contents, options)),
+            },
+            MergeDriverKind::Builtin(strategy) => {
+                Ok(files::try_merge_with_strategy(contents, options, *strategy))
+            }
+        }
+    }
+}
+
+/// Merges the `left` and `right` contents by the external `command`.
+///
+/// The program is run in a temporary directory. Variables `$base`,
+/// `$left`, `$right`, and `$output` in the command arguments are
+/// substituted with paths to the input and output files, and `$path` with
+/// the repository path of the merged file. The output file initially has
+/// the `left` contents, so programs that update the left side in place
+/// can use `$left` and `$output` interchangeably. The merged contents are
+/// read from the output file if the program exits successfully.
+fn run_external(
+    name: &str,
+    command: &[String],
+    path: &RepoPath,
+    base: &[u8],
+    left: &[u8],
+    right: &[u8],
+) -> Result<Vec<u8>, MergeDriverError> {
+    let io_error = |source| MergeDriverError::Io {
+        name: name.to_owned(),
+        source,
+    };
+    let temp_dir = tempfile::Builder::new()
+        .prefix("jj-merge-driver-")
+        .tempdir()
+        .map_err(io_error)?;
+    // Keep the file name so the program can detect the file type from it.
+    let file_name = path
+        .split()
+        .map_or("file", |(_, name)| name.as_internal_str());
+    let write_input = |role: &str, content: &[u8]| -> io::Result<_> {
+        let dir = temp_dir.path().join(role);
+        fs::create_dir(&dir)?;
+        let file_path = dir.join(file_name);
+        fs::write(&file_path, content)?;
+        Ok(file_path)
+    };
+    let base_path = write_input("base", base).map_err(io_error)?;
+    let left_path = write_input("left", left).map_err(io_error)?;
+    let right_path = write_input("right", right).map_err(io_error)?;
+    let output_path = write_input("output", left).map_err(io_error)?;
+
+    let substitute = |arg: &str| {
+        arg.replace("$base", &path_to_string(&base_path))
+            .replace("$left", &path_to_string(&left_path))
+            .replace("$right", &path_to_string(&right_path))
+            .replace("$output", &path_to_string(&output_path))
+            .replace("$path", path.as_internal_file_string())
+    };
+    let (program, args) = command.split_first().expect("command shouldn't be empty");
+    tracing::info!(name, ?path, "running merge driver");
+    let status = Command::new(substitute(program))
+        .args(args.iter().map(|arg| substitute(arg)))
+        .current_dir(temp_dir.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        // Let the user see why the program failed.
+        .stderr(Stdio::inherit())
+        .status()
+        .map_err(io_error)?;
+    if !status.success() {
+        return Err(MergeDriverError::ExitStatus {
+            name: name.to_owned(),
+            status,
+        });
+    }
+    fs::read(&output_path).map_err(io_error)
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// Merge drivers configured by the `merge-drivers` table, and the built-in
+/// strategies.
+#[derive(Clone, Debug)]
+pub struct MergeDrivers {
+    drivers: Vec<MergeDriver>,
+    failures: Arc<Mutex<Vec<MergeDriverFailure>>>,
+}
+
+impl Default for MergeDrivers {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl MergeDrivers {
+    fn new(mut drivers: Vec<MergeDriver>) -> Self {
+        // Built-in strategies can be selected by the merge attribute unless
+        // shadowed by a configured driver.
+        for strategy in MergeStrategy::ALL {
+            if drivers.iter().all(|driver| driver.name != strategy.name()) {
+                drivers.push(MergeDriver {
+                    name: strategy.name().to_owned(),
+                    kind: MergeDriverKind::Builtin(strategy),
+                    matcher: Arc::new(NothingMatcher),
+                });
+            }
+        }
+        Self {
+            drivers,
+            failures: Arc::default(),
+        }
+    }
+
+    /// Loads merge drivers from `settings`.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let drivers = settings
+            .table_keys("merge-drivers")
+            // Sort keys so the first matching driver is deterministic.
+            .sorted()
+            .map(|name| {
+                let config: RawMergeDriverConfig = settings.get(["merge-drivers", name])?;
+                let config_error = |key: &str, error: &str| ConfigGetError::Type {
+                    name: format!("merge-drivers.{name}.{key}"),
+                    error: error.into(),
+                    source_path: None,
+                };
+                let kind = match (config.command, config.builtin) {
+                    (Some(command), None) if command.is_empty() => {
+                        return Err(config_error("command", "Command must not be empty"));
+                    }
+                    (Some(command), None) => MergeDriverKind::External(command),
+                    (None, Some(strategy)) => MergeDriverKind::Builtin(strategy),
+                    (Some(_), Some(_)) => {
+                        return Err(config_error(
+                            "builtin",
+                            "Only one of `command` and `builtin` can be set",
+                        ));
+                    }
+                    (None, None) => {
+                        return Err(config_error(
+                            "command",
+                            "Either `command` or `builtin` must be set",
+                        ));
+                    }
+                };
+                let matcher =
+                    parse_patterns(&config.patterns).map_err(|err| ConfigGetError::Type {
+                        name: format!("merge-drivers.{name}.patterns"),
+                        error: err.into(),
+                        source_path: None,
+                    })?;
+                Ok(MergeDriver {
+                    name: name.to_owned(),
+                    kind,
+                    matcher,
+                })
+            })
+            .try_collect()?;
+        Ok(Self::new(drivers))
+    }
+
+    /// Looks up the merge driver for the file at `path`.
+    ///
+    /// The driver named by the `merge` attribute takes precedence over drivers
+    /// selected by patterns.
+    pub fn find(&self, path: &RepoPath, attributes: &PathAttributes) -> Option<&MergeDriver> {
+        if let Some(name) = attributes.value("merge")
+            && let Some(driver) = self.drivers.iter().find(|driver| driver.name == name)
+        {
+            return Some(driver);
+        }
+        self.drivers
+            .iter()
+            .find(|driver| driver.matcher.matches(path))
+    }
+
+    /// Records that the driver failed to merge the file at `path`.
+    pub(crate) fn record_failure(&self, path: &RepoPath, error: MergeDriverError) {
+        self.failures.lock().unwrap().push(MergeDriverFailure {
+            path: path.to_owned(),
+            error,
+        });
+    }
+
+    /// Takes the failures recorded since the last call. Files which the
+    /// drivers failed to merge are left conflicted, so the caller should let
+    /// the user know why.
+    pub fn take_failures(&self) -> Vec<MergeDriverFailure> {
+        mem::take(&mut *self.failures.lock().unwrap())
+    }
+}
+
+fn parse_patterns(patterns: &[String]) -> Result<Arc<dyn Matcher>, fileset::FilesetParseError> {
+    if patterns.is_empty() {
+        return Ok(Arc::new(NothingMatcher));
+    }
+    Ok(fileset::parse_config_patterns(patterns)?
+        .to_matcher()
+        .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::config::ConfigLayer;
+    use crate::config::ConfigSource;
+    use crate::config::StackedConfig;
+    use crate::gitattributes::GitAttributesFile;
+
+    fn drivers_from_config(text: &str) -> Result<MergeDrivers, ConfigGetError> {
+        let mut config = StackedConfig::with_defaults();
+        config.add_layer(ConfigLayer::parse(ConfigSource::User, text).unwrap());
+        let settings = UserSettings::from_config(config).unwrap();
+        MergeDrivers::from_settings(&settings)
+    }
+
+    fn attributes(text: &str, path: &str) -> PathAttributes {
+        GitAttributesFile::empty()
+            .chain("", text.as_bytes())
+            .attributes(path)
+    }
+
+    fn find_name(drivers: &MergeDrivers, path: &str, text: &str) -> Option<String> {
+        let attributes = attributes(text, path);
+        let path = RepoPath::from_internal_string(path).unwrap();
+        drivers
+            .find(path, &attributes)
+            .map(|driver| driver.name().to_owned())
+    }
+
+    #[test]
+    fn test_find_driver() {
+        let drivers = drivers_from_config(
+            r#"
+            [merge-drivers.lock]
+            command = ["lock-merge"]
+            patterns = ["glob:'**/*.lock'"]
+
+            [merge-drivers.json]
+            command = ["json-merge"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(find_name(&drivers, "Cargo.lock", ""), Some("lock".into()));
+        assert_eq!(find_name(&drivers, "dir/a.lock", ""), Some("lock".into()));
+        assert_eq!(find_name(&drivers, "a.json", ""), None);
+        assert_eq!(
+            find_name(&drivers, "a.json", "*.json merge=json"),
+            Some("json".into())
+        );
+        // Attribute takes precedence over patterns
+        assert_eq!(
+            find_name(&drivers, "a.lock", "*.lock merge=json"),
+            Some("json".into())
+        );
+        // Unknown driver name falls back to patterns
+        assert_eq!(
+            find_name(&drivers, "a.lock", "*.lock merge=unknown"),
+            Some("lock".into())
+        );
+    }
+
+    #[test]
+    fn test_find_builtin_driver() {
+        let drivers = drivers_from_config(
+            r#"
+            [merge-drivers.changes]
+            builtin = "union"
+            patterns = ["glob:'changes/*'"]
+
+            [merge-drivers.ours]
+            command = ["my-ours"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(find_name(&drivers, "changes/a", ""), Some("changes".into()));
+        assert_matches!(
+            drivers.find(
+                RepoPath::from_internal_string("changes/a").unwrap(),
+                &PathAttributes::default()
+            ),
+            Some(MergeDriver {
+                kind: MergeDriverKind::Builtin(MergeStrategy::Union),
+                ..
+            })
+        );
+        // Built-in strategies aren't selected by patterns
+        assert_eq!(find_name(&drivers, "union", ""), None);
+        // Built-in strategies can be selected by attribute
+        assert_eq!(
+            find_name(&drivers, "a", "* merge=theirs"),
+            Some("theirs".into())
+        );
+        assert_eq!(
+            find_name(&drivers, "a", "* merge=adjacent-additions"),
+            Some("adjacent-additions".into())
+        );
+        // Configured driver shadows the built-in strategy
+        assert_matches!(
+            drivers.find(
+                RepoPath::from_internal_string("a").unwrap(),
+                &attributes("* merge=ours", "a")
+            ),
+            Some(MergeDriver {
+                kind: MergeDriverKind::External(_),
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let result = drivers_from_config(
+            r#"
+            merge-drivers.foo.command = []
+            "#,
+        );
+        assert!(
+            matches!(result, Err(ConfigGetError::Type { name, .. }) if name == "merge-drivers.foo.command")
+        );
+
+        let result = drivers_from_config(
+            r#"
+            merge-drivers.foo.command = ["foo"]
+            merge-drivers.foo.patterns = ["bad:pattern"]
+            "#,
+        );
+        assert!(
+            matches!(result, Err(ConfigGetError::Type { name, .. }) if name == "merge-drivers.foo.patterns")
+        );
+
+        let result = drivers_from_config(
+            r#"
+            merge-drivers.foo.command = ["foo"]
+            merge-drivers.foo.builtin = "union"
+            "#,
+        );
+        assert!(
+            matches!(result, Err(ConfigGetError::Type { name, .. }) if name == "merge-drivers.foo.builtin")
+        );
+
+        let result = drivers_from_config(
+            r#"
+            merge-drivers.foo.patterns = ["foo"]
+            "#,
+        );
+        assert!(
+            matches!(result, Err(ConfigGetError::Type { name, .. }) if name == "merge-drivers.foo.command")
+        );
+    }
+}
@@ -162,7 +162,17 @@ impl MergedTree {
     /// Tries to resolve any conflicts, resolving any conflicts that can be
     /// automatically resolved and leaving the rest unresolved.
     pub async fn resolve(self) -> BackendResult<Self> {
-        let merged = merge_trees(&self.store, self.tree_ids).await?;
+        self.resolve_inner(false).await
+    }
+
+    /// Like [`Self::resolve()`], but also merges file contents by the
+    /// configured merge drivers which run external programs.
+    pub async fn resolve_with_drivers(self) -> BackendResult<Self> {
+        self.resolve_inner(true).await
+    }
+
+    async fn resolve_inner(self, run_external_drivers: bool) -> BackendResult<Self> {
+        let merged = merge_trees(&self.store, self.tree_ids, run_external_drivers).await?;
         // If the result can be resolved, then `merge_trees()` above would have returned
         // a resolved merge. However, that function will always preserve the arity of
         // conflicts it cannot resolve. So we simplify the conflict again
@@ -174,9 +184,12 @@ impl MergedTree {
         };
         // If debug assertions are enabled, check that the merge was idempotent. In
         // particular, that this last simplification doesn't enable further automatic
-        // resolutions
-        if cfg!(debug_assertions) {
-            let re_merged = merge_trees(&self.store, simplified.clone()).await.unwrap();
+        // resolutions. Merge drivers are external programs, which shouldn't be run
+        // again.
+        if cfg!(debug_assertions) && !run_external_drivers {
+            let re_merged = merge_trees(&self.store, simplified.clone(), false)
+                .await
+                .unwrap();
             debug_assert_eq!(re_merged, simplified);
         }
         Ok(Self {
@@ -327,6 +340,13 @@ impl MergedTree {
         Self::merge_no_resolve(merge).resolve().await
     }
 
+    /// Like [`Self::merge()`], but also merges file contents by the configured
+    /// merge drivers which run external programs. This should be used when the
+    /// merged tree is committed, not when it's only computed for display.
+    pub async fn merge_with_drivers(merge: Merge<(Self, String)>) -> BackendResult<Self> {
+        Self::merge_no_resolve(merge).resolve_with_drivers().await
+    }
+
     /// Merges the provided trees into a single `MergedTree`, without attempting
     /// to resolve file conflicts.
     pub fn merge_no_resolve(merge: Merge<(Self, String)>) -> Self {
@@ -19,6 +19,7 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::fmt::Write as _;
 use std::iter;
 use std::sync::LazyLock;
 use std::time::SystemTime;
@@ -31,6 +32,9 @@ use crate::backend::CommitId;
 use crate::backend::MillisSinceEpoch;
 use crate::backend::Timestamp;
 use crate::content_hash::ContentHash;
+use crate::content_hash::DigestUpdate;
+use crate::content_hash::blake2b_hash;
+use crate::hex_util;
 use crate::merge::Merge;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId as _;
@@ -381,6 +385,9 @@ pub struct Operation {
     // cost matters, maybe this can be changed to sorted Vec.
     #[serde(skip)] // TODO: should be exposed?
     pub commit_predecessors: Option<BTreeMap<CommitId, Vec<CommitId>>>,
+    /// Signature over [`Operation::signed_data()`] made by the signing backend.
+    #[serde(skip)] // raw data wouldn't be useful
+    pub signature: OperationSignature,
 }
 
 impl Operation {
@@ -409,6 +416,55 @@ impl Operation {
             // may be other commits created within the abandoned operations.
             // They don't have any predecessors records as well.
             commit_predecessors: Some(BTreeMap::new()),
+            signature: OperationSignature::default(),
+        }
+    }
+
+    /// Returns the data to be signed by the signing backend.
+    ///
+    /// The data lists the view and parent operation ids, followed by the hash
+    /// of the unsigned operation, which covers the rest of the operation.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: OperationSignature::default(),
+            ..self.clone()
+        };
+        let mut data = String::new();
+        writeln!(data, "view {}", self.view_id.hex()).unwrap();
+        for parent_id in &self.parents {
+            writeln!(data, "parent {}", parent_id.hex()).unwrap();
+        }
+        let hash = hex_util::encode_hex(&blake2b_hash(&unsigned));
+        writeln!(data, "operation {hash}").unwrap();
+        data.into_bytes()
+    }
+}
+
+/// Signature of an [`Operation`], which is empty if the operation isn't
+/// signed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OperationSignature(Vec<u8>);
+
+impl OperationSignature {
+    pub fn new(signature: Vec<u8>) -> Self {
+        Self(signature)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ContentHash for OperationSignature {
+    fn hash(&self, state: &mut impl DigestUpdate) {
+        // Unsigned operations keep the id they had before operations could be
+        // signed.
+        if !self.0.is_empty() {
+            self.0.hash(state);
         }
     }
 }
@@ -24,6 +24,7 @@ use itertools::Itertools as _;
 use pollster::FutureExt as _;
 use thiserror::Error;
 
+use crate::backend::SigningFn;
 use crate::dag_walk;
 use crate::object_id::HexPrefix;
 use crate::object_id::PrefixResolution;
@@ -35,6 +36,7 @@ use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
+use crate::op_store::OperationSignature;
 use crate::operation::Operation;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
@@ -340,6 +342,9 @@ pub struct ReparentStats {
     /// The number of ancestor operations that become unreachable from the
     /// rewritten heads.
     pub unreachable_count: usize,
+    /// The number of rewritten operations that were signed, but whose
+    /// signatures were dropped because they weren't signed again.
+    pub unsigned_count: usize,
 }
 
 /// Reparents the operation range `root_ops..head_ops` onto the `dest_op`.
@@ -356,6 +361,21 @@ pub fn reparent_range(
     root_ops: &[Operation],
     head_ops: &[Operation],
     dest_op: &Operation,
+) -> OpStoreResult<ReparentStats> {
+    reparent_range_with_signer(op_store, root_ops, head_ops, dest_op, None)
+}
+
+/// Like [`reparent_range()`], but signs the rewritten operations again with
+/// `sign` if they were signed.
+///
+/// The signature of an operation covers its parents, so it can't be kept when
+/// the operation is reparented.
+pub fn reparent_range_with_signer(
+    op_store: &dyn OpStore,
+    root_ops: &[Operation],
+    head_ops: &[Operation],
+    dest_op: &Operation,
+    mut sign: Option<&mut SigningFn>,
 ) -> OpStoreResult<ReparentStats> {
     let ops_to_reparent: Vec<_> = walk_ancestors_range(head_ops, root_ops).try_collect()?;
     let unreachable_count = walk_ancestors_range(root_ops, slice::from_ref(dest_op))
@@ -368,6 +388,7 @@ pub fn reparent_range(
         "root operation cannot be rewritten"
     );
     let mut rewritten_ids = HashMap::new();
+    let mut unsigned_count = 0;
     for old_op in ops_to_reparent.into_iter().rev() {
         let mut data = old_op.store_operation().clone();
         let mut dest_once = Some(dest_op.id());
@@ -377,6 +398,17 @@ pub fn reparent_range(
             .filter_map(|id| rewritten_ids.get(id).or_else(|| dest_once.take()))
             .cloned()
             .collect();
+        // The signature doesn't cover the new parents
+        data.signature = Default::default();
+        if old_op.is_signed() {
+            if let Some(sign) = sign.as_mut() {
+                let signature =
+                    sign(&data.signed_data()).map_err(|err| OpStoreError::Other(err.into()))?;
+                data.signature = OperationSignature::new(signature);
+            } else {
+                unsigned_count += 1;
+            }
+        }
         let new_id = op_store.write_operation(&data).block_on()?;
         rewritten_ids.insert(old_op.id().clone(), new_id);
     }
@@ -391,5 +423,6 @@ pub fn reparent_range(
         new_head_ids,
         rewritten_count: rewritten_ids.len(),
         unreachable_count,
+        unsigned_count,
     })
 }
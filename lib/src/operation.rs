@@ -32,6 +32,9 @@ use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
 use crate::op_store::ViewId;
+use crate::signing::SignResult;
+use crate::signing::Signer;
+use crate::signing::Verification;
 use crate::view::View;
 
 /// A wrapper around [`op_store::Operation`] that defines additional methods and
@@ -151,6 +154,23 @@ impl Operation {
         })
     }
 
+    /// A quick way to just check if a signature is present.
+    pub fn is_signed(&self) -> bool {
+        !self.data.signature.is_empty()
+    }
+
+    /// A slow (but cached) way to get the full verification.
+    pub fn verification(&self, signer: &Signer) -> SignResult<Option<Verification>> {
+        if !self.is_signed() {
+            return Ok(None);
+        }
+        let data = self.data.signed_data();
+        let signature = self.data.signature.as_bytes();
+        signer
+            .verify_operation(&self.id, &data, signature)
+            .map(Some)
+    }
+
     pub fn store_operation(&self) -> &op_store::Operation {
         &self.data
     }
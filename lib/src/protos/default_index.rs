@@ -13,3 +13,26 @@ pub struct SegmentControl {
     #[prost(bytes = "vec", repeated, tag = "3")]
     pub changed_path_segment_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
 }
+/// Cached set of commits evaluated for a revset expression.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RevsetCacheEntry {
+    /// Operation at which the set was evaluated.
+    #[prost(bytes = "vec", tag = "1")]
+    pub op_id: ::prost::alloc::vec::Vec<u8>,
+    /// Hash (or file name) of the commit index segment the positions refer to.
+    #[prost(bytes = "vec", tag = "2")]
+    pub commit_segment_id: ::prost::alloc::vec::Vec<u8>,
+    /// Number of commits in the commit index segment and its ancestors.
+    #[prost(uint32, tag = "3")]
+    pub num_commits: u32,
+    /// Whether the set is ancestors of the heads.
+    #[prost(bool, tag = "4")]
+    pub ancestors: bool,
+    /// Heads of the ancestors set.
+    #[prost(bytes = "vec", repeated, tag = "5")]
+    pub heads: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Bit set of the commit positions, starting from the word containing the
+    /// largest positions.
+    #[prost(fixed64, repeated, tag = "6")]
+    pub bitset_words: ::prost::alloc::vec::Vec<u64>,
+}
@@ -18,4 +18,40 @@ pub struct Commit {
     #[deprecated]
     #[prost(bool, tag = "9")]
     pub is_pruned: bool,
+    /// Root trees including the copy IDs of their files, in the same order as
+    /// `root_tree`. Set only if any file has a copy ID, since Git trees can't
+    /// store them.
+    #[prost(bytes = "vec", repeated, tag = "11")]
+    pub root_tree_with_copy_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CopyHistory {
+    #[prost(string, tag = "1")]
+    pub current_path: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub parents: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub salt: ::prost::alloc::vec::Vec<u8>,
+}
+/// Copy IDs of the files in a tree, and IDs of its subtrees containing files
+/// with copy IDs. Git trees can't store them, so the ID of such a tree is the
+/// Git tree ID followed by the hash of this message.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TreeCopyIds {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<tree_copy_ids::Entry>,
+}
+/// Nested message and enum types in `TreeCopyIds`.
+pub mod tree_copy_ids {
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+    pub struct Entry {
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+        /// Set for files.
+        #[prost(bytes = "vec", tag = "2")]
+        pub copy_id: ::prost::alloc::vec::Vec<u8>,
+        /// Set for subtrees.
+        #[prost(bytes = "vec", tag = "3")]
+        pub tree_id: ::prost::alloc::vec::Vec<u8>,
+    }
 }
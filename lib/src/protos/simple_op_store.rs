@@ -163,6 +163,9 @@ pub struct Operation {
     /// Whether or not `commit_predecessors` is recorded.
     #[prost(bool, tag = "5")]
     pub stores_commit_predecessors: bool,
+    /// Signature over the operation, empty if the operation isn't signed.
+    #[prost(bytes = "vec", tag = "6")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
 }
 /// TODO: Share with store.proto? Do we even need the timezone here?
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
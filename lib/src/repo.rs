@@ -47,6 +47,7 @@ use crate::dag_walk;
 use crate::default_index::DefaultIndexStore;
 use crate::default_index::DefaultMutableIndex;
 use crate::default_submodule_store::DefaultSubmoduleStore;
+use crate::evolution::EvolutionGraph;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::index::ChangeIdIndex;
@@ -109,7 +110,6 @@ use crate::store::Store;
 use crate::submodule_store::SubmoduleStore;
 use crate::transaction::Transaction;
 use crate::transaction::TransactionCommitError;
-use crate::tree_merge::MergeOptions;
 use crate::view::RenameWorkspaceError;
 use crate::view::View;
 
@@ -157,6 +157,7 @@ pub struct ReadonlyRepo {
     operation: Operation,
     index: Box<dyn ReadonlyIndex>,
     change_id_index: OnceCell<Box<dyn ChangeIdIndex>>,
+    evolution_graph: OnceCell<Arc<EvolutionGraph>>,
     // TODO: This should eventually become part of the index and not be stored fully in memory.
     view: View,
 }
@@ -216,9 +217,8 @@ impl ReadonlyRepo {
         let backend = backend_initializer(settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let merge_options =
-            MergeOptions::from_settings(settings).map_err(|err| BackendInitError(err.into()))?;
-        let store = Store::new(backend, signer, merge_options);
+        let store = Store::from_settings(backend, signer, settings)
+            .map_err(|err| BackendInitError(err.into()))?;
 
         let op_store_path = repo_path.join("op_store");
         fs::create_dir(&op_store_path).context(&op_store_path)?;
@@ -278,6 +278,7 @@ impl ReadonlyRepo {
             operation: root_operation,
             index,
             change_id_index: OnceCell::new(),
+            evolution_graph: OnceCell::new(),
             view: root_view,
         }))
     }
@@ -298,6 +299,13 @@ impl ReadonlyRepo {
         &self.view
     }
 
+    /// Returns the commit evolution graph reconstructed from the operation
+    /// history, which is cached for this operation.
+    pub fn evolution_graph(&self) -> OpStoreResult<&Arc<EvolutionGraph>> {
+        self.evolution_graph
+            .get_or_try_init(|| EvolutionGraph::from_operation(&self.operation).map(Arc::new))
+    }
+
     pub fn readonly_index(&self) -> &dyn ReadonlyIndex {
         self.index.as_ref()
     }
@@ -697,13 +705,12 @@ impl RepoLoader {
         repo_path: &Path,
         store_factories: &StoreFactories,
     ) -> Result<Self, StoreLoadError> {
-        let merge_options =
-            MergeOptions::from_settings(settings).map_err(|err| BackendLoadError(err.into()))?;
-        let store = Store::new(
+        let store = Store::from_settings(
             store_factories.load_backend(settings, &repo_path.join("store"))?,
             Signer::from_settings(settings)?,
-            merge_options,
-        );
+            settings,
+        )
+        .map_err(|err| BackendLoadError(err.into()))?;
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
         };
@@ -780,6 +787,7 @@ impl RepoLoader {
             operation,
             index,
             change_id_index: OnceCell::new(),
+            evolution_graph: OnceCell::new(),
             view,
         };
         Arc::new(repo)
@@ -848,6 +856,7 @@ impl RepoLoader {
             operation,
             index,
             change_id_index: OnceCell::new(),
+            evolution_graph: OnceCell::new(),
             view,
         };
         Ok(Arc::new(repo))
@@ -33,6 +33,8 @@ use crate::backend::CommitId;
 use crate::commit::Commit;
 use crate::dsl_util;
 use crate::dsl_util::collect_similar;
+use crate::evolution::EvolutionDirection;
+use crate::evolution::EvolutionGraph;
 use crate::fileset;
 use crate::fileset::FilesetDiagnostics;
 use crate::fileset::FilesetExpression;
@@ -48,6 +50,7 @@ use crate::op_store::RemoteRefState;
 use crate::op_walk;
 use crate::ref_name::RefName;
 use crate::ref_name::RemoteName;
+use crate::ref_name::RemoteNameBuf;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::RemoteRefSymbolBuf;
 use crate::ref_name::WorkspaceName;
@@ -169,7 +172,19 @@ pub struct RemoteRefSymbolExpression {
 /// A custom revset filter expression, defined by an extension.
 pub trait RevsetFilterExtension: std::fmt::Debug + Any + Send + Sync {
     /// Returns true iff this filter matches the specified commit.
-    fn matches_commit(&self, commit: &Commit) -> bool;
+    fn matches_commit(&self, commit: &Commit) -> Result<bool, RevsetEvaluationError>;
+
+    /// Tests the specified commits in batch, and returns whether each commit
+    /// matches this filter.
+    ///
+    /// The default implementation calls [`Self::matches_commit()`] for each
+    /// commit. Implementations may override this to amortize per-call costs.
+    fn matches_commits(&self, commits: &[Commit]) -> Result<Vec<bool>, RevsetEvaluationError> {
+        commits
+            .iter()
+            .map(|commit| self.matches_commit(commit))
+            .collect()
+    }
 }
 
 impl dyn RevsetFilterExtension {
@@ -219,6 +234,7 @@ mod private {
     pub trait ExpressionState {
         type CommitRef: Clone;
         type Operation: Clone;
+        type Evolution: Clone;
     }
 
     // Not constructible because these state types just define associated types.
@@ -235,11 +251,37 @@ use private::UserExpressionState;
 impl ExpressionState for UserExpressionState {
     type CommitRef = RevsetCommitRef;
     type Operation = String;
+    type Evolution = RevsetEvolutionQuery;
 }
 
 impl ExpressionState for ResolvedExpressionState {
     type CommitRef = Infallible;
     type Operation = Infallible;
+    type Evolution = ResolvedEvolutionQuery;
+}
+
+/// Traversal of the commit evolution graph to be resolved against the
+/// operation log.
+#[derive(Clone, Debug)]
+pub struct RevsetEvolutionQuery {
+    /// Direction to follow from the candidate commits.
+    pub direction: EvolutionDirection,
+    /// Range of evolution steps from the candidate commits.
+    pub generation: Range<u64>,
+    /// Whether to exclude commits that aren't visible.
+    pub visible_only: bool,
+}
+
+/// Traversal of the evolution graph reconstructed from the operation log. The
+/// graph is walked when the expression is evaluated.
+#[derive(Clone, Debug)]
+pub struct ResolvedEvolutionQuery {
+    /// Direction to follow from the candidate commits.
+    pub direction: EvolutionDirection,
+    /// Range of evolution steps from the candidate commits.
+    pub generation: Range<u64>,
+    /// Evolution graph at the operation the expression was resolved at.
+    pub graph: Arc<EvolutionGraph>,
 }
 
 /// [`RevsetExpression`] that may contain unresolved commit refs.
@@ -319,6 +361,11 @@ pub enum RevsetExpression<St: ExpressionState> {
         operation: St::Operation,
         candidates: Arc<Self>,
     },
+    /// Follows the commit evolution graph recorded in the operation log.
+    Evolution {
+        query: St::Evolution,
+        candidates: Arc<Self>,
+    },
     /// Makes `All` include the commits and their ancestors in addition to the
     /// visible heads.
     WithinReference {
@@ -637,9 +684,51 @@ impl<St: ExpressionState> RevsetExpression<St> {
     fn coalesce2(self: &Arc<Self>, other: &Arc<Self>) -> Arc<Self> {
         Arc::new(Self::Coalesce(self.clone(), other.clone()))
     }
+
+    /// Returns true if this expression contains filters whose results may
+    /// change while the repo stays unchanged, such as date patterns relative
+    /// to the current time and custom predicates provided by extensions.
+    pub fn has_volatile_filter(self: &Arc<Self>) -> bool {
+        let mut found = false;
+        transform_expression(
+            self,
+            |expression| {
+                found |= matches!(
+                    expression.as_ref(),
+                    Self::Filter(
+                        RevsetFilterPredicate::AuthorDate(_)
+                            | RevsetFilterPredicate::CommitterDate(_)
+                            | RevsetFilterPredicate::Extension(_)
+                    )
+                );
+                if found {
+                    ControlFlow::Break(None)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+            |_| None,
+        );
+        found
+    }
 }
 
 impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
+    /// Bookmarked commits which `jj git push` pushes to the `remote` by
+    /// default: `remote_bookmarks(remote=<remote>)..<workspace>@ & bookmarks()`.
+    pub fn default_push_heads(
+        remote: StringExpression,
+        workspace_name: WorkspaceNameBuf,
+    ) -> Arc<Self> {
+        let symbol = RemoteRefSymbolExpression {
+            name: StringExpression::all(),
+            remote,
+        };
+        Self::remote_bookmarks(symbol, None)
+            .range(&Self::working_copy(workspace_name))
+            .intersection(&Self::bookmarks(StringExpression::all()))
+    }
+
     /// Returns symbol string if this expression is of that type.
     pub fn as_symbol(&self) -> Option<&str> {
         match self {
@@ -764,6 +853,13 @@ pub enum ResolvedExpression {
         candidates: Box<Self>,
         count: usize,
     },
+    /// Commits reachable from `candidates` in the evolution graph.
+    Evolution {
+        candidates: Box<Self>,
+        direction: EvolutionDirection,
+        generation: Range<u64>,
+        graph: Arc<EvolutionGraph>,
+    },
     Coalesce(Box<Self>, Box<Self>),
     Union(Box<Self>, Box<Self>),
     /// Intersects `candidates` with `predicate` by filtering.
@@ -938,6 +1034,51 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
             Ok(RevsetExpression::remote_bookmarks(symbol, state))
         },
     );
+    map.insert("pushed", |diagnostics, function, context| {
+        let remote = parse_push_remote_argument(diagnostics, function, context, || Ok(None))?;
+        let symbol = RemoteRefSymbolExpression {
+            name: StringExpression::all(),
+            remote,
+        };
+        Ok(RevsetExpression::remote_bookmarks(symbol, None).ancestors())
+    });
+    map.insert("unpushed", |diagnostics, function, context| {
+        let remote = parse_push_remote_argument(diagnostics, function, context, || Ok(None))?;
+        let symbol = RemoteRefSymbolExpression {
+            name: StringExpression::all(),
+            remote,
+        };
+        let local_heads = RevsetExpression::bookmarks(StringExpression::all());
+        Ok(RevsetExpression::remote_bookmarks(symbol, None).range(&local_heads))
+    });
+    map.insert("push_pending", |diagnostics, function, context| {
+        let ctx = context.workspace.as_ref().ok_or_else(|| {
+            RevsetParseError::with_span(
+                RevsetParseErrorKind::WorkingCopyWithoutWorkspace,
+                function.name_span,
+            )
+        })?;
+        let remote = parse_push_remote_argument(diagnostics, function, context, || {
+            let Some(look_up) = context.default_push_remote else {
+                return Ok(None);
+            };
+            look_up().map_err(|err| {
+                RevsetParseError::expression(
+                    "Failed to look up the default push remote",
+                    function.name_span,
+                )
+                .with_source(err)
+            })
+        })?;
+        let symbol = RemoteRefSymbolExpression {
+            name: StringExpression::all(),
+            remote: remote.clone(),
+        };
+        let remote_heads = RevsetExpression::remote_bookmarks(symbol, None);
+        let local_heads =
+            RevsetExpression::default_push_heads(remote, ctx.workspace_name.to_owned());
+        Ok(remote_heads.range(&local_heads))
+    });
     map.insert("tags", |diagnostics, function, context| {
         let ([], [opt_arg]) = function.expect_arguments()?;
         let expr = if let Some(arg) = opt_arg {
@@ -1162,6 +1303,32 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
             candidates,
         }))
     });
+    map.insert("predecessors", |diagnostics, function, context| {
+        lower_evolution_walk(
+            diagnostics,
+            function,
+            context,
+            EvolutionDirection::Predecessors,
+        )
+    });
+    map.insert("successors", |diagnostics, function, context| {
+        lower_evolution_walk(
+            diagnostics,
+            function,
+            context,
+            EvolutionDirection::Successors,
+        )
+    });
+    map.insert("evolved_from", |diagnostics, function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let candidates = lower_expression(diagnostics, arg, context)?;
+        let query = RevsetEvolutionQuery {
+            direction: EvolutionDirection::Successors,
+            generation: GENERATION_RANGE_FULL,
+            visible_only: true,
+        };
+        Ok(Arc::new(RevsetExpression::Evolution { query, candidates }))
+    });
     map.insert("coalesce", |diagnostics, function, context| {
         let ([], args) = function.expect_some_arguments()?;
         let expressions: Vec<_> = args
@@ -1173,6 +1340,28 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
     map
 });
 
+fn lower_evolution_walk(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    context: &LoweringContext,
+    direction: EvolutionDirection,
+) -> Result<Arc<UserRevsetExpression>, RevsetParseError> {
+    let ([arg], [depth_opt_arg]) = function.expect_arguments()?;
+    let candidates = lower_expression(diagnostics, arg, context)?;
+    let generation = if let Some(depth_arg) = depth_opt_arg {
+        let depth: u64 = expect_literal("integer", depth_arg)?;
+        1..depth.saturating_add(1)
+    } else {
+        1..u64::MAX
+    };
+    let query = RevsetEvolutionQuery {
+        direction,
+        generation,
+        visible_only: false,
+    };
+    Ok(Arc::new(RevsetExpression::Evolution { query, candidates }))
+}
+
 /// Parses the given `node` as a fileset expression.
 pub fn expect_fileset_expression(
     diagnostics: &mut RevsetDiagnostics,
@@ -1314,6 +1503,26 @@ fn parse_remote_refs_arguments(
     Ok(RemoteRefSymbolExpression { name, remote })
 }
 
+/// Parses the optional `[remote=]remote_pattern` argument of push status
+/// functions. `default_remote()` is called only if the argument is omitted.
+fn parse_push_remote_argument(
+    diagnostics: &mut RevsetDiagnostics,
+    function: &FunctionCallNode,
+    context: &LoweringContext,
+    default_remote: impl FnOnce() -> Result<Option<RemoteNameBuf>, RevsetParseError>,
+) -> Result<StringExpression, RevsetParseError> {
+    let ([], [remote_opt_arg]) = function.expect_named_arguments(&["remote"])?;
+    if let Some(remote_arg) = remote_opt_arg {
+        expect_string_expression(diagnostics, remote_arg, context)
+    } else if let Some(remote) = default_remote()? {
+        Ok(StringExpression::exact(remote))
+    } else if let Some(remote) = context.default_ignored_remote {
+        Ok(StringExpression::exact(remote).negated())
+    } else {
+        Ok(StringExpression::all())
+    }
+}
+
 /// Resolves function call by using the given function map.
 fn lower_function_call(
     diagnostics: &mut RevsetDiagnostics,
@@ -1321,13 +1530,22 @@ fn lower_function_call(
     context: &LoweringContext,
 ) -> Result<Arc<UserRevsetExpression>, RevsetParseError> {
     let function_map = &context.extensions.function_map;
+    let filter_function_map = &context.extensions.filter_function_map;
     if let Some(func) = function_map.get(function.name) {
         func(diagnostics, function, context)
+    } else if let Some(filter) = filter_function_map.get(function.name) {
+        function.expect_no_arguments()?;
+        let predicate = RevsetFilterPredicate::Extension(filter.clone());
+        Ok(RevsetExpression::filter(predicate))
     } else {
+        let names = function_map
+            .keys()
+            .copied()
+            .chain(filter_function_map.keys().map(String::as_str));
         Err(RevsetParseError::with_span(
             RevsetParseErrorKind::NoSuchFunction {
                 name: function.name.to_owned(),
-                candidates: collect_similar(function.name, function_map.keys()),
+                candidates: collect_similar(function.name, names),
             },
             function.name_span,
         ))
@@ -1592,6 +1810,14 @@ fn try_transform_expression<St: ExpressionState, E>(
                     candidates,
                 }
             }),
+            RevsetExpression::Evolution { query, candidates } => {
+                transform_rec(candidates, pre, post)?.map(|candidates| {
+                    RevsetExpression::Evolution {
+                        query: query.clone(),
+                        candidates,
+                    }
+                })
+            }
             RevsetExpression::WithinReference {
                 candidates,
                 commits,
@@ -1711,6 +1937,13 @@ trait ExpressionStateFolder<InSt: ExpressionState, OutSt: ExpressionState> {
         operation: &InSt::Operation,
         candidates: &RevsetExpression<InSt>,
     ) -> Result<Arc<RevsetExpression<OutSt>>, Self::Error>;
+
+    /// Transforms evolution graph traversal such as `predecessors(candidates)`.
+    fn fold_evolution(
+        &mut self,
+        query: &InSt::Evolution,
+        candidates: &RevsetExpression<InSt>,
+    ) -> Result<Arc<RevsetExpression<OutSt>>, Self::Error>;
 }
 
 /// Transforms inner items of the `expression` by using the `folder`.
@@ -1838,6 +2071,9 @@ where
             operation,
             candidates,
         } => folder.fold_at_operation(operation, candidates)?,
+        RevsetExpression::Evolution { query, candidates } => {
+            folder.fold_evolution(query, candidates)?
+        }
         RevsetExpression::WithinReference {
             candidates,
             commits,
@@ -3078,6 +3314,33 @@ impl ExpressionStateFolder<UserExpressionState, ResolvedExpressionState>
             visible_heads,
         }))
     }
+
+    fn fold_evolution(
+        &mut self,
+        query: &RevsetEvolutionQuery,
+        candidates: &UserRevsetExpression,
+    ) -> Result<Arc<ResolvedRevsetExpression>, Self::Error> {
+        let candidates = self.fold_expression(candidates)?;
+        let graph = self
+            .repo()
+            .base_repo()
+            .evolution_graph()
+            .map_err(|err| RevsetResolutionError::Other(err.into()))?;
+        let resolved_query = ResolvedEvolutionQuery {
+            direction: query.direction,
+            generation: query.generation.clone(),
+            graph: graph.clone(),
+        };
+        let expression = Arc::new(RevsetExpression::Evolution {
+            query: resolved_query,
+            candidates,
+        });
+        if query.visible_only {
+            Ok(expression.intersection(&RevsetExpression::visible_heads().ancestors()))
+        } else {
+            Ok(expression)
+        }
+    }
 }
 
 fn resolve_symbols(
@@ -3213,6 +3476,12 @@ impl VisibilityResolutionContext<'_> {
                 },
             },
             RevsetExpression::AtOperation { operation, .. } => match *operation {},
+            RevsetExpression::Evolution { query, candidates } => ResolvedExpression::Evolution {
+                candidates: self.resolve(candidates).into(),
+                direction: query.direction,
+                generation: query.generation.clone(),
+                graph: query.graph.clone(),
+            },
             RevsetExpression::WithinReference {
                 candidates,
                 commits,
@@ -3337,6 +3606,9 @@ impl VisibilityResolutionContext<'_> {
                 visible_heads: self.visible_heads.to_owned(),
             },
             RevsetExpression::AtOperation { operation, .. } => match *operation {},
+            RevsetExpression::Evolution { .. } => {
+                ResolvedPredicateExpression::Set(self.resolve(expression).into())
+            }
             // Filters should be intersected with all() within the at-op repo.
             RevsetExpression::WithinReference { .. }
             | RevsetExpression::WithinVisibility { .. } => {
@@ -3448,6 +3720,7 @@ impl<I: Iterator<Item = Result<CommitId, RevsetEvaluationError>>> Iterator
 pub struct RevsetExtensions {
     symbol_resolvers: Vec<Box<dyn SymbolResolverExtension>>,
     function_map: HashMap<&'static str, RevsetFunction>,
+    filter_function_map: HashMap<String, Arc<dyn RevsetFilterExtension>>,
 }
 
 impl Default for RevsetExtensions {
@@ -3461,6 +3734,7 @@ impl RevsetExtensions {
         Self {
             symbol_resolvers: vec![],
             function_map: BUILTIN_FUNCTION_MAP.clone(),
+            filter_function_map: HashMap::new(),
         }
     }
 
@@ -3480,8 +3754,32 @@ impl RevsetExtensions {
             hash_map::Entry::Vacant(v) => v.insert(func),
         };
     }
+
+    /// Returns true if function of the given `name` is registered.
+    pub fn has_function(&self, name: &str) -> bool {
+        self.function_map.contains_key(name) || self.filter_function_map.contains_key(name)
+    }
+
+    /// Registers function `name()` that selects commits matching the `filter`.
+    ///
+    /// Unlike [`Self::add_custom_function()`], the function name can be
+    /// determined at runtime.
+    pub fn add_filter_function(&mut self, name: String, filter: Arc<dyn RevsetFilterExtension>) {
+        assert!(
+            !self.has_function(&name),
+            "Conflict registering revset function '{name}'"
+        );
+        self.filter_function_map.insert(name, filter);
+    }
 }
 
+/// Function that looks up the remote `push_pending()` refers to by default.
+///
+/// It's called only when the remote needs to be resolved, since looking it up
+/// may involve reading the repository configuration.
+pub type DefaultPushRemoteFn<'a> =
+    dyn Fn() -> Result<Option<RemoteNameBuf>, Box<dyn std::error::Error + Send + Sync>> + 'a;
+
 /// Information needed to parse revset expression.
 #[derive(Clone)]
 pub struct RevsetParseContext<'a> {
@@ -3491,6 +3789,8 @@ pub struct RevsetParseContext<'a> {
     pub date_pattern_context: DatePatternContext,
     /// Special remote that should be ignored by default. (e.g. "git")
     pub default_ignored_remote: Option<&'a RemoteName>,
+    /// Looks up the remote that `push_pending()` refers to by default.
+    pub default_push_remote: Option<&'a DefaultPushRemoteFn<'a>>,
     pub use_glob_by_default: bool,
     pub extensions: &'a RevsetExtensions,
     pub workspace: Option<RevsetWorkspaceContext<'a>>,
@@ -3504,6 +3804,7 @@ impl<'a> RevsetParseContext<'a> {
             user_email,
             date_pattern_context,
             default_ignored_remote,
+            default_push_remote,
             use_glob_by_default,
             extensions,
             workspace,
@@ -3512,6 +3813,7 @@ impl<'a> RevsetParseContext<'a> {
             user_email,
             date_pattern_context,
             default_ignored_remote,
+            default_push_remote,
             use_glob_by_default,
             extensions,
             workspace,
@@ -3525,6 +3827,7 @@ pub struct LoweringContext<'a> {
     user_email: &'a str,
     date_pattern_context: DatePatternContext,
     default_ignored_remote: Option<&'a RemoteName>,
+    default_push_remote: Option<&'a DefaultPushRemoteFn<'a>>,
     use_glob_by_default: bool,
     extensions: &'a RevsetExtensions,
     workspace: Option<RevsetWorkspaceContext<'a>>,
@@ -3613,6 +3916,7 @@ mod tests {
             user_email: "test.user@example.com",
             date_pattern_context: chrono::Utc::now().fixed_offset().into(),
             default_ignored_remote: Some("ignored".as_ref()),
+            default_push_remote: Some(&|| Ok(Some("origin".into()))),
             use_glob_by_default: true,
             extensions: &RevsetExtensions::default(),
             workspace: None,
@@ -3644,6 +3948,7 @@ mod tests {
             user_email: "test.user@example.com",
             date_pattern_context: chrono::Utc::now().fixed_offset().into(),
             default_ignored_remote: Some("ignored".as_ref()),
+            default_push_remote: Some(&|| Ok(Some("origin".into()))),
             use_glob_by_default: true,
             extensions: &RevsetExtensions::default(),
             workspace: Some(workspace_ctx),
@@ -4508,6 +4813,17 @@ mod tests {
             ), @"Some(Root)");
     }
 
+    #[test]
+    fn test_has_volatile_filter() {
+        let has_volatile_filter = |revset_str| parse(revset_str).unwrap().has_volatile_filter();
+        assert!(!has_volatile_filter("::foo ~ author(foo)"));
+        assert!(!has_volatile_filter("description(foo) | heads(bar)"));
+        assert!(has_volatile_filter("author_date(after:'1 day ago')"));
+        assert!(has_volatile_filter(
+            "::(foo & ~committer_date(before:'2020-01-01'))"
+        ));
+    }
+
     #[test]
     fn test_resolve_referenced_commits() {
         let settings = insta_settings();
@@ -16,6 +16,7 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::iter;
 use std::slice;
 use std::sync::Arc;
 
@@ -35,6 +36,7 @@ use crate::commit::CommitIteratorExt as _;
 use crate::commit::conflict_label_for_commits;
 use crate::commit_builder::CommitBuilder;
 use crate::conflict_labels::ConflictLabels;
+use crate::copies::find_recorded_copy_target;
 use crate::index::Index;
 use crate::index::IndexResult;
 use crate::index::ResolvedChangeTargets;
@@ -48,6 +50,7 @@ use crate::merged_tree_builder::MergedTreeBuilder;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::revset::RevsetExpression;
 use crate::revset::RevsetIteratorExt as _;
 use crate::store::Store;
@@ -65,6 +68,24 @@ pub async fn merge_commit_trees(repo: &dyn Repo, commits: &[Commit]) -> BackendR
     }
 }
 
+/// Like [`merge_commit_trees()`], but also merges file contents by the
+/// configured merge drivers which run external programs. This should be used
+/// when the merged tree is committed.
+#[instrument(skip(repo))]
+pub async fn merge_commit_trees_with_drivers(
+    repo: &dyn Repo,
+    commits: &[Commit],
+) -> BackendResult<MergedTree> {
+    if let [commit] = commits {
+        Ok(commit.tree())
+    } else {
+        merge_commit_trees_no_resolve_without_repo(repo.store(), repo.index(), commits)
+            .await?
+            .resolve_with_drivers()
+            .await
+    }
+}
+
 /// Merges `commits` without attempting to resolve file conflicts.
 #[instrument(skip(index))]
 pub async fn merge_commit_trees_no_resolve_without_repo(
@@ -169,7 +190,7 @@ pub async fn restore_tree(
     // TODO: using a merge is required for retaining conflict labels when restoring
     // from/into conflicted trees, but maybe we could optimize the case where both
     // trees are already resolved.
-    MergedTree::merge(Merge::from_vec(vec![
+    MergedTree::merge_with_drivers(Merge::from_vec(vec![
         (
             destination.clone(),
             format!("{destination_label} (restore destination)"),
@@ -325,30 +346,29 @@ impl<'repo> CommitRewriter<'repo> {
             let new_base_tree_fut = merge_commit_trees(self.mut_repo, &new_parents);
             let old_tree = self.old_commit.tree();
             let (old_base_tree, new_base_tree) = try_join!(old_base_tree_fut, new_base_tree_fut)?;
-            (
-                old_base_tree.tree_ids() == self.old_commit.tree_ids(),
-                MergedTree::merge(Merge::from_vec(vec![
-                    (
-                        new_base_tree,
-                        format!(
-                            "{} (rebase destination)",
-                            conflict_label_for_commits(&new_parents)
-                        ),
-                    ),
-                    (
-                        old_base_tree,
-                        format!(
-                            "{} (parents of rebased revision)",
-                            conflict_label_for_commits(&old_parents)
-                        ),
-                    ),
-                    (
-                        old_tree,
-                        format!("{} (rebased revision)", self.old_commit.conflict_label()),
-                    ),
-                ]))
-                .await?,
-            )
+            let was_empty = old_base_tree.tree_ids() == self.old_commit.tree_ids();
+            let labels = [
+                format!(
+                    "{} (rebase destination)",
+                    conflict_label_for_commits(&new_parents)
+                ),
+                format!(
+                    "{} (parents of rebased revision)",
+                    conflict_label_for_commits(&old_parents)
+                ),
+                format!("{} (rebased revision)", self.old_commit.conflict_label()),
+            ];
+            let to_merge = |trees: [MergedTree; 3]| {
+                Merge::from_vec(iter::zip(trees, labels.clone()).collect::<Vec<_>>())
+            };
+            let trees = [new_base_tree, old_base_tree, old_tree];
+            let mut new_tree = MergedTree::merge_with_drivers(to_merge(trees.clone())).await?;
+            if new_tree.has_conflict()
+                && let Some(trees) = follow_recorded_renames(&new_tree, &trees).await?
+            {
+                new_tree = MergedTree::merge_with_drivers(to_merge(trees)).await?;
+            }
+            (was_empty, new_tree)
         };
         // Ensure we don't abandon commits with multiple parents (merge commits), even
         // if they're empty.
@@ -390,6 +410,76 @@ impl<'repo> CommitRewriter<'repo> {
     }
 }
 
+/// Moves files in the trees to be merged for rebasing a commit, so that changes
+/// to the files are merged across renames.
+///
+/// The `trees` are the new base tree, the old base tree, and the tree of the
+/// commit. Changes made by the commit to files renamed in the new base tree are
+/// moved to the new paths, and so are changes made in the new base tree to
+/// files renamed by the commit. Only renames recorded in the copy IDs of the
+/// files are followed, and only for paths left conflicted by the `merged_tree`.
+async fn follow_recorded_renames(
+    merged_tree: &MergedTree,
+    trees: &[MergedTree; 3],
+) -> BackendResult<Option<[MergedTree; 3]>> {
+    let [new_base_tree, old_base_tree, old_tree] = trees;
+    let mut base_renames = vec![];
+    let mut commit_renames = vec![];
+    for (path, value) in merged_tree.conflicts() {
+        value?;
+        if old_base_tree.path_value_async(&path).await?.is_absent() {
+            continue;
+        }
+        let in_new_base = !new_base_tree.path_value_async(&path).await?.is_absent();
+        let in_commit = !old_tree.path_value_async(&path).await?.is_absent();
+        if in_commit
+            && !in_new_base
+            && let Some(target) =
+                find_recorded_copy_target(old_base_tree, &path, new_base_tree).await?
+        {
+            base_renames.push((path, target));
+        } else if in_new_base
+            && !in_commit
+            && let Some(target) = find_recorded_copy_target(old_base_tree, &path, old_tree).await?
+        {
+            commit_renames.push((path, target));
+        }
+    }
+    if base_renames.is_empty() && commit_renames.is_empty() {
+        return Ok(None);
+    }
+    let all_renames = [&base_renames[..], &commit_renames[..]].concat();
+    Ok(Some([
+        move_files(new_base_tree, &commit_renames).await?,
+        move_files(old_base_tree, &all_renames).await?,
+        move_files(old_tree, &base_renames).await?,
+    ]))
+}
+
+/// Returns the tree with the files at the source paths moved to the target
+/// paths. Files whose target path is taken are left in place.
+async fn move_files(
+    tree: &MergedTree,
+    moves: &[(RepoPathBuf, RepoPathBuf)],
+) -> BackendResult<MergedTree> {
+    let mut builder = MergedTreeBuilder::new(tree.clone());
+    let mut changed = false;
+    for (source, target) in moves {
+        let value = tree.path_value_async(source).await?;
+        if value.is_absent() || !tree.path_value_async(target).await?.is_absent() {
+            continue;
+        }
+        builder.set_or_remove(source.clone(), Merge::absent());
+        builder.set_or_remove(target.clone(), value);
+        changed = true;
+    }
+    if changed {
+        builder.write_tree()
+    } else {
+        Ok(tree.clone())
+    }
+}
+
 #[derive(Debug)]
 pub enum RebasedCommit {
     Rewritten(Commit),
@@ -565,6 +655,16 @@ impl ComputedMoveCommits {
         self.to_abandon.extend(commit_ids);
     }
 
+    /// Overrides the new parents of the target root `commit_id`, so that roots
+    /// moved together can be rebased onto different destinations.
+    ///
+    /// The new parents shouldn't be descendants of the target commits.
+    pub fn set_root_parent_ids(&mut self, commit_id: &CommitId, new_parent_ids: Vec<CommitId>) {
+        assert!(self.target_commit_ids.contains(commit_id));
+        self.commit_new_parents_map
+            .insert(commit_id.clone(), new_parent_ids);
+    }
+
     pub fn apply(
         self,
         mut_repo: &mut MutableRepo,
@@ -1312,7 +1412,7 @@ pub fn squash_commits<'repo>(
         } else {
             let source_tree = source.commit.commit.tree();
             // Apply the reverse of the selected changes onto the source
-            let new_source_tree = MergedTree::merge(Merge::from_diffs(
+            let new_source_tree = MergedTree::merge_with_drivers(Merge::from_diffs(
                 (source_tree, source.commit.commit.conflict_label()),
                 [source.diff.clone().invert()],
             ))
@@ -1353,7 +1453,7 @@ pub fn squash_commits<'repo>(
             .map(|source| source.commit.commit.id().clone()),
     );
     // Apply the selected changes onto the destination
-    let destination_tree = MergedTree::merge(Merge::from_diffs(
+    let destination_tree = MergedTree::merge_with_drivers(Merge::from_diffs(
         (
             rewritten_destination.tree(),
             format!("{} (squash destination)", destination.conflict_label()),
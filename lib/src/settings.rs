@@ -56,6 +56,7 @@ struct UserSettingsData {
     operation_username: String,
     signing_behavior: SignBehavior,
     signing_key: Option<String>,
+    sign_operations: bool,
 }
 
 pub type RemoteSettingsMap = HashMap<RemoteNameBuf, RemoteSettings>;
@@ -83,7 +84,7 @@ impl RemoteSettings {
     }
 }
 
-/// Commit signing settings, describes how to and if to sign commits.
+/// Signing settings, describes how to and if to sign commits and operations.
 #[derive(Debug, Clone)]
 pub struct SignSettings {
     /// What to actually do, see [SignBehavior].
@@ -93,6 +94,8 @@ pub struct SignSettings {
     pub user_email: String,
     /// The signing backend specific key, to be passed to the signing backend.
     pub key: Option<String>,
+    /// Whether to sign operations in addition to commits.
+    pub sign_operations: bool,
 }
 
 impl SignSettings {
@@ -144,6 +147,7 @@ impl UserSettings {
         let operation_username = config.get("operation.username")?;
         let signing_behavior = config.get("signing.behavior")?;
         let signing_key = config.get("signing.key").optional()?;
+        let sign_operations = config.get("signing.sign-operations")?;
         let data = UserSettingsData {
             user_name,
             user_email,
@@ -153,6 +157,7 @@ impl UserSettings {
             operation_username,
             signing_behavior,
             signing_key,
+            sign_operations,
         };
         Ok(Self {
             config: Arc::new(config),
@@ -229,6 +234,7 @@ impl UserSettings {
             behavior: self.data.signing_behavior,
             user_email: self.data.user_email.clone(),
             key: self.data.signing_key.clone(),
+            sign_operations: self.data.sign_operations,
         }
     }
 }
@@ -17,6 +17,7 @@
 
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::hash::Hash;
 use std::sync::Mutex;
 
 use clru::CLruCache;
@@ -26,6 +27,7 @@ use crate::backend::CommitId;
 use crate::config::ConfigGetError;
 use crate::gpg_signing::GpgBackend;
 use crate::gpg_signing::GpgsmBackend;
+use crate::op_store::OperationId;
 use crate::settings::UserSettings;
 use crate::ssh_signing::SshBackend;
 use crate::store::COMMIT_CACHE_CAPACITY;
@@ -122,6 +124,9 @@ pub enum SignError {
     /// The verification failed because the signature *format* was invalid.
     #[error("Invalid signature")]
     InvalidSignatureFormat,
+    /// Signing was requested, but no signing backend is configured.
+    #[error("No signing backend configured")]
+    NoBackend,
     /// A generic error from the backend impl.
     #[error("Signing error")]
     Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
@@ -172,6 +177,7 @@ pub struct Signer {
     /// for ownership reasons.
     backends: Vec<Box<dyn SigningBackend>>,
     cache: Mutex<CLruCache<CommitId, Verification>>,
+    operation_cache: Mutex<CLruCache<OperationId, Verification>>,
 }
 
 impl Signer {
@@ -210,6 +216,7 @@ impl Signer {
             main_backend,
             backends: other_backends,
             cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
+            operation_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
         }
     }
 
@@ -235,7 +242,27 @@ impl Signer {
         data: &[u8],
         signature: &[u8],
     ) -> SignResult<Verification> {
-        let cached = self.cache.lock().unwrap().get(commit_id).cloned();
+        self.verify_cached(&self.cache, commit_id, data, signature)
+    }
+
+    /// Like [`Signer::verify()`], but for the signature of an operation.
+    pub fn verify_operation(
+        &self,
+        operation_id: &OperationId,
+        data: &[u8],
+        signature: &[u8],
+    ) -> SignResult<Verification> {
+        self.verify_cached(&self.operation_cache, operation_id, data, signature)
+    }
+
+    fn verify_cached<K: Clone + Eq + Hash>(
+        &self,
+        cache: &Mutex<CLruCache<K, Verification>>,
+        id: &K,
+        data: &[u8],
+        signature: &[u8],
+    ) -> SignResult<Verification> {
+        let cached = cache.lock().unwrap().get(id).cloned();
         if let Some(check) = cached {
             return Ok(check);
         }
@@ -259,10 +286,7 @@ impl Signer {
             // realistically this is unlikely, but technically
             // it's correct to not cache unknowns here
             if verification.status != SigStatus::Unknown {
-                self.cache
-                    .lock()
-                    .unwrap()
-                    .put(commit_id.clone(), verification.clone());
+                cache.lock().unwrap().put(id.clone(), verification.clone());
             }
             Ok(verification)
         } else {
@@ -270,10 +294,10 @@ impl Signer {
             // have a backend that knows how to handle this signature
             //
             // not sure about how much of an optimization this is
-            self.cache
+            cache
                 .lock()
                 .unwrap()
-                .put(commit_id.clone(), Verification::unknown());
+                .put(id.clone(), Verification::unknown());
             Ok(Verification::unknown())
         }
     }
@@ -14,6 +14,7 @@
 
 #![expect(missing_docs)]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs;
 use std::fs::File;
@@ -58,6 +59,7 @@ use crate::backend::TreeValue;
 use crate::backend::make_root_commit;
 use crate::conflict_labels::ConflictLabels;
 use crate::content_hash::blake2b_hash;
+use crate::copies::collect_related_copies;
 use crate::file_util::persist_content_addressed_temp_file;
 use crate::index::Index;
 use crate::merge::MergeBuilder;
@@ -108,6 +110,7 @@ impl SimpleBackend {
         fs::create_dir(store_path.join("files")).unwrap();
         fs::create_dir(store_path.join("symlinks")).unwrap();
         fs::create_dir(store_path.join("conflicts")).unwrap();
+        fs::create_dir(store_path.join("copies")).unwrap();
         let backend = Self::load(store_path);
         let empty_tree_id = backend
             .write_tree(RepoPath::root(), &Tree::default())
@@ -139,6 +142,10 @@ impl SimpleBackend {
         self.path.join("symlinks").join(id.hex())
     }
 
+    fn copy_path(&self, id: &CopyId) -> PathBuf {
+        self.path.join("copies").join(id.hex())
+    }
+
     fn tree_path(&self, id: &TreeId) -> PathBuf {
         self.path.join("trees").join(id.hex())
     }
@@ -243,22 +250,51 @@ impl Backend for SimpleBackend {
         Ok(id)
     }
 
-    async fn read_copy(&self, _id: &CopyId) -> BackendResult<CopyHistory> {
-        Err(BackendError::Unsupported(
-            "The simple backend doesn't support copies".to_string(),
-        ))
+    async fn read_copy(&self, id: &CopyId) -> BackendResult<CopyHistory> {
+        let path = self.copy_path(id);
+        let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
+        let proto =
+            crate::protos::simple_store::CopyHistory::decode(&*buf).map_err(to_other_err)?;
+        copy_history_from_proto(proto)
     }
 
-    async fn write_copy(&self, _contents: &CopyHistory) -> BackendResult<CopyId> {
-        Err(BackendError::Unsupported(
-            "The simple backend doesn't support copies".to_string(),
-        ))
+    async fn write_copy(&self, copy: &CopyHistory) -> BackendResult<CopyId> {
+        // Repos created before copies were supported don't have the directory.
+        fs::create_dir_all(self.path.join("copies")).map_err(to_other_err)?;
+        // TODO: Write temporary file in the destination directory (#5712)
+        let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        let proto = copy_history_to_proto(copy);
+        temp_file
+            .as_file()
+            .write_all(&proto.encode_to_vec())
+            .map_err(to_other_err)?;
+
+        let id = CopyId::new(blake2b_hash(copy).to_vec());
+
+        persist_content_addressed_temp_file(temp_file, self.copy_path(&id))
+            .map_err(to_other_err)?;
+        Ok(id)
     }
 
-    async fn get_related_copies(&self, _copy_id: &CopyId) -> BackendResult<Vec<CopyHistory>> {
-        Err(BackendError::Unsupported(
-            "The simple backend doesn't support copies".to_string(),
-        ))
+    async fn get_related_copies(&self, copy_id: &CopyId) -> BackendResult<Vec<CopyHistory>> {
+        let copies_path = self.path.join("copies");
+        let mut copies = HashMap::new();
+        let entries = match fs::read_dir(&copies_path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return collect_related_copies(&copies, copy_id);
+            }
+            Err(err) => return Err(to_other_err(err)),
+        };
+        for entry in entries {
+            let entry = entry.map_err(to_other_err)?;
+            let Some(id) = entry.file_name().to_str().and_then(CopyId::try_from_hex) else {
+                continue;
+            };
+            let copy = self.read_copy(&id).await?;
+            copies.insert(id, copy);
+        }
+        collect_related_copies(&copies, copy_id)
     }
 
     async fn read_tree(&self, _path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
@@ -410,6 +446,25 @@ fn tree_to_proto(tree: &Tree) -> crate::protos::simple_store::Tree {
     proto
 }
 
+fn copy_history_to_proto(copy: &CopyHistory) -> crate::protos::simple_store::CopyHistory {
+    crate::protos::simple_store::CopyHistory {
+        current_path: copy.current_path.as_internal_file_string().to_owned(),
+        parents: copy.parents.iter().map(|id| id.to_bytes()).collect(),
+        salt: copy.salt.clone(),
+    }
+}
+
+fn copy_history_from_proto(
+    proto: crate::protos::simple_store::CopyHistory,
+) -> BackendResult<CopyHistory> {
+    Ok(CopyHistory {
+        current_path: RepoPathBuf::from_internal_string(proto.current_path)
+            .map_err(to_other_err)?,
+        parents: proto.parents.into_iter().map(CopyId::new).collect(),
+        salt: proto.salt,
+    })
+}
+
 fn tree_from_proto(proto: crate::protos::simple_store::Tree) -> Tree {
     // Serialized data should be sorted
     let entries = proto
@@ -54,6 +54,7 @@ use crate::op_store::OpStoreResult;
 use crate::op_store::Operation;
 use crate::op_store::OperationId;
 use crate::op_store::OperationMetadata;
+use crate::op_store::OperationSignature;
 use crate::op_store::RefTarget;
 use crate::op_store::RemoteRef;
 use crate::op_store::RemoteRefState;
@@ -510,6 +511,7 @@ fn operation_to_proto(operation: &Operation) -> crate::protos::simple_op_store::
         metadata: Some(operation_metadata_to_proto(&operation.metadata)),
         commit_predecessors,
         stores_commit_predecessors,
+        signature: operation.signature.as_bytes().to_vec(),
     }
 }
 
@@ -531,6 +533,7 @@ fn operation_from_proto(
         parents,
         metadata,
         commit_predecessors,
+        signature: OperationSignature::new(proto.signature),
     })
 }
 
@@ -1042,6 +1045,7 @@ mod tests {
                     CommitId::from_hex("444444"),
                 ],
             }),
+            signature: OperationSignature::default(),
         }
     }
 
@@ -1063,6 +1067,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_signed_operation_data() {
+        let unsigned_operation = create_operation();
+        let operation = Operation {
+            signature: OperationSignature::new(b"signature".to_vec()),
+            ..unsigned_operation.clone()
+        };
+        // The signature doesn't cover itself, but the operation id does
+        assert_eq!(operation.signed_data(), unsigned_operation.signed_data());
+        assert_ne!(blake2b_hash(&operation), blake2b_hash(&unsigned_operation));
+        assert_snapshot!(
+            str::from_utf8(&operation.signed_data()).unwrap(),
+            @r"
+        view aaa11100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000
+        parent bbb11100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000
+        parent bbb22200000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000
+        operation b544c80b5ededdd64d0f10468fa636a06b83c45d94dd9bdac95319f7fe11fee536506c5c110681dee6233e69db7647683e732939a3ec88e867250efd765fea18
+        "
+        );
+    }
+
     #[test]
     fn test_read_write_view() {
         let temp_dir = new_temp_dir();
@@ -1089,6 +1114,22 @@ mod tests {
         assert_eq!(read_operation, operation);
     }
 
+    #[test]
+    fn test_read_write_signed_operation() {
+        let temp_dir = new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data).unwrap();
+        let operation = Operation {
+            signature: OperationSignature::new(b"signature".to_vec()),
+            ..create_operation()
+        };
+        let op_id = store.write_operation(&operation).block_on().unwrap();
+        let read_operation = store.read_operation(&op_id).block_on().unwrap();
+        assert_eq!(read_operation, operation);
+    }
+
     #[test]
     fn test_remote_views_legacy_roundtrip() {
         let mut view = create_view();
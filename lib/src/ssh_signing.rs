@@ -16,14 +16,25 @@
 
 use std::ffi::OsString;
 use std::fmt::Debug;
+use std::fs;
+use std::io;
 use std::io::Write as _;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 use std::process::ExitStatus;
 use std::process::Stdio;
+use std::sync::OnceLock;
 
 use either::Either;
+use ssh_key::Algorithm;
+use ssh_key::EcdsaCurve;
+use ssh_key::HashAlg;
+use ssh_key::LineEnding;
+use ssh_key::PrivateKey;
+use ssh_key::PublicKey;
+use ssh_key::SshSig;
+use ssh_key::public::KeyData;
 use thiserror::Error;
 
 use crate::config::ConfigGetError;
@@ -34,11 +45,16 @@ use crate::signing::SignError;
 use crate::signing::SigningBackend;
 use crate::signing::Verification;
 
+/// Namespace of the signatures created and accepted by jj, same as Git.
+const NAMESPACE: &str = "git";
+
 #[derive(Debug)]
 pub struct SshBackend {
     program: OsString,
     allowed_signers: Option<OsString>,
     revocation_list: Option<OsString>,
+    in_process: bool,
+    trusted_keys: OnceLock<Option<TrustedKeys>>,
 }
 
 #[derive(Debug, Error)]
@@ -64,6 +80,26 @@ impl From<SshError> for SignError {
 
 type SshResult<T> = Result<T, SshError>;
 
+/// Reason why a signature can't be created or verified in-process, in which
+/// case the SSH program is run instead.
+#[derive(Debug, Error)]
+enum FallbackReason {
+    #[error("No SSH agent is available")]
+    NoAgent,
+    #[error("SSH agent failed to sign the data")]
+    AgentFailure,
+    #[error("Unsupported key algorithm {0}")]
+    UnsupportedAlgorithm(Algorithm),
+    #[error("Unsupported option in allowed signers file: {0}")]
+    UnsupportedOption(String),
+    #[error("Unsupported revocation list format")]
+    UnsupportedRevocationList,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Key(#[from] ssh_key::Error),
+}
+
 fn parse_utf8_string(data: &[u8]) -> SshResult<&str> {
     str::from_utf8(data).map_err(|_| SshError::BadResult)
 }
@@ -120,6 +156,290 @@ fn parse_fingerprint(output: &[u8]) -> SshResult<String> {
         .into())
 }
 
+/// Entry of an allowed signers file. See the ALLOWED SIGNERS section of
+/// `ssh-keygen(1)`.
+#[derive(Debug)]
+struct AllowedSigner {
+    /// First principal of the entry, which is what `ssh-keygen -Y
+    /// find-principals` reports.
+    principal: String,
+    /// Comma-separated principal patterns of the entry.
+    principals: String,
+    namespaces: Option<String>,
+    key: KeyData,
+}
+
+impl AllowedSigner {
+    fn allows_namespace(&self, namespace: &str) -> bool {
+        self.namespaces
+            .as_ref()
+            .is_none_or(|patterns| match_pattern_list(namespace, patterns))
+    }
+}
+
+/// Keys loaded from the allowed signers file and the revocation list.
+#[derive(Debug)]
+struct TrustedKeys {
+    signers: Vec<AllowedSigner>,
+    revoked: Vec<KeyData>,
+}
+
+/// Splits off the first whitespace-separated token of `line`, which may
+/// contain double-quoted whitespace.
+fn split_token(line: &str) -> (&str, &str) {
+    let line = line.trim_start();
+    let mut quoted = false;
+    let end = line
+        .char_indices()
+        .find(|&(_, c)| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            !quoted && c.is_ascii_whitespace()
+        })
+        .map_or(line.len(), |(i, _)| i);
+    (&line[..end], &line[end..])
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Parses the `namespaces` option out of an allowed signers option list.
+/// Returns an error for options that can't be evaluated in-process.
+fn parse_signer_options(options: &str) -> Result<Option<String>, FallbackReason> {
+    let mut namespaces = None;
+    let mut quoted = false;
+    let options = options.split(|c| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        !quoted && c == ','
+    });
+    for option in options {
+        let (name, value) = option.split_once('=').unwrap_or((option, ""));
+        if name.eq_ignore_ascii_case("namespaces") {
+            namespaces = Some(unquote(value).to_owned());
+        } else {
+            return Err(FallbackReason::UnsupportedOption(name.to_owned()));
+        }
+    }
+    Ok(namespaces)
+}
+
+fn parse_allowed_signers(content: &str) -> Result<Vec<AllowedSigner>, FallbackReason> {
+    let mut signers = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (principals, rest) = split_token(line);
+        let (namespaces, key) = if let Ok(key) = PublicKey::from_openssh(rest.trim()) {
+            (None, key)
+        } else {
+            let (options, rest) = split_token(rest);
+            let Ok(key) = PublicKey::from_openssh(rest.trim()) else {
+                // ssh-keygen ignores lines it can't parse
+                continue;
+            };
+            (parse_signer_options(options)?, key)
+        };
+        let principals = unquote(principals);
+        signers.push(AllowedSigner {
+            principal: principals.split(',').next().unwrap().to_owned(),
+            principals: principals.to_owned(),
+            namespaces,
+            key: key.key_data().clone(),
+        });
+    }
+    Ok(signers)
+}
+
+/// Parses a revocation list consisting of public keys. Binary KRLs aren't
+/// supported.
+fn parse_revocation_list(content: &[u8]) -> Result<Vec<KeyData>, FallbackReason> {
+    let content = str::from_utf8(content).map_err(|_| FallbackReason::UnsupportedRevocationList)?;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let key = PublicKey::from_openssh(line)
+                .map_err(|_| FallbackReason::UnsupportedRevocationList)?;
+            Ok(key.key_data().clone())
+        })
+        .collect()
+}
+
+/// Matches `text` against a comma-separated list of `*`/`?` wildcard
+/// patterns, where patterns prefixed with `!` exclude matches.
+fn match_pattern_list(text: &str, patterns: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns.split(',') {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        if match_pattern(text.as_bytes(), pattern.as_bytes()) {
+            if negated {
+                return false;
+            }
+            matched = true;
+        }
+    }
+    matched
+}
+
+fn match_pattern(text: &[u8], pattern: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| match_pattern(&text[i..], rest)),
+        Some((&c, rest)) => text
+            .split_first()
+            .is_some_and(|(&t, text)| (c == b'?' || c == t) && match_pattern(text, rest)),
+    }
+}
+
+fn is_supported_algorithm(algorithm: &Algorithm) -> bool {
+    matches!(
+        algorithm,
+        Algorithm::Ed25519
+            | Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP256 | EcdsaCurve::NistP384
+            }
+    )
+}
+
+/// Key to sign with, either loaded from disk or held by the SSH agent.
+enum SigningKey {
+    Private(Box<PrivateKey>),
+    Agent(PublicKey),
+}
+
+fn load_signing_key(key: &str) -> Result<SigningKey, FallbackReason> {
+    let key_path = crate::file_util::expand_home_path(key);
+    if !key_path.is_absolute() {
+        return Ok(SigningKey::Agent(PublicKey::from_openssh(key.trim())?));
+    }
+    let content = fs::read_to_string(&key_path)?;
+    match PrivateKey::from_openssh(&content) {
+        // Encrypted keys have to be unlocked by the agent
+        Ok(private_key) if private_key.is_encrypted() => {
+            Ok(SigningKey::Agent(private_key.public_key().clone()))
+        }
+        Ok(private_key) => Ok(SigningKey::Private(Box::new(private_key))),
+        Err(_) => Ok(SigningKey::Agent(PublicKey::from_openssh(content.trim())?)),
+    }
+}
+
+fn sign_in_process(data: &[u8], key: &str) -> Result<Vec<u8>, FallbackReason> {
+    let signature = match load_signing_key(key)? {
+        SigningKey::Private(private_key) => {
+            if !is_supported_algorithm(&private_key.algorithm()) {
+                return Err(FallbackReason::UnsupportedAlgorithm(
+                    private_key.algorithm(),
+                ));
+            }
+            private_key.sign(NAMESPACE, HashAlg::Sha512, data)?
+        }
+        SigningKey::Agent(public_key) => {
+            if !is_supported_algorithm(&public_key.algorithm()) {
+                return Err(FallbackReason::UnsupportedAlgorithm(public_key.algorithm()));
+            }
+            agent::sign(&public_key, data)?
+        }
+    };
+    let mut pem = signature.to_pem(LineEnding::LF)?;
+    if !pem.ends_with('\n') {
+        pem.push('\n');
+    }
+    Ok(pem.into_bytes())
+}
+
+/// Minimal client of the SSH agent protocol, which only supports signing.
+#[cfg(unix)]
+mod agent {
+    use std::io::Read as _;
+    use std::io::Write as _;
+    use std::os::unix::net::UnixStream;
+
+    use ssh_key::HashAlg;
+    use ssh_key::PublicKey;
+    use ssh_key::Signature;
+    use ssh_key::SshSig;
+
+    use super::FallbackReason;
+    use super::NAMESPACE;
+
+    const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+    const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+    const MAX_MESSAGE_LEN: usize = 256 * 1024;
+
+    fn put_string(buf: &mut Vec<u8>, data: &[u8]) {
+        buf.extend_from_slice(&u32::try_from(data.len()).unwrap().to_be_bytes());
+        buf.extend_from_slice(data);
+    }
+
+    fn get_string(data: &[u8]) -> Option<&[u8]> {
+        let (len, rest) = data.split_first_chunk::<4>()?;
+        rest.get(..usize::try_from(u32::from_be_bytes(*len)).ok()?)
+    }
+
+    pub(super) fn sign(public_key: &PublicKey, data: &[u8]) -> Result<SshSig, FallbackReason> {
+        let socket = std::env::var_os("SSH_AUTH_SOCK").ok_or(FallbackReason::NoAgent)?;
+        let signed_data = SshSig::signed_data(NAMESPACE, HashAlg::Sha512, data)?;
+
+        let mut request = vec![SSH_AGENTC_SIGN_REQUEST];
+        put_string(&mut request, &public_key.to_bytes()?);
+        put_string(&mut request, &signed_data);
+        request.extend_from_slice(&0_u32.to_be_bytes()); // flags
+
+        let mut stream = UnixStream::connect(socket)?;
+        let mut message = vec![];
+        put_string(&mut message, &request);
+        stream.write_all(&message)?;
+
+        let mut len = [0; 4];
+        stream.read_exact(&mut len)?;
+        let len = usize::try_from(u32::from_be_bytes(len)).unwrap();
+        if len > MAX_MESSAGE_LEN {
+            return Err(FallbackReason::AgentFailure);
+        }
+        let mut response = vec![0; len];
+        stream.read_exact(&mut response)?;
+
+        match response.split_first() {
+            Some((&SSH_AGENT_SIGN_RESPONSE, rest)) => {
+                let blob = get_string(rest).ok_or(FallbackReason::AgentFailure)?;
+                let signature = Signature::try_from(blob)?;
+                Ok(SshSig::new(
+                    public_key.key_data().clone(),
+                    NAMESPACE,
+                    HashAlg::Sha512,
+                    signature,
+                )?)
+            }
+            _ => Err(FallbackReason::AgentFailure),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod agent {
+    use ssh_key::PublicKey;
+    use ssh_key::SshSig;
+
+    use super::FallbackReason;
+
+    pub(super) fn sign(_public_key: &PublicKey, _data: &[u8]) -> Result<SshSig, FallbackReason> {
+        Err(FallbackReason::NoAgent)
+    }
+}
+
 impl SshBackend {
     pub fn new(
         program: OsString,
@@ -130,9 +450,18 @@ impl SshBackend {
             program,
             allowed_signers,
             revocation_list,
+            in_process: true,
+            trusted_keys: OnceLock::new(),
         }
     }
 
+    /// Whether to sign and verify without running the SSH program where
+    /// possible. Enabled by default.
+    pub fn with_in_process(mut self, in_process: bool) -> Self {
+        self.in_process = in_process;
+        self
+    }
+
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         let program = settings.get_string("signing.backends.ssh.program")?;
 
@@ -145,12 +474,85 @@ impl SshBackend {
 
         let allowed_signers = get_expanded_path("signing.backends.ssh.allowed-signers")?;
         let revocation_list = get_expanded_path("signing.backends.ssh.revocation-list")?;
+        let in_process = settings.get_bool("signing.backends.ssh.in-process")?;
 
         Ok(Self::new(
             program.into(),
             allowed_signers.map(Into::into),
             revocation_list.map(Into::into),
-        ))
+        )
+        .with_in_process(in_process))
+    }
+
+    fn load_trusted_keys(&self) -> Result<TrustedKeys, FallbackReason> {
+        let signers = match &self.allowed_signers {
+            Some(path) => parse_allowed_signers(&fs::read_to_string(path)?)?,
+            None => vec![],
+        };
+        let revoked = match &self.revocation_list {
+            Some(path) => parse_revocation_list(&fs::read(path)?)?,
+            None => vec![],
+        };
+        Ok(TrustedKeys { signers, revoked })
+    }
+
+    fn trusted_keys(&self) -> Option<&TrustedKeys> {
+        self.trusted_keys
+            .get_or_init(|| {
+                self.load_trusted_keys()
+                    .inspect_err(|err| {
+                        tracing::debug!(?err, "can't verify SSH signatures in-process");
+                    })
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// Verifies the signature with the same semantics as `ssh-keygen -Y
+    /// verify`. Returns `None` if the SSH program has to be run instead.
+    fn verify_in_process(&self, data: &[u8], signature: &[u8]) -> Option<Verification> {
+        let trusted_keys = self.trusted_keys()?;
+        let Ok(signature) = SshSig::from_pem(signature) else {
+            return Some(Verification::new(SigStatus::Bad, None, None));
+        };
+        let key_data = signature.public_key();
+        if !is_supported_algorithm(&key_data.algorithm()) {
+            return None;
+        }
+        let is_valid = PublicKey::from(key_data.clone())
+            .verify(NAMESPACE, data, &signature)
+            .is_ok();
+        let fingerprint = key_data.fingerprint(HashAlg::Sha256).to_string();
+
+        let signers = trusted_keys
+            .signers
+            .iter()
+            .filter(|signer| signer.key == *key_data)
+            .collect::<Vec<_>>();
+        // Like `ssh-keygen -Y verify -I <principal>`, any entry of the key
+        // matching the first principal can allow the namespace.
+        let verification = match signers.first() {
+            Some(first) => {
+                let principal = &first.principal;
+                let is_allowed = signers.iter().any(|signer| {
+                    match_pattern_list(principal, &signer.principals)
+                        && signer.allows_namespace(NAMESPACE)
+                });
+                let is_good = is_valid && is_allowed && !trusted_keys.revoked.contains(key_data);
+                if is_good {
+                    Verification::new(SigStatus::Good, Some(fingerprint), Some(principal.clone()))
+                } else {
+                    Verification::new(SigStatus::Bad, None, Some(principal.clone()))
+                }
+            }
+            None if is_valid => Verification::new(
+                SigStatus::Unknown,
+                Some(fingerprint),
+                Some("Signature OK. Unknown principal".into()),
+            ),
+            None => Verification::new(SigStatus::Bad, None, None),
+        };
+        Some(verification)
     }
 
     fn create_command(&self) -> Command {
@@ -223,6 +625,13 @@ impl SigningBackend for SshBackend {
             return Err(SshError::MissingKey.into());
         };
 
+        if self.in_process {
+            match sign_in_process(data, key) {
+                Ok(signature) => return Ok(signature),
+                Err(err) => tracing::debug!(?err, "falling back to SSH program for signing"),
+            }
+        }
+
         // The ssh-keygen `-f` flag expects to be given a file which contains either a
         // private or public key.
         //
@@ -242,12 +651,18 @@ impl SigningBackend for SshBackend {
             .arg("-f")
             .arg(path)
             .arg("-n")
-            .arg("git");
+            .arg(NAMESPACE);
 
         Ok(run_command(&mut command, data)?)
     }
 
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<Verification, SignError> {
+        if self.in_process
+            && let Some(verification) = self.verify_in_process(data, signature)
+        {
+            return Ok(verification);
+        }
+
         let mut signature_file = tempfile::Builder::new()
             .prefix(".jj-ssh-sig-")
             .tempfile()
@@ -273,7 +688,7 @@ impl SigningBackend for SshBackend {
                     .arg("-f")
                     .arg(allowed_signers)
                     .arg("-n")
-                    .arg("git");
+                    .arg(NAMESPACE);
 
                 if let Some(revocation_list) = self.revocation_list.as_ref() {
                     command.arg("-r").arg(revocation_list);
@@ -294,7 +709,7 @@ impl SigningBackend for SshBackend {
                     .arg("-s")
                     .arg(&signature_file_path)
                     .arg("-n")
-                    .arg("git");
+                    .arg(NAMESPACE);
 
                 let result = run_command(&mut command, data);
 
@@ -316,6 +731,8 @@ mod tests {
     use std::fs::File;
     use std::io::Read as _;
 
+    use assert_matches::assert_matches;
+
     use super::*;
 
     #[test]
@@ -365,4 +782,53 @@ mod tests {
             path.left().unwrap().to_str().unwrap()
         );
     }
+
+    #[test]
+    fn test_parse_allowed_signers() {
+        let key =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h";
+        let content = format!(
+            "# comment\n\
+             \n\
+             a@example.com,b@example.com {key} comment\n\
+             c@example.com namespaces=\"file,git\" {key}\n\
+             \"d@example.com\" NAMESPACES=\"!git,*\" {key}\n\
+             e@example.com INVALID KEY\n"
+        );
+        let signers = parse_allowed_signers(&content).unwrap();
+        assert_eq!(signers.len(), 3);
+        assert_eq!(signers[0].principal, "a@example.com");
+        assert!(signers[0].allows_namespace("git"));
+        assert_eq!(signers[1].principal, "c@example.com");
+        assert!(signers[1].allows_namespace("git"));
+        assert!(!signers[1].allows_namespace("other"));
+        assert_eq!(signers[2].principal, "d@example.com");
+        assert!(!signers[2].allows_namespace("git"));
+        assert!(signers[2].allows_namespace("file"));
+
+        let content = format!("a@example.com cert-authority {key}\n");
+        assert_matches!(
+            parse_allowed_signers(&content),
+            Err(FallbackReason::UnsupportedOption(name)) if name == "cert-authority"
+        );
+        let content = format!("a@example.com namespaces=\"git\",valid-after=\"20200101\" {key}\n");
+        assert_matches!(
+            parse_allowed_signers(&content),
+            Err(FallbackReason::UnsupportedOption(name)) if name == "valid-after"
+        );
+    }
+
+    #[test]
+    fn test_match_pattern_list() {
+        assert!(match_pattern_list("git", "git"));
+        assert!(match_pattern_list("git", "file,git"));
+        assert!(match_pattern_list("git", "g?t"));
+        assert!(match_pattern_list("git", "*"));
+        assert!(match_pattern_list("git", "g*"));
+        assert!(!match_pattern_list("git", "gi"));
+        assert!(!match_pattern_list("git", "file"));
+        assert!(!match_pattern_list("git", "*,!git"));
+        assert!(!match_pattern_list("git", "!g*,git"));
+        assert!(!match_pattern_list("git", "!file"));
+    }
 }
@@ -14,6 +14,7 @@
 
 #![expect(missing_docs)]
 
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::pin::Pin;
@@ -22,6 +23,10 @@ use std::sync::Mutex;
 use std::time::SystemTime;
 
 use clru::CLruCache;
+use futures::StreamExt as _;
+use futures::TryFutureExt as _;
+use futures::TryStreamExt as _;
+use futures::stream;
 use futures::stream::BoxStream;
 use pollster::FutureExt as _;
 use tokio::io::AsyncRead;
@@ -31,17 +36,24 @@ use crate::backend::Backend;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
+use crate::backend::CopyHistory;
+use crate::backend::CopyId;
 use crate::backend::CopyRecord;
 use crate::backend::FileId;
 use crate::backend::SigningFn;
 use crate::backend::SymlinkId;
 use crate::backend::TreeId;
 use crate::commit::Commit;
+use crate::config::ConfigGetError;
+use crate::copies::CopyDetectionOptions;
+use crate::copies::detect_copies;
+use crate::copies::get_recorded_copies;
 use crate::index::Index;
 use crate::merge::Merge;
 use crate::merged_tree::MergedTree;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::settings::UserSettings;
 use crate::signing::Signer;
 use crate::tree::Tree;
 use crate::tree_merge::MergeOptions;
@@ -59,6 +71,7 @@ pub struct Store {
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
     merge_options: MergeOptions,
+    copy_detection: CopyDetectionOptions,
 }
 
 impl Debug for Store {
@@ -74,6 +87,35 @@ impl Store {
         backend: Box<dyn Backend>,
         signer: Signer,
         merge_options: MergeOptions,
+    ) -> Arc<Self> {
+        Self::with_copy_detection(
+            backend,
+            signer,
+            merge_options,
+            CopyDetectionOptions::default(),
+        )
+    }
+
+    /// Creates a store with merge and copy detection options loaded from
+    /// `settings`.
+    pub fn from_settings(
+        backend: Box<dyn Backend>,
+        signer: Signer,
+        settings: &UserSettings,
+    ) -> Result<Arc<Self>, ConfigGetError> {
+        Ok(Self::with_copy_detection(
+            backend,
+            signer,
+            MergeOptions::from_settings(settings)?,
+            CopyDetectionOptions::from_settings(settings)?,
+        ))
+    }
+
+    fn with_copy_detection(
+        backend: Box<dyn Backend>,
+        signer: Signer,
+        merge_options: MergeOptions,
+        copy_detection: CopyDetectionOptions,
     ) -> Arc<Self> {
         Arc::new(Self {
             backend,
@@ -81,6 +123,7 @@ impl Store {
             commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
             merge_options,
+            copy_detection,
         })
     }
 
@@ -102,13 +145,48 @@ impl Store {
         &self.merge_options
     }
 
+    /// Options for detecting copies not reported by the backend.
+    pub fn copy_detection(&self) -> &CopyDetectionOptions {
+        &self.copy_detection
+    }
+
+    /// Returns copy records recorded in the copy IDs of the files in `head`,
+    /// the ones reported by the backend, and the ones detected by content
+    /// similarity if enabled. Recorded copies take precedence.
     pub fn get_copy_records(
-        &self,
+        self: &Arc<Self>,
         paths: Option<&[RepoPathBuf]>,
         root: &CommitId,
         head: &CommitId,
     ) -> BackendResult<BoxStream<'_, BackendResult<CopyRecord>>> {
-        self.backend.get_copy_records(paths, root, head)
+        let backend_records = self.backend.get_copy_records(paths, root, head)?;
+        let paths = paths.map(|paths| paths.to_vec());
+        let (root, head) = (root.clone(), head.clone());
+        let stream = async move {
+            let mut records = get_recorded_copies(self, &root, &head, paths.as_deref()).await?;
+            let recorded_targets: HashSet<_> =
+                records.iter().map(|record| record.target.clone()).collect();
+            let backend_records: Vec<_> = backend_records.try_collect().await?;
+            records.extend(
+                backend_records
+                    .into_iter()
+                    .filter(|record| !recorded_targets.contains(&record.target)),
+            );
+            if self.copy_detection.enabled {
+                let detected = detect_copies(
+                    self,
+                    &root,
+                    &head,
+                    paths.as_deref(),
+                    &records,
+                    &self.copy_detection,
+                )
+                .await?;
+                records.extend(detected);
+            }
+            Ok(stream::iter(records.into_iter().map(Ok)))
+        };
+        Ok(stream.try_flatten_stream().boxed())
     }
 
     pub fn commit_id_length(&self) -> usize {
@@ -259,6 +337,18 @@ impl Store {
         self.backend.write_symlink(path, contents).await
     }
 
+    pub async fn read_copy(&self, id: &CopyId) -> BackendResult<CopyHistory> {
+        self.backend.read_copy(id).await
+    }
+
+    pub async fn write_copy(&self, copy: &CopyHistory) -> BackendResult<CopyId> {
+        self.backend.write_copy(copy).await
+    }
+
+    pub async fn get_related_copies(&self, copy_id: &CopyId) -> BackendResult<Vec<CopyHistory>> {
+        self.backend.get_related_copies(copy_id).await
+    }
+
     pub fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
         self.backend.gc(index, keep_newer)
     }
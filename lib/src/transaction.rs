@@ -29,6 +29,7 @@ use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store;
 use crate::op_store::OpStoreError;
 use crate::op_store::OperationMetadata;
+use crate::op_store::OperationSignature;
 use crate::op_store::TimestampRange;
 use crate::operation::Operation;
 use crate::repo::MutableRepo;
@@ -36,7 +37,9 @@ use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
 use crate::repo::RepoLoaderError;
+use crate::settings::SignSettings;
 use crate::settings::UserSettings;
+use crate::signing::SignError;
 use crate::view::View;
 
 /// Error from attempts to write and publish transaction.
@@ -46,6 +49,7 @@ pub enum TransactionCommitError {
     IndexStore(#[from] IndexStoreError),
     OpHeadsStore(#[from] OpHeadsStoreError),
     OpStore(#[from] OpStoreError),
+    Sign(#[from] SignError),
 }
 
 /// An in-memory representation of a repo and any changes being made to it.
@@ -64,6 +68,7 @@ pub struct Transaction {
     parent_ops: Vec<Operation>,
     op_metadata: OperationMetadata,
     end_time: Option<Timestamp>,
+    sign_settings: SignSettings,
 }
 
 impl Transaction {
@@ -71,11 +76,13 @@ impl Transaction {
         let parent_ops = vec![mut_repo.base_repo().operation().clone()];
         let op_metadata = create_op_metadata(user_settings, "".to_string(), false);
         let end_time = user_settings.operation_timestamp();
+        let sign_settings = user_settings.sign_settings();
         Self {
             mut_repo,
             parent_ops,
             op_metadata,
             end_time,
+            sign_settings,
         }
     }
 
@@ -148,12 +155,22 @@ impl Transaction {
             self.op_metadata.description = description.into();
             self.op_metadata.time.end = self.end_time.unwrap_or_else(Timestamp::now);
             let parents = self.parent_ops.iter().map(|op| op.id().clone()).collect();
-            let store_operation = op_store::Operation {
+            let mut store_operation = op_store::Operation {
                 view_id,
                 parents,
                 metadata: self.op_metadata,
                 commit_predecessors: Some(predecessors),
+                signature: OperationSignature::default(),
             };
+            let signer = base_repo.store().signer();
+            if self.sign_settings.sign_operations {
+                if !signer.can_sign() {
+                    return Err(SignError::NoBackend.into());
+                }
+                let data = store_operation.signed_data();
+                let signature = signer.sign(&data, self.sign_settings.key.as_deref())?;
+                store_operation.signature = OperationSignature::new(signature);
+            }
             let new_op_id = base_repo
                 .op_store()
                 .write_operation(&store_operation)
@@ -37,10 +37,14 @@ use crate::backend::TreeValue;
 use crate::config::ConfigGetError;
 use crate::files;
 use crate::files::FileMergeHunkLevel;
+use crate::gitattributes::GitAttributes;
+use crate::gitattributes::PathAttributes;
 use crate::merge::Merge;
 use crate::merge::MergedTreeVal;
 use crate::merge::MergedTreeValue;
 use crate::merge::SameChange;
+use crate::merge_driver::MergeDrivers;
+use crate::merged_tree::MergedTree;
 use crate::merged_tree::all_merged_tree_entries;
 use crate::object_id::ObjectId as _;
 use crate::repo_path::RepoPath;
@@ -57,6 +61,9 @@ pub struct MergeOptions {
     pub hunk_level: FileMergeHunkLevel,
     /// Whether to resolve conflict that makes the same change at all sides.
     pub same_change: SameChange,
+    /// Drivers used to merge file contents. External programs are only run by
+    /// merges requesting them, such as the ones done when rewriting commits.
+    pub drivers: MergeDrivers,
 }
 
 impl MergeOptions {
@@ -67,20 +74,32 @@ impl MergeOptions {
             // needed. It wouldn't be translated to FileMergeHunkLevel.
             hunk_level: settings.get("merge.hunk-level")?,
             same_change: settings.get("merge.same-change")?,
+            drivers: MergeDrivers::from_settings(settings)?,
         })
     }
 }
 
 /// The returned conflict will either be resolved or have the same number of
 /// sides as the input.
-pub async fn merge_trees(store: &Arc<Store>, merge: Merge<TreeId>) -> BackendResult<Merge<TreeId>> {
+///
+/// File contents are merged by the configured merge drivers. Drivers running
+/// external programs are only used if `run_external_drivers` is true.
+pub async fn merge_trees(
+    store: &Arc<Store>,
+    merge: Merge<TreeId>,
+    run_external_drivers: bool,
+) -> BackendResult<Merge<TreeId>> {
     let merge = match merge.into_resolved() {
         Ok(tree) => return Ok(Merge::resolved(tree)),
         Err(merge) => merge,
     };
 
+    // Like Git, attributes are read from our side of the merge.
+    let attributes_tree = MergedTree::resolved(store.clone(), merge.first().clone());
     let mut merger = TreeMerger {
         store: store.clone(),
+        attributes: Arc::new(GitAttributes::new(attributes_tree)),
+        run_external_drivers,
         trees_to_resolve: BTreeMap::new(),
         work: FuturesUnordered::new(),
         unstarted_work: BTreeMap::new(),
@@ -195,6 +214,10 @@ enum TreeMergeWorkItemKey {
 
 struct TreeMerger {
     store: Arc<Store>,
+    // Attributes used to decide whether file contents should be merged.
+    attributes: Arc<GitAttributes>,
+    // Whether to run merge drivers which are external programs.
+    run_external_drivers: bool,
     // Trees we're currently working on.
     trees_to_resolve: BTreeMap<RepoPathBuf, MergedTreeInput>,
     // Futures we're currently processing. In order to respect the backend's concurrency limit.
@@ -305,8 +328,14 @@ impl TreeMerger {
 
     fn enqueue_file_merge(&mut self, path: RepoPathBuf, value: MergedTreeValue) {
         let key = TreeMergeWorkItemKey::MergeFiles { path: path.clone() };
-        let work_fut = resolve_file_values_owned(self.store.clone(), path.clone(), value)
-            .map(|result| TreeMergerWorkOutput::MergedFiles { path, result });
+        let work_fut = resolve_file_values_owned(
+            self.store.clone(),
+            self.attributes.clone(),
+            self.run_external_drivers,
+            path.clone(),
+            value,
+        )
+        .map(|result| TreeMergerWorkOutput::MergedFiles { path, result });
         if self.work.len() < self.store.concurrency() {
             self.work.push(Box::pin(work_fut));
         } else {
@@ -358,13 +387,28 @@ async fn write_trees(
 
 async fn resolve_file_values_owned(
     store: Arc<Store>,
+    attributes: Arc<GitAttributes>,
+    run_external_drivers: bool,
     path: RepoPathBuf,
     values: MergedTreeValue,
 ) -> BackendResult<MergedTreeValue> {
-    let maybe_resolved = try_resolve_file_values(&store, &path, &values).await?;
+    let maybe_resolved = try_resolve_file_values(
+        &store,
+        Some(&attributes),
+        run_external_drivers,
+        &path,
+        &values,
+    )
+    .await?;
     Ok(maybe_resolved.unwrap_or(values))
 }
 
+/// Returns false if the attributes say that the file contents shouldn't be
+/// merged, in which case the file is left conflicted.
+fn is_mergeable(attributes: &PathAttributes) -> bool {
+    !attributes.is_unset("merge") && attributes.value("merge") != Some("binary")
+}
+
 /// Tries to resolve file conflicts by merging the file contents. Treats missing
 /// files as empty. If the file conflict cannot be resolved, returns the passed
 /// `values` unmodified.
@@ -378,12 +422,14 @@ pub async fn resolve_file_values(
         return Ok(Merge::resolved(resolved.clone()));
     }
 
-    let maybe_resolved = try_resolve_file_values(store, path, &values).await?;
+    let maybe_resolved = try_resolve_file_values(store, None, false, path, &values).await?;
     Ok(maybe_resolved.unwrap_or(values))
 }
 
 async fn try_resolve_file_values<T: Borrow<TreeValue>>(
     store: &Arc<Store>,
+    attributes: Option<&GitAttributes>,
+    run_external_drivers: bool,
     path: &RepoPath,
     values: &Merge<Option<T>>,
 ) -> BackendResult<Option<MergedTreeValue>> {
@@ -394,7 +440,10 @@ async fn try_resolve_file_values<T: Borrow<TreeValue>>(
         .simplify();
     // No fast path for simplified.is_resolved(). If it could be resolved, it would
     // have been caught by values.resolve_trivial() above.
-    if let Some(resolved) = try_resolve_file_conflict(store, path, &simplified).await? {
+    if let Some(resolved) =
+        try_resolve_file_conflict(store, attributes, run_external_drivers, path, &simplified)
+            .await?
+    {
         Ok(Some(Merge::normal(resolved)))
     } else {
         // Failed to merge the files, or the paths are not files
@@ -405,9 +454,14 @@ async fn try_resolve_file_values<T: Borrow<TreeValue>>(
 /// Resolves file-level conflict by merging content hunks.
 ///
 /// The input `conflict` is supposed to be simplified. It shouldn't contain
-/// non-file values that cancel each other.
+/// non-file values that cancel each other. If `attributes` are given, files
+/// marked as unmergeable are left conflicted. The matching merge driver is used
+/// instead of the built-in line-based merge, unless it runs an external program
+/// and `run_external_drivers` is false.
 async fn try_resolve_file_conflict(
     store: &Store,
+    attributes: Option<&GitAttributes>,
+    run_external_drivers: bool,
     filename: &RepoPath,
     conflict: &MergedTreeVal<'_>,
 ) -> BackendResult<Option<TreeValue>> {
@@ -473,6 +527,13 @@ async fn try_resolve_file_conflict(
     // 2. The simplified conflict can sometimes be resolved when the unsimplfied one
     //    cannot
     let file_id_conflict = file_id_conflict.simplify();
+    let path_attributes = match attributes {
+        Some(attributes) => attributes.get(filename).await?,
+        None => PathAttributes::default(),
+    };
+    if !is_mergeable(&path_attributes) {
+        return Ok(None);
+    }
 
     let contents = file_id_conflict
         .try_map_async(async |file_id| {
@@ -489,7 +550,24 @@ async fn try_resolve_file_conflict(
             BackendResult::Ok(content)
         })
         .await?;
-    if let Some(merged_content) = files::try_merge(&contents, options) {
+    let driver = options
+        .drivers
+        .find(filename, &path_attributes)
+        .filter(|driver| run_external_drivers || !driver.is_external());
+    let merged_content = match driver {
+        Some(driver) => match driver.merge(filename, &contents, options).await {
+            Ok(merged_content) => merged_content,
+            // Leave the conflict to the user if the driver failed. The error
+            // is recorded so the application can tell the user why.
+            Err(err) => {
+                tracing::warn!(?err, ?filename, "merge driver failed");
+                options.drivers.record_failure(filename, err);
+                None
+            }
+        },
+        None => files::try_merge(&contents, options),
+    };
+    if let Some(merged_content) = merged_content {
         let id = store
             .write_file(filename, &mut merged_content.as_slice())
             .await?;
@@ -131,6 +131,42 @@ fn create_jj_dir(workspace_root: &Path) -> Result<PathBuf, WorkspaceInitError> {
     }
 }
 
+/// Writes `.jj/repo` file pointing to the repo at `repo_path`. Returns the
+/// canonicalized repo path.
+fn write_repo_pointer(jj_dir: &Path, repo_path: &Path) -> Result<PathBuf, WorkspaceInitError> {
+    let repo_dir = dunce::canonicalize(repo_path).context(repo_path)?;
+    let jj_dir_abs = dunce::canonicalize(jj_dir).context(jj_dir)?;
+    let path_to_store = file_util::relative_path(&jj_dir_abs, &repo_dir);
+    let path_to_store = if path_to_store.is_relative() {
+        file_util::slash_path(&path_to_store).into_owned()
+    } else {
+        path_to_store
+    };
+    let repo_dir_bytes =
+        file_util::path_to_bytes(&path_to_store).map_err(WorkspaceInitError::EncodeRepoPath)?;
+    let repo_file_path = jj_dir.join("repo");
+    fs::write(&repo_file_path, repo_dir_bytes).context(&repo_file_path)?;
+    Ok(repo_dir)
+}
+
+/// Updates the workspace at `workspace_root` to point to the repo at
+/// `repo_path`.
+///
+/// The repo location is stored as a relative path, so this needs to be called
+/// after the workspace directory is moved. Workspaces which store the repo
+/// itself are left unchanged.
+pub fn update_workspace_repo_path(
+    workspace_root: &Path,
+    repo_path: &Path,
+) -> Result<(), WorkspaceInitError> {
+    let jj_dir = workspace_root.join(".jj");
+    if jj_dir.join("repo").is_dir() {
+        return Ok(());
+    }
+    write_repo_pointer(&jj_dir, repo_path)?;
+    Ok(())
+}
+
 fn init_working_copy(
     repo: &Arc<ReadonlyRepo>,
     workspace_root: &Path,
@@ -364,19 +400,7 @@ impl Workspace {
         workspace_name: WorkspaceNameBuf,
     ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
         let jj_dir = create_jj_dir(workspace_root)?;
-
-        let repo_dir = dunce::canonicalize(repo_path).context(repo_path)?;
-        let jj_dir_abs = dunce::canonicalize(&jj_dir).context(&jj_dir)?;
-        let path_to_store = file_util::relative_path(&jj_dir_abs, &repo_dir);
-        let path_to_store = if path_to_store.is_relative() {
-            file_util::slash_path(&path_to_store).into_owned()
-        } else {
-            path_to_store
-        };
-        let repo_dir_bytes =
-            file_util::path_to_bytes(&path_to_store).map_err(WorkspaceInitError::EncodeRepoPath)?;
-        let repo_file_path = jj_dir.join("repo");
-        fs::write(&repo_file_path, repo_dir_bytes).context(&repo_file_path)?;
+        let repo_dir = write_repo_pointer(&jj_dir, repo_path)?;
 
         let workspace_store = SimpleWorkspaceStore::load(repo_path)?;
         let (working_copy, repo) = init_working_copy(
@@ -15,15 +15,19 @@
 use std::fmt::Write as _;
 use std::sync::Arc;
 
+use bstr::ByteSlice as _;
 use itertools::Itertools as _;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::annotate::FileAnnotator;
+use jj_lib::annotate::LineRangeChange;
+use jj_lib::annotate::trace_line_range;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
+use jj_lib::diff_presentation::DiffAlgorithm;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::repo::MutableRepo;
 use jj_lib::repo::Repo;
@@ -31,6 +35,7 @@ use jj_lib::repo_path::RepoPath;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::RevsetExpression;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 use testutils::create_tree;
 use testutils::read_file;
 use testutils::repo_path;
@@ -410,3 +415,321 @@ fn test_annotate_file_directory_transition() {
 
     insta::assert_snapshot!(annotate(tx.repo(), &commit2, file_path2), @"commit2:1 : 2");
 }
+
+#[test]
+fn test_annotate_copied_file() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path1 = repo_path("file1");
+    let file_path2 = repo_path("file2");
+    let file_path3 = repo_path("file3");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path1, "1a\n1b\n1c\n")]);
+    let tree2 = create_tree(repo, &[(file_path1, "1a\n2\n1c\n")]);
+    let tree3 = create_tree(repo, &[(file_path2, "1a\n2\n1c\n")]);
+    let tree4 = create_tree(
+        repo,
+        &[(file_path2, "1a\n2\n1c\n4\n"), (file_path3, "1a\n2\n1c\n")],
+    );
+    let tree5 = create_tree(
+        repo,
+        &[
+            (file_path2, "1a\n2\n1c\n4\n"),
+            (file_path3, "1a\n2\n1c\n5\n"),
+        ],
+    );
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3); // rename
+    let commit4 = create_commit("commit4", &[commit3.id()], tree4); // copy
+    let commit5 = create_commit("commit5", &[commit4.id()], tree5);
+    drop(create_commit);
+
+    let annotate_following = |commit: &Commit, file_path: &RepoPath| {
+        let mut annotator = FileAnnotator::from_commit(commit, file_path).unwrap();
+        annotator.set_follow_copies(true);
+        annotator
+            .compute(tx.repo(), &RevsetExpression::all())
+            .unwrap();
+        format_annotation(tx.repo(), &annotator.to_annotation())
+    };
+
+    insta::assert_snapshot!(annotate(tx.repo(), &commit5, file_path3), @r"
+    commit4:1 : 1a
+    commit4:2 : 2
+    commit4:3 : 1c
+    commit5:4 : 5
+    ");
+    insta::assert_snapshot!(annotate_following(&commit3, file_path2), @r"
+    commit1:1 : 1a
+    commit2:2 : 2
+    commit1:3 : 1c
+    ");
+    insta::assert_snapshot!(annotate_following(&commit5, file_path3), @r"
+    commit1:1 : 1a
+    commit2:2 : 2
+    commit1:3 : 1c
+    commit5:4 : 5
+    ");
+}
+
+#[test]
+fn test_annotate_block_algorithm() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let content1 = "fn a() {\n    x\n}\n";
+    let content2 = "fn a() {\n    y\n}\n\nfn b() {\n    x\n}\n";
+    let tree1 = create_tree(repo, &[(file_path, content1)]);
+    let tree2 = create_tree(repo, &[(file_path, content2)]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    drop(create_commit);
+
+    let annotate_with = |algorithm| {
+        let mut annotator = FileAnnotator::from_commit(&commit2, file_path).unwrap();
+        annotator.set_diff_algorithm(algorithm);
+        annotator
+            .compute(tx.repo(), &RevsetExpression::all())
+            .unwrap();
+        format_annotation(tx.repo(), &annotator.to_annotation())
+    };
+
+    // The body of the new function is attributed to the old function
+    insta::assert_snapshot!(annotate_with(DiffAlgorithm::Line), @"
+    commit1:1 : fn a() {
+    commit2:2 :     y
+    commit2:3 : }
+    commit2:4 : 
+    commit2:5 : fn b() {
+    commit1:2 :     x
+    commit1:3 : }
+    ");
+    // The old function is matched by name
+    insta::assert_snapshot!(annotate_with(DiffAlgorithm::Block), @"
+    commit1:1 : fn a() {
+    commit2:2 :     y
+    commit1:3 : }
+    commit2:4 : 
+    commit2:5 : fn b() {
+    commit2:6 :     x
+    commit2:7 : }
+    ");
+}
+
+#[test]
+fn test_annotate_line_range() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "1a\n1b\n1c\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "1a\n2b\n1c\n")]);
+    let tree3 = create_tree(repo, &[(file_path, "3a\n2b\n1c\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3);
+    drop(create_commit);
+
+    let annotate_range = |range| {
+        let mut annotator = FileAnnotator::from_commit(&commit3, file_path).unwrap();
+        annotator.set_line_range(range);
+        annotator
+            .compute(tx.repo(), &RevsetExpression::all())
+            .unwrap();
+        assert_eq!(annotator.pending_commits().count(), 0);
+        format_annotation(tx.repo(), &annotator.to_annotation())
+    };
+
+    // Lines outside of the range are left unresolved
+    insta::assert_snapshot!(annotate_range(1..2), @r"
+    commit3:1*: 3a
+    commit2:2 : 2b
+    commit3:3*: 1c
+    ");
+    insta::assert_snapshot!(annotate_range(1..3), @r"
+    commit3:1*: 3a
+    commit2:2 : 2b
+    commit1:3 : 1c
+    ");
+    insta::assert_snapshot!(annotate_range(3..5), @r"
+    commit3:1*: 3a
+    commit3:2*: 2b
+    commit3:3*: 1c
+    ");
+}
+
+#[test]
+fn test_annotate_ignored_revisions() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "1a\n1b\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "1a\n1b\n2c\n")]);
+    let tree3 = create_tree(repo, &[(file_path, "3a\n3b\n2c\n3d\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3);
+    drop(create_commit);
+
+    let annotate_ignoring = |ignored: &[&CommitId]| {
+        let mut annotator = FileAnnotator::from_commit(&commit3, file_path).unwrap();
+        annotator.set_ignored_revisions(RevsetExpression::commits(
+            ignored.iter().map(|&id| id.clone()).collect(),
+        ));
+        annotator
+            .compute(tx.repo(), &RevsetExpression::all())
+            .unwrap();
+        format_annotation(tx.repo(), &annotator.to_annotation())
+    };
+
+    insta::assert_snapshot!(annotate_ignoring(&[]), @r"
+    commit3:1 : 3a
+    commit3:2 : 3b
+    commit2:3 : 2c
+    commit3:4 : 3d
+    ");
+    // Changed lines are attributed to the parent, but the added line isn't
+    insta::assert_snapshot!(annotate_ignoring(&[commit3.id()]), @r"
+    commit1:1 : 3a
+    commit1:2 : 3b
+    commit2:3 : 2c
+    commit3:4 : 3d
+    ");
+    // Ignored revisions are looked through transitively
+    insta::assert_snapshot!(annotate_ignoring(&[commit2.id(), commit3.id()]), @r"
+    commit1:1 : 3a
+    commit1:2 : 3b
+    commit2:3 : 2c
+    commit3:4 : 3d
+    ");
+}
+
+#[test]
+fn test_annotate_ignored_revisions_similar_lines() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = repo_path("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path, "f(x,y)\ng(z)\n")]);
+    let tree2 = create_tree(repo, &[(file_path, "{\n  F( x, y )\n  G( z )\n}\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    drop(create_commit);
+
+    let mut annotator = FileAnnotator::from_commit(&commit2, file_path).unwrap();
+    annotator.set_ignored_revisions(RevsetExpression::commit(commit2.id().clone()));
+    annotator
+        .compute(tx.repo(), &RevsetExpression::all())
+        .unwrap();
+    // Changed lines are mapped to the similar lines, not by position
+    insta::assert_snapshot!(format_annotation(tx.repo(), &annotator.to_annotation()), @r"
+    commit2:1 : {
+    commit1:1 :   F( x, y )
+    commit1:2 :   G( z )
+    commit2:4 : }
+    ");
+}
+
+fn format_line_range_changes(repo: &dyn Repo, changes: &[LineRangeChange]) -> String {
+    let mut output = String::new();
+    for change in changes {
+        let commit = repo.store().get_commit(&change.current.commit_id).unwrap();
+        let desc = commit.description().trim_end();
+        let format_version = |path: &RepoPath, range: &std::ops::Range<usize>| {
+            format!(
+                "{}:{}-{}",
+                path.as_internal_file_string(),
+                range.start + 1,
+                range.end
+            )
+        };
+        let current = format_version(&change.current.path, &change.current.range);
+        let parent = match &change.parent {
+            Some(parent) => format_version(&parent.path, &parent.range),
+            None => "none".to_owned(),
+        };
+        writeln!(output, "{desc}: {parent} -> {current}").unwrap();
+        for line in change.current.text.lines() {
+            writeln!(output, "  {}", line.to_str_lossy()).unwrap();
+        }
+    }
+    output
+}
+
+#[test]
+fn test_trace_line_range() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path1 = repo_path("file1");
+    let file_path2 = repo_path("file2");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let tree1 = create_tree(repo, &[(file_path1, "a\nb\nc\n")]);
+    let tree2 = create_tree(repo, &[(file_path1, "a\nB\nc\nd\n")]);
+    let tree3 = create_tree(repo, &[(file_path1, "x\na\nB\nc\nd\n")]);
+    let tree4 = create_tree(repo, &[(file_path2, "x\na\nB\nc\nd\n")]);
+    let tree5 = create_tree(repo, &[(file_path2, "x\na\nB\nC\nd\n")]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1);
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2);
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3);
+    let commit4 = create_commit("commit4", &[commit3.id()], tree4); // rename
+    let commit5 = create_commit("commit5", &[commit4.id()], tree5);
+    drop(create_commit);
+
+    let trace = |range| {
+        let changes = trace_line_range(
+            tx.repo(),
+            &commit5,
+            file_path2,
+            range,
+            DiffAlgorithm::Line,
+            &RevsetExpression::all(),
+        )
+        .unwrap();
+        format_line_range_changes(tx.repo(), &changes)
+    };
+
+    // Moved lines and renames are followed, but aren't reported as changes
+    insta::assert_snapshot!(trace(2..4), @r"
+    commit5: file2:3-4 -> file2:3-4
+      B
+      C
+    commit2: file1:2-3 -> file1:2-3
+      B
+      c
+    commit1: none -> file1:2-3
+      b
+      c
+    ");
+    // Lines added by a commit have no counterpart in the parent
+    insta::assert_snapshot!(trace(0..1), @r"
+    commit3: file1:1-0 -> file1:1-1
+      x
+    ");
+}
@@ -27,6 +27,7 @@ use jj_lib::conflicts::update_from_content;
 use jj_lib::files::FileMergeHunkLevel;
 use jj_lib::merge::Merge;
 use jj_lib::merge::SameChange;
+use jj_lib::merge_driver::MergeDrivers;
 use jj_lib::repo::Repo as _;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::store::Store;
@@ -2547,6 +2548,7 @@ fn materialize_conflict_string_with_labels(
         merge: MergeOptions {
             hunk_level: FileMergeHunkLevel::Line,
             same_change: SameChange::Accept,
+            drivers: MergeDrivers::default(),
         },
     };
     String::from_utf8(
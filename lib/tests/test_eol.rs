@@ -16,6 +16,7 @@ use std::fs::File;
 use std::io::Write as _;
 
 use bstr::ByteSlice as _;
+use jj_lib::backend::TreeValue;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::repo::Repo as _;
@@ -583,3 +584,62 @@ fn test_eol_conversion_checkout(
     assert!(std::fs::exists(&file_disk_path).unwrap());
     std::fs::read(&file_disk_path).unwrap()
 }
+
+#[test_case("", CRLF_FILE_CONTENT => LF_FILE_CONTENT; "unspecified")]
+#[test_case("* -text", CRLF_FILE_CONTENT => CRLF_FILE_CONTENT; "text unset")]
+#[test_case("* binary", CRLF_FILE_CONTENT => CRLF_FILE_CONTENT; "binary")]
+#[test_case("*.txt -text", CRLF_FILE_CONTENT => LF_FILE_CONTENT; "other pattern")]
+#[test_case("* -text\n* text", CRLF_FILE_CONTENT => LF_FILE_CONTENT; "later line wins")]
+fn test_eol_conversion_snapshot_with_gitattributes(
+    attributes: &str,
+    file_content: &[u8],
+) -> Vec<u8> {
+    let user_settings =
+        base_user_settings_with_extra_configs("working-copy.eol-conversion = \"input\"\n");
+    let mut test_workspace =
+        TestWorkspace::init_with_backend_and_settings(TestRepoBackend::Git, &user_settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let attributes_path = repo_path(".gitattributes");
+    let file_repo_path = repo_path("test-eol-file");
+    // Attributes are read from the working copy, so they apply to files
+    // snapshotted together with the attributes file.
+    testutils::write_working_copy_file(&workspace_root, attributes_path, attributes);
+    testutils::write_working_copy_file(&workspace_root, file_repo_path, file_content);
+    let tree = test_workspace.snapshot().unwrap();
+    let value = tree.path_value(file_repo_path).unwrap();
+    let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+        panic!("The test file should be a resolved file: {value:?}");
+    };
+    testutils::read_file(test_workspace.repo.store(), file_repo_path, id)
+}
+
+#[test_case("", LF_FILE_CONTENT => LF_FILE_CONTENT; "unspecified")]
+#[test_case("*.bat eol=crlf", LF_FILE_CONTENT => CRLF_FILE_CONTENT; "eol crlf")]
+#[test_case("*.bat text eol=crlf", BINARY_FILE_CONTENT => BINARY_FILE_CONTENT; "eol crlf binary")]
+#[test_case("*.bat -text eol=crlf", LF_FILE_CONTENT => LF_FILE_CONTENT; "eol crlf text unset")]
+#[test_case("dir/*.bat eol=crlf", LF_FILE_CONTENT => LF_FILE_CONTENT; "other directory")]
+fn test_eol_conversion_checkout_with_gitattributes(
+    attributes: &str,
+    file_content: &[u8],
+) -> Vec<u8> {
+    let user_settings =
+        base_user_settings_with_extra_configs("working-copy.eol-conversion = \"none\"\n");
+    let mut test_workspace =
+        TestWorkspace::init_with_backend_and_settings(TestRepoBackend::Git, &user_settings);
+    let attributes_path = repo_path(".gitattributes");
+    let file_repo_path = repo_path("test.bat");
+    let file_disk_path = file_repo_path
+        .to_fs_path(test_workspace.workspace.workspace_root())
+        .unwrap();
+    let tree = testutils::create_tree_with(&test_workspace.repo, |builder| {
+        builder.file(attributes_path, attributes);
+        builder.file(file_repo_path, file_content);
+    });
+    let commit = commit_with_tree(test_workspace.repo.store(), tree);
+    test_workspace
+        .workspace
+        .check_out(test_workspace.repo.op_id().clone(), None, &commit)
+        .unwrap();
+
+    std::fs::read(&file_disk_path).unwrap()
+}
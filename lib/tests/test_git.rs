@@ -504,6 +504,49 @@ fn test_import_refs_reimport_head_removed() {
     assert!(!tx.repo().view().heads().contains(&commit_id));
 }
 
+#[test]
+fn test_revert_imported_git_refs() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+    let import_options = default_import_options();
+
+    let commit1 = empty_git_commit(&git_repo, "refs/remotes/origin/main", &[]);
+    let mut tx = repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &import_options).unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    // Simulate a fetch which moves one remote bookmark and adds another
+    let commit2 = empty_git_commit(&git_repo, "refs/remotes/origin/main", &[commit1]);
+    git_ref(&git_repo, "refs/remotes/origin/feature", commit2);
+    let mut tx = repo.start_transaction();
+    let stats = git::import_refs(tx.repo_mut(), &import_options).unwrap();
+    assert_eq!(stats.changed_remote_bookmarks.len(), 2);
+
+    // The rejected import is discarded, and the Git refs are restored
+    drop(tx);
+    git::revert_imported_git_refs(repo.store(), &stats).unwrap();
+    assert_eq!(
+        git_repo
+            .find_reference("refs/remotes/origin/main")
+            .unwrap()
+            .id()
+            .detach(),
+        commit1
+    );
+    assert!(
+        git_repo
+            .try_find_reference("refs/remotes/origin/feature")
+            .unwrap()
+            .is_none()
+    );
+
+    let mut tx = repo.start_transaction();
+    let stats = git::import_refs(tx.repo_mut(), &import_options).unwrap();
+    assert!(stats.changed_remote_bookmarks.is_empty());
+    assert!(!tx.repo().view().heads().contains(&jj_id(commit2)));
+}
+
 #[test]
 fn test_import_refs_reimport_git_head_does_not_count() {
     // Test that if a bookmark is removed, the corresponding commit is abandoned
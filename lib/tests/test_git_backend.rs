@@ -23,8 +23,11 @@ use futures::executor::block_on_stream;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::CopyRecord;
+use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::conflict_labels::ConflictLabels;
+use jj_lib::copies::CopyDetectionOptions;
+use jj_lib::copies::detect_copies;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::git_backend::JJ_TREES_COMMIT_HEADER;
 use jj_lib::merge::Merge;
@@ -40,6 +43,7 @@ use jj_lib::store::Store;
 use jj_lib::transaction::Transaction;
 use maplit::hashmap;
 use maplit::hashset;
+use pollster::FutureExt as _;
 use testutils::TestRepo;
 use testutils::TestRepoBackend;
 use testutils::assert_tree_eq;
@@ -70,7 +74,7 @@ fn collect_no_gc_refs(git_repo_path: &Path) -> HashSet<CommitId> {
 }
 
 fn get_copy_records(
-    store: &Store,
+    store: &Arc<Store>,
     paths: Option<&[RepoPathBuf]>,
     a: &Commit,
     b: &Commit,
@@ -405,6 +409,111 @@ fn test_copy_detection_file_and_dir() {
     );
 }
 
+#[test]
+fn test_copy_detection_by_similarity() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    // "part" is split out of "big", "moved" is renamed from "old" with
+    // changes, and "new" is unrelated.
+    let mut tx = repo.start_transaction();
+    let commit_a = make_commit(
+        &mut tx,
+        vec![repo.store().root_commit_id().clone()],
+        &[
+            (repo_path("big"), "a\nb\nc\nd\ne\nf\n"),
+            (repo_path("old"), "1\n2\n3\n4\n5\n"),
+        ],
+    );
+    let commit_b = make_commit(
+        &mut tx,
+        vec![commit_a.id().clone()],
+        &[
+            (repo_path("big"), "a\nb\nc\n"),
+            (repo_path("part"), "d\ne\n\nf\ng\n"),
+            (repo_path("moved"), "1\n2\n3\n4\n6\n"),
+            (repo_path("new"), "x\ny\n"),
+        ],
+    );
+
+    let detect_in = |paths: Option<&[RepoPathBuf]>,
+                     known_records: &[CopyRecord],
+                     threshold: u32,
+                     max_candidates: usize| {
+        let options = CopyDetectionOptions {
+            enabled: true,
+            threshold,
+            max_candidates,
+        };
+        let records = detect_copies(
+            repo.store(),
+            commit_a.id(),
+            commit_b.id(),
+            paths,
+            known_records,
+            &options,
+        )
+        .block_on()
+        .unwrap();
+        records
+            .into_iter()
+            .map(|record| {
+                (
+                    record.target.as_internal_file_string().to_owned(),
+                    record.source.as_internal_file_string().to_owned(),
+                )
+            })
+            .collect::<HashMap<_, _>>()
+    };
+    let detect = |known_records: &[CopyRecord], threshold: u32, max_candidates: usize| {
+        detect_in(None, known_records, threshold, max_candidates)
+    };
+    assert_eq!(
+        detect(&[], 50, 100),
+        hashmap! {
+            "part".to_owned() => "big".to_owned(),
+            "moved".to_owned() => "old".to_owned(),
+        }
+    );
+    assert_eq!(
+        detect(&[], 80, 100),
+        hashmap! {
+            "moved".to_owned() => "old".to_owned(),
+        }
+    );
+    // Too many added files
+    assert_eq!(detect(&[], 50, 2), HashMap::new());
+    // Only targets in the requested paths are detected
+    assert_eq!(
+        detect_in(Some(&[repo_path_buf("part")]), &[], 50, 2),
+        hashmap! {
+            "part".to_owned() => "big".to_owned(),
+        }
+    );
+    assert_eq!(
+        detect_in(Some(&[repo_path_buf("new")]), &[], 50, 100),
+        HashMap::new()
+    );
+    // Targets of known records are skipped
+    let source_value = commit_a.tree().path_value(repo_path("big")).unwrap();
+    let Ok(Some(TreeValue::File { id, .. })) = source_value.into_resolved() else {
+        panic!("big should be a file");
+    };
+    let known_record = CopyRecord {
+        target: repo_path_buf("moved"),
+        target_commit: commit_b.id().clone(),
+        source: repo_path_buf("big"),
+        source_file: id,
+        source_commit: commit_a.id().clone(),
+    };
+    assert_eq!(
+        detect(&[known_record], 50, 100),
+        hashmap! {
+            "part".to_owned() => "big".to_owned(),
+        }
+    );
+}
+
 #[test]
 fn test_jj_trees_header_with_one_tree() {
     let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
@@ -42,6 +42,7 @@ use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::revset::GENERATION_RANGE_FULL;
 use jj_lib::revset::PARENTS_RANGE_FULL;
 use jj_lib::revset::ResolvedExpression;
+use jj_lib::revset::Revset;
 use pollster::FutureExt as _;
 use test_case::test_case;
 use testutils::TestRepo;
@@ -798,6 +799,111 @@ fn test_index_store_type() {
     );
 }
 
+#[test]
+fn test_revset_cache() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let default_index_store: &DefaultIndexStore = repo.index_store().downcast_ref().unwrap();
+    let cache = default_index_store.revset_cache();
+    let collect_ids = |revset: Box<dyn Revset>| revset.iter().map(Result::unwrap).collect_vec();
+
+    // D C
+    // | |
+    // | B
+    // |/
+    // A
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit_with_parents(tx.repo_mut(), &[&commit_a]);
+    let repo_1 = tx.commit("test").unwrap();
+    let mut tx = repo_1.start_transaction();
+    let commit_c = write_random_commit_with_parents(tx.repo_mut(), &[&commit_b]);
+    let commit_d = write_random_commit_with_parents(tx.repo_mut(), &[&commit_a]);
+    let repo_2 = tx.commit("test").unwrap();
+    let root_id = repo.store().root_commit_id().clone();
+
+    // Nothing cached yet
+    let index_1 = as_readonly_index(&repo_1);
+    assert!(cache.get(index_1, "key", repo_1.op_id()).is_none());
+
+    // Evaluate from scratch
+    let revset = cache
+        .ancestors(index_1, "key", repo_1.op_id(), &[commit_b.id().clone()])
+        .unwrap();
+    assert_eq!(
+        collect_ids(revset),
+        [
+            commit_b.id().clone(),
+            commit_a.id().clone(),
+            root_id.clone()
+        ]
+    );
+    let revset = cache.get(index_1, "key", repo_1.op_id()).unwrap();
+    assert_eq!(
+        collect_ids(revset),
+        [
+            commit_b.id().clone(),
+            commit_a.id().clone(),
+            root_id.clone()
+        ]
+    );
+
+    // Cached set is looked up by operation
+    let index_2 = as_readonly_index(&repo_2);
+    assert!(cache.get(index_2, "key", repo_2.op_id()).is_none());
+
+    // Extend the cached set to descendant heads
+    let revset = cache
+        .ancestors(index_2, "key", repo_2.op_id(), &[commit_c.id().clone()])
+        .unwrap();
+    assert_eq!(
+        collect_ids(revset),
+        [
+            commit_c.id().clone(),
+            commit_b.id().clone(),
+            commit_a.id().clone(),
+            root_id.clone(),
+        ]
+    );
+
+    // Old heads aren't ancestors of the new heads, so the cached set can't be
+    // reused
+    let revset = cache
+        .ancestors(index_2, "key", repo_2.op_id(), &[commit_d.id().clone()])
+        .unwrap();
+    assert_eq!(
+        collect_ids(revset),
+        [
+            commit_d.id().clone(),
+            commit_a.id().clone(),
+            root_id.clone()
+        ]
+    );
+
+    // Arbitrary set can be cached
+    let revset = cache
+        .insert(
+            index_2,
+            "other",
+            repo_2.op_id(),
+            [commit_d.id(), commit_b.id()],
+        )
+        .unwrap();
+    assert_eq!(
+        collect_ids(revset),
+        [commit_d.id().clone(), commit_b.id().clone()]
+    );
+    let revset = cache.get(index_2, "other", repo_2.op_id()).unwrap();
+    assert_eq!(
+        collect_ids(revset),
+        [commit_d.id().clone(), commit_b.id().clone()]
+    );
+
+    // Reindexing invalidates cached sets
+    default_index_store.reinit().unwrap();
+    assert!(cache.get(index_2, "other", repo_2.op_id()).is_none());
+}
+
 #[test]
 fn test_read_legacy_operation_link_file() {
     let test_repo = TestRepo::init();
@@ -48,6 +48,7 @@ use jj_lib::local_working_copy::TreeStateSettings;
 use jj_lib::matchers::FilesMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::SameChange;
+use jj_lib::merge_driver::MergeDrivers;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree_builder::MergedTreeBuilder;
 use jj_lib::op_store::OperationId;
@@ -1354,6 +1355,7 @@ fn test_snapshot_modified_materialized_conflict(
                 merge: MergeOptions {
                     hunk_level: FileMergeHunkLevel::Line,
                     same_change: SameChange::Accept,
+                    drivers: MergeDrivers::default(),
                 },
             },
         )
@@ -2140,6 +2142,38 @@ fn test_check_out_malformed_file_path_windows(file_path_str: &str) {
     assert!(!workspace_root.parent().unwrap().join("pwned").exists());
 }
 
+#[test]
+fn test_check_out_tree_config_file() {
+    let mut test_workspace = TestWorkspace::init();
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let config_path = repo_path(".jj/config.toml");
+    let file_path = repo_path("file");
+    let tree1 = create_tree(&repo, &[(config_path, "contents"), (file_path, "contents")]);
+    let tree2 = create_tree(&repo, &[(file_path, "contents")]);
+    let commit1 = commit_with_tree(repo.store(), tree1.clone());
+    let commit2 = commit_with_tree(repo.store(), tree2.clone());
+
+    // The shared config file isn't written to the .jj directory, and isn't
+    // counted in the stats.
+    let ws = &mut test_workspace.workspace;
+    let stats = ws.check_out(repo.op_id().clone(), None, &commit1).unwrap();
+    assert_eq!(stats.added_files, 1);
+    assert_eq!(stats.skipped_files, 0);
+    assert!(!config_path.to_fs_path_unchecked(&workspace_root).exists());
+
+    // Snapshot shouldn't remove the file from the tree.
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_tree_eq!(new_tree, tree1);
+
+    // The file can be removed by checking out another tree.
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(repo.op_id().clone(), None, &commit2).unwrap();
+    let new_tree = test_workspace.snapshot().unwrap();
+    assert_tree_eq!(new_tree, tree2);
+}
+
 #[test_case(".git"; "root .git file")]
 #[test_case(".jj"; "root .jj file")]
 #[test_case(".git/pwned"; "root .git dir")]
@@ -1347,6 +1347,90 @@ fn test_merge_partial_resolution() {
     assert_tree_eq!(merged, expected_merged);
 }
 
+/// Files whose `merge` attribute is unset are not merged line by line
+#[test]
+fn test_merge_unset_merge_attribute() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let attributes_path = repo_path(".gitattributes");
+    let text_path = repo_path("dir/file.txt");
+    let binary_path = repo_path("dir/file.bin");
+    let attributes = "*.bin -merge\n";
+    let create = |content: &str| {
+        let tree = create_single_tree(
+            repo,
+            &[
+                (attributes_path, attributes),
+                (text_path, content),
+                (binary_path, content),
+            ],
+        );
+        MergedTree::resolved(repo.store().clone(), tree.id().clone())
+    };
+    let base = create("a\nb\nc\n");
+    let side1 = create("a1\nb\nc\n");
+    let side2 = create("a\nb\nc2\n");
+
+    let merged = MergedTree::merge(Merge::from_vec(vec![
+        (side1, "side 1".into()),
+        (base, "base".into()),
+        (side2, "side 2".into()),
+    ]))
+    .block_on()
+    .unwrap();
+    assert!(merged.path_value(text_path).unwrap().is_resolved());
+    assert!(!merged.path_value(binary_path).unwrap().is_resolved());
+}
+
+/// Files can be merged by built-in strategy selected by the `merge` attribute.
+/// Built-in strategies are used by all merges, not only the ones running
+/// external merge drivers.
+#[test]
+fn test_merge_builtin_strategy_attribute() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let attributes_path = repo_path(".gitattributes");
+    let union_path = repo_path("union");
+    let theirs_path = repo_path("theirs");
+    let text_path = repo_path("text");
+    let attributes = "union merge=union\ntheirs merge=theirs\n";
+    let create = |content: &str| {
+        let tree = create_single_tree(
+            repo,
+            &[
+                (attributes_path, attributes),
+                (union_path, content),
+                (theirs_path, content),
+                (text_path, content),
+            ],
+        );
+        MergedTree::resolved(repo.store().clone(), tree.id().clone())
+    };
+    let base = create("a\n");
+    let side1 = create("a\nb\n");
+    let side2 = create("a\nc\n");
+
+    let merged = MergedTree::merge(Merge::from_vec(vec![
+        (side1, "side 1".into()),
+        (base, "base".into()),
+        (side2, "side 2".into()),
+    ]))
+    .block_on()
+    .unwrap();
+    let read_resolved = |path| {
+        let value = merged.path_value(path).unwrap();
+        let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+            panic!("unexpected value: {value:?}");
+        };
+        testutils::read_file(repo.store(), path, id)
+    };
+    assert_eq!(read_resolved(union_path), b"a\nb\nc\n");
+    assert_eq!(read_resolved(theirs_path), b"a\nc\n");
+    assert!(!merged.path_value(text_path).unwrap().is_resolved());
+}
+
 /// Merge 3 trees where each one is a 3-way conflict and the result is arrived
 /// at by only simplifying the conflict (no need to recurse)
 #[test]
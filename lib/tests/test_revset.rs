@@ -99,6 +99,7 @@ fn resolve_symbol(repo: &dyn Repo, symbol: &str) -> Result<Vec<CommitId>, Revset
         user_email: "",
         date_pattern_context: chrono::Local::now().into(),
         default_ignored_remote: Some(git::REMOTE_NAME_FOR_LOCAL_GIT_REPO),
+        default_push_remote: None,
         use_glob_by_default: true,
         extensions: &RevsetExtensions::default(),
         workspace: None,
@@ -233,6 +234,7 @@ fn test_resolve_symbol_commit_id() {
         user_email: settings.user_email(),
         date_pattern_context: chrono::Utc::now().fixed_offset().into(),
         default_ignored_remote: Some(git::REMOTE_NAME_FOR_LOCAL_GIT_REPO),
+        default_push_remote: None,
         use_glob_by_default: true,
         extensions: &RevsetExtensions::default(),
         workspace: None,
@@ -1183,6 +1185,7 @@ fn try_resolve_expression(
         user_email: settings.user_email(),
         date_pattern_context: chrono::Utc::now().fixed_offset().into(),
         default_ignored_remote: Some(git::REMOTE_NAME_FOR_LOCAL_GIT_REPO),
+        default_push_remote: None,
         use_glob_by_default: true,
         extensions: &RevsetExtensions::default(),
         workspace: None,
@@ -1234,6 +1237,7 @@ fn resolve_commit_ids_in_workspace(
         user_email: settings.user_email(),
         date_pattern_context: chrono::Utc::now().fixed_offset().into(),
         default_ignored_remote: Some(git::REMOTE_NAME_FOR_LOCAL_GIT_REPO),
+        default_push_remote: None,
         use_glob_by_default: true,
         extensions: &RevsetExtensions::default(),
         workspace: Some(workspace_ctx),
@@ -4248,6 +4252,136 @@ fn test_evaluate_expression_at_operation() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_evolution() {
+    let test_repo = TestRepo::init();
+    let repo0 = &test_repo.repo;
+
+    let mut tx = repo0.start_transaction();
+    let commit1_op1 = create_random_commit(tx.repo_mut())
+        .set_description("commit1@op1")
+        .write()
+        .unwrap();
+    let commit2_op1 = create_random_commit(tx.repo_mut())
+        .set_description("commit2@op1")
+        .write()
+        .unwrap();
+    let repo1 = tx.commit("test").unwrap();
+
+    let mut tx = repo1.start_transaction();
+    let commit1_op2 = tx
+        .repo_mut()
+        .rewrite_commit(&commit1_op1)
+        .set_description("commit1@op2")
+        .write()
+        .unwrap();
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo2 = tx.commit("test").unwrap();
+
+    let mut tx = repo2.start_transaction();
+    let commit1_op3 = tx
+        .repo_mut()
+        .rewrite_commit(&commit1_op2)
+        .set_description("commit1@op3")
+        .write()
+        .unwrap();
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo3 = tx.commit("test").unwrap();
+
+    let ref_str = |commit: &Commit| commit.id().hex();
+
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("predecessors({})", ref_str(&commit1_op3))
+        ),
+        vec![commit1_op2.id().clone(), commit1_op1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("predecessors({}, 1)", ref_str(&commit1_op3))
+        ),
+        vec![commit1_op2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("successors({})", ref_str(&commit1_op1))
+        ),
+        vec![commit1_op3.id().clone(), commit1_op2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("successors({}, 1)", ref_str(&commit1_op1))
+        ),
+        vec![commit1_op2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("predecessors({})", ref_str(&commit2_op1))
+        ),
+        vec![]
+    );
+    // Start commits are included if they're reachable from other start commits
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!(
+                "predecessors({}|{})",
+                ref_str(&commit1_op3),
+                ref_str(&commit1_op2)
+            )
+        ),
+        vec![commit1_op2.id().clone(), commit1_op1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!(
+                "successors({}|{}, 1)",
+                ref_str(&commit1_op1),
+                ref_str(&commit1_op2)
+            )
+        ),
+        vec![commit1_op3.id().clone(), commit1_op2.id().clone()]
+    );
+
+    // Only visible commits are included.
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("evolved_from({})", ref_str(&commit1_op1))
+        ),
+        vec![commit1_op3.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("evolved_from({})", ref_str(&commit1_op3))
+        ),
+        vec![commit1_op3.id().clone()]
+    );
+
+    // Evolution history is resolved at the specified operation.
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("at_operation(@-, evolved_from({}))", ref_str(&commit1_op1))
+        ),
+        vec![commit1_op2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            repo3.as_ref(),
+            &format!("at_operation(@-, successors({}))", ref_str(&commit1_op1))
+        ),
+        vec![commit1_op2.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_coalesce() {
     let test_repo = TestRepo::init();
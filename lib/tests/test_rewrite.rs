@@ -12,14 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use assert_matches::assert_matches;
 use itertools::Itertools as _;
 use jj_lib::backend::ChangeId;
+use jj_lib::backend::CopyId;
+use jj_lib::backend::FileId;
+use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
+use jj_lib::copies::record_copy;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::FilesMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree_builder::MergedTreeBuilder;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
@@ -28,7 +35,9 @@ use jj_lib::ref_name::RemoteName;
 use jj_lib::ref_name::RemoteRefSymbol;
 use jj_lib::ref_name::WorkspaceName;
 use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::rewrite::CommitRewriter;
 use jj_lib::rewrite::CommitWithSelection;
 use jj_lib::rewrite::EmptyBehavior;
@@ -38,6 +47,7 @@ use jj_lib::rewrite::RewriteRefsOptions;
 use jj_lib::rewrite::find_duplicate_divergent_commits;
 use jj_lib::rewrite::find_recursive_merge_commits;
 use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::rewrite::rebase_commit;
 use jj_lib::rewrite::rebase_commit_with_options;
 use jj_lib::rewrite::restore_tree;
 use maplit::hashmap;
@@ -45,6 +55,7 @@ use maplit::hashset;
 use pollster::FutureExt as _;
 use test_case::test_case;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 use testutils::assert_abandoned_with_parent;
 use testutils::assert_rebased_onto;
 use testutils::assert_tree_eq;
@@ -53,6 +64,7 @@ use testutils::create_tree;
 use testutils::create_tree_with;
 use testutils::rebase_descendants_with_options_return_map;
 use testutils::repo_path;
+use testutils::write_file;
 use testutils::write_random_commit;
 use testutils::write_random_commit_with_parents;
 
@@ -1300,6 +1312,146 @@ fn test_rebase_descendants_contents() {
     );
 }
 
+/// Renames `old_path` to `new_path` in `tree`, recording the rename, and
+/// replaces the contents of the renamed file.
+fn rename_file(
+    repo: &Arc<ReadonlyRepo>,
+    tree: &MergedTree,
+    old_path: &RepoPath,
+    new_path: &RepoPath,
+    contents: &str,
+) -> MergedTree {
+    let renamed = record_copy(tree, old_path, new_path, true)
+        .block_on()
+        .unwrap();
+    let copy_id = file_copy_id(&renamed, new_path);
+    let mut builder = MergedTreeBuilder::new(renamed);
+    builder.set_or_remove(
+        new_path.to_owned(),
+        Merge::normal(TreeValue::File {
+            id: write_file(repo.store(), new_path, contents),
+            executable: false,
+            copy_id,
+        }),
+    );
+    builder.write_tree().unwrap()
+}
+
+fn file_copy_id(tree: &MergedTree, path: &RepoPath) -> CopyId {
+    match tree.path_value(path).unwrap().into_resolved() {
+        Ok(Some(TreeValue::File { copy_id, .. })) => copy_id,
+        value => panic!("unexpected value at {path:?}: {value:?}"),
+    }
+}
+
+fn file_id(tree: &MergedTree, path: &RepoPath) -> Option<FileId> {
+    match tree.path_value(path).unwrap().into_resolved() {
+        Ok(Some(TreeValue::File { id, .. })) => Some(id),
+        _ => None,
+    }
+}
+
+#[test_case(TestRepoBackend::Git; "git backend")]
+#[test_case(TestRepoBackend::Simple; "simple backend")]
+fn test_rebase_onto_recorded_rename(backend: TestRepoBackend) {
+    let test_repo = TestRepo::init_with_backend(backend);
+    let repo = &test_repo.repo;
+
+    // Commit B renamed the file modified by commit C. When C is rebased onto B,
+    // its changes should be applied to the renamed file.
+    //
+    // C B
+    // |/
+    // A
+    let old_path = repo_path("old");
+    let new_path = repo_path("new");
+    let mut tx = repo.start_transaction();
+    let tree_a = create_tree(repo, &[(old_path, "1\n2\n3\n")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a)
+        .write()
+        .unwrap();
+    let tree_b = rename_file(repo, &commit_a.tree(), old_path, new_path, "1\n2\n3B\n");
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b.clone())
+        .write()
+        .unwrap();
+    let tree_c = create_tree(repo, &[(old_path, "1C\n2\n3\n")]);
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c)
+        .write()
+        .unwrap();
+
+    let new_commit_c = rebase_commit(tx.repo_mut(), commit_c, vec![commit_b.id().clone()])
+        .block_on()
+        .unwrap();
+    let new_tree_c = new_commit_c.tree();
+    assert!(!new_tree_c.has_conflict());
+    assert_eq!(file_id(&new_tree_c, old_path), None);
+    assert_eq!(
+        file_id(&new_tree_c, new_path),
+        Some(write_file(repo.store(), new_path, "1C\n2\n3B\n"))
+    );
+    assert_eq!(
+        file_copy_id(&new_tree_c, new_path),
+        file_copy_id(&tree_b, new_path)
+    );
+}
+
+#[test_case(TestRepoBackend::Git; "git backend")]
+#[test_case(TestRepoBackend::Simple; "simple backend")]
+fn test_rebase_recorded_rename(backend: TestRepoBackend) {
+    let test_repo = TestRepo::init_with_backend(backend);
+    let repo = &test_repo.repo;
+
+    // Commit C renamed the file modified by commit B. When C is rebased onto B,
+    // the changes from B should be carried over to the renamed file.
+    //
+    // C B
+    // |/
+    // A
+    let old_path = repo_path("old");
+    let new_path = repo_path("new");
+    let mut tx = repo.start_transaction();
+    let tree_a = create_tree(repo, &[(old_path, "1\n2\n3\n")]);
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree_a)
+        .write()
+        .unwrap();
+    let tree_b = create_tree(repo, &[(old_path, "1\n2\n3B\n")]);
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_b)
+        .write()
+        .unwrap();
+    let tree_c = rename_file(repo, &commit_a.tree(), old_path, new_path, "1C\n2\n3\n");
+    let commit_c = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree_c.clone())
+        .write()
+        .unwrap();
+
+    let new_commit_c = rebase_commit(tx.repo_mut(), commit_c, vec![commit_b.id().clone()])
+        .block_on()
+        .unwrap();
+    let new_tree_c = new_commit_c.tree();
+    assert!(!new_tree_c.has_conflict());
+    assert_eq!(file_id(&new_tree_c, old_path), None);
+    assert_eq!(
+        file_id(&new_tree_c, new_path),
+        Some(write_file(repo.store(), new_path, "1C\n2\n3B\n"))
+    );
+    // The recorded rename is kept
+    assert_eq!(
+        file_copy_id(&new_tree_c, new_path),
+        file_copy_id(&tree_c, new_path)
+    );
+}
+
 #[test]
 fn test_rebase_descendants_basic_bookmark_update() {
     let test_repo = TestRepo::init();
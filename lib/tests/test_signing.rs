@@ -10,6 +10,7 @@ use jj_lib::signing::SignBehavior;
 use jj_lib::signing::Signer;
 use jj_lib::signing::Verification;
 use jj_lib::test_signing_backend::TestSigningBackend;
+use pollster::FutureExt as _;
 use test_case::test_case;
 use testutils::TestRepoBackend;
 use testutils::TestWorkspace;
@@ -208,3 +209,57 @@ fn drop_behavior(backend: TestRepoBackend) {
     let rewritten_commit = repo.store().get_commit(rewritten.id()).unwrap();
     assert_eq!(rewritten_commit.verification().unwrap(), None);
 }
+
+#[test]
+fn signed_operation() {
+    let mut config = testutils::base_user_config();
+    config.add_layer(
+        ConfigLayer::parse(
+            ConfigSource::User,
+            r#"
+            signing.key = "impeccable"
+            signing.sign-operations = true
+            "#,
+        )
+        .unwrap(),
+    );
+    let settings = UserSettings::from_config(config).unwrap();
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace =
+        TestWorkspace::init_with_backend_and_signer(TestRepoBackend::Simple, signer, &settings);
+
+    let repo = &test_workspace.repo;
+    let loader = repo.loader();
+    let signer = repo.store().signer();
+
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+    let op = repo.operation();
+    assert!(op.is_signed());
+    assert_eq!(op.verification(signer).unwrap(), good_verification());
+
+    // Pointing the operation at another view invalidates the signature
+    let mut data = op.store_operation().clone();
+    data.view_id = op.parents().next().unwrap().unwrap().view_id().clone();
+    let op_id = loader.op_store().write_operation(&data).block_on().unwrap();
+    let tampered_op = loader.load_operation(&op_id).unwrap();
+    assert!(tampered_op.is_signed());
+    assert_eq!(
+        tampered_op.verification(signer).unwrap().unwrap().status,
+        SigStatus::Bad
+    );
+
+    // Operations aren't signed unless configured
+    let settings = user_settings(SignBehavior::Drop);
+    let unsigned_workspace = TestWorkspace::init_with_backend_and_signer(
+        TestRepoBackend::Simple,
+        Signer::new(Some(Box::new(TestSigningBackend)), vec![]),
+        &settings,
+    );
+    let tx = unsigned_workspace.repo.start_transaction();
+    let repo = tx.commit("test").unwrap();
+    assert!(!repo.operation().is_signed());
+    assert_eq!(repo.operation().verification(signer).unwrap(), None);
+}
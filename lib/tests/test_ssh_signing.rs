@@ -108,6 +108,18 @@ impl SshEnvironment {
         self.allowed_signers = Some(allowed_signers_path);
     }
 
+    fn with_allowed_signers(&mut self, content: &str) {
+        let mut allowed_signers = tempfile::Builder::new()
+            .prefix("jj-test-allowed-signers-")
+            .tempfile()
+            .unwrap();
+
+        allowed_signers.write_all(content.as_bytes()).unwrap();
+        allowed_signers.flush().unwrap();
+
+        self.allowed_signers = Some(allowed_signers.into_temp_path());
+    }
+
     fn with_revocation_list(&mut self, revoked_key: &[u8]) {
         let mut revocation_list = tempfile::Builder::new()
             .prefix("jj-test-revocation-list-")
@@ -226,3 +238,84 @@ fn ssh_signing_revocation_unrevoked() {
     assert_eq!(check.status, SigStatus::Good);
     assert_eq!(check.key.unwrap(), FINGERPRINT);
 }
+
+#[test]
+fn ssh_signing_in_process_matches_program() {
+    let env = SshEnvironment::new().unwrap();
+    let in_process = backend(&env);
+    let program = backend(&env).with_in_process(false);
+    let data = b"hello world";
+    let key = env.private_key_path.to_str().unwrap();
+
+    // Ed25519 signatures are deterministic
+    let signature = in_process.sign(data, Some(key)).unwrap();
+    assert_eq!(signature, program.sign(data, Some(key)).unwrap());
+
+    let check = program.verify(data, &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Good);
+    assert_eq!(check.key.unwrap(), FINGERPRINT);
+}
+
+#[test]
+fn ssh_signing_in_process_verify_matches_program() {
+    let mut env = SshEnvironment::new().unwrap();
+    let data = b"hello world";
+    let signature = backend(&env)
+        .sign(data, Some(env.private_key_path.to_str().unwrap()))
+        .unwrap();
+
+    let mut check_both = |allowed_signers: Option<&str>, data: &[u8], signature: &[u8]| {
+        match allowed_signers {
+            Some(content) => env.with_allowed_signers(content),
+            None => env.allowed_signers = None,
+        }
+        let expected = backend(&env)
+            .with_in_process(false)
+            .verify(data, signature)
+            .unwrap();
+        let actual = backend(&env).verify(data, signature).unwrap();
+        assert_eq!(actual, expected, "allowed signers: {allowed_signers:?}");
+        actual.status
+    };
+
+    let allowed = format!("test@example.com {PUBLIC_KEY}");
+    assert_eq!(
+        check_both(Some(&allowed), data, &signature),
+        SigStatus::Good
+    );
+    assert_eq!(
+        check_both(Some(&allowed), b"other", &signature),
+        SigStatus::Bad
+    );
+    assert_eq!(check_both(None, data, &signature), SigStatus::Unknown);
+    assert_eq!(check_both(None, b"other", &signature), SigStatus::Bad);
+    assert_eq!(check_both(None, data, b"garbage"), SigStatus::Bad);
+
+    let allowed = format!("# comment\n\nfoo@example.com,test@example.com {PUBLIC_KEY} comment\n");
+    assert_eq!(
+        check_both(Some(&allowed), data, &signature),
+        SigStatus::Good
+    );
+    let allowed = format!(r#"test@example.com namespaces="file,git" {PUBLIC_KEY}"#);
+    assert_eq!(
+        check_both(Some(&allowed), data, &signature),
+        SigStatus::Good
+    );
+    let allowed = format!(r#"test@example.com namespaces="file" {PUBLIC_KEY}"#);
+    assert_eq!(check_both(Some(&allowed), data, &signature), SigStatus::Bad);
+
+    // Any entry of the key can allow the namespace for the first principal
+    let allowed = format!(
+        "test@example.com namespaces=\"file\" {PUBLIC_KEY}\n\
+         test@example.com namespaces=\"git\" {PUBLIC_KEY}\n"
+    );
+    assert_eq!(
+        check_both(Some(&allowed), data, &signature),
+        SigStatus::Good
+    );
+    let allowed = format!(
+        "test@example.com namespaces=\"file\" {PUBLIC_KEY}\n\
+         foo@example.com namespaces=\"git\" {PUBLIC_KEY}\n"
+    );
+    assert_eq!(check_both(Some(&allowed), data, &signature), SigStatus::Bad);
+}